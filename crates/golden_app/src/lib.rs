@@ -1,9 +1,11 @@
 use std::net::SocketAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use golden_core::persistence::{ProjectStore, Recorder};
 use golden_core::Engine;
+use golden_net::rest::PROJECT_VERSION;
 use golden_net::{start_app_server, AppServerConfig};
 
 #[derive(Clone, Debug)]
@@ -11,6 +13,12 @@ pub struct RuntimeConfig {
     pub port: u16,
     pub static_dir: PathBuf,
     pub tick_ms: u64,
+    /// Project file loaded at startup, journaled and saved to.
+    pub project: Option<PathBuf>,
+    /// Autosave interval while the project has unsaved changes.
+    pub autosave: Option<Duration>,
+    /// File recording every tick's input, for replaying the session later.
+    pub record: Option<PathBuf>,
 }
 
 impl RuntimeConfig {
@@ -19,11 +27,18 @@ impl RuntimeConfig {
             .ok()
             .and_then(|value| value.parse::<u16>().ok())
             .unwrap_or(9010);
+        let autosave_secs = std::env::var("GOLDEN_AUTOSAVE_SECS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(60);
 
         Self {
             port,
             static_dir: PathBuf::from("src-ui/build"),
             tick_ms: 16,
+            project: std::env::var_os("GOLDEN_PROJECT").map(PathBuf::from),
+            autosave: (autosave_secs > 0).then(|| Duration::from_secs(autosave_secs)),
+            record: std::env::var_os("GOLDEN_RECORD").map(PathBuf::from),
         }
     }
 
@@ -33,6 +48,8 @@ impl RuntimeConfig {
 }
 
 pub fn start_runtime(engine: Arc<Mutex<Engine>>, config: RuntimeConfig) {
+    let mut store = config.project.as_ref().and_then(|path| open_project(&engine, path, &config));
+    let mut recorder = config.record.as_ref().and_then(|path| start_recording(&engine, path));
     let server_engine = Arc::clone(&engine);
     let server_config = AppServerConfig {
         addr: config.addr(),
@@ -50,11 +67,58 @@ pub fn start_runtime(engine: Arc<Mutex<Engine>>, config: RuntimeConfig) {
             interval.tick().await;
             if let Ok(mut engine) = engine.lock() {
                 engine.tick();
+                if let Some(store) = &mut store {
+                    if let Err(err) = store.tick(&mut engine) {
+                        eprintln!("saving {} failed: {err}", store.path().display());
+                    }
+                }
+                if let Some(recorder) = &mut recorder {
+                    if let Err(err) = recorder.tick(&mut engine) {
+                        eprintln!("recording failed: {err}");
+                    }
+                }
             }
         }
     });
 }
 
+/// Loads the project, replaying edits a crashed run left in its journal.
+fn open_project(
+    engine: &Mutex<Engine>,
+    path: &Path,
+    config: &RuntimeConfig,
+) -> Option<ProjectStore> {
+    let mut engine = engine.lock().ok()?;
+    let mut store = ProjectStore::new(path, engine.root_id(), PROJECT_VERSION);
+    if let Some(interval) = config.autosave {
+        store = store.with_autosave(interval);
+    }
+    match store.open(&mut engine) {
+        Ok(recovery) => {
+            if recovery.replayed > 0 {
+                println!("Recovered {} unsaved edits for {}", recovery.replayed, path.display());
+            }
+            Some(store)
+        }
+        Err(err) => {
+            eprintln!("cannot open project {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Records from the state the project was opened in, after any recovery.
+fn start_recording(engine: &Mutex<Engine>, path: &Path) -> Option<Recorder> {
+    let mut engine = engine.lock().ok()?;
+    match Recorder::create(path, &mut engine, PROJECT_VERSION) {
+        Ok(recorder) => Some(recorder),
+        Err(err) => {
+            eprintln!("cannot record to {}: {err}", path.display());
+            None
+        }
+    }
+}
+
 pub fn wait_for_ctrl_c() {
     match tokio::runtime::Runtime::new() {
         Ok(rt) => {
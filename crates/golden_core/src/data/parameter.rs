@@ -1,5 +1,11 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use golden_schema::NodeId;
-use golden_schema::{ColorRgba, ReferenceValue, Trigger, Value, Vec2, Vec3};
+use golden_schema::{
+    ColorRgba, Curve, FloatRange, Gradient, IntRange, Quat, ReferenceValue, Timestamp, Trigger,
+    Value, ValuePatch, Vec2, Vec3, Vec4,
+};
 
 use crate::edits::Propagation;
 use crate::engine::ProcessCtx;
@@ -9,6 +15,19 @@ pub type ParameterData = golden_schema::ParameterData;
 pub trait ParameterValue: Sized {
     fn into_value(self) -> Value;
     fn from_value(value: &Value) -> Option<Self>;
+
+    /// How a `Vec<Self>` is stored: a `List` unless the element type has a
+    /// dense array variant.
+    fn vec_into_value(items: Vec<Self>) -> Value {
+        Value::List(items.into_iter().map(Self::into_value).collect())
+    }
+
+    fn vec_from_value(value: &Value) -> Option<Vec<Self>> {
+        match value {
+            Value::List(items) => items.iter().map(Self::from_value).collect(),
+            _ => None,
+        }
+    }
 }
 
 pub struct ParameterHandle<T> {
@@ -29,10 +48,15 @@ impl<T> ParameterHandle<T>
 where
     T: ParameterValue,
 {
+    /// Reads the stored value. Values are only converted and clamped when
+    /// submitted as external edits, so a value of another kind (written by
+    /// a behaviour, say) reads as `None`.
     pub fn get(&self, ctx: &ProcessCtx) -> Option<T> {
         ctx.read_param(self.node_id).and_then(T::from_value)
     }
 
+    /// Writes the value as given, without clamping it into the parameter's
+    /// constraints.
     pub fn set(&self, ctx: &mut ProcessCtx, value: T) {
         ctx.set_param(self.node_id, value.into_value());
     }
@@ -44,6 +68,11 @@ where
     pub fn set_next_tick(&self, ctx: &mut ProcessCtx, value: T) {
         ctx.set_param_with(self.node_id, value.into_value(), Propagation::NextTick);
     }
+
+    /// Changes one element of a collection parameter.
+    pub fn patch(&self, ctx: &mut ProcessCtx, patch: ValuePatch) {
+        ctx.patch_param(self.node_id, patch);
+    }
 }
 
 impl ParameterValue for bool {
@@ -70,6 +99,17 @@ impl ParameterValue for i64 {
             _ => None,
         }
     }
+
+    fn vec_into_value(items: Vec<Self>) -> Value {
+        Value::IntArray(items)
+    }
+
+    fn vec_from_value(value: &Value) -> Option<Vec<Self>> {
+        match value {
+            Value::IntArray(items) => Some(items.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl ParameterValue for f64 {
@@ -83,6 +123,17 @@ impl ParameterValue for f64 {
             _ => None,
         }
     }
+
+    fn vec_into_value(items: Vec<Self>) -> Value {
+        Value::FloatArray(items)
+    }
+
+    fn vec_from_value(value: &Value) -> Option<Vec<Self>> {
+        match value {
+            Value::FloatArray(items) => Some(items.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl ParameterValue for String {
@@ -137,6 +188,135 @@ impl ParameterValue for ColorRgba {
     }
 }
 
+impl ParameterValue for Vec4 {
+    fn into_value(self) -> Value {
+        Value::Vec4(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Vec4(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for Quat {
+    fn into_value(self) -> Value {
+        Value::Quat(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Quat(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for FloatRange {
+    fn into_value(self) -> Value {
+        Value::FloatRange(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::FloatRange(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for IntRange {
+    fn into_value(self) -> Value {
+        Value::IntRange(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::IntRange(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for Timestamp {
+    fn into_value(self) -> Value {
+        Value::Timestamp(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Timestamp(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for Duration {
+    fn into_value(self) -> Value {
+        Value::Duration(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Duration(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for Curve {
+    fn into_value(self) -> Value {
+        Value::Curve(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Curve(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl ParameterValue for Gradient {
+    fn into_value(self) -> Value {
+        Value::Gradient(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Gradient(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Bytes are stored as `Int`; a `Vec<u8>` is a `Bytes` value.
+impl ParameterValue for u8 {
+    fn into_value(self) -> Value {
+        Value::Int(self.into())
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Int(v) => u8::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    fn vec_into_value(items: Vec<Self>) -> Value {
+        Value::Bytes(items)
+    }
+
+    fn vec_from_value(value: &Value) -> Option<Vec<Self>> {
+        match value {
+            Value::Bytes(bytes) => Some(bytes.clone()),
+            _ => None,
+        }
+    }
+}
+
 impl ParameterValue for ReferenceValue {
     fn into_value(self) -> Value {
         Value::Reference(self)
@@ -162,3 +342,29 @@ impl ParameterValue for Trigger {
         }
     }
 }
+
+impl<T: ParameterValue> ParameterValue for Vec<T> {
+    fn into_value(self) -> Value {
+        T::vec_into_value(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        T::vec_from_value(value)
+    }
+}
+
+impl<T: ParameterValue> ParameterValue for BTreeMap<String, T> {
+    fn into_value(self) -> Value {
+        Value::Map(self.into_iter().map(|(key, value)| (key, value.into_value())).collect())
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| T::from_value(value).map(|value| (key.clone(), value)))
+                .collect(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use core::fmt;
+
+use golden_schema::{NodeId, Value};
+
+use crate::edits::Edit;
+use crate::engine::Engine;
+use crate::graph::node::NodeData;
+
+/// Whether external parameter writes of another value kind are converted
+/// with [`Value::coerce_to`] before validation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoercionMode {
+    /// Convert, warning when the conversion loses information.
+    #[default]
+    Lenient,
+    /// Reject any kind mismatch as `EditError::TypeMismatch`.
+    Strict,
+}
+
+/// Something an accepted edit changed on its way in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditWarning {
+    /// `from` was converted to the parameter's kind as `to`, losing information.
+    LossyConversion {
+        node: NodeId,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl EditWarning {
+    /// Stable machine-readable identifier, used in protocol replies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EditWarning::LossyConversion {
+                ..
+            } => "lossy_conversion",
+        }
+    }
+}
+
+impl fmt::Display for EditWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditWarning::LossyConversion {
+                node,
+                from,
+                to,
+            } => write!(
+                f,
+                "parameter {}: {from} became {to} ({} to {})",
+                node.0,
+                from.kind_name(),
+                to.kind_name()
+            ),
+        }
+    }
+}
+
+/// Converts the value of a `SetParam` or `RampParam` to its parameter's kind
+/// under `mode`. Values that cannot be converted are left for validation to
+/// reject.
+pub(crate) fn coerce_edit(
+    engine: &Engine,
+    edit: &mut Edit,
+    mode: CoercionMode,
+) -> Option<EditWarning> {
+    if mode == CoercionMode::Strict {
+        return None;
+    }
+    let (Edit::SetParam {
+        node,
+        value,
+    }
+    | Edit::RampParam {
+        node,
+        target: value,
+        ..
+    }) = edit
+    else {
+        return None;
+    };
+    let NodeData::Parameter(param) = &engine.nodes.get(node)?.data else {
+        return None;
+    };
+    let coerced = value.coerce_to(&param.value)?;
+    let from = std::mem::replace(value, coerced.value);
+    coerced.lossy.then(|| EditWarning::LossyConversion {
+        node: *node,
+        from,
+        to: value.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::edits::{EditError, EditOrigin, Propagation};
+    use crate::engine::Easing;
+
+    use super::*;
+
+    fn set(engine: &mut Engine, node: NodeId, value: Value) -> Result<Vec<EditWarning>, EditError> {
+        let edit = Edit::SetParam {
+            node,
+            value,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::Network)
+    }
+
+    fn value(engine: &Engine, node: NodeId) -> Value {
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    #[test]
+    fn lenient_writes_are_converted_before_validation() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        let count = engine.create_child_parameter(root, "count", Value::Int(0));
+
+        assert_eq!(set(&mut engine, gain, Value::Int(1)), Ok(Vec::new()));
+        assert_eq!(set(&mut engine, count, Value::String(" 4 ".to_string())), Ok(Vec::new()));
+        engine.tick();
+        assert_eq!(value(&engine, gain), Value::Float(1.0));
+        assert_eq!(value(&engine, count), Value::Int(4));
+    }
+
+    #[test]
+    fn lossy_conversions_are_accepted_with_a_warning() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let count = engine.create_child_parameter(root, "count", Value::Int(0));
+
+        let warnings = set(&mut engine, count, Value::Float(1.5)).unwrap();
+        assert_eq!(
+            warnings,
+            vec![EditWarning::LossyConversion {
+                node: count,
+                from: Value::Float(1.5),
+                to: Value::Int(2),
+            }]
+        );
+        assert_eq!(warnings[0].code(), "lossy_conversion");
+
+        let ramp = Edit::RampParam {
+            node: count,
+            target: Value::Float(9.6),
+            duration: Duration::ZERO,
+            easing: Easing::Linear,
+        };
+        let warnings =
+            engine.submit_edit(ramp, Propagation::EndOfTick, EditOrigin::Network).unwrap();
+        assert_eq!(warnings.len(), 1);
+        engine.tick();
+        assert_eq!(value(&engine, count), Value::Int(10));
+    }
+
+    #[test]
+    fn unconvertible_values_are_rejected() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+
+        let vector = Value::Vec2(golden_schema::Vec2 {
+            x: 1.0,
+            y: 0.0,
+        });
+        for bad in [Value::String("loud".to_string()), vector] {
+            let err = set(&mut engine, gain, bad).unwrap_err();
+            assert_eq!(err.code(), "type_mismatch");
+        }
+    }
+
+    #[test]
+    fn strict_mode_rejects_every_kind_mismatch() {
+        let mut engine = Engine::new();
+        engine.coercion = CoercionMode::Strict;
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+
+        let err = set(&mut engine, gain, Value::Int(1)).unwrap_err();
+        assert_eq!(
+            err,
+            EditError::TypeMismatch {
+                node: gain,
+                expected: "Float",
+                found: "Int",
+            }
+        );
+        assert_eq!(set(&mut engine, gain, Value::Float(0.5)), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn only_submitted_values_are_clamped() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        if let NodeData::Parameter(param) = &mut engine.nodes.get_mut(&gain).unwrap().data {
+            param.constraints = golden_schema::ValueConstraints::Float {
+                min: Some(0.0),
+                max: Some(1.0),
+                clamp: true,
+                step: None,
+            };
+        }
+
+        assert_eq!(set(&mut engine, gain, Value::Int(3)), Ok(Vec::new()));
+        engine.tick();
+        assert_eq!(value(&engine, gain), Value::Float(1.0));
+
+        engine.apply_edit_now(Edit::SetParam {
+            node: gain,
+            value: Value::Float(3.0),
+        });
+        assert_eq!(value(&engine, gain), Value::Float(3.0));
+    }
+}
@@ -1,11 +1,23 @@
 pub mod apply;
 pub mod coalesce;
+pub mod coerce;
+pub mod validate;
 
+use std::time::Duration;
+
+use crate::engine::Easing;
 use crate::graph::node::NodeExecution;
 use golden_schema::NodeId;
 use golden_schema::NodeMetaPatch;
 use golden_schema::NodeTypeId;
 use golden_schema::Value;
+use golden_schema::ValuePatch;
+use golden_schema::persistence::FullNodeRecord;
+use golden_schema::persistence::file_format::ProjectFile;
+use golden_schema::ui::messages;
+
+pub use coerce::{CoercionMode, EditWarning};
+pub use validate::{EditError, validate_edit};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Propagation {
@@ -22,8 +34,39 @@ pub enum EditOrigin {
     Internal,
 }
 
+impl From<EditOrigin> for messages::EditOrigin {
+    fn from(origin: EditOrigin) -> Self {
+        match origin {
+            EditOrigin::UI => messages::EditOrigin::UI,
+            EditOrigin::Network => messages::EditOrigin::Network,
+            EditOrigin::Script => messages::EditOrigin::Script,
+            EditOrigin::Internal => messages::EditOrigin::Internal,
+        }
+    }
+}
+
+impl From<messages::EditOrigin> for EditOrigin {
+    fn from(origin: messages::EditOrigin) -> Self {
+        match origin {
+            messages::EditOrigin::UI => EditOrigin::UI,
+            messages::EditOrigin::Network => EditOrigin::Network,
+            messages::EditOrigin::Script => EditOrigin::Script,
+            messages::EditOrigin::Internal => EditOrigin::Internal,
+        }
+    }
+}
+
 pub enum Edit {
     SetParam { node: NodeId, value: Value },
+    /// Moves a parameter to `target` over `duration` of engine time.
+    RampParam {
+        node: NodeId,
+        target: Value,
+        duration: Duration,
+        easing: Easing,
+    },
+    /// Changes one element of a collection parameter.
+    PatchParam { node: NodeId, patch: ValuePatch },
     PatchMeta { node: NodeId, patch: NodeMetaPatch },
     InstantiateChildFromManager {
         manager: NodeId,
@@ -31,6 +74,34 @@ pub enum Edit {
         label: String,
         execution: NodeExecution,
     },
+    CreateNode {
+        parent: NodeId,
+        node_type: NodeTypeId,
+        label: String,
+        execution: NodeExecution,
+    },
+    MoveNode {
+        node: NodeId,
+        new_parent: NodeId,
+        index: usize,
+    },
+    DeleteNode {
+        node: NodeId,
+    },
+    /// Makes the subtree at `root` match a project file; see
+    /// [`import_project`](crate::persistence::import_project).
+    ImportProject {
+        root: NodeId,
+        project: Box<ProjectFile>,
+    },
+    /// Creates `root` and its subtree under `parent`, keeping the uuids in the
+    /// records. Pasting a fragment queues this once
+    /// [`fragment_subtree`](crate::persistence::load::fragment_subtree) has
+    /// given it fresh uuids.
+    InsertSubtree {
+        parent: NodeId,
+        root: Box<FullNodeRecord>,
+    },
 }
 
 pub struct EditRequest {
@@ -0,0 +1,858 @@
+use core::fmt;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use golden_schema::persistence::file_format::ProjectFile;
+use golden_schema::persistence::{FullNodeRecord, NodeRecord};
+use golden_schema::{
+    Curve, FloatRange, Gradient, IntRange, NodeId, NodeMetaPatch, NodeTypeId, ParameterData, Value,
+    ValueConstraints, ValuePatch,
+};
+use uuid::Uuid;
+
+use crate::data::{AllowedTypes, ContainerData};
+use crate::edits::Edit;
+use crate::engine::Engine;
+use crate::expr;
+use crate::graph::node::NodeData;
+
+/// Why an external edit was rejected before reaching the edit queue.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditError {
+    UnknownNode(NodeId),
+    NotAParameter(NodeId),
+    ReadOnly(NodeId),
+    /// The parameter's value comes from the expression in its meta.
+    ExpressionDriven(NodeId),
+    TypeMismatch {
+        node: NodeId,
+        expected: &'static str,
+        found: &'static str,
+    },
+    ConstraintViolation {
+        node: NodeId,
+        message: String,
+    },
+    /// The patch does not fit the parameter's value (wrong kind, index out of
+    /// bounds, missing key).
+    InvalidPatch {
+        node: NodeId,
+        message: String,
+    },
+    /// A value sent in a unit that cannot be converted to the parameter's.
+    UnitMismatch {
+        node: NodeId,
+        message: String,
+    },
+    NotAContainer(NodeId),
+    TypeNotAllowed {
+        parent: NodeId,
+        node_type: NodeTypeId,
+    },
+    ContainerFull(NodeId),
+    /// The node belongs to its parent's schema and cannot be moved or deleted.
+    DeclaredChild(NodeId),
+    /// Moving a node below itself.
+    CyclicMove {
+        node: NodeId,
+        new_parent: NodeId,
+    },
+    RootNode,
+    /// The expression in a meta patch does not parse.
+    InvalidExpression {
+        node: NodeId,
+        message: String,
+    },
+    /// The meta patch sets a field only the engine maintains.
+    EngineManagedField {
+        node: NodeId,
+        field: &'static str,
+    },
+    /// A project's root record names a different type than the node it is
+    /// imported into.
+    RootTypeMismatch {
+        node: NodeId,
+        expected: NodeTypeId,
+        found: NodeTypeId,
+    },
+}
+
+impl EditError {
+    /// Stable machine-readable identifier, used in protocol error replies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EditError::UnknownNode(_) => "unknown_node",
+            EditError::NotAParameter(_) => "not_a_parameter",
+            EditError::ReadOnly(_) => "read_only",
+            EditError::ExpressionDriven(_) => "expression_driven",
+            EditError::TypeMismatch {
+                ..
+            } => "type_mismatch",
+            EditError::ConstraintViolation {
+                ..
+            } => "constraint_violation",
+            EditError::InvalidPatch {
+                ..
+            } => "invalid_patch",
+            EditError::UnitMismatch {
+                ..
+            } => "unit_mismatch",
+            EditError::NotAContainer(_) => "not_a_container",
+            EditError::TypeNotAllowed {
+                ..
+            } => "type_not_allowed",
+            EditError::ContainerFull(_) => "container_full",
+            EditError::DeclaredChild(_) => "declared_child",
+            EditError::CyclicMove {
+                ..
+            } => "cyclic_move",
+            EditError::RootNode => "root_node",
+            EditError::InvalidExpression {
+                ..
+            } => "invalid_expression",
+            EditError::EngineManagedField {
+                ..
+            } => "engine_managed_field",
+            EditError::RootTypeMismatch {
+                ..
+            } => "root_type_mismatch",
+        }
+    }
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::UnknownNode(node) => write!(f, "node {} does not exist", node.0),
+            EditError::NotAParameter(node) => write!(f, "node {} is not a parameter", node.0),
+            EditError::ReadOnly(node) => write!(f, "parameter {} is read-only", node.0),
+            EditError::ExpressionDriven(node) => {
+                write!(f, "parameter {} is driven by an expression", node.0)
+            }
+            EditError::TypeMismatch {
+                node,
+                expected,
+                found,
+            } => write!(f, "parameter {} expects {expected}, got {found}", node.0),
+            EditError::ConstraintViolation {
+                node,
+                message,
+            } => write!(f, "parameter {}: {message}", node.0),
+            EditError::InvalidPatch {
+                node,
+                message,
+            } => write!(f, "cannot patch parameter {}: {message}", node.0),
+            EditError::UnitMismatch {
+                node,
+                message,
+            } => write!(f, "parameter {}: {message}", node.0),
+            EditError::NotAContainer(node) => write!(f, "node {} cannot hold children", node.0),
+            EditError::TypeNotAllowed {
+                parent,
+                node_type,
+            } => write!(f, "node {} does not accept children of type {}", parent.0, node_type.0),
+            EditError::ContainerFull(node) => write!(f, "node {} is full", node.0),
+            EditError::DeclaredChild(node) => {
+                write!(f, "node {} is declared by its parent's schema", node.0)
+            }
+            EditError::CyclicMove {
+                node,
+                new_parent,
+            } => write!(f, "cannot move node {} below its descendant {}", node.0, new_parent.0),
+            EditError::RootNode => write!(f, "the root node cannot be moved or deleted"),
+            EditError::InvalidExpression {
+                node,
+                message,
+            } => write!(f, "invalid expression for node {}: {message}", node.0),
+            EditError::EngineManagedField {
+                node,
+                field,
+            } => write!(f, "{field} of node {} is maintained by the engine", node.0),
+            EditError::RootTypeMismatch {
+                node,
+                expected,
+                found,
+            } => {
+                write!(f, "cannot import a {} record into {} node {}", found.0, expected.0, node.0)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Checks an edit against the current graph without applying it.
+///
+/// External edits (UI, network, scripts) go through this before being queued;
+/// internal edits from node behaviours are trusted.
+pub fn validate_edit(engine: &Engine, edit: &Edit) -> Result<(), EditError> {
+    match edit {
+        Edit::SetParam {
+            node,
+            value,
+        }
+        | Edit::RampParam {
+            node,
+            target: value,
+            ..
+        } => validate_set_param(engine, *node, value),
+        Edit::PatchParam {
+            node,
+            patch,
+        } => validate_patch_param(engine, *node, patch),
+        Edit::PatchMeta {
+            node,
+            patch,
+        } => validate_patch_meta(engine, *node, patch),
+        Edit::InstantiateChildFromManager {
+            manager,
+            node_type,
+            ..
+        }
+        | Edit::CreateNode {
+            parent: manager,
+            node_type,
+            ..
+        } => validate_new_child(engine, *manager, node_type),
+        Edit::MoveNode {
+            node,
+            new_parent,
+            ..
+        } => validate_move(engine, *node, *new_parent),
+        Edit::DeleteNode {
+            node,
+        } => validate_detach(engine, *node),
+        Edit::ImportProject {
+            root,
+            project,
+        } => validate_import(engine, *root, project),
+        Edit::InsertSubtree {
+            parent,
+            root,
+        } => validate_subtree(engine, *parent, root),
+    }
+}
+
+fn validate_patch_meta(
+    engine: &Engine,
+    node: NodeId,
+    patch: &NodeMetaPatch,
+) -> Result<(), EditError> {
+    if engine.nodes.get(&node).is_none() {
+        return Err(EditError::UnknownNode(node));
+    }
+    if patch.expression_error.is_some() {
+        return Err(EditError::EngineManagedField {
+            node,
+            field: "expression_error",
+        });
+    }
+    // Paths are resolved once the edit is applied, as they may name nodes
+    // created later; a source that does not parse never will.
+    if let Some(Some(source)) = &patch.expression {
+        expr::parse_list(source).map_err(|err| EditError::InvalidExpression {
+            node,
+            message: err.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// The parameter at `node`, if external edits may write it.
+fn writable_param(engine: &Engine, node: NodeId) -> Result<&ParameterData, EditError> {
+    let entry = engine.nodes.get(&node).ok_or(EditError::UnknownNode(node))?;
+    let NodeData::Parameter(param) = &entry.data else {
+        return Err(EditError::NotAParameter(node));
+    };
+    if param.read_only {
+        return Err(EditError::ReadOnly(node));
+    }
+    if entry.meta.expression.is_some() {
+        return Err(EditError::ExpressionDriven(node));
+    }
+    Ok(param)
+}
+
+fn validate_set_param(engine: &Engine, node: NodeId, value: &Value) -> Result<(), EditError> {
+    let param = writable_param(engine, node)?;
+    if !param.value.same_kind(value) {
+        return Err(EditError::TypeMismatch {
+            node,
+            expected: param.value.kind_name(),
+            found: value.kind_name(),
+        });
+    }
+    check_constraints(&param.constraints, value).map_err(|message| EditError::ConstraintViolation {
+        node,
+        message,
+    })
+}
+
+fn validate_patch_param(
+    engine: &Engine,
+    node: NodeId,
+    patch: &ValuePatch,
+) -> Result<(), EditError> {
+    let param = writable_param(engine, node)?;
+    let mut value = param.value.clone();
+    patch.apply(&mut value).map_err(|message| EditError::InvalidPatch {
+        node,
+        message,
+    })?;
+    check_constraints(&param.constraints, &value).map_err(|message| {
+        EditError::ConstraintViolation {
+            node,
+            message,
+        }
+    })
+}
+
+/// Rejects values outside non-clamping constraints; clamping constraints are
+/// applied by [`clamp_to_constraints`] instead.
+fn check_constraints(constraints: &ValueConstraints, value: &Value) -> Result<(), String> {
+    match (constraints, value) {
+        (_, Value::FloatRange(range)) if range.min > range.max => {
+            Err(format!("range start {} is above its end {}", range.min, range.max))
+        }
+        (_, Value::IntRange(range)) if range.min > range.max => {
+            Err(format!("range start {} is above its end {}", range.min, range.max))
+        }
+        (_, Value::Curve(curve)) => {
+            let keys = curve.keys.iter().map(|key| (key.id, key.x));
+            check_keyframes(keys)?;
+            let ValueConstraints::Curve {
+                domain,
+                range,
+                clamp: false,
+            } = constraints
+            else {
+                return Ok(());
+            };
+            curve.keys.iter().enumerate().try_for_each(|(index, key)| {
+                check_within(key.x, *domain)
+                    .and(check_within(key.y, *range))
+                    .map_err(|message| format!("key {index}: {message}"))
+            })
+        }
+        (_, Value::Gradient(gradient)) => {
+            check_keyframes(gradient.stops.iter().map(|stop| (stop.id, stop.t)))?;
+            let ValueConstraints::Gradient {
+                domain,
+                clamp: false,
+            } = constraints
+            else {
+                return Ok(());
+            };
+            gradient.stops.iter().enumerate().try_for_each(|(index, stop)| {
+                check_within(stop.t, *domain).map_err(|message| format!("stop {index}: {message}"))
+            })
+        }
+        (
+            ValueConstraints::Float {
+                min,
+                max,
+                clamp: false,
+                ..
+            },
+            Value::Float(v),
+        ) => check_range(*v, *min, *max),
+        (
+            ValueConstraints::Int {
+                min,
+                max,
+                clamp: false,
+                ..
+            },
+            Value::Int(v),
+        ) => check_range(*v, *min, *max),
+        (
+            ValueConstraints::Float {
+                min,
+                max,
+                clamp: false,
+                ..
+            },
+            Value::FloatRange(range),
+        ) => check_range(range.min, *min, *max).and(check_range(range.max, *min, *max)),
+        (
+            ValueConstraints::Int {
+                min,
+                max,
+                clamp: false,
+                ..
+            },
+            Value::IntRange(range),
+        ) => check_range(range.min, *min, *max).and(check_range(range.max, *min, *max)),
+        (
+            ValueConstraints::Duration {
+                min,
+                max,
+                clamp: false,
+            },
+            Value::Duration(v),
+        ) => {
+            if let Some(min) = min
+                && v < min
+            {
+                return Err(format!("{v:?} is below the minimum {min:?}"));
+            }
+            if let Some(max) = max
+                && v > max
+            {
+                return Err(format!("{v:?} is above the maximum {max:?}"));
+            }
+            Ok(())
+        }
+        (
+            ValueConstraints::String {
+                max_len: Some(max_len),
+                ..
+            },
+            Value::String(v),
+        ) if v.chars().count() > *max_len => {
+            Err(format!("string is longer than {max_len} characters"))
+        }
+        (
+            ValueConstraints::Enum {
+                allowed,
+                ..
+            },
+            Value::Enum {
+                variant,
+                ..
+            },
+        ) if !allowed.is_empty() && !allowed.contains(variant) => {
+            Err(format!("variant '{}' is not allowed", variant.0))
+        }
+        (
+            ValueConstraints::Collection {
+                min_len,
+                max_len,
+                element,
+            },
+            value,
+        ) => {
+            let Some(len) = value.collection_len() else {
+                return Ok(());
+            };
+            if let Some(min_len) = min_len
+                && len < *min_len
+            {
+                return Err(format!("{len} elements are fewer than the minimum {min_len}"));
+            }
+            if let Some(max_len) = max_len
+                && len > *max_len
+            {
+                return Err(format!("{len} elements are more than the maximum {max_len}"));
+            }
+            check_elements(element, value)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_elements(element: &ValueConstraints, value: &Value) -> Result<(), String> {
+    let check = |index: &dyn fmt::Display, item: &Value| {
+        if let Some(expected) = element_kind(element)
+            && item.kind_name() != expected
+        {
+            return Err(format!("element {index} is {}, expected {expected}", item.kind_name()));
+        }
+        check_constraints(element, item).map_err(|message| format!("element {index}: {message}"))
+    };
+    match value {
+        Value::List(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(&index, item))
+        }
+        Value::FloatArray(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, item)| check(&index, &Value::Float(*item))),
+        Value::IntArray(items) => {
+            items.iter().enumerate().try_for_each(|(index, item)| check(&index, &Value::Int(*item)))
+        }
+        Value::Map(entries) => entries.iter().try_for_each(|(key, item)| check(&key, item)),
+        _ => Ok(()),
+    }
+}
+
+/// The value kind an element constraint implies, if any.
+fn element_kind(constraints: &ValueConstraints) -> Option<&'static str> {
+    match constraints {
+        ValueConstraints::Int {
+            ..
+        } => Some("Int"),
+        ValueConstraints::Float {
+            ..
+        } => Some("Float"),
+        ValueConstraints::String {
+            ..
+        } => Some("String"),
+        ValueConstraints::Enum {
+            ..
+        } => Some("Enum"),
+        ValueConstraints::Reference {
+            ..
+        } => Some("Reference"),
+        ValueConstraints::Duration {
+            ..
+        } => Some("Duration"),
+        ValueConstraints::None
+        | ValueConstraints::Curve {
+            ..
+        }
+        | ValueConstraints::Gradient {
+            ..
+        }
+        | ValueConstraints::Collection {
+            ..
+        } => None,
+    }
+}
+
+/// Curve keys and gradient stops must be sorted by position and have
+/// distinct ids.
+fn check_keyframes(keys: impl Iterator<Item = (Uuid, f64)>) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    let mut previous = f64::MIN;
+    for (index, (id, position)) in keys.enumerate() {
+        if !ids.insert(id) {
+            return Err(format!("key {index} repeats id {id}"));
+        }
+        if position < previous {
+            return Err(format!("key {index} at {position} is before the key preceding it"));
+        }
+        previous = position;
+    }
+    Ok(())
+}
+
+fn check_within(value: f64, bounds: Option<FloatRange>) -> Result<(), String> {
+    check_range(value, bounds.map(|bounds| bounds.min), bounds.map(|bounds| bounds.max))
+}
+
+fn clamp_within(value: f64, bounds: Option<FloatRange>) -> f64 {
+    match bounds {
+        Some(bounds) => value.max(bounds.min).min(bounds.max),
+        None => value,
+    }
+}
+
+fn check_range<T: PartialOrd + fmt::Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), String> {
+    if let Some(min) = min
+        && value < min
+    {
+        return Err(format!("{value} is below the minimum {min}"));
+    }
+    if let Some(max) = max
+        && value > max
+    {
+        return Err(format!("{value} is above the maximum {max}"));
+    }
+    Ok(())
+}
+
+/// Clamps the value written by an external `SetParam`, `RampParam` or
+/// `PatchParam` into its parameter's constraints where they ask for it.
+/// Writes from node behaviours are stored as given.
+pub(crate) fn clamp_edit(engine: &Engine, edit: &mut Edit) {
+    let (Edit::SetParam {
+        node,
+        ..
+    }
+    | Edit::RampParam {
+        node,
+        ..
+    }
+    | Edit::PatchParam {
+        node,
+        ..
+    }) = edit
+    else {
+        return;
+    };
+    let Some(NodeData::Parameter(param)) = engine.nodes.get(node).map(|entry| &entry.data) else {
+        return;
+    };
+    let constraints = &param.constraints;
+    match edit {
+        Edit::SetParam {
+            value,
+            ..
+        }
+        | Edit::RampParam {
+            target: value,
+            ..
+        } => *value = clamp_to_constraints(constraints, std::mem::replace(value, Value::Trigger)),
+        Edit::PatchParam {
+            patch:
+                ValuePatch::SetIndex {
+                    value,
+                    ..
+                }
+                | ValuePatch::Insert {
+                    value,
+                    ..
+                }
+                | ValuePatch::SetKey {
+                    value,
+                    ..
+                },
+            ..
+        } => {
+            if let ValueConstraints::Collection {
+                element,
+                ..
+            } = constraints
+            {
+                *value = clamp_to_constraints(element, std::mem::replace(value, Value::Trigger));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Clamps numeric values into their range when the constraint asks for it.
+pub(crate) fn clamp_to_constraints(constraints: &ValueConstraints, value: Value) -> Value {
+    match (constraints, value) {
+        (
+            ValueConstraints::Float {
+                min,
+                max,
+                clamp: true,
+                ..
+            },
+            Value::Float(v),
+        ) => Value::Float(v.max(min.unwrap_or(f64::MIN)).min(max.unwrap_or(f64::MAX))),
+        (
+            ValueConstraints::Int {
+                min,
+                max,
+                clamp: true,
+                ..
+            },
+            Value::Int(v),
+        ) => Value::Int(v.max(min.unwrap_or(i64::MIN)).min(max.unwrap_or(i64::MAX))),
+        (
+            ValueConstraints::Float {
+                min,
+                max,
+                clamp: true,
+                ..
+            },
+            Value::FloatRange(range),
+        ) => {
+            let clamp = |v: f64| v.max(min.unwrap_or(f64::MIN)).min(max.unwrap_or(f64::MAX));
+            Value::FloatRange(FloatRange {
+                min: clamp(range.min),
+                max: clamp(range.max),
+            })
+        }
+        (
+            ValueConstraints::Int {
+                min,
+                max,
+                clamp: true,
+                ..
+            },
+            Value::IntRange(range),
+        ) => {
+            let clamp = |v: i64| v.max(min.unwrap_or(i64::MIN)).min(max.unwrap_or(i64::MAX));
+            Value::IntRange(IntRange {
+                min: clamp(range.min),
+                max: clamp(range.max),
+            })
+        }
+        (
+            ValueConstraints::Duration {
+                min,
+                max,
+                clamp: true,
+            },
+            Value::Duration(v),
+        ) => {
+            Value::Duration(v.max(min.unwrap_or(Duration::ZERO)).min(max.unwrap_or(Duration::MAX)))
+        }
+        (
+            ValueConstraints::Curve {
+                domain,
+                range,
+                clamp: true,
+            },
+            Value::Curve(curve),
+        ) => Value::Curve(Curve {
+            keys: curve
+                .keys
+                .into_iter()
+                .map(|mut key| {
+                    key.x = clamp_within(key.x, *domain);
+                    key.y = clamp_within(key.y, *range);
+                    key
+                })
+                .collect(),
+        }),
+        (
+            ValueConstraints::Gradient {
+                domain,
+                clamp: true,
+            },
+            Value::Gradient(gradient),
+        ) => Value::Gradient(Gradient {
+            stops: gradient
+                .stops
+                .into_iter()
+                .map(|mut stop| {
+                    stop.t = clamp_within(stop.t, *domain);
+                    stop
+                })
+                .collect(),
+        }),
+        (
+            ValueConstraints::Collection {
+                element,
+                ..
+            },
+            value,
+        ) => clamp_elements(element, value),
+        (_, value) => value,
+    }
+}
+
+fn clamp_elements(element: &ValueConstraints, value: Value) -> Value {
+    let clamp_float = |item: f64| match clamp_to_constraints(element, Value::Float(item)) {
+        Value::Float(item) => item,
+        _ => item,
+    };
+    let clamp_int = |item: i64| match clamp_to_constraints(element, Value::Int(item)) {
+        Value::Int(item) => item,
+        _ => item,
+    };
+    match value {
+        Value::List(items) => {
+            Value::List(items.into_iter().map(|item| clamp_to_constraints(element, item)).collect())
+        }
+        Value::FloatArray(items) => Value::FloatArray(items.into_iter().map(clamp_float).collect()),
+        Value::IntArray(items) => Value::IntArray(items.into_iter().map(clamp_int).collect()),
+        Value::Map(entries) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(key, item)| (key, clamp_to_constraints(element, item)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn validate_new_child(
+    engine: &Engine,
+    parent: NodeId,
+    node_type: &NodeTypeId,
+) -> Result<(), EditError> {
+    let entry = engine.nodes.get(&parent).ok_or(EditError::UnknownNode(parent))?;
+    match &entry.data {
+        NodeData::Manager(manager) => {
+            if manager.registration_for(node_type).is_none() {
+                return Err(EditError::TypeNotAllowed {
+                    parent,
+                    node_type: node_type.clone(),
+                });
+            }
+            Ok(())
+        }
+        NodeData::Container(container) => {
+            // Parameters need a value and are only created from schemas.
+            if node_type.0 == "Parameter" || !container_accepts(container, node_type) {
+                return Err(EditError::TypeNotAllowed {
+                    parent,
+                    node_type: node_type.clone(),
+                });
+            }
+            if container.limits.max_children.is_some_and(|max| engine.child_count(parent) >= max) {
+                return Err(EditError::ContainerFull(parent));
+            }
+            Ok(())
+        }
+        _ => Err(EditError::NotAContainer(parent)),
+    }
+}
+
+fn container_accepts(container: &ContainerData, node_type: &NodeTypeId) -> bool {
+    if node_type.0 == "Folder" {
+        return matches!(container.folders, crate::data::FolderPolicy::Allowed);
+    }
+    match &container.allowed_types {
+        AllowedTypes::Any => true,
+        AllowedTypes::Only(types) => types.contains(node_type),
+    }
+}
+
+fn validate_move(engine: &Engine, node: NodeId, new_parent: NodeId) -> Result<(), EditError> {
+    validate_detach(engine, node)?;
+    let entry = engine.nodes.get(&node).ok_or(EditError::UnknownNode(node))?;
+    if engine.is_in_subtree(node, new_parent) {
+        return Err(EditError::CyclicMove {
+            node,
+            new_parent,
+        });
+    }
+    if entry.parent == Some(new_parent) {
+        return Ok(());
+    }
+    validate_new_child(engine, new_parent, &entry.node_type)
+}
+
+fn validate_detach(engine: &Engine, node: NodeId) -> Result<(), EditError> {
+    if node == engine.root_id() {
+        return Err(EditError::RootNode);
+    }
+    if engine.nodes.get(&node).is_none() {
+        return Err(EditError::UnknownNode(node));
+    }
+    if engine.is_declared_child(node) {
+        return Err(EditError::DeclaredChild(node));
+    }
+    Ok(())
+}
+
+fn validate_import(engine: &Engine, root: NodeId, project: &ProjectFile) -> Result<(), EditError> {
+    let node = engine.nodes.get(&root).ok_or(EditError::UnknownNode(root))?;
+    match &project.root {
+        NodeRecord::Full(record) if record.node_type != node.node_type => {
+            Err(EditError::RootTypeMismatch {
+                node: root,
+                expected: node.node_type.clone(),
+                found: record.node_type.clone(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `root` can be created under `parent`.
+pub(crate) fn validate_subtree(
+    engine: &Engine,
+    parent: NodeId,
+    root: &FullNodeRecord,
+) -> Result<(), EditError> {
+    validate_new_child(engine, parent, &root.node_type)?;
+    // A type only a manager knows how to build would come out as a bare node
+    // without its behaviour anywhere else.
+    let parent_is_manager =
+        engine.nodes.get(&parent).is_some_and(|node| matches!(node.data, NodeData::Manager(_)));
+    let managed = engine.schema.schema_for(&root.node_type).is_none()
+        && engine.nodes.values().any(|node| match &node.data {
+            NodeData::Manager(manager) => manager.registration_for(&root.node_type).is_some(),
+            _ => false,
+        });
+    if managed && !parent_is_manager {
+        return Err(EditError::TypeNotAllowed {
+            parent,
+            node_type: root.node_type.clone(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+/// Monotonic engine time, sampled once per tick.
+///
+/// `Engine::tick` advances it by the wall time since the previous tick;
+/// `Engine::tick_by` advances it by a fixed step for offline or deterministic
+/// runs.
+pub struct EngineClock {
+    now: Duration,
+    delta: Duration,
+    last_sample: Instant,
+}
+
+impl EngineClock {
+    pub fn new() -> Self {
+        Self {
+            now: Duration::ZERO,
+            delta: Duration::ZERO,
+            last_sample: Instant::now(),
+        }
+    }
+
+    /// Time since the engine started.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Time between the last two ticks.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub(crate) fn advance(&mut self) {
+        let elapsed = self.last_sample.elapsed();
+        self.advance_by(elapsed);
+    }
+
+    pub(crate) fn advance_by(&mut self, step: Duration) {
+        self.now += step;
+        self.delta = step;
+        self.last_sample = Instant::now();
+    }
+}
+
+impl Default for EngineClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_steps_accumulate() {
+        let mut clock = EngineClock::new();
+        assert_eq!((clock.now(), clock.delta()), (Duration::ZERO, Duration::ZERO));
+        clock.advance_by(Duration::from_millis(10));
+        clock.advance_by(Duration::from_millis(15));
+        assert_eq!(clock.now(), Duration::from_millis(25));
+        assert_eq!(clock.delta(), Duration::from_millis(15));
+    }
+
+    #[test]
+    fn wall_time_steps_count_from_the_last_sample() {
+        let mut clock = EngineClock::new();
+        clock.advance_by(Duration::from_secs(1));
+        std::thread::sleep(Duration::from_millis(5));
+        clock.advance();
+        assert!(clock.delta() >= Duration::from_millis(5));
+        assert!(clock.delta() < Duration::from_secs(1), "the fixed step was counted twice");
+        assert_eq!(clock.now(), Duration::from_secs(1) + clock.delta());
+    }
+}
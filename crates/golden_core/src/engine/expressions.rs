@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use golden_schema::{NodeId, Value, Vec2, Vec3};
+
+use crate::expr::{self, Expr};
+
+/// Variables every expression can read besides node paths.
+const TIME_VARIABLES: [&str; 2] = ["time", "dt"];
+
+/// A parameter whose value is computed from the expression in its meta.
+pub struct BoundExpression {
+    pub node: NodeId,
+    /// One expression per component of the driven value.
+    components: Vec<Expr>,
+    /// Path references as written, with the node and vector component read.
+    inputs: Vec<(String, NodeId, Option<usize>)>,
+    uses_time: bool,
+    /// Input values at the last evaluation; `None` until first evaluated.
+    last_inputs: Option<Vec<Option<Value>>>,
+}
+
+impl BoundExpression {
+    /// Parses `source` and resolves its paths for the parameter `node`,
+    /// whose current value decides the number of components.
+    pub fn bind(
+        node: NodeId,
+        source: &str,
+        current: &Value,
+        resolve: impl Fn(&str) -> Option<NodeId>,
+    ) -> Result<Self, String> {
+        let expected = match current {
+            Value::Float(_) | Value::Int(_) | Value::Bool(_) => 1,
+            Value::Vec2(_) => 2,
+            Value::Vec3(_) => 3,
+            other => {
+                return Err(format!(
+                    "{} parameters cannot be driven by expressions",
+                    other.kind_name()
+                ));
+            }
+        };
+        let components = expr::parse_list(source).map_err(|err| err.to_string())?;
+        if components.len() != expected {
+            return Err(format!(
+                "{} needs {expected} comma-separated expressions, got {}",
+                current.kind_name(),
+                components.len()
+            ));
+        }
+
+        let mut inputs = Vec::new();
+        let mut uses_time = false;
+        for component in &components {
+            for name in component.variables() {
+                if TIME_VARIABLES.contains(&name) {
+                    uses_time = true;
+                    continue;
+                }
+                if inputs.iter().any(|(path, _, _)| path == name) {
+                    continue;
+                }
+                if !name.starts_with('/') {
+                    return Err(format!("unknown variable '{name}'"));
+                }
+                let (target, component) = resolve_input(name, &resolve)
+                    .ok_or_else(|| format!("unknown path '{name}'"))?;
+                if target == node {
+                    return Err(format!("'{name}' refers to the parameter itself"));
+                }
+                inputs.push((name.to_string(), target, component));
+            }
+        }
+
+        Ok(Self {
+            node,
+            components,
+            inputs,
+            uses_time,
+            last_inputs: None,
+        })
+    }
+
+    /// Nodes whose values the expression reads.
+    pub fn dependencies(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.inputs.iter().map(|(_, node, _)| *node)
+    }
+
+    /// Computes the parameter's new value, or `None` when neither the inputs
+    /// nor, for time-based expressions, the clock have moved.
+    pub fn evaluate(
+        &mut self,
+        now: Duration,
+        delta: Duration,
+        current: &Value,
+        read: impl Fn(NodeId) -> Option<Value>,
+    ) -> Option<Result<Value, String>> {
+        let values: Vec<Option<Value>> =
+            self.inputs.iter().map(|(_, node, _)| read(*node)).collect();
+        if !self.uses_time && self.last_inputs.as_ref() == Some(&values) {
+            return None;
+        }
+
+        let mut scalars = HashMap::new();
+        for ((path, _, component), value) in self.inputs.iter().zip(&values) {
+            match value.as_ref().and_then(|value| scalar(value, *component)) {
+                Some(scalar) => scalars.insert(path.as_str(), scalar),
+                None => {
+                    self.last_inputs = Some(values);
+                    return Some(Err(format!("'{path}' is not a number")));
+                }
+            };
+        }
+        self.last_inputs = Some(values);
+
+        let lookup = |name: &str| match name {
+            "time" => Some(now.as_secs_f64()),
+            "dt" => Some(delta.as_secs_f64()),
+            _ => scalars.get(name).copied(),
+        };
+        let results = self
+            .components
+            .iter()
+            .map(|component| component.eval(&lookup))
+            .collect::<Result<Vec<f64>, _>>();
+        let results = match results {
+            Ok(results) => results,
+            Err(err) => return Some(Err(err.to_string())),
+        };
+        if results.iter().any(|value| !value.is_finite()) {
+            return Some(Err("result is not a finite number".to_string()));
+        }
+        Some(Ok(match (current, results.as_slice()) {
+            (Value::Int(_), [value]) => Value::Int(value.round() as i64),
+            (Value::Bool(_), [value]) => Value::Bool(*value != 0.0),
+            (Value::Vec2(_), [x, y]) => Value::Vec2(Vec2 {
+                x: *x,
+                y: *y,
+            }),
+            (Value::Vec3(_), [x, y, z]) => Value::Vec3(Vec3 {
+                x: *x,
+                y: *y,
+                z: *z,
+            }),
+            (_, [value]) => Value::Float(*value),
+            _ => return Some(Err("parameter kind changed".to_string())),
+        }))
+    }
+}
+
+/// Resolves `path`, falling back to a `.x`, `.y` or `.z` component suffix.
+fn resolve_input(
+    path: &str,
+    resolve: &impl Fn(&str) -> Option<NodeId>,
+) -> Option<(NodeId, Option<usize>)> {
+    if let Some(node) = resolve(path) {
+        return Some((node, None));
+    }
+    let (base, suffix) = path.rsplit_once('.')?;
+    let component = ["x", "y", "z"].iter().position(|axis| *axis == suffix)?;
+    resolve(base).map(|node| (node, Some(component)))
+}
+
+fn scalar(value: &Value, component: Option<usize>) -> Option<f64> {
+    match (value, component) {
+        (Value::Float(v), None) => Some(*v),
+        (Value::Int(v), None) => Some(*v as f64),
+        (Value::Bool(v), None) => Some(if *v {
+            1.0
+        } else {
+            0.0
+        }),
+        (Value::Vec2(v), Some(index)) => [v.x, v.y].get(index).copied(),
+        (Value::Vec3(v), Some(index)) => [v.x, v.y, v.z].get(index).copied(),
+        _ => None,
+    }
+}
+
+/// Orders expressions so each runs after the driven parameters it reads.
+///
+/// Expressions on a dependency cycle are returned separately, each with the
+/// cycle it belongs to.
+pub fn order(
+    bound: Vec<BoundExpression>,
+) -> (Vec<BoundExpression>, Vec<(BoundExpression, Vec<NodeId>)>) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        Unvisited,
+        Active,
+        Done,
+    }
+
+    let index_of: HashMap<NodeId, usize> =
+        bound.iter().enumerate().map(|(index, entry)| (entry.node, index)).collect();
+    let edges: Vec<Vec<usize>> = bound
+        .iter()
+        .map(|entry| entry.dependencies().filter_map(|dep| index_of.get(&dep).copied()).collect())
+        .collect();
+
+    let mut marks = vec![Mark::Unvisited; bound.len()];
+    let mut sorted = Vec::with_capacity(bound.len());
+    let mut cycles: HashMap<usize, Vec<NodeId>> = HashMap::new();
+    for start in 0..bound.len() {
+        if marks[start] != Mark::Unvisited {
+            continue;
+        }
+        // Iterative depth-first search; `stack` holds the active path.
+        let mut stack = vec![(start, 0)];
+        marks[start] = Mark::Active;
+        while let Some((current, next_edge)) = stack.last_mut() {
+            let current = *current;
+            if let Some(&dep) = edges[current].get(*next_edge) {
+                *next_edge += 1;
+                match marks[dep] {
+                    Mark::Unvisited => {
+                        marks[dep] = Mark::Active;
+                        stack.push((dep, 0));
+                    }
+                    Mark::Active => {
+                        let from = stack.iter().position(|(index, _)| *index == dep).unwrap_or(0);
+                        let members: Vec<usize> =
+                            stack[from..].iter().map(|(index, _)| *index).collect();
+                        let mut path: Vec<NodeId> =
+                            members.iter().map(|index| bound[*index].node).collect();
+                        path.push(bound[dep].node);
+                        for member in members {
+                            cycles.entry(member).or_insert_with(|| path.clone());
+                        }
+                    }
+                    Mark::Done => {}
+                }
+            } else {
+                marks[current] = Mark::Done;
+                sorted.push(current);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<BoundExpression>> = bound.into_iter().map(Some).collect();
+    let mut cyclic = Vec::new();
+    let mut ordered = Vec::new();
+    for index in sorted {
+        let Some(entry) = slots[index].take() else {
+            continue;
+        };
+        match cycles.remove(&index) {
+            Some(path) => cyclic.push((entry, path)),
+            None => ordered.push(entry),
+        }
+    }
+    (ordered, cyclic)
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::{EventKind, NodeMetaPatch};
+
+    use crate::edits::{Edit, EditError, EditOrigin, Propagation};
+    use crate::engine::Engine;
+    use crate::graph::node::NodeData;
+
+    use super::*;
+
+    fn set_expression(engine: &mut Engine, node: NodeId, expression: Option<&str>) {
+        let patch = NodeMetaPatch {
+            expression: Some(expression.map(str::to_string)),
+            ..NodeMetaPatch::default()
+        };
+        submit_patch(engine, node, patch).unwrap();
+    }
+
+    fn submit_patch(
+        engine: &mut Engine,
+        node: NodeId,
+        patch: NodeMetaPatch,
+    ) -> Result<(), EditError> {
+        let edit = Edit::PatchMeta {
+            node,
+            patch,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).map(|_| ())
+    }
+
+    fn value(engine: &Engine, node: NodeId) -> Value {
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn error(engine: &Engine, node: NodeId) -> Option<String> {
+        engine.nodes.get(&node).unwrap().meta.expression_error.clone()
+    }
+
+    #[test]
+    fn chains_settle_in_dependency_order() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        // Created before what it reads, so id order would evaluate it first.
+        let c = engine.create_child_parameter(root, "c", Value::Float(0.0));
+        let b = engine.create_child_parameter(root, "b", Value::Float(0.0));
+        let a = engine.create_child_parameter(root, "a", Value::Float(1.0));
+        set_expression(&mut engine, c, Some("/b + 1"));
+        set_expression(&mut engine, b, Some("/a * 2"));
+        engine.tick();
+        assert_eq!(value(&engine, c), Value::Float(3.0));
+        assert!(engine.is_expression_driven(b) && engine.is_expression_driven(c));
+
+        let edit = Edit::SetParam {
+            node: a,
+            value: Value::Float(2.0),
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+        assert_eq!(value(&engine, b), Value::Float(4.0));
+        assert_eq!(value(&engine, c), Value::Float(5.0));
+    }
+
+    #[test]
+    fn cycles_are_reported_and_cleared() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let a = engine.create_child_parameter(root, "a", Value::Float(1.0));
+        let b = engine.create_child_parameter(root, "b", Value::Float(2.0));
+        let c = engine.create_child_parameter(root, "c", Value::Float(0.0));
+        set_expression(&mut engine, a, Some("/b + 1"));
+        set_expression(&mut engine, b, Some("/a + 1"));
+        set_expression(&mut engine, c, Some("/a"));
+        let before = engine.time;
+        engine.tick();
+
+        for node in [a, b] {
+            let error = error(&engine, node).expect("cycle not reported");
+            assert!(error.starts_with("dependency cycle: "), "{error}");
+            assert!(!engine.is_expression_driven(node));
+        }
+        assert_eq!((value(&engine, a), value(&engine, b)), (Value::Float(1.0), Value::Float(2.0)));
+        // Reading a cyclic parameter is fine; only the cycle is left out.
+        assert_eq!(error(&engine, c), None);
+        assert_eq!(value(&engine, c), Value::Float(1.0));
+        let reported = engine.events_since(before).into_iter().any(|event| {
+            matches!(event.kind, EventKind::MetaChanged { node, patch }
+                if node == a && matches!(patch.expression_error, Some(Some(_))))
+        });
+        assert!(reported, "no MetaChanged event for the error");
+
+        set_expression(&mut engine, b, None);
+        engine.tick();
+        assert_eq!(error(&engine, a), None);
+        assert_eq!(error(&engine, b), None);
+        assert_eq!(value(&engine, a), Value::Float(3.0));
+        assert_eq!(value(&engine, c), Value::Float(3.0));
+    }
+
+    #[test]
+    fn bind_errors_land_in_expression_error() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let a = engine.create_child_parameter(root, "a", Value::Float(1.0));
+        set_expression(&mut engine, a, Some("/missing * 2"));
+        engine.tick();
+        assert_eq!(error(&engine, a).as_deref(), Some("unknown path '/missing'"));
+
+        engine.create_child_parameter(root, "missing", Value::Float(4.0));
+        set_expression(&mut engine, a, Some("/missing * 2"));
+        engine.tick();
+        assert_eq!(error(&engine, a), None);
+        assert_eq!(value(&engine, a), Value::Float(8.0));
+    }
+
+    #[test]
+    fn meta_patches_are_validated() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let a = engine.create_child_parameter(root, "a", Value::Float(1.0));
+
+        let patch = |expression: &str| NodeMetaPatch {
+            expression: Some(Some(expression.to_string())),
+            ..NodeMetaPatch::default()
+        };
+        let err = submit_patch(&mut engine, a, patch("1 +")).unwrap_err();
+        assert_eq!(err.code(), "invalid_expression");
+        let deep = format!("{}1{}", "(".repeat(300), ")".repeat(300));
+        let err = submit_patch(&mut engine, a, patch(&deep)).unwrap_err();
+        assert_eq!(err.code(), "invalid_expression");
+        let long = vec!["1"; 1000].join("+");
+        let err = submit_patch(&mut engine, a, patch(&long)).unwrap_err();
+        assert_eq!(err.code(), "invalid_expression");
+
+        let forged = NodeMetaPatch {
+            expression_error: Some(Some("forged".to_string())),
+            ..NodeMetaPatch::default()
+        };
+        let err = submit_patch(&mut engine, a, forged).unwrap_err();
+        assert_eq!(
+            err,
+            EditError::EngineManagedField {
+                node: a,
+                field: "expression_error",
+            }
+        );
+        assert!(engine.pending_edits.is_empty());
+    }
+
+    #[test]
+    fn null_expression_clears_it() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let a = engine.create_child_parameter(root, "a", Value::Float(1.0));
+        set_expression(&mut engine, a, Some("5"));
+        engine.tick();
+        assert!(engine.is_expression_driven(a));
+
+        let patch = serde_json::from_str(r#"{ "expression": null }"#).unwrap();
+        submit_patch(&mut engine, a, patch).unwrap();
+        engine.tick();
+        assert_eq!(engine.nodes.get(&a).unwrap().meta.expression, None);
+        assert!(!engine.is_expression_driven(a));
+        let edit = Edit::SetParam {
+            node: a,
+            value: Value::Float(2.0),
+        };
+        assert!(engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).is_ok());
+    }
+}
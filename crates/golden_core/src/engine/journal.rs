@@ -0,0 +1,201 @@
+use golden_schema::{JournalEntry, NodeId, NodeTypeId, SavePolicy, Value};
+
+use crate::edits::{Edit, EditOrigin, EditRequest};
+use crate::engine::Engine;
+use crate::graph::node::NodeData;
+
+/// What a user edit will change, captured before it is applied while the
+/// paths it names still resolve.
+pub(crate) enum PendingEntry {
+    /// The parameter's value is read back once the edit has been applied.
+    Param(NodeId),
+    Ramp {
+        node: NodeId,
+        target: Value,
+    },
+    Create {
+        parent: String,
+        node_type: NodeTypeId,
+        label: String,
+    },
+    Ready(JournalEntry),
+}
+
+impl Engine {
+    /// Number of edits from the UI or the network committed so far. A project
+    /// has unsaved changes while this differs from its count at the last save.
+    pub fn edit_revision(&self) -> u64 {
+        self.edit_revision
+    }
+
+    /// Starts or stops keeping committed user edits for
+    /// [`Engine::take_journal`]. Stopping drops the ones not yet taken.
+    pub fn set_journaling(&mut self, enabled: bool) {
+        self.journal = enabled.then(|| self.journal.take().unwrap_or_default());
+    }
+
+    /// Committed user edits since the last call, oldest first.
+    pub fn take_journal(&mut self) -> Vec<JournalEntry> {
+        self.journal.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Counts and journals a user change made outside the edit queue, such
+    /// as a pasted fragment.
+    pub(crate) fn journal_entry_committed(&mut self, entry: JournalEntry) {
+        self.commit_journal_entry(PendingEntry::Ready(entry), None);
+    }
+
+    /// Only UI and network edits count: internal edits rebuild state that is
+    /// already saved, and script edits are recomputed on every run.
+    pub(crate) fn pending_journal_entry(&self, request: &EditRequest) -> Option<PendingEntry> {
+        if !matches!(request.origin, EditOrigin::UI | EditOrigin::Network) {
+            return None;
+        }
+        let path = |node: &NodeId| self.path_of(*node).map(str::to_string);
+        Some(match &request.edit {
+            Edit::SetParam {
+                node,
+                ..
+            }
+            | Edit::PatchParam {
+                node,
+                ..
+            } => PendingEntry::Param(*node),
+            Edit::RampParam {
+                node,
+                target,
+                ..
+            } => PendingEntry::Ramp {
+                node: *node,
+                target: target.clone(),
+            },
+            Edit::PatchMeta {
+                node,
+                patch,
+            } => PendingEntry::Ready(JournalEntry::PatchMeta {
+                path: path(node)?,
+                patch: patch.clone(),
+            }),
+            Edit::InstantiateChildFromManager {
+                manager: parent,
+                node_type,
+                label,
+                ..
+            }
+            | Edit::CreateNode {
+                parent,
+                node_type,
+                label,
+                ..
+            } => PendingEntry::Create {
+                parent: path(parent)?,
+                node_type: node_type.clone(),
+                label: label.clone(),
+            },
+            Edit::MoveNode {
+                node,
+                new_parent,
+                index,
+            } => PendingEntry::Ready(JournalEntry::MoveNode {
+                path: path(node)?,
+                new_parent: path(new_parent)?,
+                index: *index,
+            }),
+            Edit::DeleteNode {
+                node,
+            } => PendingEntry::Ready(JournalEntry::DeleteNode {
+                path: path(node)?,
+            }),
+            Edit::InsertSubtree {
+                parent,
+                root,
+            } => PendingEntry::Ready(JournalEntry::InsertSubtree {
+                parent: path(parent)?,
+                root: root.clone(),
+            }),
+            // An import replaces the state the journal is replayed onto.
+            Edit::ImportProject {
+                ..
+            } => return None,
+        })
+    }
+
+    /// Counts an applied user edit and journals it. Triggers and runtime-only
+    /// parameters change nothing that is saved, so they are neither counted
+    /// nor journaled.
+    pub(crate) fn commit_journal_entry(&mut self, pending: PendingEntry, created: Option<NodeId>) {
+        let entry = match pending {
+            PendingEntry::Param(node) => {
+                let Some(NodeData::Parameter(param)) = self.nodes.get(&node).map(|n| &n.data)
+                else {
+                    return;
+                };
+                if param.save == SavePolicy::None {
+                    return;
+                }
+                JournalEntry::SetParam {
+                    path: self.path_of(node).unwrap_or_default().to_string(),
+                    value: param.value.clone(),
+                }
+            }
+            PendingEntry::Ramp {
+                node,
+                target,
+            } => {
+                if let Some(NodeData::Parameter(param)) = self.nodes.get(&node).map(|n| &n.data)
+                    && param.save == SavePolicy::None
+                {
+                    return;
+                }
+                JournalEntry::SetParam {
+                    path: self.path_of(node).unwrap_or_default().to_string(),
+                    value: target,
+                }
+            }
+            PendingEntry::Create {
+                parent,
+                node_type,
+                label,
+            } => {
+                let Some(node) = created.and_then(|node| self.nodes.get(&node)) else {
+                    return;
+                };
+                JournalEntry::CreateNode {
+                    parent,
+                    node_type,
+                    label,
+                    uuid: node.meta.uuid,
+                }
+            }
+            PendingEntry::Ready(entry) => entry,
+        };
+        if let JournalEntry::SetParam {
+            value: Value::Trigger,
+            ..
+        } = entry
+        {
+            return;
+        }
+
+        self.edit_revision += 1;
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        // A dragged fader commits many values in a row; only the last matters.
+        if let (
+            Some(JournalEntry::SetParam {
+                path: last,
+                ..
+            }),
+            JournalEntry::SetParam {
+                path,
+                ..
+            },
+        ) = (journal.last(), &entry)
+            && last == path
+        {
+            journal.pop();
+        }
+        journal.push(entry);
+    }
+}
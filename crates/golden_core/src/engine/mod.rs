@@ -1,23 +1,45 @@
+pub mod clock;
+pub mod expressions;
+mod journal;
 pub mod process_ctx;
+pub mod ramps;
+mod recording;
 pub mod scheduling;
 
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use std::time::Duration;
 
 use golden_schema::{
-    DeclId, Event, EventKind, EventTime, NodeId, NodeMeta, NodeTypeId, NodeUuid, ShortName, Value,
+    DeclId, Event, EventKind, EventTime, JournalEntry, NodeId, NodeMeta, NodeMetaPatch, NodeTypeId,
+    NodeUuid, ShortName, Value, ValuePatch, units,
 };
 use slotmap::{Key, KeyData, SlotMap, new_key_type};
 use uuid::Uuid;
 
-use crate::edits::{Edit, EditOrigin, EditQueue, EditRequest, Propagation};
+use crate::edits::coerce::coerce_edit;
+use crate::edits::validate::clamp_edit;
+use crate::edits::{
+    CoercionMode, Edit, EditError, EditOrigin, EditQueue, EditRequest, EditWarning, Propagation,
+};
+use crate::engine::recording::Recording;
 use crate::events::inbox::Inbox;
 use crate::events::routing::subscriptions::{EventFilter, ListenerSpec};
 use crate::graph::node::{ManagerData, Node, NodeBehaviour, NodeBinding, NodeData, NodeExecution};
+use crate::graph::paths::{
+    PathIndex, child_path, pattern_segments, sanitize_short_name, segment_matches,
+};
+use crate::graph::queries::{DataKind, NodeInfo, NodeQuery};
+use crate::history::sessions::EditSession;
 use crate::meta::apply_patch;
+use crate::persistence::load::{ImportReport, import_project, insert_subtree};
+use crate::presets::PresetLibrary;
 use crate::schema::{NodeSchema, SchemaRegistry};
 
-pub use process_ctx::{EnginePhase, ProcessCtx};
+pub use clock::EngineClock;
+pub use expressions::BoundExpression;
+pub use process_ctx::{EnginePhase, ProcessCtx, SubscriptionChange};
+pub use ramps::{Easing, Ramp};
 
 new_key_type! {
     struct NodeKey;
@@ -69,6 +91,10 @@ impl NodeStore {
         NodeId(key.data().as_ffi())
     }
 
+    pub fn remove(&mut self, id: &NodeId) -> Option<Node> {
+        self.inner.remove(Self::key_from_id(*id))
+    }
+
     fn key_from_id(id: NodeId) -> NodeKey {
         NodeKey::from(KeyData::from_ffi(id.0))
     }
@@ -76,15 +102,35 @@ impl NodeStore {
 
 pub struct Engine {
     pub time: EventTime,
+    pub clock: EngineClock,
     pub nodes: NodeStore,
     pub inboxes: HashMap<NodeId, Inbox>,
     pub subscriptions: Vec<ListenerSpec>,
     pub pending_edits: Vec<EditRequest>,
     pub schema: SchemaRegistry,
     pub event_log: VecDeque<Event>,
+    pub presets: PresetLibrary,
+    /// How submitted parameter values of another kind are handled.
+    pub coercion: CoercionMode,
     param_values: Arc<HashMap<NodeId, Value>>,
     meta_values: Arc<HashMap<NodeId, NodeMeta>>,
+    node_infos: Arc<HashMap<NodeId, NodeInfo>>,
+    ramps: Vec<Ramp>,
+    /// Expression-driven parameters in evaluation order.
+    expressions: Vec<BoundExpression>,
+    /// Set when expressions or the paths they name may have changed.
+    expressions_stale: bool,
+    paths: Arc<PathIndex>,
     root: NodeId,
+    /// Committed user edits so far; see [`Engine::edit_revision`].
+    edit_revision: u64,
+    /// Committed user edits not yet taken, kept while journaling is on.
+    journal: Option<Vec<JournalEntry>>,
+    /// Kept while recording is on; see [`Engine::set_recording`].
+    recording: Option<Recording>,
+    /// What the last queued import or paste could not restore; see
+    /// [`Engine::take_import_report`].
+    import_report: Option<ImportReport>,
 }
 
 impl Engine {
@@ -95,15 +141,27 @@ impl Engine {
                 micro: 0,
                 seq: 0,
             },
+            clock: EngineClock::new(),
             nodes: NodeStore::new(),
             inboxes: HashMap::new(),
             subscriptions: Vec::new(),
             pending_edits: Vec::new(),
             schema: SchemaRegistry::new(),
             event_log: VecDeque::new(),
+            presets: PresetLibrary::new(),
+            coercion: CoercionMode::default(),
             param_values: Arc::new(HashMap::new()),
             meta_values: Arc::new(HashMap::new()),
+            node_infos: Arc::new(HashMap::new()),
+            ramps: Vec::new(),
+            expressions: Vec::new(),
+            expressions_stale: false,
+            paths: Arc::new(PathIndex::new()),
             root: NodeId(0),
+            edit_revision: 0,
+            journal: None,
+            recording: None,
+            import_report: None,
         };
 
         let mut root_meta = engine.create_meta("root");
@@ -119,6 +177,7 @@ impl Engine {
             None,
         );
         engine.root = root;
+        Arc::make_mut(&mut engine.paths).insert(root, "/".to_string());
 
         engine
     }
@@ -158,13 +217,15 @@ impl Engine {
         NodeMeta {
             uuid: NodeUuid(Uuid::new_v4()),
             decl_id: DeclId(short.to_string()),
-            short_name: ShortName(short.to_string()),
+            short_name: ShortName(sanitize_short_name(short)),
             enabled: true,
             label: short.to_string(),
             description: None,
             tags: Vec::new(),
             semantics: Default::default(),
             presentation: Default::default(),
+            expression: None,
+            expression_error: None,
         }
     }
 
@@ -181,6 +242,11 @@ impl Engine {
             _ => None,
         };
         let meta_value = meta.clone();
+        let info = NodeInfo {
+            node_type: node_type.clone(),
+            data_kind: DataKind::of(&data),
+            parent: None,
+        };
 
         let node = Node {
             id: NodeId(0),
@@ -200,6 +266,7 @@ impl Engine {
             Arc::make_mut(&mut self.param_values).insert(node_id, value);
         }
         Arc::make_mut(&mut self.meta_values).insert(node_id, meta_value);
+        Arc::make_mut(&mut self.node_infos).insert(node_id, info);
         self.inboxes.insert(node_id, Inbox::new());
         self.emit_event(EventKind::NodeCreated {
             node: node_id,
@@ -310,38 +377,210 @@ impl Engine {
     }
 
     pub fn add_child(&mut self, parent: NodeId, child: NodeId) {
-        let Some(last_child) = self.nodes.get(&parent).and_then(|node| node.last_child) else {
-            if let Some(parent_node) = self.nodes.get_mut(&parent) {
-                parent_node.first_child = Some(child);
-                parent_node.last_child = Some(child);
-            }
-            if let Some(child_node) = self.nodes.get_mut(&child) {
-                child_node.parent = Some(parent);
-                child_node.prev_sibling = None;
-                child_node.next_sibling = None;
-            }
-            self.emit_event(EventKind::ChildAdded {
-                parent,
-                child,
-            });
+        self.link_last(parent, child);
+        self.emit_event(EventKind::ChildAdded {
+            parent,
+            child,
+        });
+    }
+
+    /// Inserts `child` before the child currently at `index`, or appends it.
+    pub fn insert_child_at(&mut self, parent: NodeId, child: NodeId, index: usize) {
+        let mut current = self.nodes.get(&parent).and_then(|node| node.first_child);
+        for _ in 0..index {
+            current = current.and_then(|id| self.nodes.get(&id)).and_then(|node| node.next_sibling);
+        }
+        let Some(next) = current else {
+            self.link_last(parent, child);
             return;
         };
+        self.ensure_unique_short_name(parent, child);
 
+        let prev = self.nodes.get(&next).and_then(|node| node.prev_sibling);
+        if let Some(child_node) = self.nodes.get_mut(&child) {
+            child_node.parent = Some(parent);
+            child_node.prev_sibling = prev;
+            child_node.next_sibling = Some(next);
+        }
+        if let Some(next_node) = self.nodes.get_mut(&next) {
+            next_node.prev_sibling = Some(child);
+        }
+        match prev {
+            Some(prev) => {
+                if let Some(prev_node) = self.nodes.get_mut(&prev) {
+                    prev_node.next_sibling = Some(child);
+                }
+            }
+            None => {
+                if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                    parent_node.first_child = Some(child);
+                }
+            }
+        }
+        self.set_info_parent(child, Some(parent));
+        self.index_subtree(child);
+    }
+
+    fn link_last(&mut self, parent: NodeId, child: NodeId) {
+        self.ensure_unique_short_name(parent, child);
+        let last = self.nodes.get(&parent).and_then(|node| node.last_child);
         if let Some(parent_node) = self.nodes.get_mut(&parent) {
+            if parent_node.first_child.is_none() {
+                parent_node.first_child = Some(child);
+            }
             parent_node.last_child = Some(child);
         }
-        if let Some(last_node) = self.nodes.get_mut(&last_child) {
+        if let Some(last_node) = last.and_then(|last| self.nodes.get_mut(&last)) {
             last_node.next_sibling = Some(child);
         }
         if let Some(child_node) = self.nodes.get_mut(&child) {
             child_node.parent = Some(parent);
-            child_node.prev_sibling = Some(last_child);
+            child_node.prev_sibling = last;
             child_node.next_sibling = None;
         }
-        self.emit_event(EventKind::ChildAdded {
-            parent,
-            child,
-        });
+        self.set_info_parent(child, Some(parent));
+        self.index_subtree(child);
+    }
+
+    /// Detaches a node from its parent and siblings without emitting events.
+    fn unlink(&mut self, child: NodeId) -> Option<NodeId> {
+        self.unindex_subtree(child);
+        self.set_info_parent(child, None);
+        let (parent, prev, next) = {
+            let node = self.nodes.get_mut(&child)?;
+            let links = (node.parent?, node.prev_sibling, node.next_sibling);
+            node.parent = None;
+            node.prev_sibling = None;
+            node.next_sibling = None;
+            links
+        };
+
+        match prev {
+            Some(prev) => {
+                if let Some(prev_node) = self.nodes.get_mut(&prev) {
+                    prev_node.next_sibling = next;
+                }
+            }
+            None => {
+                if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                    parent_node.first_child = next;
+                }
+            }
+        }
+        match next {
+            Some(next) => {
+                if let Some(next_node) = self.nodes.get_mut(&next) {
+                    next_node.prev_sibling = prev;
+                }
+            }
+            None => {
+                if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                    parent_node.last_child = prev;
+                }
+            }
+        }
+        Some(parent)
+    }
+
+    /// Moves a node under `new_parent` at `index`, emitting `ChildMoved` or,
+    /// within the same parent, `ChildReordered`.
+    pub fn move_node(&mut self, node: NodeId, new_parent: NodeId, index: usize) -> bool {
+        if self.nodes.get(&new_parent).is_none() || self.is_in_subtree(node, new_parent) {
+            return false;
+        }
+        let Some(old_parent) = self.unlink(node) else {
+            return false;
+        };
+        self.insert_child_at(new_parent, node, index);
+        if old_parent == new_parent {
+            self.emit_event(EventKind::ChildReordered {
+                parent: new_parent,
+                child: node,
+            });
+        } else {
+            self.emit_event(EventKind::ChildMoved {
+                child: node,
+                old_parent,
+                new_parent,
+            });
+        }
+        true
+    }
+
+    /// Removes a node and its whole subtree.
+    ///
+    /// Emits `ChildRemoved` for the node itself and `NodeDeleted` for every
+    /// removed node, children first.
+    pub fn delete_node(&mut self, node: NodeId) -> bool {
+        if node == self.root || self.nodes.get(&node).is_none() {
+            return false;
+        }
+
+        let mut doomed = Vec::new();
+        self.collect_subtree_post_order(node, &mut doomed);
+
+        if let Some(parent) = self.nodes.get(&node).and_then(|entry| entry.parent) {
+            self.emit_event(EventKind::ChildRemoved {
+                parent,
+                child: node,
+            });
+        }
+        for id in &doomed {
+            self.emit_event(EventKind::NodeDeleted {
+                node: *id,
+            });
+        }
+
+        self.unlink(node);
+        for id in doomed {
+            self.nodes.remove(&id);
+            self.inboxes.remove(&id);
+            Arc::make_mut(&mut self.param_values).remove(&id);
+            Arc::make_mut(&mut self.meta_values).remove(&id);
+            Arc::make_mut(&mut self.node_infos).remove(&id);
+            self.ramps.retain(|ramp| ramp.node != id);
+            self.subscriptions.retain(|spec| spec.subscriber != id);
+        }
+        true
+    }
+
+    fn collect_subtree_post_order(&self, node: NodeId, out: &mut Vec<NodeId>) {
+        let mut current = self.nodes.get(&node).and_then(|entry| entry.first_child);
+        while let Some(child) = current {
+            self.collect_subtree_post_order(child, out);
+            current = self.nodes.get(&child).and_then(|entry| entry.next_sibling);
+        }
+        out.push(node);
+    }
+
+    /// Creates a child of `node_type` below a container or manager.
+    ///
+    /// Managers instantiate their registered type, containers create the node
+    /// from the schema registry (or an empty node for unknown types).
+    pub fn create_child_of_type(
+        &mut self,
+        parent: NodeId,
+        node_type: NodeTypeId,
+        label: &str,
+        execution: NodeExecution,
+    ) -> Option<NodeId> {
+        match &self.nodes.get(&parent)?.data {
+            NodeData::Manager(_) => {
+                self.instantiate_child_from_manager(parent, node_type, label.to_string(), execution)
+            }
+            NodeData::Container(_) => {
+                let data = if node_type.0 == "Folder" {
+                    NodeData::Container(Self::default_container_data())
+                } else {
+                    self.default_node_data_for_type(&node_type)
+                };
+                let meta = self.create_meta(label);
+                let child = self.create_node(node_type, execution, data, meta, None);
+                self.add_child(parent, child);
+                Some(child)
+            }
+            _ => None,
+        }
     }
 
     fn instantiate_declared_children(&mut self, parent: NodeId, parent_type: &NodeTypeId) {
@@ -468,6 +707,181 @@ impl Engine {
         None
     }
 
+    /// Node at a `/`-separated path of short names, e.g. `/outputs/osc_output_a`.
+    pub fn resolve_path(&self, path: &str) -> Option<NodeId> {
+        self.paths.resolve(path)
+    }
+
+    pub fn node_by_uuid(&self, uuid: NodeUuid) -> Option<NodeId> {
+        self.nodes.values().find(|node| node.meta.uuid == uuid).map(|node| node.id)
+    }
+
+    /// Canonical path of an attached node; `/` for the root.
+    pub fn path_of(&self, node: NodeId) -> Option<&str> {
+        self.paths.path_of(node)
+    }
+
+    /// Nodes whose path matches a glob pattern, in tree order.
+    ///
+    /// `*` and `?` match within one segment, `**` matches any number of
+    /// segments: `/outputs/*/intensity`, `/**/host`.
+    pub fn query_paths(&self, pattern: &str) -> Vec<NodeId> {
+        let segments = pattern_segments(pattern);
+        let mut out = Vec::new();
+        self.collect_matches(self.root, &segments, &mut out);
+        out
+    }
+
+    fn collect_matches(&self, node: NodeId, segments: &[&str], out: &mut Vec<NodeId>) {
+        let Some((first, rest)) = segments.split_first() else {
+            if !out.contains(&node) {
+                out.push(node);
+            }
+            return;
+        };
+        if *first == "**" {
+            self.collect_matches(node, rest, out);
+        }
+
+        let mut current = self.nodes.get(&node).and_then(|entry| entry.first_child);
+        while let Some(child) = current {
+            let Some(entry) = self.nodes.get(&child) else {
+                break;
+            };
+            if *first == "**" {
+                self.collect_matches(child, segments, out);
+            } else if segment_matches(first, &entry.meta.short_name.0) {
+                self.collect_matches(child, rest, out);
+            }
+            current = entry.next_sibling;
+        }
+    }
+
+    /// Structural info for every node, as shared with `ProcessCtx`.
+    pub fn node_infos(&self) -> &HashMap<NodeId, NodeInfo> {
+        &self.node_infos
+    }
+
+    pub fn query<'a>(&'a self, query: &'a NodeQuery) -> impl Iterator<Item = NodeId> + 'a {
+        query.run(self)
+    }
+
+    fn set_info_parent(&mut self, node: NodeId, parent: Option<NodeId>) {
+        if let Some(info) = Arc::make_mut(&mut self.node_infos).get_mut(&node) {
+            info.parent = parent;
+        }
+        // Paths below `node` changed, which may break or fix expression inputs.
+        self.expressions_stale = true;
+    }
+
+    /// Renames `child` with a numeric suffix if a sibling already uses its
+    /// short name.
+    fn ensure_unique_short_name(&mut self, parent: NodeId, child: NodeId) {
+        let Some(name) = self.nodes.get(&child).map(|node| node.meta.short_name.0.clone()) else {
+            return;
+        };
+        let mut taken = std::collections::HashSet::new();
+        let mut current = self.nodes.get(&parent).and_then(|node| node.first_child);
+        while let Some(sibling) = current {
+            let Some(entry) = self.nodes.get(&sibling) else {
+                break;
+            };
+            if sibling != child {
+                taken.insert(entry.meta.short_name.0.clone());
+            }
+            current = entry.next_sibling;
+        }
+        if !taken.contains(&name) {
+            return;
+        }
+
+        let unique = (2..)
+            .map(|n| format!("{name}_{n}"))
+            .find(|candidate| !taken.contains(candidate))
+            .expect("suffixes are unbounded");
+        if let Some(node) = self.nodes.get_mut(&child) {
+            node.meta.short_name = ShortName(unique);
+            Arc::make_mut(&mut self.meta_values).insert(child, node.meta.clone());
+        }
+    }
+
+    fn index_subtree(&mut self, node: NodeId) {
+        let Some(entry) = self.nodes.get(&node) else {
+            return;
+        };
+        let Some(parent_path) = entry.parent.and_then(|parent| self.paths.path_of(parent)) else {
+            return;
+        };
+        let path = child_path(parent_path, &entry.meta.short_name.0);
+        let mut current = entry.first_child;
+        Arc::make_mut(&mut self.paths).insert(node, path);
+        while let Some(child) = current {
+            self.index_subtree(child);
+            current = self.nodes.get(&child).and_then(|entry| entry.next_sibling);
+        }
+    }
+
+    fn unindex_subtree(&mut self, node: NodeId) {
+        if Arc::make_mut(&mut self.paths).remove(node).is_none() {
+            return;
+        }
+        let mut current = self.nodes.get(&node).and_then(|entry| entry.first_child);
+        while let Some(child) = current {
+            self.unindex_subtree(child);
+            current = self.nodes.get(&child).and_then(|entry| entry.next_sibling);
+        }
+    }
+
+    pub fn child_count(&self, parent: NodeId) -> usize {
+        let mut count = 0;
+        let mut current = self.nodes.get(&parent).and_then(|node| node.first_child);
+        while let Some(node_id) = current {
+            count += 1;
+            current = self.nodes.get(&node_id).and_then(|node| node.next_sibling);
+        }
+        count
+    }
+
+    /// True when `node` is `root` or one of its descendants.
+    pub fn is_in_subtree(&self, root: NodeId, node: NodeId) -> bool {
+        is_node_in_subtree(&self.nodes, root, node)
+    }
+
+    /// Schema of a node, from the registry or from the manager that created it.
+    pub fn schema_of(&self, node: NodeId) -> Option<&NodeSchema> {
+        let entry = self.nodes.get(&node)?;
+        if let Some(schema) = self.schema.schema_for(&entry.node_type) {
+            return Some(schema);
+        }
+        let parent = self.nodes.get(&entry.parent?)?;
+        let NodeData::Manager(manager) = &parent.data else {
+            return None;
+        };
+        manager.registration_for(&entry.node_type).map(|registration| &registration.schema)
+    }
+
+    /// True when the nearest ancestor with a schema declares this node, i.e.
+    /// it was instantiated as a param, folder or declared child.
+    pub fn is_declared_child(&self, node: NodeId) -> bool {
+        let Some(entry) = self.nodes.get(&node) else {
+            return false;
+        };
+        let decl_id = entry.meta.decl_id.0.as_str();
+        let mut current = entry.parent;
+        while let Some(ancestor) = current {
+            if let Some(schema) = self.schema_of(ancestor) {
+                let folder_prefix = format!("{decl_id}.");
+                return schema.params.iter().any(|param| param.decl_id.0 == decl_id)
+                    || schema.folders.iter().any(|folder| {
+                        folder.decl_id.0 == decl_id || folder.decl_id.0.starts_with(&folder_prefix)
+                    })
+                    || schema.declared_children.iter().any(|child| child.decl_id.0 == decl_id);
+            }
+            current = self.nodes.get(&ancestor).and_then(|node| node.parent);
+        }
+        false
+    }
+
     fn default_container_data() -> crate::data::ContainerData {
         crate::data::ContainerData {
             allowed_types: crate::data::AllowedTypes::Any,
@@ -524,12 +938,16 @@ impl Engine {
         self.instantiate_declared_children_from_schema(child, &manager_schema);
 
         let binding = self.build_node_binding_from_schema(child, &manager_schema);
-        let manager_behaviour = {
-            let manager_node = self.nodes.get(&manager)?;
-            let NodeData::Manager(manager_data) = &manager_node.data else {
-                return None;
-            };
-            manager_data.create_behaviour(&node_type, binding)?
+        let manager_behaviour = match self.nodes.get(&manager).map(|node| &node.data) {
+            Some(NodeData::Manager(manager_data)) => {
+                manager_data.create_behaviour(&node_type, binding)
+            }
+            _ => None,
+        };
+        let Some(Ok(manager_behaviour)) = manager_behaviour else {
+            // A node without its behaviour would sit in the graph doing nothing.
+            self.delete_node(child);
+            return None;
         };
 
         if let Some(child_node) = self.nodes.get_mut(&child) {
@@ -567,6 +985,23 @@ impl Engine {
         self.subscriptions.push(spec);
     }
 
+    pub fn unsubscribe(&mut self, spec: &ListenerSpec) {
+        self.subscriptions.retain(|existing| existing != spec);
+    }
+
+    fn apply_subscription_changes(&mut self, changes: Vec<SubscriptionChange>) {
+        for change in changes {
+            match change {
+                SubscriptionChange::Subscribe(spec) => {
+                    if !self.subscriptions.contains(&spec) {
+                        self.subscribe(spec);
+                    }
+                }
+                SubscriptionChange::Unsubscribe(spec) => self.unsubscribe(&spec),
+            }
+        }
+    }
+
     pub fn on_param_change(&mut self, subscriber: NodeId, param: NodeId) {
         self.subscribe(ListenerSpec::on_param_change(subscriber, param));
     }
@@ -611,14 +1046,135 @@ impl Engine {
         });
     }
 
+    pub fn validate_edit(&self, edit: &Edit) -> Result<(), EditError> {
+        crate::edits::validate_edit(self, edit)
+    }
+
+    /// Converts `value`, written in `unit`, to the unit in the parameter's
+    /// `SemanticsHint`. Both units must measure the same
+    /// [`golden_schema::Dimension`].
+    pub fn convert_to_param_unit(
+        &self,
+        node: NodeId,
+        value: &Value,
+        unit: &str,
+    ) -> Result<Value, EditError> {
+        let entry = self.nodes.get(&node).ok_or(EditError::UnknownNode(node))?;
+        if !matches!(entry.data, NodeData::Parameter(_)) {
+            return Err(EditError::NotAParameter(node));
+        }
+        let mismatch = |message| EditError::UnitMismatch {
+            node,
+            message,
+        };
+        let Some(param_unit) = entry.meta.semantics.unit.as_deref() else {
+            return Err(mismatch(format!("has no unit to convert {unit} to")));
+        };
+        units::convert_value(value, unit, param_unit).map_err(|err| mismatch(err.to_string()))
+    }
+
+    /// Converts the value of an external edit to its parameter's kind (see
+    /// [`Engine::coercion`]), clamps it into the parameter's constraints,
+    /// validates it and queues it for the next tick. Returns a warning for
+    /// each conversion that lost information.
+    pub fn submit_edit(
+        &mut self,
+        mut edit: Edit,
+        propagation: Propagation,
+        origin: EditOrigin,
+    ) -> Result<Vec<EditWarning>, EditError> {
+        let warning = coerce_edit(self, &mut edit, self.coercion);
+        clamp_edit(self, &mut edit);
+        self.validate_edit(&edit)?;
+        self.enqueue_edit(edit, propagation, origin);
+        Ok(warning.into_iter().collect())
+    }
+
+    /// Converts and validates a group of external edits like
+    /// [`Engine::submit_edit`] and queues them back to back, so they land in
+    /// the same tick. Nothing is queued if any edit is rejected.
+    pub fn submit_session(
+        &mut self,
+        session: &EditSession,
+        mut edits: Vec<Edit>,
+        propagation: Propagation,
+    ) -> Result<Vec<EditWarning>, EditError> {
+        let mut warnings = Vec::new();
+        for edit in &mut edits {
+            warnings.extend(coerce_edit(self, edit, self.coercion));
+            clamp_edit(self, edit);
+            self.validate_edit(edit)?;
+        }
+        for edit in edits {
+            self.enqueue_edit(edit, propagation, session.origin);
+        }
+        Ok(warnings)
+    }
+
+    /// Applies an internal edit right away, outside the tick cycle.
+    pub(crate) fn apply_edit_now(&mut self, edit: Edit) {
+        self.apply_edit_requests(vec![EditRequest {
+            edit,
+            propagation: Propagation::EndOfTick,
+            origin: EditOrigin::Internal,
+        }]);
+    }
+
+    /// Replaces a node's metadata wholesale, including its identity fields.
+    pub(crate) fn replace_meta(&mut self, node: NodeId, meta: NodeMeta) {
+        let Some(node_ref) = self.nodes.get_mut(&node) else {
+            return;
+        };
+        let patch = NodeMetaPatch {
+            enabled: Some(meta.enabled),
+            label: Some(meta.label.clone()),
+            description: Some(meta.description.clone()),
+            tags: Some(meta.tags.clone()),
+            semantics: Some(meta.semantics.clone()),
+            presentation: Some(meta.presentation.clone()),
+            expression: Some(meta.expression.clone()),
+            expression_error: Some(meta.expression_error.clone()),
+        };
+        let renamed = node_ref.meta.short_name != meta.short_name;
+        let parent = node_ref.parent;
+        node_ref.meta = meta.clone();
+        Arc::make_mut(&mut self.meta_values).insert(node, meta);
+        self.expressions_stale = true;
+        if let Some(parent) = parent
+            && renamed
+        {
+            self.unindex_subtree(node);
+            self.ensure_unique_short_name(parent, node);
+            self.index_subtree(node);
+        }
+        self.emit_event(EventKind::MetaChanged {
+            node,
+            patch,
+        });
+    }
+
     pub fn tick(&mut self) {
+        self.clock.advance();
+        self.run_tick();
+    }
+
+    /// Ticks with a fixed clock step instead of the elapsed wall time.
+    pub fn tick_by(&mut self, step: Duration) {
+        self.clock.advance_by(step);
+        self.run_tick();
+    }
+
+    fn run_tick(&mut self) {
         self.time.tick += 1;
         self.time.micro = 0;
         self.time.seq = 0;
 
         let external = std::mem::take(&mut self.pending_edits);
+        self.record_requests(&external);
         self.apply_edit_requests(external);
 
+        self.advance_ramps();
+        self.evaluate_expressions();
         self.run_update_pass();
 
         self.process_pending(EnginePhase::EngineTick);
@@ -632,10 +1188,11 @@ impl Engine {
             self.time.seq = 0;
             self.process_pending(EnginePhase::EndOfTickStabilization);
         }
+        self.finish_recorded_tick();
     }
 
     fn process_pending(&mut self, phase: EnginePhase) {
-        let ready: Vec<NodeId> = self
+        let mut ready: Vec<NodeId> = self
             .inboxes
             .iter()
             .filter_map(|(id, inbox)| {
@@ -646,6 +1203,9 @@ impl Engine {
                 }
             })
             .collect();
+        // In id order rather than hash order, so runs fed the same edits
+        // process alike.
+        ready.sort_by_key(|node| node.0);
 
         for node_id in ready {
             let inbox_events = self.take_inbox(node_id);
@@ -654,8 +1214,13 @@ impl Engine {
                 edits: EditQueue::new(),
                 inbox: inbox_events,
                 time: self.time,
+                now: self.clock.now(),
+                delta: self.clock.delta(),
                 param_values: Arc::clone(&self.param_values),
                 meta_values: Arc::clone(&self.meta_values),
+                node_infos: Arc::clone(&self.node_infos),
+                paths: Arc::clone(&self.paths),
+                subscription_changes: Vec::new(),
             };
 
             if let Some(node) = self.nodes.get_mut(&node_id) {
@@ -665,7 +1230,9 @@ impl Engine {
             }
 
             let edits = ctx.edits.drain();
+            let subscription_changes = std::mem::take(&mut ctx.subscription_changes);
             drop(ctx);
+            self.apply_subscription_changes(subscription_changes);
             self.apply_edit_requests(edits);
         }
     }
@@ -686,8 +1253,13 @@ impl Engine {
                 edits: EditQueue::new(),
                 inbox: Vec::new(),
                 time: self.time,
+                now: self.clock.now(),
+                delta: self.clock.delta(),
                 param_values: Arc::clone(&self.param_values),
                 meta_values: Arc::clone(&self.meta_values),
+                node_infos: Arc::clone(&self.node_infos),
+                paths: Arc::clone(&self.paths),
+                subscription_changes: Vec::new(),
             };
 
             if let Some(node) = self.nodes.get_mut(&node_id) {
@@ -697,7 +1269,9 @@ impl Engine {
             }
 
             let edits = ctx.edits.drain();
+            let subscription_changes = std::mem::take(&mut ctx.subscription_changes);
             drop(ctx);
+            self.apply_subscription_changes(subscription_changes);
             self.apply_edit_requests(edits);
         }
     }
@@ -714,26 +1288,49 @@ impl Engine {
     }
 
     fn apply_edit_requests(&mut self, edits: Vec<EditRequest>) {
-        for request in edits {
-            let _ = request.origin;
+        for mut request in edits {
+            // Scripts run inside a behaviour but are user code, so their edits
+            // are clamped and checked like external ones and dropped when
+            // rejected.
+            if request.origin == EditOrigin::Script {
+                clamp_edit(self, &mut request.edit);
+                if self.validate_edit(&request.edit).is_err() {
+                    continue;
+                }
+            }
+            let pending = self.pending_journal_entry(&request);
+            let mut created = None;
+            let mut applied = true;
             match request.edit {
                 Edit::SetParam {
                     node,
                     value,
                 } => {
-                    if self.set_param(node, value.clone()) {
-                        self.emit_event(EventKind::ParamChanged {
-                            param: node,
-                            value,
-                        });
-                    }
+                    self.cancel_ramp(node);
+                    self.write_param(node, value);
+                }
+                Edit::PatchParam {
+                    node,
+                    patch,
+                } => {
+                    self.cancel_ramp(node);
+                    self.patch_param(node, patch);
                 }
+                Edit::RampParam {
+                    node,
+                    target,
+                    duration,
+                    easing,
+                } => self.start_ramp(node, target, duration, easing),
                 Edit::PatchMeta {
                     node,
                     patch,
                 } => {
                     if let Some(node_ref) = self.nodes.get_mut(&node) {
                         apply_patch(&mut node_ref.meta, &patch);
+                        if patch.expression.is_some() {
+                            self.expressions_stale = true;
+                        }
                         Arc::make_mut(&mut self.meta_values).insert(node, node_ref.meta.clone());
                         self.emit_event(EventKind::MetaChanged {
                             node,
@@ -747,9 +1344,53 @@ impl Engine {
                     label,
                     execution,
                 } => {
-                    let _ =
+                    created =
                         self.instantiate_child_from_manager(manager, node_type, label, execution);
                 }
+                Edit::CreateNode {
+                    parent,
+                    node_type,
+                    label,
+                    execution,
+                } => {
+                    created = self.create_child_of_type(parent, node_type, &label, execution);
+                }
+                Edit::MoveNode {
+                    node,
+                    new_parent,
+                    index,
+                } => {
+                    applied = self.move_node(node, new_parent, index);
+                }
+                Edit::DeleteNode {
+                    node,
+                } => {
+                    applied = self.delete_node(node);
+                }
+                Edit::ImportProject {
+                    root,
+                    project,
+                } => {
+                    let report = import_project(self, root, &project);
+                    applied = report.is_ok();
+                    self.import_report = report.ok();
+                }
+                Edit::InsertSubtree {
+                    parent,
+                    root,
+                } => match insert_subtree(self, parent, &root) {
+                    Ok((node, report)) => {
+                        created = Some(node);
+                        self.import_report = Some(report);
+                    }
+                    Err(_) => {
+                        applied = false;
+                        self.import_report = None;
+                    }
+                },
+            }
+            if applied && let Some(pending) = pending {
+                self.commit_journal_entry(pending, created);
             }
 
             if matches!(request.propagation, Propagation::Immediate) {
@@ -758,25 +1399,202 @@ impl Engine {
         }
     }
 
-    fn set_param(&mut self, node: NodeId, value: Value) -> bool {
+    /// What the last applied [`Edit::ImportProject`] or [`Edit::InsertSubtree`]
+    /// skipped or rejected, taken once; `None` if it failed.
+    pub fn take_import_report(&mut self) -> Option<ImportReport> {
+        self.import_report.take()
+    }
+
+    fn write_param(&mut self, node: NodeId, value: Value) {
+        if let Some(value) = self.set_param(node, value) {
+            self.emit_event(EventKind::ParamChanged {
+                param: node,
+                value,
+            });
+        }
+    }
+
+    fn patch_param(&mut self, node: NodeId, patch: ValuePatch) {
+        let Some(mut value) = self.param_values.get(&node).cloned() else {
+            return;
+        };
+        if patch.apply(&mut value).is_err() {
+            return;
+        }
+        if self.set_param(node, value).is_some() {
+            self.emit_event(EventKind::ParamPatched {
+                param: node,
+                patch,
+            });
+        }
+    }
+
+    /// Starts moving a parameter towards `target`, replacing any running ramp.
+    ///
+    /// Triggers fire at the start; other values that cannot be interpolated,
+    /// such as strings, switch when the ramp completes.
+    fn start_ramp(&mut self, node: NodeId, target: Value, duration: Duration, easing: Easing) {
+        self.cancel_ramp(node);
+        let Some(from) = self.param_values.get(&node).cloned() else {
+            return;
+        };
+        if duration.is_zero() || matches!(target, Value::Trigger) {
+            self.write_param(node, target);
+            self.emit_event(EventKind::RampFinished {
+                param: node,
+            });
+            return;
+        }
+        self.ramps.push(Ramp {
+            node,
+            from,
+            to: target,
+            start: self.clock.now(),
+            duration,
+            easing,
+        });
+    }
+
+    fn cancel_ramp(&mut self, node: NodeId) {
+        let Some(index) = self.ramps.iter().position(|ramp| ramp.node == node) else {
+            return;
+        };
+        self.ramps.remove(index);
+        self.emit_event(EventKind::RampCancelled {
+            param: node,
+        });
+    }
+
+    /// True while a ramp is driving the parameter.
+    pub fn is_ramping(&self, node: NodeId) -> bool {
+        self.ramps.iter().any(|ramp| ramp.node == node)
+    }
+
+    fn advance_ramps(&mut self) {
+        let now = self.clock.now();
+        for ramp in std::mem::take(&mut self.ramps) {
+            self.write_param(ramp.node, ramp.value_at(now));
+            if ramp.is_done(now) {
+                self.emit_event(EventKind::RampFinished {
+                    param: ramp.node,
+                });
+            } else {
+                self.ramps.push(ramp);
+            }
+        }
+    }
+
+    /// True while the parameter's value comes from an expression that binds.
+    pub fn is_expression_driven(&self, node: NodeId) -> bool {
+        self.expressions.iter().any(|expression| expression.node == node)
+    }
+
+    /// Recomputes expression-driven parameters whose inputs changed since the
+    /// last tick, and time-based ones on every tick.
+    ///
+    /// Expressions run in dependency order, so a chain of expressions settles
+    /// within one tick.
+    fn evaluate_expressions(&mut self) {
+        if self.expressions_stale {
+            self.rebind_expressions();
+        }
+        let now = self.clock.now();
+        let delta = self.clock.delta();
+        let mut expressions = std::mem::take(&mut self.expressions);
+        for expression in &mut expressions {
+            let Some(current) = self.param_values.get(&expression.node).cloned() else {
+                continue;
+            };
+            let values = &self.param_values;
+            match expression.evaluate(now, delta, &current, |node| values.get(&node).cloned()) {
+                Some(Ok(value)) => {
+                    self.set_expression_error(expression.node, None);
+                    self.write_param(expression.node, value);
+                }
+                Some(Err(err)) => self.set_expression_error(expression.node, Some(err)),
+                None => {}
+            }
+        }
+        self.expressions = expressions;
+    }
+
+    /// Parses every parameter's expression and resolves its paths again.
+    ///
+    /// Expressions that fail to bind or sit on a dependency cycle are left
+    /// out, with the reason in their `expression_error`.
+    fn rebind_expressions(&mut self) {
+        self.expressions_stale = false;
+        let candidates: Vec<(NodeId, Option<String>)> = self
+            .nodes
+            .iter()
+            .filter(|(_, node)| {
+                matches!(node.data, NodeData::Parameter(_))
+                    && (node.meta.expression.is_some() || node.meta.expression_error.is_some())
+            })
+            .map(|(id, node)| (id, node.meta.expression.clone()))
+            .collect();
+
+        let mut bound = Vec::new();
+        let mut errors = Vec::new();
+        for (node, source) in candidates {
+            let (Some(source), Some(current)) = (source, self.param_values.get(&node)) else {
+                errors.push((node, None));
+                continue;
+            };
+            match BoundExpression::bind(node, &source, current, |path| self.resolve_path(path)) {
+                Ok(expression) => bound.push(expression),
+                Err(err) => errors.push((node, Some(err))),
+            }
+        }
+
+        let (ordered, cyclic) = expressions::order(bound);
+        for (expression, cycle) in cyclic {
+            let path: Vec<&str> =
+                cycle.iter().map(|node| self.path_of(*node).unwrap_or("?")).collect();
+            let message = format!("dependency cycle: {}", path.join(" -> "));
+            errors.push((expression.node, Some(message)));
+        }
+        self.expressions = ordered;
+        for (node, error) in errors {
+            self.set_expression_error(node, error);
+        }
+    }
+
+    fn set_expression_error(&mut self, node: NodeId, error: Option<String>) {
         let Some(node_ref) = self.nodes.get_mut(&node) else {
-            return false;
+            return;
         };
+        if node_ref.meta.expression_error == error {
+            return;
+        }
+        node_ref.meta.expression_error = error.clone();
+        Arc::make_mut(&mut self.meta_values).insert(node, node_ref.meta.clone());
+        self.emit_event(EventKind::MetaChanged {
+            node,
+            patch: NodeMetaPatch {
+                expression_error: Some(error),
+                ..NodeMetaPatch::default()
+            },
+        });
+    }
+
+    /// Stores a parameter value, returning it when it counts as a change.
+    fn set_param(&mut self, node: NodeId, value: Value) -> Option<Value> {
+        let node_ref = self.nodes.get_mut(&node)?;
         let NodeData::Parameter(param) = &mut node_ref.data else {
-            return false;
+            return None;
         };
-
         let changed = match param.change {
             golden_schema::ChangePolicy::Always => true,
             golden_schema::ChangePolicy::ValueChange => param.value != value,
         };
 
-        if changed {
-            param.value = value;
-            Arc::make_mut(&mut self.param_values).insert(node, param.value.clone());
+        if !changed {
+            return None;
         }
-
-        changed
+        param.value = value.clone();
+        Arc::make_mut(&mut self.param_values).insert(node, value.clone());
+        Some(value)
     }
 
     fn emit_event(&mut self, kind: EventKind) {
@@ -789,6 +1607,7 @@ impl Engine {
             kind,
         };
         self.time.seq += 1;
+        self.record_event(&event);
         self.event_log.push_back(event.clone());
         const MAX_EVENT_LOG: usize = 4096;
         if self.event_log.len() > MAX_EVENT_LOG {
@@ -844,6 +1663,10 @@ fn event_targets(kind: &EventKind) -> Vec<NodeId> {
         EventKind::ParamChanged {
             param,
             ..
+        }
+        | EventKind::ParamPatched {
+            param,
+            ..
         } => vec![*param],
         EventKind::ChildAdded {
             parent,
@@ -877,6 +1700,12 @@ fn event_targets(kind: &EventKind) -> Vec<NodeId> {
             node,
             ..
         } => vec![*node],
+        EventKind::RampFinished {
+            param,
+        }
+        | EventKind::RampCancelled {
+            param,
+        } => vec![*param],
     }
 }
 
@@ -885,6 +1714,10 @@ fn event_bubble_source(kind: &EventKind) -> Option<NodeId> {
         EventKind::ParamChanged {
             param,
             ..
+        }
+        | EventKind::ParamPatched {
+            param,
+            ..
         } => Some(*param),
         EventKind::MetaChanged {
             node,
@@ -916,6 +1749,12 @@ fn event_bubble_source(kind: &EventKind) -> Option<NodeId> {
         EventKind::NodeDeleted {
             node,
         } => Some(*node),
+        EventKind::RampFinished {
+            param,
+        }
+        | EventKind::RampCancelled {
+            param,
+        } => Some(*param),
     }
 }
 
@@ -923,7 +1762,7 @@ fn matches_filter(filter: &EventFilter, event: &Event, nodes: &NodeStore) -> boo
     match filter {
         EventFilter::Node(node_id) => event_targets(&event.kind).contains(node_id),
         EventFilter::Param(node_id) => {
-            matches!(&event.kind, EventKind::ParamChanged { param, .. } if param == node_id)
+            matches!(&event.kind, EventKind::ParamChanged { param, .. } | EventKind::ParamPatched { param, .. } if param == node_id)
         }
         EventFilter::Subtree {
             root,
@@ -936,7 +1775,7 @@ fn matches_filter(filter: &EventFilter, event: &Event, nodes: &NodeStore) -> boo
         EventFilter::ParamChanged {
             param,
         } => {
-            matches!(&event.kind, EventKind::ParamChanged { param: actual, .. } if param.is_none_or(|expected| expected == *actual))
+            matches!(&event.kind, EventKind::ParamChanged { param: actual, .. } | EventKind::ParamPatched { param: actual, .. } if param.is_none_or(|expected| expected == *actual))
         }
         EventFilter::ChildAdded {
             parent,
@@ -1,9 +1,16 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::edits::{Edit, EditOrigin, EditQueue, Propagation};
+use crate::engine::Easing;
+use crate::events::routing::subscriptions::ListenerSpec;
 use crate::graph::node::NodeExecution;
-use golden_schema::{Event, EventTime, NodeId, NodeMeta, NodeMetaPatch, Value};
+use crate::graph::paths::PathIndex;
+use crate::graph::queries::{NodeInfo, NodeQuery};
 use golden_schema::NodeTypeId;
+use golden_schema::{
+    Event, EventTime, NodeId, NodeMeta, NodeMetaPatch, ReferenceValue, Value, ValuePatch,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EnginePhase {
@@ -12,13 +19,27 @@ pub enum EnginePhase {
     FlushImmediate,
 }
 
+/// A subscription request made from a behaviour, applied after the callback.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SubscriptionChange {
+    Subscribe(ListenerSpec),
+    Unsubscribe(ListenerSpec),
+}
+
 pub struct ProcessCtx {
     pub phase: EnginePhase,
     pub edits: EditQueue,
     pub inbox: Vec<Event>,
     pub time: EventTime,
+    /// Engine clock at the start of this tick.
+    pub now: Duration,
+    /// Clock step since the previous tick.
+    pub delta: Duration,
     pub param_values: Arc<std::collections::HashMap<NodeId, Value>>,
     pub meta_values: Arc<std::collections::HashMap<NodeId, NodeMeta>>,
+    pub node_infos: Arc<std::collections::HashMap<NodeId, NodeInfo>>,
+    pub paths: Arc<PathIndex>,
+    pub subscription_changes: Vec<SubscriptionChange>,
 }
 
 impl ProcessCtx {
@@ -45,6 +66,33 @@ impl ProcessCtx {
         self.set_param_with(node, value, Propagation::NextTick);
     }
 
+    /// Changes one element of a collection parameter at the end of this tick.
+    pub fn patch_param(&mut self, node: NodeId, patch: ValuePatch) {
+        self.edits.push(
+            Edit::PatchParam {
+                node,
+                patch,
+            },
+            Propagation::EndOfTick,
+            EditOrigin::Internal,
+        );
+    }
+
+    /// Moves a parameter to `target` over `duration`, starting at the end of
+    /// this tick. A later write to the parameter cancels the ramp.
+    pub fn ramp_param(&mut self, node: NodeId, target: Value, duration: Duration, easing: Easing) {
+        self.edits.push(
+            Edit::RampParam {
+                node,
+                target,
+                duration,
+                easing,
+            },
+            Propagation::EndOfTick,
+            EditOrigin::Internal,
+        );
+    }
+
     pub fn patch_meta(&mut self, node: NodeId, patch: NodeMetaPatch) {
         self.edits.push(
             Edit::PatchMeta {
@@ -98,4 +146,36 @@ impl ProcessCtx {
     pub fn read_meta(&self, node: NodeId) -> Option<&NodeMeta> {
         self.meta_values.get(&node)
     }
+
+    pub fn resolve_path(&self, path: &str) -> Option<NodeId> {
+        self.paths.resolve(path)
+    }
+
+    pub fn path_of(&self, node: NodeId) -> Option<&str> {
+        self.paths.path_of(node)
+    }
+
+    /// Finds the node a reference points at, trusting `cached_id` only while
+    /// it still carries the referenced uuid.
+    pub fn resolve_reference(&self, reference: &ReferenceValue) -> Option<NodeId> {
+        if let Some(id) = reference.cached_id
+            && self.meta_values.get(&id).is_some_and(|meta| meta.uuid == reference.uuid)
+        {
+            return Some(id);
+        }
+        self.meta_values.iter().find(|(_, meta)| meta.uuid == reference.uuid).map(|(id, _)| *id)
+    }
+
+    pub fn subscribe(&mut self, spec: ListenerSpec) {
+        self.subscription_changes.push(SubscriptionChange::Subscribe(spec));
+    }
+
+    pub fn unsubscribe(&mut self, spec: ListenerSpec) {
+        self.subscription_changes.push(SubscriptionChange::Unsubscribe(spec));
+    }
+
+    /// Runs a query against the graph as of the start of this callback.
+    pub fn query<'a>(&'a self, query: &'a NodeQuery) -> impl Iterator<Item = NodeId> + 'a {
+        query.run(self)
+    }
 }
@@ -0,0 +1,387 @@
+use std::time::Duration;
+
+use golden_schema::{ColorRgba, FloatRange, IntRange, NodeId, Quat, Value, Vec2, Vec3, Vec4};
+
+/// Shape of a ramp over its normalized time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps `t` in `0..=1` to eased progress in `0..=1`.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// A parameter moving towards a target value.
+pub struct Ramp {
+    pub node: NodeId,
+    pub from: Value,
+    pub to: Value,
+    pub start: Duration,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Ramp {
+    /// Eased progress at engine time `now`.
+    pub fn progress(&self, now: Duration) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_sub(self.start);
+        self.easing.apply(elapsed.as_secs_f64() / self.duration.as_secs_f64())
+    }
+
+    pub fn is_done(&self, now: Duration) -> bool {
+        now.saturating_sub(self.start) >= self.duration
+    }
+
+    /// Value at engine time `now`. Values that cannot be interpolated hold
+    /// `from` until the ramp completes.
+    pub fn value_at(&self, now: Duration) -> Value {
+        if self.is_done(now) {
+            return self.to.clone();
+        }
+        interpolate(&self.from, &self.to, self.progress(now)).unwrap_or_else(|| self.from.clone())
+    }
+}
+
+/// Blends two values of the same numeric kind; `None` for kinds that switch
+/// instead of blending.
+pub fn interpolate(from: &Value, to: &Value, t: f64) -> Option<Value> {
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+    match (from, to) {
+        (Value::Float(a), Value::Float(b)) => Some(Value::Float(lerp(*a, *b))),
+        (Value::Int(a), Value::Int(b)) => {
+            Some(Value::Int(lerp(*a as f64, *b as f64).round() as i64))
+        }
+        (Value::Vec2(a), Value::Vec2(b)) => Some(Value::Vec2(Vec2 {
+            x: lerp(a.x, b.x),
+            y: lerp(a.y, b.y),
+        })),
+        (Value::Vec3(a), Value::Vec3(b)) => Some(Value::Vec3(Vec3 {
+            x: lerp(a.x, b.x),
+            y: lerp(a.y, b.y),
+            z: lerp(a.z, b.z),
+        })),
+        (Value::ColorRgba(a), Value::ColorRgba(b)) => Some(Value::ColorRgba(ColorRgba {
+            r: lerp(a.r, b.r),
+            g: lerp(a.g, b.g),
+            b: lerp(a.b, b.b),
+            a: lerp(a.a, b.a),
+        })),
+        (Value::Vec4(a), Value::Vec4(b)) => Some(Value::Vec4(Vec4 {
+            x: lerp(a.x, b.x),
+            y: lerp(a.y, b.y),
+            z: lerp(a.z, b.z),
+            w: lerp(a.w, b.w),
+        })),
+        (Value::Quat(a), Value::Quat(b)) => Some(Value::Quat(nlerp(*a, *b, t))),
+        (Value::FloatRange(a), Value::FloatRange(b)) => Some(Value::FloatRange(FloatRange {
+            min: lerp(a.min, b.min),
+            max: lerp(a.max, b.max),
+        })),
+        (Value::IntRange(a), Value::IntRange(b)) => Some(Value::IntRange(IntRange {
+            min: lerp(a.min as f64, b.min as f64).round() as i64,
+            max: lerp(a.max as f64, b.max as f64).round() as i64,
+        })),
+        (Value::Duration(a), Value::Duration(b)) => {
+            Some(Value::Duration(Duration::from_secs_f64(lerp(a.as_secs_f64(), b.as_secs_f64()))))
+        }
+        (Value::FloatArray(a), Value::FloatArray(b)) if a.len() == b.len() => {
+            Some(Value::FloatArray(a.iter().zip(b).map(|(a, b)| lerp(*a, *b)).collect()))
+        }
+        _ => None,
+    }
+}
+
+/// Normalized linear blend along the shorter arc; close enough to slerp for
+/// ramps and stable for nearly equal rotations.
+fn nlerp(a: Quat, b: Quat, t: f64) -> Quat {
+    let dot = a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w;
+    let sign = if dot < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let lerp = |a: f64, b: f64| a + (sign * b - a) * t;
+    let q = [lerp(a.x, b.x), lerp(a.y, b.y), lerp(a.z, b.z), lerp(a.w, b.w)];
+    let len = q.iter().map(|c| c * c).sum::<f64>().sqrt();
+    if len == 0.0 {
+        return a;
+    }
+    Quat {
+        x: q[0] / len,
+        y: q[1] / len,
+        z: q[2] / len,
+        w: q[3] / len,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::{Event, EventKind};
+
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::engine::Engine;
+    use crate::graph::node::NodeData;
+
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(25);
+
+    fn value(engine: &Engine, node: NodeId) -> Value {
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    /// Queues `edit` and lets it land on a tick that does not move the clock.
+    fn submit(engine: &mut Engine, edit: Edit) -> Vec<Event> {
+        let before = engine.time;
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick_by(Duration::ZERO);
+        engine.events_since(before)
+    }
+
+    fn ramp(node: NodeId, target: Value, millis: u64) -> Edit {
+        Edit::RampParam {
+            node,
+            target,
+            duration: Duration::from_millis(millis),
+            easing: Easing::Linear,
+        }
+    }
+
+    fn ramp_events(events: &[Event]) -> Vec<&EventKind> {
+        events
+            .iter()
+            .map(|event| &event.kind)
+            .filter(|kind| {
+                matches!(kind, EventKind::RampFinished { .. } | EventKind::RampCancelled { .. })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn easing_curves_meet_at_the_ends() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.apply(-1.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(2.0), 1.0, "{easing:?}");
+        }
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+        assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+    }
+
+    #[test]
+    fn interpolates_numeric_kinds() {
+        assert_eq!(
+            interpolate(&Value::Float(1.0), &Value::Float(3.0), 0.5),
+            Some(Value::Float(2.0))
+        );
+        assert_eq!(interpolate(&Value::Int(0), &Value::Int(3), 0.5), Some(Value::Int(2)));
+        let black = ColorRgba {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        let red = ColorRgba {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        };
+        assert_eq!(
+            interpolate(&Value::ColorRgba(black), &Value::ColorRgba(red), 0.25),
+            Some(Value::ColorRgba(ColorRgba {
+                r: 0.25,
+                g: 0.0,
+                b: 0.0,
+                a: 0.75,
+            }))
+        );
+        assert_eq!(
+            interpolate(
+                &Value::Duration(Duration::from_secs(1)),
+                &Value::Duration(Duration::from_secs(2)),
+                0.5
+            ),
+            Some(Value::Duration(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            interpolate(&Value::FloatArray(vec![0.0, 2.0]), &Value::FloatArray(vec![1.0]), 0.5),
+            None
+        );
+        assert_eq!(interpolate(&Value::Float(0.0), &Value::Int(1), 0.5), None);
+        assert_eq!(interpolate(&Value::Bool(false), &Value::Bool(true), 0.5), None);
+    }
+
+    #[test]
+    fn quaternions_blend_along_the_shorter_arc() {
+        let identity = Quat {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        };
+        let half_turn_z = Quat {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+            w: 0.0,
+        };
+        let Some(Value::Quat(q)) =
+            interpolate(&Value::Quat(identity), &Value::Quat(half_turn_z), 0.5)
+        else {
+            panic!("quaternions interpolate");
+        };
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((q.z - half).abs() < 1e-12 && (q.w - half).abs() < 1e-12, "{q:?}");
+
+        // The same rotation with every sign flipped is not a detour.
+        let flipped = Quat {
+            x: -0.0,
+            y: -0.0,
+            z: -0.0,
+            w: -1.0,
+        };
+        assert_eq!(nlerp(identity, flipped, 0.5), identity);
+        // Opposite quaternions cancel out; the blend keeps the start.
+        let opposite = Quat {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: -1.0,
+        };
+        assert_eq!(nlerp(identity, opposite, 0.5), identity);
+    }
+
+    #[test]
+    fn ramps_finish_on_engine_clock_ticks() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+
+        // Quarter seconds, so progress is exact in binary.
+        let step = Duration::from_millis(250);
+        let events = submit(&mut engine, ramp(gain, Value::Float(1.0), 1000));
+        assert!(ramp_events(&events).is_empty());
+        assert!(engine.is_ramping(gain));
+
+        let mut before = engine.time;
+        for expected in [0.25, 0.5, 0.75] {
+            engine.tick_by(step);
+            assert_eq!(value(&engine, gain), Value::Float(expected));
+            assert!(ramp_events(&engine.events_since(before)).is_empty());
+            before = engine.time;
+        }
+        engine.tick_by(step);
+        assert_eq!(value(&engine, gain), Value::Float(1.0));
+        assert!(!engine.is_ramping(gain));
+        assert_eq!(
+            ramp_events(&engine.events_since(before)),
+            vec![&EventKind::RampFinished {
+                param: gain
+            }]
+        );
+    }
+
+    #[test]
+    fn set_param_cancels_a_running_ramp() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        submit(&mut engine, ramp(gain, Value::Float(1.0), 100));
+        engine.tick_by(STEP);
+
+        let edit = Edit::SetParam {
+            node: gain,
+            value: Value::Float(0.1),
+        };
+        let events = submit(&mut engine, edit);
+        assert_eq!(
+            ramp_events(&events),
+            vec![&EventKind::RampCancelled {
+                param: gain
+            }]
+        );
+        assert!(!engine.is_ramping(gain));
+        engine.tick_by(Duration::from_millis(200));
+        assert_eq!(value(&engine, gain), Value::Float(0.1));
+    }
+
+    #[test]
+    fn a_new_ramp_replaces_a_running_one_from_where_it_stands() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        submit(&mut engine, ramp(gain, Value::Float(1.0), 100));
+        engine.tick_by(Duration::from_millis(50));
+
+        let events = submit(&mut engine, ramp(gain, Value::Float(0.0), 50));
+        assert_eq!(
+            ramp_events(&events),
+            vec![&EventKind::RampCancelled {
+                param: gain
+            }]
+        );
+        engine.tick_by(STEP);
+        assert_eq!(value(&engine, gain), Value::Float(0.25));
+        engine.tick_by(STEP);
+        assert_eq!(value(&engine, gain), Value::Float(0.0));
+        assert!(!engine.is_ramping(gain));
+    }
+
+    #[test]
+    fn values_that_cannot_blend_switch_at_the_end() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let on = engine.create_child_parameter(root, "on", Value::Bool(false));
+        let name = engine.create_child_parameter(root, "name", Value::String("a".into()));
+        submit(&mut engine, ramp(on, Value::Bool(true), 100));
+        submit(&mut engine, ramp(name, Value::String("b".into()), 100));
+
+        engine.tick_by(Duration::from_millis(99));
+        assert_eq!(value(&engine, on), Value::Bool(false));
+        assert_eq!(value(&engine, name), Value::String("a".into()));
+        engine.tick_by(Duration::from_millis(1));
+        assert_eq!(value(&engine, on), Value::Bool(true));
+        assert_eq!(value(&engine, name), Value::String("b".into()));
+    }
+
+    #[test]
+    fn triggers_fire_when_the_ramp_starts() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let go = engine.create_child_parameter(root, "go", Value::Trigger);
+
+        let events = submit(&mut engine, ramp(go, Value::Trigger, 100));
+        assert!(!engine.is_ramping(go));
+        assert_eq!(
+            ramp_events(&events),
+            vec![&EventKind::RampFinished {
+                param: go
+            }]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use golden_schema::{
+    Event, EventKind, NodeId, NodeUuid, RecordedNode, RecordedRequest, RecordedTick,
+};
+
+use crate::edits::EditRequest;
+use crate::engine::Engine;
+use crate::graph::node::NodeData;
+use crate::persistence::journal::checksum;
+use crate::persistence::recording::recorded_request;
+
+/// Ticks recorded so far, and the input and output of the one in progress.
+#[derive(Default)]
+pub(crate) struct Recording {
+    ticks: Vec<RecordedTick>,
+    requests: Vec<RecordedRequest>,
+    created: Vec<RecordedNode>,
+    events: usize,
+    /// Events emitted since the previous tick, one JSON line each.
+    emitted: Vec<u8>,
+}
+
+impl Engine {
+    /// Starts or stops recording each tick's external edits, clock step and
+    /// emitted events for [`Engine::take_recording`]. Either way, anything not
+    /// yet taken is dropped.
+    pub fn set_recording(&mut self, enabled: bool) {
+        self.recording = enabled.then(Recording::default);
+    }
+
+    /// Ticks recorded since the last call, oldest first.
+    pub fn take_recording(&mut self) -> Vec<RecordedTick> {
+        self.recording
+            .as_mut()
+            .map(|recording| std::mem::take(&mut recording.ticks))
+            .unwrap_or_default()
+    }
+
+    /// Checksum of every node's path and `NodeId`, and of parameter values.
+    /// Two engines with the same fingerprint react to the same edits alike.
+    pub fn state_fingerprint(&self) -> String {
+        let mut nodes: Vec<(&str, NodeId)> =
+            self.nodes.keys().filter_map(|node| Some((self.path_of(node)?, node))).collect();
+        nodes.sort_by(|a, b| a.0.cmp(b.0));
+        let mut state = String::new();
+        for (path, node) in nodes {
+            let value = match self.nodes.get(&node).map(|entry| &entry.data) {
+                Some(NodeData::Parameter(param)) => {
+                    serde_json::to_string(&param.value).unwrap_or_default()
+                }
+                _ => String::new(),
+            };
+            let _ = writeln!(state, "{path} {} {value}", node.0);
+        }
+        checksum(state.as_bytes())
+    }
+
+    /// Gives a node the uuid it had when recorded. Uuids are not part of any
+    /// event, so no event is emitted.
+    pub(crate) fn restore_uuid(&mut self, node: NodeId, uuid: NodeUuid) {
+        let Some(entry) = self.nodes.get_mut(&node) else {
+            return;
+        };
+        entry.meta.uuid = uuid;
+        let meta = entry.meta.clone();
+        Arc::make_mut(&mut self.meta_values).insert(node, meta);
+    }
+
+    pub(crate) fn record_requests(&mut self, requests: &[EditRequest]) {
+        if let Some(recording) = &mut self.recording {
+            recording.requests.extend(requests.iter().map(recorded_request));
+        }
+    }
+
+    pub(crate) fn record_event(&mut self, event: &Event) {
+        let created = match &event.kind {
+            EventKind::NodeCreated {
+                node,
+            } => self.nodes.get(node).map(|entry| RecordedNode {
+                node: *node,
+                uuid: entry.meta.uuid,
+            }),
+            _ => None,
+        };
+        if let Some(recording) = &mut self.recording
+            && let Ok(line) = serde_json::to_vec(event)
+        {
+            recording.created.extend(created);
+            recording.events += 1;
+            recording.emitted.extend(line);
+            recording.emitted.push(b'\n');
+        }
+    }
+
+    pub(crate) fn finish_recorded_tick(&mut self) {
+        let (tick, step) = (self.time.tick, self.clock.delta());
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let tick = RecordedTick {
+            tick,
+            step,
+            requests: std::mem::take(&mut recording.requests),
+            created: std::mem::take(&mut recording.created),
+            events: std::mem::take(&mut recording.events),
+            digest: checksum(&std::mem::take(&mut recording.emitted)),
+        };
+        recording.ticks.push(tick);
+    }
+}
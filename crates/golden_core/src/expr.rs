@@ -0,0 +1,554 @@
+use core::fmt;
+
+/// A parsed arithmetic expression over named `f64` variables.
+///
+/// Supports `+ - * / % ^`, comparisons and `&& || !` (yielding `1.0` or
+/// `0.0`), `cond ? a : b`, parentheses, the constants `pi`, `tau` and `e`, and
+/// the functions listed in [`FUNCTIONS`].
+///
+/// A `/` where an operand is expected starts a node path such as
+/// `/inputs/fader1`, read as a variable named by the path. Paths may contain
+/// `-`, `.` and `/`, so subtracting from or dividing a path needs a space
+/// before the operator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+/// Longest source [`parse`] accepts, in bytes.
+pub const MAX_SOURCE_LEN: usize = 1024;
+
+/// How deeply parentheses, calls, conditionals, unary operators and `^` may
+/// nest.
+pub const MAX_DEPTH: usize = 64;
+
+/// Built-in functions and their argument counts.
+pub const FUNCTIONS: &[(&str, usize)] = &[
+    ("sin", 1),
+    ("cos", 1),
+    ("tan", 1),
+    ("asin", 1),
+    ("acos", 1),
+    ("atan", 1),
+    ("atan2", 2),
+    ("abs", 1),
+    ("sqrt", 1),
+    ("exp", 1),
+    ("ln", 1),
+    ("log10", 1),
+    ("floor", 1),
+    ("ceil", 1),
+    ("round", 1),
+    ("fract", 1),
+    ("sign", 1),
+    ("min", 2),
+    ("max", 2),
+    ("pow", 2),
+    ("clamp", 3),
+    ("lerp", 3),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// Byte offset into the source.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EvalError {
+    UnknownVariable(String),
+    UnknownFunction(String),
+    Arity {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::UnknownVariable(name) => write!(f, "unknown variable '{name}'"),
+            EvalError::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            EvalError::Arity {
+                function,
+                expected,
+                found,
+            } => write!(f, "{function} takes {expected} arguments, got {found}"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+pub fn parse(source: &str) -> Result<Expr, ParseError> {
+    let mut parser = Parser::new(source)?;
+    let expr = parser.conditional()?;
+    parser.finish()?;
+    Ok(expr)
+}
+
+/// Parses comma-separated expressions, e.g. one per vector component.
+pub fn parse_list(source: &str) -> Result<Vec<Expr>, ParseError> {
+    let mut parser = Parser::new(source)?;
+    let mut exprs = vec![parser.conditional()?];
+    while parser.eat(",") {
+        exprs.push(parser.conditional()?);
+    }
+    parser.finish()?;
+    Ok(exprs)
+}
+
+impl Expr {
+    /// Evaluates with `lookup` resolving variables other than the constants.
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<f64, EvalError> {
+        Ok(match self {
+            Expr::Number(value) => *value,
+            Expr::Var(name) => match lookup(name) {
+                Some(value) => value,
+                None => constant(name).ok_or_else(|| EvalError::UnknownVariable(name.clone()))?,
+            },
+            Expr::Unary(op, operand) => {
+                let value = operand.eval(lookup)?;
+                match op {
+                    UnaryOp::Neg => -value,
+                    UnaryOp::Not => bool_value(value == 0.0),
+                }
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let a = lhs.eval(lookup)?;
+                // Logical operators short-circuit.
+                match op {
+                    BinaryOp::And if a == 0.0 => return Ok(0.0),
+                    BinaryOp::Or if a != 0.0 => return Ok(1.0),
+                    _ => {}
+                }
+                let b = rhs.eval(lookup)?;
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Rem => a % b,
+                    BinaryOp::Pow => a.powf(b),
+                    BinaryOp::Lt => bool_value(a < b),
+                    BinaryOp::Le => bool_value(a <= b),
+                    BinaryOp::Gt => bool_value(a > b),
+                    BinaryOp::Ge => bool_value(a >= b),
+                    BinaryOp::Eq => bool_value(a == b),
+                    BinaryOp::Ne => bool_value(a != b),
+                    BinaryOp::And | BinaryOp::Or => bool_value(b != 0.0),
+                }
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                if condition.eval(lookup)? != 0.0 {
+                    then.eval(lookup)?
+                } else {
+                    otherwise.eval(lookup)?
+                }
+            }
+            Expr::Call(name, args) => {
+                let values =
+                    args.iter().map(|arg| arg.eval(lookup)).collect::<Result<Vec<_>, _>>()?;
+                call(name, &values)?
+            }
+        })
+    }
+
+    /// Names of the variables the expression reads, without duplicates and
+    /// excluding the built-in constants.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_variables(&mut out);
+        out
+    }
+
+    fn collect_variables<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Var(name) => {
+                if constant(name).is_none() && !out.contains(&name.as_str()) {
+                    out.push(name);
+                }
+            }
+            Expr::Unary(_, operand) => operand.collect_variables(out),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.collect_variables(out);
+                rhs.collect_variables(out);
+            }
+            Expr::Conditional(condition, then, otherwise) => {
+                condition.collect_variables(out);
+                then.collect_variables(out);
+                otherwise.collect_variables(out);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|arg| arg.collect_variables(out)),
+        }
+    }
+}
+
+fn bool_value(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "tau" => Some(std::f64::consts::TAU),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+fn call(name: &str, args: &[f64]) -> Result<f64, EvalError> {
+    let Some(&(_, arity)) = FUNCTIONS.iter().find(|(function, _)| *function == name) else {
+        return Err(EvalError::UnknownFunction(name.to_string()));
+    };
+    if args.len() != arity {
+        return Err(EvalError::Arity {
+            function: name.to_string(),
+            expected: arity,
+            found: args.len(),
+        });
+    }
+    Ok(match (name, args) {
+        ("sin", [x]) => x.sin(),
+        ("cos", [x]) => x.cos(),
+        ("tan", [x]) => x.tan(),
+        ("asin", [x]) => x.asin(),
+        ("acos", [x]) => x.acos(),
+        ("atan", [x]) => x.atan(),
+        ("atan2", [y, x]) => y.atan2(*x),
+        ("abs", [x]) => x.abs(),
+        ("sqrt", [x]) => x.sqrt(),
+        ("exp", [x]) => x.exp(),
+        ("ln", [x]) => x.ln(),
+        ("log10", [x]) => x.log10(),
+        ("floor", [x]) => x.floor(),
+        ("ceil", [x]) => x.ceil(),
+        ("round", [x]) => x.round(),
+        ("fract", [x]) => x.fract(),
+        ("sign", [x]) => {
+            if *x == 0.0 {
+                0.0
+            } else {
+                x.signum()
+            }
+        }
+        ("min", [a, b]) => a.min(*b),
+        ("max", [a, b]) => a.max(*b),
+        ("pow", [a, b]) => a.powf(*b),
+        ("clamp", [x, lo, hi]) => x.max(*lo).min(*hi),
+        ("lerp", [a, b, t]) => a + (b - a) * t,
+        _ => unreachable!("FUNCTIONS and call disagree on {name}"),
+    })
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos: usize,
+    /// Nesting of the rules that recurse on their own input.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Result<Self, ParseError> {
+        if source.len() > MAX_SOURCE_LEN {
+            return Err(ParseError {
+                position: MAX_SOURCE_LEN,
+                message: format!("expression longer than {MAX_SOURCE_LEN} bytes"),
+            });
+        }
+        Ok(Self {
+            source,
+            pos: 0,
+            depth: 0,
+        })
+    }
+
+    /// Runs `rule` one nesting level deeper, failing past [`MAX_DEPTH`] rather
+    /// than overflowing the stack.
+    fn nested<T>(
+        &mut self,
+        rule: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+        self.depth += 1;
+        let result = rule(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.source[self.pos..]
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.pos < self.source.len() {
+            return Err(self.error("unexpected input"));
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.source.len() - trimmed.len();
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{token}'")))
+        }
+    }
+
+    fn conditional(&mut self) -> Result<Expr, ParseError> {
+        self.nested(Self::conditional_inner)
+    }
+
+    fn conditional_inner(&mut self) -> Result<Expr, ParseError> {
+        let condition = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.conditional()?;
+        self.expect(":")?;
+        let otherwise = self.conditional()?;
+        Ok(Expr::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)))
+    }
+
+    /// Precedence climbing over the binary operators, lowest level first.
+    fn binary(&mut self, level: usize) -> Result<Expr, ParseError> {
+        const LEVELS: &[&[(&str, BinaryOp)]] = &[
+            &[("||", BinaryOp::Or)],
+            &[("&&", BinaryOp::And)],
+            &[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne)],
+            &[("<=", BinaryOp::Le), (">=", BinaryOp::Ge), ("<", BinaryOp::Lt), (">", BinaryOp::Gt)],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[("*", BinaryOp::Mul), ("/", BinaryOp::Div), ("%", BinaryOp::Rem)],
+        ];
+        let Some(operators) = LEVELS.get(level) else {
+            return self.power();
+        };
+
+        let mut lhs = self.binary(level + 1)?;
+        'outer: loop {
+            for (token, op) in operators.iter() {
+                if self.eat(token) {
+                    let rhs = self.binary(level + 1)?;
+                    lhs = Expr::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    /// `^` binds tighter than unary minus on its left and is right-associative.
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.unary()?;
+        if self.eat("^") {
+            let exponent = self.nested(Self::power)?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        self.nested(Self::unary_inner)
+    }
+
+    fn unary_inner(&mut self) -> Result<Expr, ParseError> {
+        if self.eat("-") {
+            return Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.power()?)));
+        }
+        if self.eat("+") {
+            return self.power();
+        }
+        if self.rest().trim_start().starts_with("!=") {
+            return Err(self.error("unexpected '!='"));
+        }
+        if self.eat("!") {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.power()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        if self.eat("(") {
+            let inner = self.conditional()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+
+        let rest = self.rest();
+        let Some(first) = rest.chars().next() else {
+            return Err(self.error("unexpected end of expression"));
+        };
+
+        if first.is_ascii_digit() || first == '.' {
+            let len = number_length(rest);
+            let literal = &rest[..len];
+            let value = literal.parse().map_err(|_| self.error("invalid number"))?;
+            self.pos += len;
+            return Ok(Expr::Number(value));
+        }
+
+        if first == '/' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/')))
+                .unwrap_or(rest.len());
+            let path = rest[..len].to_string();
+            self.pos += len;
+            return Ok(Expr::Var(path));
+        }
+
+        if first.is_ascii_alphabetic() || first == '_' {
+            let len =
+                rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let name = rest[..len].to_string();
+            self.pos += len;
+            if self.eat("(") {
+                let mut args = Vec::new();
+                if !self.eat(")") {
+                    loop {
+                        args.push(self.conditional()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                return Ok(Expr::Call(name, args));
+            }
+            return Ok(Expr::Var(name));
+        }
+
+        Err(self.error(&format!("unexpected '{first}'")))
+    }
+}
+
+fn number_length(source: &str) -> usize {
+    let bytes = source.as_bytes();
+    let mut len = 0;
+    while len < bytes.len() && (bytes[len].is_ascii_digit() || bytes[len] == b'.') {
+        len += 1;
+    }
+    if len < bytes.len() && matches!(bytes[len], b'e' | b'E') {
+        let mut exp = len + 1;
+        if exp < bytes.len() && matches!(bytes[exp], b'+' | b'-') {
+            exp += 1;
+        }
+        if exp < bytes.len() && bytes[exp].is_ascii_digit() {
+            while exp < bytes.len() && bytes[exp].is_ascii_digit() {
+                exp += 1;
+            }
+            len = exp;
+        }
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nesting_up_to_the_limit() {
+        // Each level of parentheses nests a conditional and a unary.
+        let depth = MAX_DEPTH / 2 - 1;
+        let source = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(parse(&source).unwrap(), Expr::Number(1.0));
+    }
+
+    #[test]
+    fn rejects_deep_nesting_without_overflowing() {
+        for source in [
+            format!("{}1{}", "(".repeat(300), ")".repeat(300)),
+            format!("{}1", "-".repeat(300)),
+            format!("{}1", "!".repeat(300)),
+            format!("{}1", "2^".repeat(300)),
+            format!("{}1{}", "min(1,".repeat(100), ")".repeat(100)),
+            format!("{}1", "1?1:".repeat(200)),
+        ] {
+            let err = parse(&source).unwrap_err();
+            assert_eq!(err.message, "expression nested too deeply", "{source}");
+        }
+        assert!(parse_list(&format!("1,{}1{}", "(".repeat(300), ")".repeat(300))).is_err());
+    }
+
+    #[test]
+    fn rejects_long_sources() {
+        let source = vec!["1"; MAX_SOURCE_LEN / 2 + 1].join("+");
+        let err = parse(&source).unwrap_err();
+        assert_eq!(err.position, MAX_SOURCE_LEN);
+        assert!(parse_list(&source).is_err());
+
+        let source = vec!["1"; MAX_SOURCE_LEN / 2].join("+");
+        assert_eq!(parse(&source).unwrap().eval(&|_| None), Ok((MAX_SOURCE_LEN / 2) as f64));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod hierarchy;
 pub mod node;
+pub mod paths;
 pub mod queries;
@@ -1,8 +1,9 @@
+use core::fmt;
 use std::collections::HashMap;
 
 use crate::data::{ContainerData, CustomData, FolderHandle, ParameterData, ParameterHandle};
 use crate::engine::ProcessCtx;
-use crate::schema::NodeSchema;
+use crate::schema::{GoldenNodeDecl, NodeSchema};
 use golden_schema::{NodeId, NodeMeta, NodeMetaPatch, NodeTypeId, Value};
 
 pub struct NodeBinding {
@@ -31,7 +32,30 @@ impl NodeBinding {
     }
 }
 
-pub type NodeBehaviourFactory = Box<dyn Fn(NodeBinding) -> Box<dyn NodeBehaviour> + Send + Sync>;
+/// A declaration of the node's schema with no child bound to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingError {
+    MissingParam(String),
+    MissingFolder(String),
+    MissingChild(String),
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::MissingParam(decl) => write!(f, "missing param '{decl}'"),
+            BindingError::MissingFolder(decl) => write!(f, "missing folder '{decl}'"),
+            BindingError::MissingChild(decl) => write!(f, "missing child '{decl}'"),
+        }
+    }
+}
+
+impl std::error::Error for BindingError {}
+
+/// Builds a manager-created node's behaviour; fails if a declaration of the
+/// node's schema has no child bound to it.
+pub type NodeBehaviourFactory =
+    Box<dyn Fn(NodeBinding) -> Result<Box<dyn NodeBehaviour>, BindingError> + Send + Sync>;
 
 pub struct ManagerNodeRegistration {
     pub schema: NodeSchema,
@@ -52,7 +76,7 @@ impl ManagerData {
 
     pub fn register_node_type<F>(&mut self, node_type: NodeTypeId, schema: NodeSchema, factory: F)
     where
-        F: Fn(NodeBinding) -> Box<dyn NodeBehaviour> + Send + Sync + 'static,
+        F: Fn(NodeBinding) -> Result<Box<dyn NodeBehaviour>, BindingError> + Send + Sync + 'static,
     {
         self.registrations.insert(
             node_type,
@@ -63,15 +87,32 @@ impl ManagerData {
         );
     }
 
+    /// Registers a `GoldenNode` type with its schema, building instances
+    /// with its generated `from_binding` and callback dispatch.
+    pub fn register<T>(&mut self)
+    where
+        T: GoldenNodeDecl + 'static,
+    {
+        self.register_node_type(T::node_type(), T::schema(), |binding| {
+            T::from_binding(&binding).map(T::into_behaviour)
+        });
+    }
+
     pub fn registration_for(&self, node_type: &NodeTypeId) -> Option<&ManagerNodeRegistration> {
         self.registrations.get(node_type)
     }
 
+    /// Schemas of every registered node type, e.g. to fill a `SchemaRegistry`.
+    pub fn node_types(&self) -> impl Iterator<Item = (&NodeTypeId, &NodeSchema)> {
+        self.registrations.iter().map(|(node_type, registration)| (node_type, &registration.schema))
+    }
+
+    /// `None` if the type is not registered with this manager.
     pub fn create_behaviour(
         &self,
         node_type: &NodeTypeId,
         binding: NodeBinding,
-    ) -> Option<Box<dyn NodeBehaviour>> {
+    ) -> Option<Result<Box<dyn NodeBehaviour>, BindingError>> {
         self.registrations
             .get(node_type)
             .map(|registration| (registration.behaviour_factory)(binding))
@@ -112,6 +153,28 @@ pub trait NodeBehaviour: Send {
     fn update(&mut self, _ctx: &mut ProcessCtx) {}
 }
 
+/// Runs a [`NodeReactive`] node as a behaviour.
+pub struct Reactive<T>(pub T);
+
+impl<T: NodeReactive + Send> NodeBehaviour for Reactive<T> {
+    fn process(&mut self, ctx: &mut ProcessCtx) {
+        NodeReactive::process(&mut self.0, ctx);
+    }
+}
+
+/// Runs a [`NodeContinuous`] node as a behaviour, updating it every tick.
+pub struct Continuous<T>(pub T);
+
+impl<T: NodeContinuous + Send> NodeBehaviour for Continuous<T> {
+    fn process(&mut self, ctx: &mut ProcessCtx) {
+        NodeReactive::process(&mut self.0, ctx);
+    }
+
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        NodeContinuous::update(&mut self.0, ctx);
+    }
+}
+
 pub trait NodeReactive {
     fn process(&mut self, ctx: &mut ProcessCtx) {
         self.dispatch_inbox(ctx);
@@ -127,6 +190,14 @@ pub trait NodeReactive {
                 } => {
                     self.on_param_change(ctx, param, value);
                 }
+                golden_schema::EventKind::ParamPatched {
+                    param,
+                    ..
+                } => {
+                    if let Some(value) = ctx.read_param(param).cloned() {
+                        self.on_param_change(ctx, param, value);
+                    }
+                }
                 golden_schema::EventKind::ChildAdded {
                     parent,
                     child,
@@ -175,6 +246,16 @@ pub trait NodeReactive {
                 } => {
                     self.on_meta_changed(ctx, node, patch);
                 }
+                golden_schema::EventKind::RampFinished {
+                    param,
+                } => {
+                    self.on_ramp_finished(ctx, param);
+                }
+                golden_schema::EventKind::RampCancelled {
+                    param,
+                } => {
+                    self.on_ramp_cancelled(ctx, param);
+                }
             }
         }
     }
@@ -210,6 +291,10 @@ pub trait NodeReactive {
     fn on_node_deleted(&mut self, _ctx: &mut ProcessCtx, _node: NodeId) {}
 
     fn on_meta_changed(&mut self, _ctx: &mut ProcessCtx, _node: NodeId, _patch: NodeMetaPatch) {}
+
+    fn on_ramp_finished(&mut self, _ctx: &mut ProcessCtx, _param: NodeId) {}
+
+    fn on_ramp_cancelled(&mut self, _ctx: &mut ProcessCtx, _param: NodeId) {}
 }
 
 pub trait NodeContinuous: NodeReactive {
@@ -220,3 +305,72 @@ pub trait NodeLifecycle {
     fn init(&mut self, _ctx: &mut ProcessCtx) {}
     fn destroy(&mut self, _ctx: &mut ProcessCtx) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::engine::Engine;
+
+    use super::*;
+
+    /// Binds a parameter its schema does not declare.
+    struct Unbindable {
+        _gain: ParameterHandle<f64>,
+    }
+
+    impl NodeBehaviour for Unbindable {
+        fn process(&mut self, _ctx: &mut ProcessCtx) {}
+    }
+
+    impl GoldenNodeDecl for Unbindable {
+        fn node_type() -> NodeTypeId {
+            NodeTypeId("Unbindable".to_string())
+        }
+
+        fn schema() -> NodeSchema {
+            NodeSchema::new()
+        }
+
+        fn from_binding(binding: &NodeBinding) -> Result<Self, BindingError> {
+            let gain = binding.param("gain");
+            Ok(Self {
+                _gain: gain.ok_or_else(|| BindingError::MissingParam("gain".to_string()))?,
+            })
+        }
+
+        fn into_behaviour(self) -> Box<dyn NodeBehaviour> {
+            Box::new(self)
+        }
+    }
+
+    #[test]
+    fn instances_that_fail_to_bind_are_dropped() {
+        let mut manager = ManagerData::new();
+        manager.register::<Unbindable>();
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let manager = engine.create_child_manager(root, "Manager", "manager", manager);
+        let nodes = engine.nodes.keys().count();
+
+        let node_type = Unbindable::node_type();
+        let created = engine.create_child_of_type(
+            manager,
+            node_type.clone(),
+            "direct",
+            NodeExecution::Reactive,
+        );
+        assert_eq!(created, None);
+
+        let edit = Edit::InstantiateChildFromManager {
+            manager,
+            node_type,
+            label: "queued".to_string(),
+            execution: NodeExecution::Reactive,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+        assert_eq!(engine.child_count(manager), 0);
+        assert_eq!(engine.nodes.keys().count(), nodes);
+        assert_eq!(engine.edit_revision(), 0);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use golden_schema::NodeId;
+
+/// Bidirectional map between attached nodes and their `/`-separated paths.
+///
+/// Paths are built from `short_name`s below the engine root, which itself has
+/// the path `/`. Detached nodes are not indexed.
+#[derive(Clone, Default)]
+pub struct PathIndex {
+    by_path: HashMap<String, NodeId>,
+    by_node: HashMap<NodeId, String>,
+}
+
+impl PathIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, node: NodeId, path: String) {
+        if let Some(old) = self.by_node.insert(node, path.clone()) {
+            self.by_path.remove(&old);
+        }
+        self.by_path.insert(path, node);
+    }
+
+    pub fn remove(&mut self, node: NodeId) -> Option<String> {
+        let path = self.by_node.remove(&node)?;
+        self.by_path.remove(&path);
+        Some(path)
+    }
+
+    pub fn resolve(&self, path: &str) -> Option<NodeId> {
+        self.by_path.get(normalize(path).as_ref()).copied()
+    }
+
+    pub fn path_of(&self, node: NodeId) -> Option<&str> {
+        self.by_node.get(&node).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_node.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_node.is_empty()
+    }
+}
+
+/// Path of a child given its parent's path.
+pub fn child_path(parent: &str, short_name: &str) -> String {
+    if parent == "/" {
+        format!("/{short_name}")
+    } else {
+        format!("{parent}/{short_name}")
+    }
+}
+
+/// Strips a trailing slash and adds a leading one, so `outputs/` and
+/// `/outputs` resolve alike.
+fn normalize(path: &str) -> std::borrow::Cow<'_, str> {
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() {
+        return "/".into();
+    }
+    if trimmed.starts_with('/') {
+        trimmed.into()
+    } else {
+        format!("/{trimmed}").into()
+    }
+}
+
+/// Makes a label usable as a path segment.
+///
+/// Anything other than ASCII letters, digits, `_`, `-` and `.` becomes `_`.
+pub fn sanitize_short_name(label: &str) -> String {
+    let name: String = label
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "node".to_string()
+    } else {
+        name
+    }
+}
+
+/// Splits a glob pattern such as `/outputs/*/intensity` into segments.
+pub fn pattern_segments(pattern: &str) -> Vec<&str> {
+    pattern.split('/').filter(|segment| !segment.is_empty()).collect()
+}
+
+/// Matches one path segment against a pattern segment.
+///
+/// `*` matches any run of characters and `?` a single one; `**` is handled by
+/// the caller since it spans segments.
+pub fn segment_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::{ShortName, Value};
+
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::engine::Engine;
+
+    use super::*;
+
+    fn submit(engine: &mut Engine, edit: Edit) {
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn rename(engine: &mut Engine, node: NodeId, short_name: &str) {
+        let mut meta = engine.nodes.get(&node).unwrap().meta.clone();
+        meta.short_name = ShortName(short_name.to_string());
+        engine.replace_meta(node, meta);
+    }
+
+    #[test]
+    fn resolves_paths_with_or_without_slashes() {
+        let mut index = PathIndex::new();
+        index.insert(NodeId(1), "/".to_string());
+        index.insert(NodeId(2), "/outputs".to_string());
+        for path in ["/outputs", "outputs", "outputs/", "/outputs//"] {
+            assert_eq!(index.resolve(path), Some(NodeId(2)), "{path}");
+        }
+        assert_eq!(index.resolve(""), Some(NodeId(1)));
+        assert_eq!(index.resolve("//"), Some(NodeId(1)));
+        assert_eq!(index.resolve("/out"), None);
+    }
+
+    #[test]
+    fn reinserting_a_node_drops_its_old_path() {
+        let mut index = PathIndex::new();
+        index.insert(NodeId(2), "/a".to_string());
+        index.insert(NodeId(2), "/b".to_string());
+        assert_eq!(index.resolve("/a"), None);
+        assert_eq!(index.path_of(NodeId(2)), Some("/b"));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.remove(NodeId(2)), Some("/b".to_string()));
+        assert_eq!(index.resolve("/b"), None);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn child_paths_join_below_the_root() {
+        assert_eq!(child_path("/", "outputs"), "/outputs");
+        assert_eq!(child_path("/outputs", "main"), "/outputs/main");
+    }
+
+    #[test]
+    fn sanitizes_labels() {
+        assert_eq!(sanitize_short_name("Main Out"), "Main_Out");
+        assert_eq!(sanitize_short_name("  gain (dB)! "), "gain__dB__");
+        assert_eq!(sanitize_short_name("a/b"), "a_b");
+        assert_eq!(sanitize_short_name("v1.2-beta_x"), "v1.2-beta_x");
+        assert_eq!(sanitize_short_name("Grün"), "Gr_n");
+        assert_eq!(sanitize_short_name("   "), "node");
+    }
+
+    #[test]
+    fn segments_match_wildcards() {
+        assert_eq!(pattern_segments("/outputs/*/intensity/"), vec!["outputs", "*", "intensity"]);
+        assert!(segment_matches("*", "anything"));
+        assert!(segment_matches("*", ""));
+        assert!(segment_matches("out*", "outputs"));
+        assert!(segment_matches("*put*", "outputs"));
+        assert!(segment_matches("ch?", "ch1"));
+        assert!(segment_matches("a*b*c", "aXbYbZc"));
+        assert!(!segment_matches("ch?", "ch10"));
+        assert!(!segment_matches("out*", "input"));
+        assert!(!segment_matches("gain", "gain2"));
+    }
+
+    #[test]
+    fn single_stars_match_one_level_and_double_stars_any() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let outputs = engine.create_child_container(root, "Container", "outputs");
+        let main = engine.create_child_container(outputs, "Container", "main");
+        let main_gain = engine.create_child_parameter(main, "gain", Value::Float(0.0));
+        let nested = engine.create_child_container(main, "Container", "sub");
+        let sub_gain = engine.create_child_parameter(nested, "gain", Value::Float(0.0));
+        let top_gain = engine.create_child_parameter(outputs, "gain", Value::Float(0.0));
+
+        assert_eq!(engine.query_paths("/outputs/*/gain"), vec![main_gain]);
+        let mut all = engine.query_paths("/outputs/**/gain");
+        all.sort_by_key(|node| node.0);
+        let mut expected = vec![main_gain, sub_gain, top_gain];
+        expected.sort_by_key(|node| node.0);
+        assert_eq!(all, expected);
+        assert_eq!(engine.query_paths("outputs/main/"), vec![main]);
+    }
+
+    #[test]
+    fn colliding_names_get_a_numeric_suffix() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        let second = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        let third = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        assert_eq!(engine.resolve_path("/gain_2"), Some(second));
+        assert_eq!(engine.resolve_path("/gain_3"), Some(third));
+
+        // Renaming onto a taken name is suffixed too.
+        let level = engine.create_child_parameter(root, "level", Value::Float(0.0));
+        rename(&mut engine, level, "gain");
+        assert_eq!(engine.resolve_path("/gain_4"), Some(level));
+        assert_eq!(engine.resolve_path("/level"), None);
+    }
+
+    #[test]
+    fn index_follows_renames_moves_and_deletes() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let a = engine.create_child_container(root, "Container", "a");
+        let b = engine.create_child_container(root, "Container", "b");
+        let group = engine.create_child_container(a, "Container", "group");
+        let gain = engine.create_child_parameter(group, "gain", Value::Float(0.0));
+
+        rename(&mut engine, group, "bus");
+        assert_eq!(engine.resolve_path("/a/group/gain"), None);
+        assert_eq!(engine.resolve_path("/a/bus/gain"), Some(gain));
+
+        submit(
+            &mut engine,
+            Edit::MoveNode {
+                node: group,
+                new_parent: b,
+                index: 0,
+            },
+        );
+        assert_eq!(engine.resolve_path("/a/bus"), None);
+        assert_eq!(engine.resolve_path("/b/bus/gain"), Some(gain));
+
+        submit(
+            &mut engine,
+            Edit::DeleteNode {
+                node: group,
+            },
+        );
+        assert_eq!(engine.resolve_path("/b/bus"), None);
+        assert_eq!(engine.resolve_path("/b/bus/gain"), None);
+        assert_eq!(engine.resolve_path("/b"), Some(b));
+    }
+}
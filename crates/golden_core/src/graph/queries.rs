@@ -1,6 +1,165 @@
-use golden_schema::NodeId;
+use golden_schema::{NodeId, NodeMeta, NodeTypeId};
 
-pub fn is_descendant(_root: NodeId, _node: NodeId) -> bool {
-    // TODO: implement hierarchy query.
+use crate::engine::{Engine, ProcessCtx};
+use crate::graph::node::NodeData;
+
+/// Broad category of a node's data, as used in queries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataKind {
+    None,
+    Container,
+    Parameter,
+    Custom,
+    Manager,
+}
+
+impl DataKind {
+    pub fn of(data: &NodeData) -> Self {
+        match data {
+            NodeData::None => DataKind::None,
+            NodeData::Container(_) => DataKind::Container,
+            NodeData::Parameter(_) => DataKind::Parameter,
+            NodeData::Custom(_) => DataKind::Custom,
+            NodeData::Manager(_) => DataKind::Manager,
+        }
+    }
+}
+
+/// Structural facts about a node, shared with [`ProcessCtx`] so behaviours can
+/// run queries without access to the engine.
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    pub node_type: NodeTypeId,
+    pub data_kind: DataKind,
+    pub parent: Option<NodeId>,
+}
+
+/// Read access needed to evaluate a [`NodeQuery`].
+pub trait QuerySource {
+    fn node_ids(&self) -> Box<dyn Iterator<Item = NodeId> + '_>;
+    fn info(&self, node: NodeId) -> Option<&NodeInfo>;
+    fn meta(&self, node: NodeId) -> Option<&NodeMeta>;
+}
+
+impl QuerySource for Engine {
+    fn node_ids(&self) -> Box<dyn Iterator<Item = NodeId> + '_> {
+        Box::new(self.nodes.keys())
+    }
+
+    fn info(&self, node: NodeId) -> Option<&NodeInfo> {
+        self.node_infos().get(&node)
+    }
+
+    fn meta(&self, node: NodeId) -> Option<&NodeMeta> {
+        self.nodes.get(&node).map(|entry| &entry.meta)
+    }
+}
+
+impl QuerySource for ProcessCtx {
+    fn node_ids(&self) -> Box<dyn Iterator<Item = NodeId> + '_> {
+        Box::new(self.node_infos.keys().copied())
+    }
+
+    fn info(&self, node: NodeId) -> Option<&NodeInfo> {
+        self.node_infos.get(&node)
+    }
+
+    fn meta(&self, node: NodeId) -> Option<&NodeMeta> {
+        self.read_meta(node)
+    }
+}
+
+/// True when `node` is a strict descendant of `root`.
+pub fn is_descendant(source: &impl QuerySource, root: NodeId, node: NodeId) -> bool {
+    let mut current = source.info(node).and_then(|info| info.parent);
+    while let Some(parent) = current {
+        if parent == root {
+            return true;
+        }
+        current = source.info(parent).and_then(|info| info.parent);
+    }
     false
 }
+
+/// Filters nodes by type, tags, semantics, data kind, enabled state and
+/// ancestry. Every condition that is set must hold, e.g.
+/// `NodeQuery::new().parameters().tag("dimmer").under(stage).run(ctx)`.
+#[derive(Clone, Debug, Default)]
+pub struct NodeQuery {
+    node_type: Option<NodeTypeId>,
+    tags: Vec<String>,
+    intent: Option<String>,
+    unit: Option<String>,
+    data_kind: Option<DataKind>,
+    enabled: Option<bool>,
+    under: Option<NodeId>,
+}
+
+impl NodeQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn node_type(mut self, node_type: &str) -> Self {
+        self.node_type = Some(NodeTypeId(node_type.to_string()));
+        self
+    }
+
+    /// Requires the tag; repeat to require several.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn intent(mut self, intent: &str) -> Self {
+        self.intent = Some(intent.to_string());
+        self
+    }
+
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    pub fn data_kind(mut self, kind: DataKind) -> Self {
+        self.data_kind = Some(kind);
+        self
+    }
+
+    pub fn parameters(self) -> Self {
+        self.data_kind(DataKind::Parameter)
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    /// Restricts results to strict descendants of `ancestor`.
+    pub fn under(mut self, ancestor: NodeId) -> Self {
+        self.under = Some(ancestor);
+        self
+    }
+
+    pub fn matches(&self, source: &impl QuerySource, node: NodeId) -> bool {
+        let (Some(info), Some(meta)) = (source.info(node), source.meta(node)) else {
+            return false;
+        };
+        self.node_type.as_ref().is_none_or(|node_type| &info.node_type == node_type)
+            && self.data_kind.is_none_or(|kind| info.data_kind == kind)
+            && self.enabled.is_none_or(|enabled| meta.enabled == enabled)
+            && self.tags.iter().all(|tag| meta.tags.contains(tag))
+            && self
+                .intent
+                .as_ref()
+                .is_none_or(|intent| meta.semantics.intent.as_ref() == Some(intent))
+            && self.unit.as_ref().is_none_or(|unit| meta.semantics.unit.as_ref() == Some(unit))
+            && self.under.is_none_or(|ancestor| is_descendant(source, ancestor, node))
+    }
+
+    /// Lazily yields every matching node. Order follows the source and is not
+    /// tree order.
+    pub fn run<'a, S: QuerySource>(&'a self, source: &'a S) -> impl Iterator<Item = NodeId> + 'a {
+        source.node_ids().filter(move |node| self.matches(source, *node))
+    }
+}
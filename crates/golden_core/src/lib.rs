@@ -2,23 +2,29 @@ pub mod data;
 pub mod edits;
 pub mod engine;
 pub mod events;
+pub mod expr;
 pub mod graph;
 pub mod history;
 pub mod meta;
+pub mod nodes;
 pub mod persistence;
+pub mod presets;
 pub mod schema;
+pub mod typegen;
 pub mod values;
 
 pub use data::{
     AllowedTypes, ChildListHandle, ContainerData, ContainerLimits, FolderHandle, FolderPolicy,
     ParameterData, ParameterValue, PotentialSlotHandle,
 };
-pub use engine::{Engine, EnginePhase, ProcessCtx};
+pub use engine::{Easing, Engine, EngineClock, EnginePhase, ProcessCtx};
 pub use events::{Event, EventKind, EventTime};
 pub use graph::node::{
-    ManagerData, ManagerNodeRegistration, Node, NodeBehaviour, NodeBehaviourFactory, NodeBinding,
-    NodeContinuous, NodeData, NodeExecution, NodeLifecycle, NodeReactive,
+    BindingError, Continuous, ManagerData, ManagerNodeRegistration, Node, NodeBehaviour,
+    NodeBehaviourFactory, NodeBinding, NodeContinuous, NodeData, NodeExecution, NodeLifecycle,
+    NodeReactive, Reactive,
 };
+pub use graph::queries::{DataKind, NodeQuery};
 pub use schema::{
     ContainerDecl, DeclaredChild, FolderDecl, GoldenNodeDecl, InboxBehavior, NodeSchema, ParamDecl,
     PotentialSlot, SchemaRegistry,
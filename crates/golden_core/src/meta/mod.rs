@@ -19,4 +19,10 @@ pub fn apply_patch(meta: &mut NodeMeta, patch: &NodeMetaPatch) {
     if let Some(presentation) = &patch.presentation {
         meta.presentation = presentation.clone();
     }
+    if let Some(expression) = &patch.expression {
+        meta.expression = expression.clone();
+    }
+    if let Some(error) = &patch.expression_error {
+        meta.expression_error = error.clone();
+    }
 }
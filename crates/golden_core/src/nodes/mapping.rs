@@ -0,0 +1,666 @@
+use golden_schema::{
+    ChangePolicy, ColorRgba, DeclId, EnumId, EnumVariantId, NodeId, NodeTypeId, NodeUuid,
+    PresentationHint, ReferenceValue, SavePolicy, SemanticsHint, UpdatePolicy, Value,
+    ValueConstraints, units,
+};
+use uuid::Uuid;
+
+use crate::data::ParameterHandle;
+use crate::engine::{Easing, ProcessCtx};
+use crate::events::routing::subscriptions::ListenerSpec;
+use crate::graph::node::{
+    BindingError, ManagerData, NodeBehaviour, NodeBinding, NodeContinuous, NodeReactive,
+};
+use crate::schema::{DeclaredChild, InboxBehavior, NodeSchema, ParamDecl};
+
+pub const MAPPING_NODE_TYPE: &str = "Mapping";
+pub const MAPPING_CURVE_ENUM: &str = "MappingCurve";
+
+const CURVES: [(&str, Easing); 4] = [
+    ("Linear", Easing::Linear),
+    ("EaseIn", Easing::EaseIn),
+    ("EaseOut", Easing::EaseOut),
+    ("EaseInOut", Easing::EaseInOut),
+];
+
+/// Forwards a source parameter to a target parameter through a range mapping.
+///
+/// The source value (Float, Int or Bool) is normalized against the input
+/// range, optionally clamped, inverted and shaped by the curve, then written
+/// to the target according to its kind:
+///
+/// - Float and Int targets receive the value in the output range.
+/// - Bool targets compare the output value with `threshold`.
+/// - Trigger targets fire when the output value rises past `threshold`.
+/// - ColorRgba targets blend from `color_low` to `color_high`.
+///
+/// With `convert_units` on, and source and target both carrying units of the
+/// same dimension in their `SemanticsHint`, the source value is first
+/// converted to the target's unit, so the input range is given in that unit.
+/// A source in ms driving a target in s, with both ranges `0..1`, passes
+/// 500 ms through as 0.5 s.
+///
+/// With `smoothing` above zero the normalized value follows the source with
+/// that time constant in seconds; this needs the node to run as
+/// `NodeExecution::Continuous`.
+pub struct Mapping {
+    id: NodeId,
+    source: ParameterHandle<ReferenceValue>,
+    target: ParameterHandle<ReferenceValue>,
+    in_min: ParameterHandle<f64>,
+    in_max: ParameterHandle<f64>,
+    out_min: ParameterHandle<f64>,
+    out_max: ParameterHandle<f64>,
+    curve: NodeId,
+    invert: ParameterHandle<bool>,
+    clamp: ParameterHandle<bool>,
+    convert_units: ParameterHandle<bool>,
+    smoothing: ParameterHandle<f64>,
+    threshold: ParameterHandle<f64>,
+    color_low: ParameterHandle<ColorRgba>,
+    color_high: ParameterHandle<ColorRgba>,
+    bound_source: Option<NodeId>,
+    goal: Option<f64>,
+    current: Option<f64>,
+    above_threshold: bool,
+}
+
+impl Mapping {
+    pub fn node_type() -> NodeTypeId {
+        NodeTypeId(MAPPING_NODE_TYPE.to_string())
+    }
+
+    pub fn schema() -> NodeSchema {
+        let unbound = Value::Reference(ReferenceValue {
+            uuid: NodeUuid(Uuid::nil()),
+            cached_id: None,
+        });
+        let reference = ValueConstraints::Reference {
+            target: Some("Parameter".to_string()),
+        };
+        let curve = Value::Enum {
+            enum_id: EnumId(MAPPING_CURVE_ENUM.to_string()),
+            variant: EnumVariantId(CURVES[0].0.to_string()),
+        };
+        let curve_constraints = ValueConstraints::Enum {
+            enum_id: EnumId(MAPPING_CURVE_ENUM.to_string()),
+            allowed: CURVES.iter().map(|(name, _)| EnumVariantId(name.to_string())).collect(),
+        };
+        let black = ColorRgba {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        let white = ColorRgba {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        };
+
+        let params = vec![
+            param("source", unbound.clone(), reference.clone()),
+            param("target", unbound, reference),
+            param("in_min", Value::Float(0.0), ValueConstraints::None),
+            param("in_max", Value::Float(1.0), ValueConstraints::None),
+            param("out_min", Value::Float(0.0), ValueConstraints::None),
+            param("out_max", Value::Float(1.0), ValueConstraints::None),
+            param("curve", curve, curve_constraints),
+            param("invert", Value::Bool(false), ValueConstraints::None),
+            param("clamp", Value::Bool(true), ValueConstraints::None),
+            param("convert_units", Value::Bool(true), ValueConstraints::None),
+            ParamDecl {
+                semantics: SemanticsHint {
+                    intent: None,
+                    unit: Some("s".to_string()),
+                },
+                ..param(
+                    "smoothing",
+                    Value::Float(0.0),
+                    ValueConstraints::Float {
+                        min: Some(0.0),
+                        max: None,
+                        clamp: true,
+                        step: None,
+                    },
+                )
+            },
+            param("threshold", Value::Float(0.5), ValueConstraints::None),
+            param("color_low", Value::ColorRgba(black), ValueConstraints::None),
+            param("color_high", Value::ColorRgba(white), ValueConstraints::None),
+        ];
+
+        let mut schema = NodeSchema::new();
+        schema.declared_children = params
+            .iter()
+            .map(|decl| DeclaredChild {
+                decl_id: decl.decl_id.clone(),
+                node_type: NodeTypeId("Parameter".to_string()),
+                default_label: Some(decl.decl_id.0.clone()),
+                default_enabled: true,
+            })
+            .collect();
+        schema.params = params;
+        schema
+    }
+
+    pub fn from_binding(binding: &NodeBinding) -> Result<Self, BindingError> {
+        Ok(Self {
+            id: binding.node_id,
+            source: bound_param(binding, "source")?,
+            target: bound_param(binding, "target")?,
+            in_min: bound_param(binding, "in_min")?,
+            in_max: bound_param(binding, "in_max")?,
+            out_min: bound_param(binding, "out_min")?,
+            out_max: bound_param(binding, "out_max")?,
+            curve: binding
+                .node("curve")
+                .ok_or_else(|| BindingError::MissingChild("curve".to_string()))?,
+            invert: bound_param(binding, "invert")?,
+            clamp: bound_param(binding, "clamp")?,
+            convert_units: bound_param(binding, "convert_units")?,
+            smoothing: bound_param(binding, "smoothing")?,
+            threshold: bound_param(binding, "threshold")?,
+            color_low: bound_param(binding, "color_low")?,
+            color_high: bound_param(binding, "color_high")?,
+            bound_source: None,
+            goal: None,
+            current: None,
+            above_threshold: false,
+        })
+    }
+
+    /// Registers the mapping node type with a manager.
+    pub fn register(manager: &mut ManagerData) {
+        manager.register_node_type(Self::node_type(), Self::schema(), |binding| {
+            Ok(Box::new(Mapping::from_binding(&binding)?))
+        });
+    }
+
+    /// Follows the source reference, moving the subscription when it changed.
+    fn rebind(&mut self, ctx: &mut ProcessCtx) {
+        let source = self.source.get(ctx).and_then(|reference| ctx.resolve_reference(&reference));
+        if source == self.bound_source {
+            return;
+        }
+        if let Some(old) = self.bound_source.take() {
+            ctx.unsubscribe(ListenerSpec::on_param_change(self.id, old));
+        }
+        if let Some(new) = source {
+            ctx.subscribe(ListenerSpec::on_param_change(self.id, new));
+        }
+        self.bound_source = source;
+        self.current = None;
+    }
+
+    fn target_node(&self, ctx: &ProcessCtx) -> Option<NodeId> {
+        let target = self.target.get(ctx).and_then(|reference| ctx.resolve_reference(&reference));
+        target.filter(|target| Some(*target) != self.bound_source)
+    }
+
+    fn refresh(&mut self, ctx: &mut ProcessCtx) {
+        let value = self.bound_source.and_then(|source| ctx.read_param(source)).cloned();
+        if let Some(value) = value {
+            self.on_source_change(ctx, &value);
+        }
+    }
+
+    fn on_source_change(&mut self, ctx: &mut ProcessCtx, value: &Value) {
+        let Some(input) = scalar(value) else {
+            return;
+        };
+        let input = self.in_target_unit(ctx, input);
+        let normalized = self.normalize(ctx, input);
+        if self.smoothing.get(ctx).unwrap_or(0.0) > 0.0 && self.current.is_some() {
+            self.goal = Some(normalized);
+            return;
+        }
+        self.goal = None;
+        self.current = Some(normalized);
+        self.write(ctx, normalized);
+    }
+
+    /// `input` converted from the source's unit to the target's, when
+    /// `convert_units` is on and the two units measure the same dimension.
+    fn in_target_unit(&self, ctx: &ProcessCtx, input: f64) -> f64 {
+        if !self.convert_units.get(ctx).unwrap_or(true) {
+            return input;
+        }
+        let unit_of = |node| ctx.read_meta(node)?.semantics.unit.as_deref();
+        let source = self.bound_source.and_then(unit_of);
+        let target = self.target_node(ctx).and_then(unit_of);
+        match (source, target) {
+            (Some(source), Some(target)) => units::convert(input, source, target).unwrap_or(input),
+            _ => input,
+        }
+    }
+
+    /// Maps the input range onto `0..=1`, then applies clamp, invert and curve.
+    fn normalize(&self, ctx: &ProcessCtx, input: f64) -> f64 {
+        let in_min = self.in_min.get(ctx).unwrap_or(0.0);
+        let in_max = self.in_max.get(ctx).unwrap_or(1.0);
+        let span = in_max - in_min;
+        let mut t = if span == 0.0 {
+            0.0
+        } else {
+            (input - in_min) / span
+        };
+        if self.clamp.get(ctx).unwrap_or(true) {
+            t = t.clamp(0.0, 1.0);
+        }
+        if self.invert.get(ctx).unwrap_or(false) {
+            t = 1.0 - t;
+        }
+        // Curves meet the line at 0 and 1, so unclamped values extrapolate linearly.
+        if (0.0..=1.0).contains(&t) {
+            self.easing(ctx).apply(t)
+        } else {
+            t
+        }
+    }
+
+    fn easing(&self, ctx: &ProcessCtx) -> Easing {
+        let variant = match ctx.read_param(self.curve) {
+            Some(Value::Enum {
+                variant,
+                ..
+            }) => variant.0.as_str(),
+            _ => return Easing::Linear,
+        };
+        CURVES
+            .iter()
+            .find(|(name, _)| *name == variant)
+            .map(|(_, easing)| *easing)
+            .unwrap_or_default()
+    }
+
+    fn write(&mut self, ctx: &mut ProcessCtx, normalized: f64) {
+        let Some(target) = self.target_node(ctx) else {
+            return;
+        };
+        let out_min = self.out_min.get(ctx).unwrap_or(0.0);
+        let out_max = self.out_max.get(ctx).unwrap_or(1.0);
+        let output = out_min + normalized * (out_max - out_min);
+        let threshold = self.threshold.get(ctx).unwrap_or(0.5);
+        let was_above = std::mem::replace(&mut self.above_threshold, output >= threshold);
+
+        let value = match ctx.read_param(target) {
+            Some(Value::Float(_)) => Value::Float(output),
+            Some(Value::Int(_)) => Value::Int(output.round() as i64),
+            Some(Value::Bool(_)) => Value::Bool(output >= threshold),
+            Some(Value::Trigger) if output >= threshold && !was_above => Value::Trigger,
+            Some(Value::ColorRgba(_)) => {
+                let low = self.color_low.get(ctx).unwrap_or(ColorRgba {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 1.0,
+                });
+                let high = self.color_high.get(ctx).unwrap_or(low);
+                let t = normalized.clamp(0.0, 1.0);
+                Value::ColorRgba(ColorRgba {
+                    r: low.r + (high.r - low.r) * t,
+                    g: low.g + (high.g - low.g) * t,
+                    b: low.b + (high.b - low.b) * t,
+                    a: low.a + (high.a - low.a) * t,
+                })
+            }
+            _ => return,
+        };
+        ctx.set_param(target, value);
+    }
+}
+
+fn scalar(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(v) => Some(*v),
+        Value::Int(v) => Some(*v as f64),
+        Value::Bool(v) => Some(if *v {
+            1.0
+        } else {
+            0.0
+        }),
+        _ => None,
+    }
+}
+
+fn bound_param<T>(
+    binding: &NodeBinding,
+    decl_id: &str,
+) -> Result<ParameterHandle<T>, BindingError> {
+    binding.param(decl_id).ok_or_else(|| BindingError::MissingParam(decl_id.to_string()))
+}
+
+fn param(decl_id: &str, default: Value, constraints: ValueConstraints) -> ParamDecl {
+    ParamDecl {
+        decl_id: DeclId(decl_id.to_string()),
+        default,
+        constraints,
+        read_only: false,
+        update: UpdatePolicy::Immediate,
+        change: ChangePolicy::ValueChange,
+        save: SavePolicy::Delta,
+        semantics: SemanticsHint::default(),
+        presentation: PresentationHint::default(),
+        folder: None,
+        behavior: InboxBehavior::Coalesce,
+        alias: None,
+    }
+}
+
+impl NodeReactive for Mapping {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.rebind(ctx);
+            self.refresh(ctx);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, value: Value) {
+        if Some(param) == self.bound_source {
+            self.on_source_change(ctx, &value);
+        } else if param == self.source.node_id {
+            self.rebind(ctx);
+            self.refresh(ctx);
+        } else if param != self.smoothing.node_id {
+            // Range, curve or target settings changed; re-map the current input.
+            self.current = None;
+            self.refresh(ctx);
+        }
+    }
+}
+
+impl NodeContinuous for Mapping {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        let (Some(goal), Some(current)) = (self.goal, self.current) else {
+            return;
+        };
+        let tau = self.smoothing.get(ctx).unwrap_or(0.0);
+        let next = if tau <= 0.0 {
+            goal
+        } else {
+            let alpha = 1.0 - (-ctx.delta.as_secs_f64() / tau).exp();
+            current + (goal - current) * alpha
+        };
+        let next = if (goal - next).abs() < 1e-6 {
+            goal
+        } else {
+            next
+        };
+        if next == goal {
+            self.goal = None;
+        }
+        self.current = Some(next);
+        self.write(ctx, next);
+    }
+}
+
+impl NodeBehaviour for Mapping {
+    fn process(&mut self, ctx: &mut ProcessCtx) {
+        NodeReactive::process(self, ctx);
+    }
+
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        NodeContinuous::update(self, ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use golden_schema::NodeMetaPatch;
+
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::engine::Engine;
+    use crate::graph::node::{NodeData, NodeExecution};
+
+    use super::*;
+
+    struct Rig {
+        engine: Engine,
+        mapping: NodeId,
+        source: NodeId,
+        target: NodeId,
+    }
+
+    impl Rig {
+        /// A mapping from a Float `source` to a `target` holding `target_value`.
+        fn new(target_value: Value) -> Self {
+            let mut manager = ManagerData::new();
+            Mapping::register(&mut manager);
+            let mut engine = Engine::new();
+            let root = engine.root_id();
+            let source = engine.create_child_parameter(root, "source", Value::Float(0.0));
+            let target = engine.create_child_parameter(root, "target", target_value);
+            let manager = engine.create_child_manager(root, "Manager", "mappings", manager);
+            let mapping = engine
+                .create_child_of_type(
+                    manager,
+                    Mapping::node_type(),
+                    "map",
+                    NodeExecution::Continuous,
+                )
+                .unwrap();
+            let mut rig = Self {
+                engine,
+                mapping,
+                source,
+                target,
+            };
+            rig.set("source", rig.reference(source));
+            rig.set("target", rig.reference(target));
+            rig
+        }
+
+        fn reference(&self, node: NodeId) -> Value {
+            Value::Reference(ReferenceValue {
+                uuid: self.engine.nodes.get(&node).unwrap().meta.uuid,
+                cached_id: None,
+            })
+        }
+
+        fn submit(&mut self, edit: Edit) {
+            self.engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+            self.engine.tick_by(Duration::ZERO);
+            self.engine.tick_by(Duration::ZERO);
+        }
+
+        /// Sets one of the mapping's own parameters.
+        fn set(&mut self, decl_id: &str, value: Value) {
+            let node = self.engine.resolve_path(&format!("/mappings/map/{decl_id}")).unwrap();
+            self.submit(Edit::SetParam {
+                node,
+                value,
+            });
+        }
+
+        fn input(&mut self, value: f64) {
+            self.submit(Edit::SetParam {
+                node: self.source,
+                value: Value::Float(value),
+            });
+        }
+
+        fn unit(&mut self, node: NodeId, unit: &str) {
+            let patch = NodeMetaPatch {
+                semantics: Some(SemanticsHint {
+                    intent: None,
+                    unit: Some(unit.to_string()),
+                }),
+                ..NodeMetaPatch::default()
+            };
+            self.submit(Edit::PatchMeta {
+                node,
+                patch,
+            });
+        }
+
+        fn output(&self) -> Value {
+            match &self.engine.nodes.get(&self.target).unwrap().data {
+                NodeData::Parameter(param) => param.value.clone(),
+                _ => panic!("not a parameter"),
+            }
+        }
+
+        fn float(&self) -> f64 {
+            match self.output() {
+                Value::Float(value) => value,
+                other => panic!("expected a Float, got {other:?}"),
+            }
+        }
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn remaps_ranges_and_clamps() {
+        let mut rig = Rig::new(Value::Float(0.0));
+        assert!(rig.engine.nodes.get(&rig.mapping).is_some());
+        rig.set("in_min", Value::Float(10.0));
+        rig.set("in_max", Value::Float(20.0));
+        rig.set("out_min", Value::Float(100.0));
+        rig.set("out_max", Value::Float(200.0));
+
+        rig.input(15.0);
+        assert_near(rig.float(), 150.0);
+        rig.input(30.0);
+        assert_near(rig.float(), 200.0);
+        rig.input(0.0);
+        assert_near(rig.float(), 100.0);
+
+        rig.set("clamp", Value::Bool(false));
+        rig.input(30.0);
+        assert_near(rig.float(), 300.0);
+        rig.input(5.0);
+        assert_near(rig.float(), 50.0);
+
+        rig.set("invert", Value::Bool(true));
+        rig.input(12.5);
+        assert_near(rig.float(), 175.0);
+    }
+
+    #[test]
+    fn int_targets_round() {
+        let mut rig = Rig::new(Value::Int(0));
+        rig.set("out_max", Value::Float(10.0));
+        rig.input(0.26);
+        assert_eq!(rig.output(), Value::Int(3));
+    }
+
+    #[test]
+    fn curves_shape_the_normalized_value() {
+        let mut rig = Rig::new(Value::Float(0.0));
+        let curve = Value::Enum {
+            enum_id: EnumId(MAPPING_CURVE_ENUM.to_string()),
+            variant: EnumVariantId("EaseIn".to_string()),
+        };
+        rig.set("curve", curve);
+        rig.input(0.5);
+        assert_near(rig.float(), 0.25);
+    }
+
+    #[test]
+    fn thresholds_switch_bools_and_fire_triggers_on_the_way_up() {
+        let mut rig = Rig::new(Value::Bool(false));
+        rig.input(0.7);
+        assert_eq!(rig.output(), Value::Bool(true));
+        rig.input(0.3);
+        assert_eq!(rig.output(), Value::Bool(false));
+
+        let mut rig = Rig::new(Value::Trigger);
+        if let Some(NodeData::Parameter(param)) =
+            rig.engine.nodes.get_mut(&rig.target).map(|node| &mut node.data)
+        {
+            param.change = ChangePolicy::Always;
+        }
+        let fired = |rig: &mut Rig, input: f64| {
+            let before = rig.engine.time;
+            rig.input(input);
+            rig.engine.events_since(before).iter().any(|event| {
+                matches!(event.kind, golden_schema::EventKind::ParamChanged { param, .. }
+                    if param == rig.target)
+            })
+        };
+        assert!(fired(&mut rig, 0.6));
+        assert!(!fired(&mut rig, 0.8), "fired again while above the threshold");
+        assert!(!fired(&mut rig, 0.2));
+        assert!(fired(&mut rig, 0.9));
+    }
+
+    #[test]
+    fn colours_blend_between_low_and_high() {
+        let black = ColorRgba {
+            r: 0.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+        let mut rig = Rig::new(Value::ColorRgba(black));
+        let red = ColorRgba {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        };
+        rig.set("color_high", Value::ColorRgba(red));
+        rig.input(0.25);
+        assert_eq!(
+            rig.output(),
+            Value::ColorRgba(ColorRgba {
+                r: 0.25,
+                g: 0.0,
+                b: 0.0,
+                a: 0.75,
+            })
+        );
+        rig.set("clamp", Value::Bool(false));
+        rig.input(2.0);
+        assert_eq!(rig.output(), Value::ColorRgba(red));
+    }
+
+    #[test]
+    fn converts_source_units_to_the_target_unit() {
+        let mut rig = Rig::new(Value::Float(0.0));
+        let (source, target) = (rig.source, rig.target);
+        rig.unit(source, "ms");
+        rig.unit(target, "s");
+        rig.input(500.0);
+        assert_near(rig.float(), 0.5);
+
+        rig.set("convert_units", Value::Bool(false));
+        rig.input(500.0);
+        assert_near(rig.float(), 1.0);
+
+        // Units of different dimensions are left alone.
+        rig.set("convert_units", Value::Bool(true));
+        rig.unit(target, "Hz");
+        rig.input(0.5);
+        assert_near(rig.float(), 0.5);
+    }
+
+    #[test]
+    fn smoothing_converges_across_ticks() {
+        let mut rig = Rig::new(Value::Float(0.0));
+        rig.set("smoothing", Value::Float(0.1));
+        rig.input(1.0);
+        assert_eq!(rig.float(), 0.0);
+
+        let mut last = 0.0;
+        for _ in 0..5 {
+            rig.engine.tick_by(Duration::from_millis(50));
+            let now = rig.float();
+            assert!(now > last && now < 1.0, "{now} after {last}");
+            last = now;
+        }
+        // One time constant covers about 63% of the way.
+        assert!((0.9..1.0).contains(&last), "{last}");
+        for _ in 0..100 {
+            rig.engine.tick_by(Duration::from_millis(50));
+        }
+        assert_eq!(rig.float(), 1.0);
+    }
+}
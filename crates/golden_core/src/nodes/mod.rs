@@ -0,0 +1,3 @@
+pub mod mapping;
+
+pub use mapping::Mapping;
@@ -0,0 +1,1157 @@
+//! Structural comparison and three-way merge of project files.
+//!
+//! Nodes are matched by identity rather than position: full records by uuid,
+//! declared nodes (delta records) by `decl_id` under their matched parent. A
+//! renamed or moved node is therefore reported as such instead of as a removal
+//! and an addition.
+
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+use golden_schema::persistence::file_format::ProjectFile;
+use golden_schema::persistence::{DeltaNodeRecord, NodeDataDto, NodeRecord};
+use golden_schema::{DeclId, NodeMetaPatch, NodeTypeId, NodeUuid, Preset, Value};
+use serde_json::{Map, Value as Json};
+use uuid::Uuid;
+
+/// Identity of a node across versions of a project file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKey {
+    /// The exported root, whatever its uuid.
+    Root,
+    /// A full record, followed by uuid wherever it moves.
+    Uuid(NodeUuid),
+    /// A delta record: the declared child `DeclId` of the node `NodeKey`.
+    Declared(Box<NodeKey>, DeclId),
+}
+
+/// One difference between two versions of a project file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub node: NodeKey,
+    /// Path of the node in the newer file, or in the older one for removals.
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeKind {
+    /// A node only the newer file has, with its subtree. Nodes below it are
+    /// not reported separately.
+    Added {
+        parent: NodeKey,
+        index: usize,
+        record: Box<NodeRecord>,
+    },
+    /// A node only the older file has, with its subtree.
+    Removed,
+    /// A node under another parent, or in another place among its siblings.
+    Moved {
+        /// Path of the node in the older file.
+        from: String,
+        parent: NodeKey,
+        index: usize,
+    },
+    Retyped {
+        from: NodeTypeId,
+        to: NodeTypeId,
+    },
+    /// A parameter value; `None` when the file leaves it at its default.
+    Value {
+        from: Option<Value>,
+        to: Option<Value>,
+    },
+    /// A metadata field, or `data` for the rest of a full record's node data.
+    /// `None` when the field is not set.
+    Field {
+        name: String,
+        from: Option<Json>,
+        to: Option<Json>,
+    },
+}
+
+/// The part of a node a change is about; two changes to the same aspect of
+/// the same node conflict unless they agree.
+#[derive(PartialEq, Eq, Hash)]
+enum Aspect {
+    Place,
+    Type,
+    Value,
+    Field(String),
+}
+
+impl ChangeKind {
+    fn aspect(&self) -> Aspect {
+        match self {
+            ChangeKind::Added {
+                ..
+            }
+            | ChangeKind::Removed
+            | ChangeKind::Moved {
+                ..
+            } => Aspect::Place,
+            ChangeKind::Retyped {
+                ..
+            } => Aspect::Type,
+            ChangeKind::Value {
+                ..
+            } => Aspect::Value,
+            ChangeKind::Field {
+                name,
+                ..
+            } => Aspect::Field(name.clone()),
+        }
+    }
+
+    /// Order in which a merge applies changes: nodes are edited in place
+    /// first, then added, so moves can target new nodes, then removed.
+    fn phase(&self) -> u8 {
+        match self {
+            ChangeKind::Retyped {
+                ..
+            } => 0,
+            ChangeKind::Value {
+                ..
+            }
+            | ChangeKind::Field {
+                ..
+            } => 1,
+            ChangeKind::Added {
+                ..
+            } => 2,
+            ChangeKind::Moved {
+                ..
+            } => 3,
+            ChangeKind::Removed => 4,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = &self.path;
+        match &self.kind {
+            ChangeKind::Added {
+                record,
+                ..
+            } => match &**record {
+                NodeRecord::Full(record) => write!(f, "+ {path} ({})", record.node_type.0),
+                NodeRecord::Delta(_) => write!(f, "+ {path}"),
+            },
+            ChangeKind::Removed => write!(f, "- {path}"),
+            ChangeKind::Moved {
+                from,
+                index,
+                ..
+            } if from == path => write!(f, "> {path} to position {index}"),
+            ChangeKind::Moved {
+                from,
+                ..
+            } => write!(f, "> {path} (was {from})"),
+            ChangeKind::Retyped {
+                from,
+                to,
+            } => write!(f, "~ {path} type: {} -> {}", from.0, to.0),
+            ChangeKind::Value {
+                from,
+                to,
+            } => write!(f, "~ {path} = {} -> {}", Shown(from.as_ref()), Shown(to.as_ref())),
+            ChangeKind::Field {
+                name,
+                from,
+                to,
+            } => write!(f, "~ {path} {name}: {} -> {}", Shown(from.as_ref()), Shown(to.as_ref())),
+        }
+    }
+}
+
+/// An optional value as shown in a diff.
+struct Shown<'a, T>(Option<&'a T>);
+
+impl<T: fmt::Display> fmt::Display for Shown<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "(default)"),
+        }
+    }
+}
+
+/// Differences between two versions of a project, in the newer file's tree
+/// order followed by removals in the older file's.
+pub fn diff(old: &ProjectFile, new: &ProjectFile) -> Vec<Change> {
+    let (mut old, mut new) = (Tree::new(&old.root), Tree::new(&new.root));
+    old.complete_declared(&new);
+    new.complete_declared(&old);
+    diff_trees(&old, &new)
+}
+
+/// Something [`merge`] could not combine. The merged project keeps our side.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Conflict {
+    /// Both sides changed the same aspect of a node differently, or one side
+    /// removed a node the other changed.
+    Node {
+        ours: Change,
+        theirs: Change,
+    },
+    /// A change that no longer fits the merged tree, such as a move that
+    /// would put a node inside itself.
+    Unapplied(Change),
+    /// Both sides changed the preset called `name` differently, or one side
+    /// removed it while the other changed it.
+    Preset(String),
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Node {
+                ours,
+                theirs,
+            } => write!(f, "! {}\n  ours:   {ours}\n  theirs: {theirs}", theirs.path),
+            Conflict::Unapplied(change) => write!(f, "! {}\n  not applied: {change}", change.path),
+            Conflict::Preset(name) => write!(f, "! preset \"{name}\" changed on both sides"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Merge {
+    pub project: ProjectFile,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Combines the changes `ours` and `theirs` each made to `base`.
+///
+/// Changes to different nodes, or to different fields of one node, are all
+/// kept. Curves and gradients edited on both sides are merged key by key. Where
+/// the sides disagree, ours is kept and a [`Conflict`] is reported; a node
+/// removed on one side and changed on the other is kept if ours changed it
+/// and removed if ours removed it. The version is taken from ours.
+pub fn merge(base: &ProjectFile, ours: &ProjectFile, theirs: &ProjectFile) -> Merge {
+    let mut base_tree = Tree::new(&base.root);
+    let mut our_tree = Tree::new(&ours.root);
+    let mut their_tree = Tree::new(&theirs.root);
+    base_tree.complete_declared(&our_tree);
+    base_tree.complete_declared(&their_tree);
+    our_tree.complete_declared(&base_tree);
+    their_tree.complete_declared(&base_tree);
+    let our_changes = diff_trees(&base_tree, &our_tree);
+    let their_changes = diff_trees(&base_tree, &their_tree);
+
+    let ours_by_aspect: HashMap<(&NodeKey, Aspect), &Change> =
+        our_changes.iter().map(|change| ((&change.node, change.kind.aspect()), change)).collect();
+    let our_removals = removals(&our_changes);
+    let their_removals = removals(&their_changes);
+
+    let mut conflicts = Vec::new();
+    let mut accepted = Vec::new();
+    // Removals of theirs that lose to a change of ours inside the subtree.
+    let mut kept = HashSet::new();
+    for change in &our_changes {
+        if change.kind != ChangeKind::Removed
+            && let Some(removal) = removed_above(&base_tree, &our_tree, change, &their_removals)
+            && kept.insert(&removal.node)
+        {
+            conflicts.push(Conflict::Node {
+                ours: change.clone(),
+                theirs: removal.clone(),
+            });
+        }
+        accepted.push(change.clone());
+    }
+    for change in &their_changes {
+        if change.kind == ChangeKind::Removed && kept.contains(&change.node) {
+            continue;
+        }
+        if let Some(ours) = ours_by_aspect.get(&(&change.node, change.kind.aspect())) {
+            if ours.kind == change.kind {
+                continue;
+            }
+            if let Some(kind) = merge_keyed_values(&ours.kind, &change.kind) {
+                accepted.push(Change {
+                    kind,
+                    ..change.clone()
+                });
+            } else {
+                conflicts.push(Conflict::Node {
+                    ours: (*ours).clone(),
+                    theirs: change.clone(),
+                });
+            }
+            continue;
+        }
+        if let Some(removal) = removed_above(&base_tree, &their_tree, change, &our_removals) {
+            // Removing something already removed is no conflict.
+            if change.kind != ChangeKind::Removed {
+                conflicts.push(Conflict::Node {
+                    ours: removal.clone(),
+                    theirs: change.clone(),
+                });
+            }
+            continue;
+        }
+        accepted.push(change.clone());
+    }
+
+    // The sort is stable, so within a phase ours apply before theirs.
+    accepted.sort_by_key(|change| change.kind.phase());
+    let mut merged = base_tree;
+    for change in accepted {
+        if !merged.apply(&change) {
+            conflicts.push(Conflict::Unapplied(change));
+        }
+    }
+
+    Merge {
+        project: ProjectFile {
+            version: ours.version.clone(),
+            root: merged.record(&NodeKey::Root),
+            presets: merge_presets(&base.presets, &ours.presets, &theirs.presets, &mut conflicts),
+        },
+        conflicts,
+    }
+}
+
+/// A project file flattened into nodes keyed by identity.
+#[derive(Clone, Default)]
+struct Tree {
+    nodes: HashMap<NodeKey, TreeNode>,
+}
+
+#[derive(Clone)]
+struct TreeNode {
+    /// The node's record with its children left out.
+    record: NodeRecord,
+    parent: Option<NodeKey>,
+    children: Vec<NodeKey>,
+    /// Added by [`Tree::complete_declared`] rather than read from the file.
+    filled: bool,
+}
+
+impl Tree {
+    fn new(root: &NodeRecord) -> Self {
+        let mut tree = Tree::default();
+        tree.insert(NodeKey::Root, root.clone(), None);
+        tree
+    }
+
+    fn insert(&mut self, key: NodeKey, mut record: NodeRecord, parent: Option<NodeKey>) {
+        let mut children = Vec::new();
+        for child in std::mem::take(children_mut(&mut record)) {
+            let child_key = key_of(&key, &child);
+            // A file listing one node twice keeps the first.
+            if self.nodes.contains_key(&child_key) {
+                continue;
+            }
+            self.insert(child_key.clone(), child, Some(key.clone()));
+            children.push(child_key);
+        }
+        self.nodes.insert(
+            key,
+            TreeNode {
+                record,
+                parent,
+                children,
+                filled: false,
+            },
+        );
+    }
+
+    /// Adds an empty delta record for each declared node `other` has below a
+    /// node this tree has, so a declared node left at its defaults compares as
+    /// one rather than as a missing node. The uuid is copied, as it is the
+    /// same node.
+    fn complete_declared(&mut self, other: &Tree) {
+        for key in other.keys() {
+            let NodeKey::Declared(parent, decl_id) = &key else {
+                continue;
+            };
+            if self.nodes.contains_key(&key) {
+                continue;
+            }
+            let Some(parent_node) = self.nodes.get_mut(parent) else {
+                continue;
+            };
+            parent_node.children.push(key.clone());
+            let record = NodeRecord::Delta(DeltaNodeRecord {
+                decl_id: decl_id.clone(),
+                uuid: match &other.nodes[&key].record {
+                    NodeRecord::Delta(record) => record.uuid,
+                    NodeRecord::Full(_) => None,
+                },
+                meta: None,
+                value: None,
+                children: Vec::new(),
+            });
+            self.nodes.insert(
+                key.clone(),
+                TreeNode {
+                    record,
+                    parent: Some((**parent).clone()),
+                    children: Vec::new(),
+                    filled: true,
+                },
+            );
+        }
+    }
+
+    /// The record of `key` with its subtree, leaving out delta records filled
+    /// in that are still empty.
+    fn record(&self, key: &NodeKey) -> NodeRecord {
+        let node = &self.nodes[key];
+        let mut record = node.record.clone();
+        *children_mut(&mut record) = node
+            .children
+            .iter()
+            .filter(|child| !self.is_unused_filler(child))
+            .map(|child| self.record(child))
+            .collect();
+        record
+    }
+
+    fn is_unused_filler(&self, key: &NodeKey) -> bool {
+        let node = &self.nodes[key];
+        node.filled
+            && node.children.iter().all(|child| self.is_unused_filler(child))
+            && matches!(&node.record, NodeRecord::Delta(record) if record.meta.is_none() && record.value.is_none())
+    }
+
+    /// Every key, parents before their children and siblings in file order.
+    fn keys(&self) -> Vec<NodeKey> {
+        let mut keys = Vec::new();
+        let mut stack = vec![NodeKey::Root];
+        while let Some(key) = stack.pop() {
+            if let Some(node) = self.nodes.get(&key) {
+                stack.extend(node.children.iter().rev().cloned());
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    fn parent(&self, key: &NodeKey) -> Option<&NodeKey> {
+        self.nodes.get(key)?.parent.as_ref()
+    }
+
+    fn index(&self, key: &NodeKey) -> usize {
+        self.parent(key)
+            .and_then(|parent| self.nodes[parent].children.iter().position(|child| child == key))
+            .unwrap_or_default()
+    }
+
+    /// Short names of full records and `decl_id`s of delta records, joined
+    /// like engine paths.
+    fn path(&self, key: &NodeKey) -> String {
+        let mut segments = Vec::new();
+        let mut current = key;
+        while let Some(node) = self.nodes.get(current) {
+            let Some(parent) = &node.parent else {
+                break;
+            };
+            segments.push(match &node.record {
+                NodeRecord::Full(record) => record.meta.short_name.0.as_str(),
+                NodeRecord::Delta(record) => record.decl_id.0.as_str(),
+            });
+            current = parent;
+        }
+        segments.reverse();
+        format!("/{}", segments.join("/"))
+    }
+
+    /// Whether `key` is `ancestor` or lies below it.
+    fn is_within(&self, key: &NodeKey, ancestor: &NodeKey) -> bool {
+        let mut current = Some(key);
+        while let Some(key) = current {
+            if key == ancestor {
+                return true;
+            }
+            current = self.parent(key);
+        }
+        false
+    }
+
+    fn detach(&mut self, key: &NodeKey) {
+        if let Some(parent) = self.parent(key).cloned()
+            && let Some(parent) = self.nodes.get_mut(&parent)
+        {
+            parent.children.retain(|child| child != key);
+        }
+    }
+
+    fn attach(&mut self, key: &NodeKey, parent: &NodeKey, index: usize) {
+        if let Some(parent) = self.nodes.get_mut(parent) {
+            let index = index.min(parent.children.len());
+            parent.children.insert(index, key.clone());
+        }
+        if let Some(node) = self.nodes.get_mut(key) {
+            node.parent = Some(parent.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &NodeKey) {
+        self.detach(key);
+        let mut stack = vec![key.clone()];
+        while let Some(key) = stack.pop() {
+            if let Some(node) = self.nodes.remove(&key) {
+                stack.extend(node.children);
+            }
+        }
+    }
+
+    /// Applies a change found by [`diff`]; `false` when the nodes it names
+    /// are missing or a move would put a node inside itself.
+    fn apply(&mut self, change: &Change) -> bool {
+        let key = &change.node;
+        match &change.kind {
+            ChangeKind::Added {
+                parent,
+                index,
+                record,
+            } => {
+                if self.nodes.contains_key(key) || !self.nodes.contains_key(parent) {
+                    return false;
+                }
+                self.insert(key.clone(), (**record).clone(), Some(parent.clone()));
+                self.attach(key, parent, *index);
+            }
+            ChangeKind::Removed => self.remove(key),
+            ChangeKind::Moved {
+                parent,
+                index,
+                ..
+            } => {
+                if !self.nodes.contains_key(key)
+                    || !self.nodes.contains_key(parent)
+                    || self.is_within(parent, key)
+                {
+                    return false;
+                }
+                self.detach(key);
+                self.attach(key, parent, *index);
+            }
+            ChangeKind::Retyped {
+                to,
+                ..
+            } => match self.nodes.get_mut(key).map(|node| &mut node.record) {
+                Some(NodeRecord::Full(record)) => record.node_type = to.clone(),
+                _ => return false,
+            },
+            ChangeKind::Value {
+                to,
+                ..
+            } => match self.nodes.get_mut(key).map(|node| &mut node.record) {
+                Some(NodeRecord::Full(record)) => match (&mut record.data.parameter, to) {
+                    (Some(param), Some(to)) => param.value = to.clone(),
+                    _ => return false,
+                },
+                Some(NodeRecord::Delta(record)) => record.value = to.clone(),
+                None => return false,
+            },
+            ChangeKind::Field {
+                name,
+                to,
+                ..
+            } => match self.nodes.get_mut(key) {
+                Some(node) => set_field(&mut node.record, name, to.as_ref()),
+                None => return false,
+            },
+        }
+        true
+    }
+}
+
+fn key_of(parent: &NodeKey, record: &NodeRecord) -> NodeKey {
+    match record {
+        NodeRecord::Full(record) => NodeKey::Uuid(record.uuid),
+        NodeRecord::Delta(record) => {
+            NodeKey::Declared(Box::new(parent.clone()), record.decl_id.clone())
+        }
+    }
+}
+
+fn children_mut(record: &mut NodeRecord) -> &mut Vec<NodeRecord> {
+    match record {
+        NodeRecord::Full(record) => &mut record.children,
+        NodeRecord::Delta(record) => &mut record.children,
+    }
+}
+
+fn diff_trees(old: &Tree, new: &Tree) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for key in new.keys() {
+        let node = &new.nodes[&key];
+        let path = new.path(&key);
+        let Some(before) = old.nodes.get(&key) else {
+            if let Some(parent) = &node.parent
+                && old.nodes.contains_key(parent)
+            {
+                changes.push(Change {
+                    node: key.clone(),
+                    path,
+                    kind: ChangeKind::Added {
+                        parent: parent.clone(),
+                        index: new.index(&key),
+                        record: Box::new(new.record(&key)),
+                    },
+                });
+            }
+            continue;
+        };
+        if let Some(parent) = &node.parent
+            && before.parent.as_ref() != Some(parent)
+        {
+            changes.push(Change {
+                node: key.clone(),
+                path: path.clone(),
+                kind: ChangeKind::Moved {
+                    from: old.path(&key),
+                    parent: parent.clone(),
+                    index: new.index(&key),
+                },
+            });
+        }
+        compare_records(&before.record, &node.record, |kind| {
+            changes.push(Change {
+                node: key.clone(),
+                path: path.clone(),
+                kind,
+            })
+        });
+        reordered_children(old, new, &key, &mut changes);
+    }
+    for key in old.keys() {
+        if !new.nodes.contains_key(&key)
+            && old.parent(&key).is_some_and(|parent| new.nodes.contains_key(parent))
+        {
+            changes.push(Change {
+                path: old.path(&key),
+                node: key,
+                kind: ChangeKind::Removed,
+            });
+        }
+    }
+    changes
+}
+
+/// Reports children of `parent` that stayed under it but changed places: the
+/// ones outside the longest run of siblings kept in the same order.
+fn reordered_children(old: &Tree, new: &Tree, parent: &NodeKey, changes: &mut Vec<Change>) {
+    let stayed = |tree: &Tree, other: &Tree| -> Vec<NodeKey> {
+        tree.nodes[parent]
+            .children
+            .iter()
+            // Declared nodes keep the order their schema gives them.
+            .filter(|child| !matches!(child, NodeKey::Declared(..)))
+            .filter(|child| other.parent(child) == Some(parent))
+            .cloned()
+            .collect()
+    };
+    let before = stayed(old, new);
+    let after = stayed(new, old);
+    if before == after {
+        return;
+    }
+    let in_order = longest_common_subsequence(&before, &after);
+    for child in after.iter().filter(|child| !in_order.contains(child)) {
+        changes.push(Change {
+            node: child.clone(),
+            path: new.path(child),
+            kind: ChangeKind::Moved {
+                from: old.path(child),
+                parent: parent.clone(),
+                index: new.index(child),
+            },
+        });
+    }
+}
+
+fn longest_common_subsequence<'a>(a: &[NodeKey], b: &'a [NodeKey]) -> HashSet<&'a NodeKey> {
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut common = HashSet::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            common.insert(&b[j]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// Reports how one version of a node's record differs from another, children
+/// aside.
+fn compare_records(old: &NodeRecord, new: &NodeRecord, mut change: impl FnMut(ChangeKind)) {
+    if let (NodeRecord::Full(old), NodeRecord::Full(new)) = (old, new)
+        && old.node_type != new.node_type
+    {
+        change(ChangeKind::Retyped {
+            from: old.node_type.clone(),
+            to: new.node_type.clone(),
+        });
+    }
+    let (old_value, new_value) = (value_of(old), value_of(new));
+    if old_value != new_value {
+        change(ChangeKind::Value {
+            from: old_value.cloned(),
+            to: new_value.cloned(),
+        });
+    }
+    let (old_fields, new_fields) = (fields_of(old), fields_of(new));
+    let mut names: Vec<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let (from, to) = (old_fields.get(name), new_fields.get(name));
+        if from != to {
+            change(ChangeKind::Field {
+                name: name.clone(),
+                from: from.cloned(),
+                to: to.cloned(),
+            });
+        }
+    }
+}
+
+fn value_of(record: &NodeRecord) -> Option<&Value> {
+    match record {
+        NodeRecord::Full(record) => record.data.parameter.as_ref().map(|param| &param.value),
+        NodeRecord::Delta(record) => record.value.as_ref(),
+    }
+}
+
+/// A record's metadata and, for full records, its node data without the
+/// parameter value, as named JSON fields. Unset fields are left out.
+fn fields_of(record: &NodeRecord) -> Map<String, Json> {
+    match record {
+        NodeRecord::Full(record) => {
+            let mut fields = json_object(serde_json::to_value(&record.meta));
+            fields.remove("uuid");
+            let mut data = serde_json::to_value(&record.data).unwrap_or_default();
+            if let Some(Json::Object(param)) = data.get_mut("parameter") {
+                param.remove("value");
+            }
+            fields.insert("data".to_string(), data);
+            fields
+        }
+        NodeRecord::Delta(record) => {
+            let mut fields = json_object(serde_json::to_value(&record.meta));
+            fields.retain(|_, value| !value.is_null());
+            if let Some(uuid) = &record.uuid {
+                fields.insert("uuid".to_string(), serde_json::to_value(uuid).unwrap_or_default());
+            }
+            fields
+        }
+    }
+}
+
+fn json_object(value: serde_json::Result<Json>) -> Map<String, Json> {
+    match value {
+        Ok(Json::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn set_field(record: &mut NodeRecord, name: &str, to: Option<&Json>) {
+    match record {
+        NodeRecord::Full(record) if name == "data" => {
+            let Some(mut data) = to.cloned() else {
+                return;
+            };
+            // The value is compared on its own; keep the merged one.
+            if let (Some(param), Some(Json::Object(target))) =
+                (&record.data.parameter, data.get_mut("parameter"))
+                && let Ok(value) = serde_json::to_value(&param.value)
+            {
+                target.insert("value".to_string(), value);
+            }
+            if let Ok(data) = serde_json::from_value::<NodeDataDto>(data) {
+                record.data = data;
+            }
+        }
+        // A value the field cannot hold leaves the record as it was.
+        NodeRecord::Delta(record) if name == "uuid" => {
+            record.uuid = to.and_then(|to| serde_json::from_value(to.clone()).ok());
+        }
+        NodeRecord::Full(record) => {
+            let meta = with_field(serde_json::to_value(&record.meta), name, to);
+            if let Ok(meta) = serde_json::from_value(meta) {
+                record.meta = meta;
+            }
+        }
+        NodeRecord::Delta(record) => {
+            let patch = record.meta.take().unwrap_or_default();
+            let updated = with_field(serde_json::to_value(&patch), name, to);
+            let patch = serde_json::from_value(updated).unwrap_or(patch);
+            record.meta = (patch != NodeMetaPatch::default()).then_some(patch);
+        }
+    }
+}
+
+/// A serialized struct with one field set, or cleared when `to` is `None`.
+fn with_field(value: serde_json::Result<Json>, name: &str, to: Option<&Json>) -> Json {
+    let mut fields = json_object(value);
+    match to {
+        Some(to) => fields.insert(name.to_string(), to.clone()),
+        None => fields.remove(name),
+    };
+    Json::Object(fields)
+}
+
+fn removals(changes: &[Change]) -> HashMap<&NodeKey, &Change> {
+    changes
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Removed)
+        .map(|change| (&change.node, change))
+        .collect()
+}
+
+/// The removal, among the other side's, of a node `change` touches or one of
+/// its ancestors, where it was in the base or where `change` puts it.
+fn removed_above<'a>(
+    base: &Tree,
+    source: &Tree,
+    change: &Change,
+    removals: &HashMap<&NodeKey, &'a Change>,
+) -> Option<&'a Change> {
+    let mut touched = vec![&change.node];
+    if let ChangeKind::Added {
+        parent,
+        ..
+    }
+    | ChangeKind::Moved {
+        parent,
+        ..
+    } = &change.kind
+    {
+        touched.push(parent);
+    }
+    for tree in [base, source] {
+        for key in &touched {
+            let mut current = Some(*key);
+            while let Some(key) = current {
+                if let Some(removal) = removals.get(key) {
+                    return Some(removal);
+                }
+                current = tree.parent(key);
+            }
+        }
+    }
+    None
+}
+
+/// Merges two edits of the same curve or gradient that touched different
+/// keys. Gives up when a key was changed differently on each side.
+fn merge_keyed_values(ours: &ChangeKind, theirs: &ChangeKind) -> Option<ChangeKind> {
+    let (
+        ChangeKind::Value {
+            from: Some(base),
+            to: Some(ours),
+        },
+        ChangeKind::Value {
+            to: Some(theirs),
+            ..
+        },
+    ) = (ours, theirs)
+    else {
+        return None;
+    };
+    let merged = match (base, ours, theirs) {
+        (Value::Curve(base), Value::Curve(ours), Value::Curve(theirs)) => {
+            let mut merged = ours.clone();
+            merged.keys = merge_by_id(&base.keys, &ours.keys, &theirs.keys, |key| key.id)?;
+            merged.keys.sort_by(|a, b| a.x.total_cmp(&b.x));
+            Value::Curve(merged)
+        }
+        (Value::Gradient(base), Value::Gradient(ours), Value::Gradient(theirs)) => {
+            let mut merged = ours.clone();
+            merged.stops = merge_by_id(&base.stops, &ours.stops, &theirs.stops, |stop| stop.id)?;
+            merged.stops.sort_by(|a, b| a.t.total_cmp(&b.t));
+            Value::Gradient(merged)
+        }
+        _ => return None,
+    };
+    Some(ChangeKind::Value {
+        from: Some(base.clone()),
+        to: Some(merged),
+    })
+}
+
+fn merge_by_id<T: Clone + PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    id: impl Fn(&T) -> Uuid,
+) -> Option<Vec<T>> {
+    let find = |items: &[T], key: Uuid| items.iter().find(|item| id(item) == key).cloned();
+    let mut ids = Vec::new();
+    for item in ours.iter().chain(theirs).chain(base) {
+        if !ids.contains(&id(item)) {
+            ids.push(id(item));
+        }
+    }
+    let mut merged = Vec::new();
+    for key in ids {
+        let (base, ours, theirs) = (find(base, key), find(ours, key), find(theirs, key));
+        merged.extend(if ours == base {
+            theirs
+        } else if theirs == base || theirs == ours {
+            ours
+        } else {
+            return None;
+        });
+    }
+    Some(merged)
+}
+
+/// Presets are matched by name; ours is kept where both sides changed one.
+fn merge_presets(
+    base: &[Preset],
+    ours: &[Preset],
+    theirs: &[Preset],
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<Preset> {
+    let find = |presets: &[Preset], name: &str| -> Option<Preset> {
+        presets.iter().find(|preset| preset.name == name).cloned()
+    };
+    let mut names = Vec::new();
+    for preset in ours.iter().chain(theirs).chain(base) {
+        if !names.contains(&preset.name) {
+            names.push(preset.name.clone());
+        }
+    }
+    let mut merged = Vec::new();
+    for name in names {
+        let (base, ours, theirs) = (find(base, &name), find(ours, &name), find(theirs, &name));
+        if ours == base {
+            merged.extend(theirs);
+            continue;
+        }
+        if theirs != base && theirs != ours {
+            conflicts.push(Conflict::Preset(name));
+        }
+        merged.extend(ours);
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::persistence::FullNodeRecord;
+
+    use crate::engine::Engine;
+    use crate::persistence::save::export_project;
+
+    use super::*;
+
+    /// `/a`, `/b` and `/group/c`, all floats at 0.
+    fn base() -> ProjectFile {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        engine.create_child_parameter(root, "a", Value::Float(0.0));
+        engine.create_child_parameter(root, "b", Value::Float(0.0));
+        let group = engine.create_child_container(root, "Container", "group");
+        engine.create_child_parameter(group, "c", Value::Float(0.0));
+        export_project(&engine, root, "test")
+    }
+
+    fn children(record: &mut NodeRecord) -> &mut Vec<NodeRecord> {
+        match record {
+            NodeRecord::Full(record) => &mut record.children,
+            NodeRecord::Delta(record) => &mut record.children,
+        }
+    }
+
+    /// The full record labelled `label` anywhere below `record`.
+    fn node<'a>(record: &'a mut NodeRecord, label: &str) -> &'a mut FullNodeRecord {
+        fn find<'a>(record: &'a mut NodeRecord, label: &str) -> Option<&'a mut FullNodeRecord> {
+            // Checked before binding, as returning from a guarded arm keeps
+            // `record` borrowed for the fallback.
+            if matches!(record, NodeRecord::Full(full) if full.meta.label == label) {
+                let NodeRecord::Full(full) = record else {
+                    unreachable!();
+                };
+                return Some(full);
+            }
+            children(record).iter_mut().find_map(|child| find(child, label))
+        }
+        find(record, label).unwrap_or_else(|| panic!("no node labelled {label}"))
+    }
+
+    fn set(project: &mut ProjectFile, label: &str, value: f64) {
+        node(&mut project.root, label).data.parameter.as_mut().unwrap().value = Value::Float(value);
+    }
+
+    fn value_of(project: &mut ProjectFile, label: &str) -> Value {
+        node(&mut project.root, label).data.parameter.as_ref().unwrap().value.clone()
+    }
+
+    /// Takes the node labelled `label` out of its parent's children.
+    fn take(project: &mut ProjectFile, label: &str) -> NodeRecord {
+        fn take_from(record: &mut NodeRecord, label: &str) -> Option<NodeRecord> {
+            let children = children(record);
+            if let Some(index) = children.iter().position(
+                |child| matches!(child, NodeRecord::Full(full) if full.meta.label == label),
+            ) {
+                return Some(children.remove(index));
+            }
+            children.iter_mut().find_map(|child| take_from(child, label))
+        }
+        take_from(&mut project.root, label).unwrap()
+    }
+
+    fn kinds(changes: &[Change]) -> Vec<(&str, &ChangeKind)> {
+        changes.iter().map(|change| (change.path.as_str(), &change.kind)).collect()
+    }
+
+    #[test]
+    fn identical_projects_have_no_changes() {
+        let base = base();
+        assert_eq!(diff(&base, &base.clone()), Vec::new());
+    }
+
+    #[test]
+    fn changes_are_matched_by_identity() {
+        let old = base();
+        let mut new = old.clone();
+        set(&mut new, "a", 1.0);
+        node(&mut new.root, "b").meta.label = "bee".to_string();
+        let c = take(&mut new, "c");
+        children(&mut new.root).push(c);
+        take(&mut new, "group");
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            kinds(&changes),
+            vec![
+                (
+                    "/a",
+                    &ChangeKind::Value {
+                        from: Some(Value::Float(0.0)),
+                        to: Some(Value::Float(1.0)),
+                    }
+                ),
+                (
+                    "/b",
+                    &ChangeKind::Field {
+                        name: "label".to_string(),
+                        from: Some(Json::from("b")),
+                        to: Some(Json::from("bee")),
+                    }
+                ),
+                (
+                    "/c",
+                    &ChangeKind::Moved {
+                        from: "/group/c".to_string(),
+                        parent: NodeKey::Root,
+                        index: 2,
+                    }
+                ),
+                ("/group", &ChangeKind::Removed),
+            ]
+        );
+        assert_eq!(changes[3].to_string(), "- /group");
+    }
+
+    #[test]
+    fn added_nodes_carry_their_subtree() {
+        let old = base();
+        let mut new = old.clone();
+        let mut copy = NodeRecord::Full(node(&mut new.root, "group").clone());
+        let NodeRecord::Full(record) = &mut copy else {
+            unreachable!();
+        };
+        record.uuid = NodeUuid(Uuid::new_v4());
+        record.meta.short_name.0 = "copy".to_string();
+        for child in &mut record.children {
+            if let NodeRecord::Full(child) = child {
+                child.uuid = NodeUuid(Uuid::new_v4());
+            }
+        }
+        children(&mut new.root).push(copy.clone());
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/copy");
+        assert_eq!(
+            changes[0].kind,
+            ChangeKind::Added {
+                parent: NodeKey::Root,
+                index: 3,
+                record: Box::new(copy),
+            }
+        );
+    }
+
+    #[test]
+    fn merge_keeps_changes_from_both_sides() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        set(&mut ours, "a", 1.0);
+        set(&mut theirs, "c", 2.0);
+        node(&mut theirs.root, "a").meta.label = "alpha".to_string();
+
+        let mut merge = merge(&base, &ours, &theirs);
+        assert_eq!(merge.conflicts, Vec::new());
+        assert_eq!(value_of(&mut merge.project, "alpha"), Value::Float(1.0));
+        assert_eq!(value_of(&mut merge.project, "c"), Value::Float(2.0));
+        assert_eq!(diff(&ours, &merge.project).len(), 2);
+    }
+
+    #[test]
+    fn conflicting_changes_keep_ours() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        set(&mut ours, "a", 1.0);
+        set(&mut theirs, "a", 2.0);
+        set(&mut theirs, "b", 3.0);
+
+        let mut merge = merge(&base, &ours, &theirs);
+        let [
+            Conflict::Node {
+                ours,
+                theirs,
+            },
+        ] = &merge.conflicts[..]
+        else {
+            panic!("expected one conflict, got {:?}", merge.conflicts);
+        };
+        assert_eq!((ours.path.as_str(), theirs.path.as_str()), ("/a", "/a"));
+        assert_eq!(value_of(&mut merge.project, "a"), Value::Float(1.0));
+        assert_eq!(value_of(&mut merge.project, "b"), Value::Float(3.0));
+    }
+
+    #[test]
+    fn removals_lose_to_changes_of_ours_inside_them() {
+        let base = base();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        set(&mut ours, "c", 1.0);
+        take(&mut theirs, "group");
+        let mut kept = merge(&base, &ours, &theirs);
+        assert_eq!(kept.conflicts.len(), 1);
+        assert_eq!(value_of(&mut kept.project, "c"), Value::Float(1.0));
+
+        // The other way round, our removal wins over their change.
+        let removed = merge(&base, &theirs, &ours);
+        assert_eq!(removed.conflicts.len(), 1);
+        assert_eq!(diff(&theirs, &removed.project), Vec::new());
+    }
+}
@@ -0,0 +1,185 @@
+use golden_schema::JournalEntry;
+
+use crate::edits::Edit;
+use crate::engine::Engine;
+use crate::graph::node::NodeExecution;
+use crate::persistence::load::insert_subtree;
+
+/// Entries read back from a journal file.
+#[derive(Debug, Default)]
+pub struct JournalContents {
+    /// Checksum of the project file the entries apply on top of.
+    pub base: String,
+    pub entries: Vec<JournalEntry>,
+    /// Whether the last line was cut short, as when power fails mid-write.
+    pub torn: bool,
+}
+
+/// The first line of a journal file, naming the project file it extends.
+pub fn journal_header(base: &str) -> String {
+    format!("{}\n", serde_json::json!({ "base": base }))
+}
+
+/// One entry as a journal line.
+pub fn journal_line(entry: &JournalEntry) -> Result<String, serde_json::Error> {
+    Ok(format!("{}\n", serde_json::to_string(entry)?))
+}
+
+/// Parses a journal file. Reading stops at the first line that does not
+/// parse; `None` means the header itself is missing or unreadable.
+pub fn read_journal(data: &str) -> Option<JournalContents> {
+    let mut lines = data.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next()?).ok()?;
+    let mut contents = JournalContents {
+        base: header.get("base")?.as_str()?.to_string(),
+        ..JournalContents::default()
+    };
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(entry) => contents.entries.push(entry),
+            Err(_) => {
+                contents.torn = true;
+                break;
+            }
+        }
+    }
+    Some(contents)
+}
+
+/// Checksum identifying one version of a project file (64-bit FNV-1a).
+pub fn checksum(data: &[u8]) -> String {
+    let hash = data.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Applies a journaled edit as an internal one, so it is not journaled
+/// again. Returns `false` when a path it names does not resolve.
+pub fn replay_entry(engine: &mut Engine, entry: &JournalEntry) -> bool {
+    match entry {
+        JournalEntry::SetParam {
+            path,
+            value,
+        } => {
+            let Some(node) = engine.resolve_path(path) else {
+                return false;
+            };
+            engine.apply_edit_now(Edit::SetParam {
+                node,
+                value: value.clone(),
+            });
+            true
+        }
+        JournalEntry::PatchMeta {
+            path,
+            patch,
+        } => {
+            let Some(node) = engine.resolve_path(path) else {
+                return false;
+            };
+            engine.apply_edit_now(Edit::PatchMeta {
+                node,
+                patch: patch.clone(),
+            });
+            true
+        }
+        JournalEntry::CreateNode {
+            parent,
+            node_type,
+            label,
+            uuid,
+        } => {
+            // Execution is not journaled; nodes are restored as on import.
+            let created = engine.resolve_path(parent).and_then(|parent| {
+                engine.create_child_of_type(
+                    parent,
+                    node_type.clone(),
+                    label,
+                    NodeExecution::Reactive,
+                )
+            });
+            let Some(node) = created else {
+                return false;
+            };
+            if let Some(mut meta) = engine.nodes.get(&node).map(|entry| entry.meta.clone()) {
+                meta.uuid = *uuid;
+                engine.replace_meta(node, meta);
+            }
+            true
+        }
+        JournalEntry::MoveNode {
+            path,
+            new_parent,
+            index,
+        } => match (engine.resolve_path(path), engine.resolve_path(new_parent)) {
+            (Some(node), Some(new_parent)) => engine.move_node(node, new_parent, *index),
+            _ => false,
+        },
+        JournalEntry::DeleteNode {
+            path,
+        } => engine.resolve_path(path).is_some_and(|node| engine.delete_node(node)),
+        JournalEntry::InsertSubtree {
+            parent,
+            root,
+        } => engine
+            .resolve_path(parent)
+            .is_some_and(|parent| insert_subtree(engine, parent, root).is_ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::Value;
+
+    use crate::graph::node::NodeData;
+
+    use super::*;
+
+    fn set_param(path: &str, value: f64) -> JournalEntry {
+        JournalEntry::SetParam {
+            path: path.to_string(),
+            value: Value::Float(value),
+        }
+    }
+
+    #[test]
+    fn reading_stops_at_a_torn_line() {
+        let mut data = journal_header("abc");
+        data.push_str(&journal_line(&set_param("/gain", 0.5)).unwrap());
+        data.push_str("{\"SetParam\":{\"path\":\"/ga");
+        let journal = read_journal(&data).unwrap();
+        assert_eq!(journal.base, "abc");
+        assert_eq!(journal.entries, vec![set_param("/gain", 0.5)]);
+        assert!(journal.torn);
+
+        assert!(read_journal("").is_none());
+        assert!(read_journal("{\"SetParam\":{}}\n").is_none());
+    }
+
+    #[test]
+    fn checksums_tell_file_versions_apart() {
+        assert_eq!(checksum(b"project"), checksum(b"project"));
+        assert_ne!(checksum(b"project"), checksum(b"projecT"));
+        assert_eq!(checksum(b"").len(), 16);
+    }
+
+    #[test]
+    fn replayed_entries_are_applied_without_being_journaled_again() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.0));
+        engine.set_journaling(true);
+
+        assert!(replay_entry(&mut engine, &set_param("/gain", 0.25)));
+        assert!(!replay_entry(&mut engine, &set_param("/missing", 1.0)));
+        engine.tick();
+        match &engine.nodes.get(&gain).unwrap().data {
+            NodeData::Parameter(param) => {
+                assert_eq!(param.value, Value::Float(0.25))
+            }
+            _ => panic!("not a parameter"),
+        }
+        assert!(engine.take_journal().is_empty());
+    }
+}
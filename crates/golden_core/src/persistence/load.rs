@@ -1,5 +1,398 @@
-use golden_schema::persistence::file_format::ProjectFile;
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+use golden_schema::persistence::file_format::{FragmentFile, ProjectFile};
+use golden_schema::persistence::{
+    DeltaNodeRecord, FullNodeRecord, NodeDataDto, NodeDataKind, NodeRecord,
+};
+use golden_schema::{DeclId, JournalEntry, NodeId, NodeTypeId, NodeUuid, SavePolicy, Value};
+use uuid::Uuid;
+
+use crate::data::{AllowedTypes, ContainerData, ContainerLimits, FolderPolicy};
+use crate::edits::validate::validate_subtree;
+use crate::edits::{Edit, EditError};
+use crate::engine::Engine;
+use crate::graph::node::{NodeData, NodeExecution};
 
 pub fn load_project(data: &str) -> Result<ProjectFile, serde_json::Error> {
     serde_json::from_str(data)
 }
+
+#[derive(Debug)]
+pub enum ImportError {
+    UnknownNode(NodeId),
+    /// A full record for the import root names a different node type.
+    RootTypeMismatch {
+        expected: NodeTypeId,
+        found: NodeTypeId,
+    },
+    /// A fragment's root cannot be created under the chosen parent.
+    Incompatible(EditError),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::UnknownNode(node) => write!(f, "node {} does not exist", node.0),
+            ImportError::RootTypeMismatch {
+                expected,
+                found,
+            } => write!(f, "cannot import a {} record into a {} node", found.0, expected.0),
+            ImportError::Incompatible(err) => write!(f, "cannot paste here: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Records that could not be matched to or created in the graph.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Delta records whose declared child no longer exists.
+    pub skipped: Vec<DeclId>,
+    /// Full records whose node could not be created under their parent.
+    pub rejected: Vec<NodeTypeId>,
+}
+
+/// Makes the subtree at `root` match a project file.
+///
+/// Children are matched by uuid first, then by `decl_id` and type, so a file
+/// saved by an earlier run maps onto nodes rebuilt at startup. Unmatched dynamic
+/// children are deleted, unmatched declared parameters reset to their default,
+/// and records without a counterpart are created. Presets in the file are added
+/// to the engine's library, replacing it entirely when `root` is the engine root.
+pub fn import_project(
+    engine: &mut Engine,
+    root: NodeId,
+    project: &ProjectFile,
+) -> Result<ImportReport, ImportError> {
+    let node = engine.nodes.get(&root).ok_or(ImportError::UnknownNode(root))?;
+    if let NodeRecord::Full(record) = &project.root
+        && record.node_type != node.node_type
+    {
+        return Err(ImportError::RootTypeMismatch {
+            expected: node.node_type.clone(),
+            found: record.node_type.clone(),
+        });
+    }
+
+    let mut report = ImportReport::default();
+    apply_record(engine, root, &project.root, &mut report);
+    if root == engine.root_id() {
+        engine.presets.clear();
+    }
+    for preset in &project.presets {
+        engine.presets.insert(preset.clone());
+    }
+    Ok(report)
+}
+
+/// Pastes a fragment from [`export_fragment`] as a new child of `parent` and
+/// returns the node created for its root.
+///
+/// Every node of the fragment gets a fresh uuid; see [`fragment_subtree`].
+/// Unlike a project import, a paste is a user edit: it counts towards
+/// [`Engine::edit_revision`] and is journaled. Clients paste by queueing
+/// [`Edit::InsertSubtree`] instead, so the paste is validated and recorded
+/// like their other edits.
+///
+/// [`export_fragment`]: crate::persistence::save::export_fragment
+pub fn import_fragment(
+    engine: &mut Engine,
+    parent: NodeId,
+    fragment: &FragmentFile,
+) -> Result<(NodeId, ImportReport), ImportError> {
+    let root = fragment_subtree(fragment);
+    let (node, report) = insert_subtree(engine, parent, &root)?;
+    if let Some(parent) = engine.path_of(parent).map(str::to_string) {
+        engine.journal_entry_committed(JournalEntry::InsertSubtree {
+            parent,
+            root: Box::new(root),
+        });
+    }
+    Ok((node, report))
+}
+
+/// The fragment's subtree with a fresh uuid for every node. References
+/// between its nodes follow; references to nodes outside it are kept.
+pub fn fragment_subtree(fragment: &FragmentFile) -> FullNodeRecord {
+    let mut root = NodeRecord::Full(fragment.root.clone());
+    let mut fresh = HashMap::new();
+    assign_fresh_uuids(&mut root, &mut fresh);
+    remap_references(&mut root, &fresh);
+    let NodeRecord::Full(root) = root else {
+        unreachable!("the root stays a full record");
+    };
+    root
+}
+
+/// Creates `root` and its subtree under `parent`, keeping the uuids in the
+/// records.
+pub(crate) fn insert_subtree(
+    engine: &mut Engine,
+    parent: NodeId,
+    root: &FullNodeRecord,
+) -> Result<(NodeId, ImportReport), ImportError> {
+    validate_subtree(engine, parent, root).map_err(ImportError::Incompatible)?;
+    let mut report = ImportReport::default();
+    let record = NodeRecord::Full(root.clone());
+    let node = create_from_record(engine, parent, &record, &mut report).ok_or_else(|| {
+        ImportError::Incompatible(EditError::TypeNotAllowed {
+            parent,
+            node_type: root.node_type.clone(),
+        })
+    })?;
+    apply_record(engine, node, &record, &mut report);
+    Ok((node, report))
+}
+
+fn assign_fresh_uuids(record: &mut NodeRecord, fresh: &mut HashMap<NodeUuid, NodeUuid>) {
+    let mut renew = |uuid: &mut NodeUuid| {
+        let new = *fresh.entry(*uuid).or_insert_with(|| NodeUuid(Uuid::new_v4()));
+        *uuid = new;
+    };
+    let children = match record {
+        NodeRecord::Full(full) => {
+            renew(&mut full.uuid);
+            renew(&mut full.meta.uuid);
+            &mut full.children
+        }
+        NodeRecord::Delta(delta) => {
+            if let Some(uuid) = &mut delta.uuid {
+                renew(uuid);
+            }
+            &mut delta.children
+        }
+    };
+    for child in children {
+        assign_fresh_uuids(child, fresh);
+    }
+}
+
+fn remap_references(record: &mut NodeRecord, fresh: &HashMap<NodeUuid, NodeUuid>) {
+    let (value, children) = match record {
+        NodeRecord::Full(full) => {
+            (full.data.parameter.as_mut().map(|param| &mut param.value), &mut full.children)
+        }
+        NodeRecord::Delta(delta) => (delta.value.as_mut(), &mut delta.children),
+    };
+    if let Some(Value::Reference(reference)) = value
+        && let Some(uuid) = fresh.get(&reference.uuid)
+    {
+        reference.uuid = *uuid;
+        reference.cached_id = None;
+    }
+    for child in children {
+        remap_references(child, fresh);
+    }
+}
+
+fn apply_record(engine: &mut Engine, node: NodeId, record: &NodeRecord, report: &mut ImportReport) {
+    match record {
+        NodeRecord::Full(full) => {
+            engine.replace_meta(node, full.meta.clone());
+            if let Some(param) = &full.data.parameter {
+                engine.apply_edit_now(Edit::SetParam {
+                    node,
+                    value: param.value.clone(),
+                });
+            }
+            sync_children(engine, node, &full.children, report);
+        }
+        NodeRecord::Delta(delta) => {
+            apply_delta(engine, node, delta);
+            sync_children(engine, node, &delta.children, report);
+        }
+    }
+}
+
+fn apply_delta(engine: &mut Engine, node: NodeId, delta: &DeltaNodeRecord) {
+    if let Some(uuid) = delta.uuid
+        && let Some(mut meta) = engine.nodes.get(&node).map(|entry| entry.meta.clone())
+        && meta.uuid != uuid
+    {
+        meta.uuid = uuid;
+        engine.replace_meta(node, meta);
+    }
+    if let Some(patch) = &delta.meta {
+        engine.apply_edit_now(Edit::PatchMeta {
+            node,
+            patch: patch.clone(),
+        });
+    }
+    if let Some(value) = &delta.value {
+        engine.apply_edit_now(Edit::SetParam {
+            node,
+            value: value.clone(),
+        });
+    }
+}
+
+fn sync_children(
+    engine: &mut Engine,
+    parent: NodeId,
+    records: &[NodeRecord],
+    report: &mut ImportReport,
+) {
+    let existing = children_of(engine, parent);
+    let mut matched = HashSet::new();
+
+    for record in records {
+        let target = match_child(engine, &existing, &matched, record)
+            .or_else(|| create_from_record(engine, parent, record, report));
+        let Some(target) = target else {
+            continue;
+        };
+        matched.insert(target);
+        apply_record(engine, target, record, report);
+    }
+
+    for child in existing {
+        if matched.contains(&child) {
+            continue;
+        }
+        if engine.is_declared_child(child) {
+            reset_to_default(engine, child);
+        } else {
+            engine.delete_node(child);
+        }
+    }
+}
+
+fn match_child(
+    engine: &Engine,
+    candidates: &[NodeId],
+    matched: &HashSet<NodeId>,
+    record: &NodeRecord,
+) -> Option<NodeId> {
+    let (uuid, decl_id, node_type) = match record {
+        NodeRecord::Full(full) => (
+            Some(full.uuid),
+            full.decl_id.as_ref().unwrap_or(&full.meta.decl_id),
+            Some(&full.node_type),
+        ),
+        NodeRecord::Delta(delta) => (delta.uuid, &delta.decl_id, None),
+    };
+    let available = || {
+        candidates
+            .iter()
+            .copied()
+            .filter(|id| !matched.contains(id))
+            .filter_map(|id| engine.nodes.get(&id).map(|node| (id, node)))
+    };
+
+    if let Some(uuid) = uuid
+        && let Some((id, _)) = available().find(|(_, node)| node.meta.uuid == uuid)
+    {
+        return Some(id);
+    }
+    available()
+        .find(|(_, node)| {
+            &node.meta.decl_id == decl_id && node_type.is_none_or(|ty| &node.node_type == ty)
+        })
+        .map(|(id, _)| id)
+}
+
+fn create_from_record(
+    engine: &mut Engine,
+    parent: NodeId,
+    record: &NodeRecord,
+    report: &mut ImportReport,
+) -> Option<NodeId> {
+    let full = match record {
+        NodeRecord::Full(full) => full,
+        NodeRecord::Delta(delta) => {
+            report.skipped.push(delta.decl_id.clone());
+            return None;
+        }
+    };
+
+    let parent_is_manager =
+        engine.nodes.get(&parent).is_some_and(|node| matches!(node.data, NodeData::Manager(_)));
+    let created = if parent_is_manager {
+        engine.create_child_of_type(
+            parent,
+            full.node_type.clone(),
+            &full.meta.label,
+            NodeExecution::Reactive,
+        )
+    } else {
+        create_plain_node(engine, parent, full)
+    };
+    if created.is_none() {
+        report.rejected.push(full.node_type.clone());
+    }
+    created
+}
+
+fn create_plain_node(
+    engine: &mut Engine,
+    parent: NodeId,
+    record: &FullNodeRecord,
+) -> Option<NodeId> {
+    let (data, execution) = node_data_from_dto(&record.data)?;
+    let node =
+        engine.create_node(record.node_type.clone(), execution, data, record.meta.clone(), None);
+    engine.add_child(parent, node);
+    Some(node)
+}
+
+fn node_data_from_dto(dto: &NodeDataDto) -> Option<(NodeData, NodeExecution)> {
+    match &dto.kind {
+        NodeDataKind::None => Some((NodeData::None, NodeExecution::Reactive)),
+        NodeDataKind::Container => {
+            let container = dto.container.as_ref();
+            let allowed_types = match container {
+                Some(container) if !container.allowed_types.is_empty() => {
+                    AllowedTypes::Only(container.allowed_types.clone())
+                }
+                _ => AllowedTypes::Any,
+            };
+            let folders = if container.is_none_or(|container| container.folders_allowed) {
+                FolderPolicy::Allowed
+            } else {
+                FolderPolicy::Forbidden
+            };
+            Some((
+                NodeData::Container(ContainerData {
+                    allowed_types,
+                    folders,
+                    limits: ContainerLimits {
+                        max_children: None,
+                    },
+                }),
+                NodeExecution::Passive,
+            ))
+        }
+        NodeDataKind::Parameter => {
+            dto.parameter.clone().map(|param| (NodeData::Parameter(param), NodeExecution::Passive))
+        }
+        // Managers and custom data are built by code and cannot be restored from a file.
+        NodeDataKind::Custom(_) => None,
+    }
+}
+
+/// Resets a declared parameter the file has no value for. Runtime-only
+/// parameters are never saved, so they keep their current value.
+fn reset_to_default(engine: &mut Engine, node: NodeId) {
+    let default = engine.nodes.get(&node).and_then(|entry| match &entry.data {
+        NodeData::Parameter(param) if param.save != SavePolicy::None => param.default.clone(),
+        _ => None,
+    });
+    if let Some(value) = default {
+        engine.apply_edit_now(Edit::SetParam {
+            node,
+            value,
+        });
+    }
+}
+
+fn children_of(engine: &Engine, parent: NodeId) -> Vec<NodeId> {
+    let mut children = Vec::new();
+    let mut current = engine.nodes.get(&parent).and_then(|node| node.first_child);
+    while let Some(child) = current {
+        children.push(child);
+        current = engine.nodes.get(&child).and_then(|node| node.next_sibling);
+    }
+    children
+}
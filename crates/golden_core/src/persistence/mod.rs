@@ -1,5 +1,12 @@
+pub mod diff;
+pub mod journal;
 pub mod load;
 pub mod migrate;
+pub mod recording;
 pub mod save;
+pub mod store;
 
-pub use save::{export_project, save_project};
+pub use load::{ImportError, ImportReport, import_fragment, import_project, load_project};
+pub use recording::{Recorder, Replay, ReplayError, read_recording};
+pub use save::{export_fragment, export_project, save_project};
+pub use store::{ProjectStore, Recovery, StoreError, write_atomic};
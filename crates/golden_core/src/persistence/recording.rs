@@ -0,0 +1,551 @@
+use core::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use golden_schema::ui::messages;
+use golden_schema::{
+    RecordedEasing, RecordedEdit, RecordedExecution, RecordedNode, RecordedRequest, RecordedTick,
+    RecordingHeader,
+};
+
+use crate::edits::{Edit, EditRequest, Propagation};
+use crate::engine::{Easing, Engine};
+use crate::graph::node::NodeExecution;
+use crate::persistence::store::StoreError;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The recording has no readable header.
+    Format(serde_json::Error),
+    /// The engine built for replay is not in the state recording started
+    /// from: another project, another tick, or nodes created in another order.
+    StartMismatch,
+    /// A replayed tick emitted other events than the recorded one.
+    Diverged {
+        tick: u64,
+        events: usize,
+        expected_events: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Format(err) => write!(f, "invalid recording: {err}"),
+            ReplayError::StartMismatch => {
+                write!(f, "the engine is not in the state the recording started from")
+            }
+            ReplayError::Diverged {
+                tick,
+                events,
+                expected_events,
+            } => write!(
+                f,
+                "tick {tick} emitted {events} events that differ from the {expected_events} recorded"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(err: serde_json::Error) -> Self {
+        ReplayError::Format(err)
+    }
+}
+
+/// Writes what an engine is fed to a file as it runs, one line per tick:
+/// the external edits the tick applied, its clock step, and a digest of the
+/// events it emitted. [`Replay`] feeds the same input to a fresh engine.
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Creates `path` and starts recording `engine` from its current state.
+    pub fn create(path: &Path, engine: &mut Engine, version: &str) -> Result<Self, StoreError> {
+        let header = RecordingHeader {
+            version: version.to_string(),
+            start_tick: engine.time.tick,
+            fingerprint: engine.state_fingerprint(),
+            nodes: engine
+                .nodes
+                .values()
+                .map(|node| RecordedNode {
+                    node: node.id,
+                    uuid: node.meta.uuid,
+                })
+                .collect(),
+        };
+        let mut file = File::create(path)?;
+        file.write_all(format!("{}\n", serde_json::to_string(&header)?).as_bytes())?;
+        engine.set_recording(true);
+        Ok(Self {
+            file,
+        })
+    }
+
+    /// Appends the ticks run since the last call. Call after each engine tick;
+    /// lines are handed to the OS right away, so they outlive a crash of the
+    /// process.
+    pub fn tick(&mut self, engine: &mut Engine) -> Result<(), StoreError> {
+        let mut lines = String::new();
+        for tick in engine.take_recording() {
+            lines.push_str(&serde_json::to_string(&tick)?);
+            lines.push('\n');
+        }
+        if !lines.is_empty() {
+            self.file.write_all(lines.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining ticks and stops recording.
+    pub fn finish(mut self, engine: &mut Engine) -> Result<(), StoreError> {
+        self.tick(engine)?;
+        engine.set_recording(false);
+        Ok(())
+    }
+}
+
+/// Parses a recording file. Reading stops at the first tick that does not
+/// parse, as when the recording process died mid-write.
+pub fn read_recording(
+    data: &str,
+) -> Result<(RecordingHeader, Vec<RecordedTick>), serde_json::Error> {
+    let mut lines = data.lines();
+    let header = serde_json::from_str(lines.next().unwrap_or_default())?;
+    let ticks = lines
+        .filter(|line| !line.trim().is_empty())
+        .map_while(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok((header, ticks))
+}
+
+/// Feeds a recording to an engine tick by tick and checks that each tick
+/// emits the events it emitted when recorded.
+///
+/// Replay is exact when the engine starts from the same graph, with the same
+/// `NodeId`s, as the recorded one. Changes made outside the edit queue, such
+/// as calling `import_fragment` directly, are not recorded; the tick after
+/// one diverges. Queued imports and pastes are recorded like other edits.
+pub struct Replay {
+    build: Box<dyn FnMut() -> Engine>,
+    engine: Engine,
+    header: RecordingHeader,
+    ticks: Vec<RecordedTick>,
+    position: usize,
+}
+
+impl Replay {
+    /// Reads a recording and calls `build` for the engine to replay it on,
+    /// typically by building the graph and importing the same project file
+    /// as the recorded run. `build` is called again to seek backwards.
+    pub fn new(data: &str, build: impl FnMut() -> Engine + 'static) -> Result<Self, ReplayError> {
+        let (header, ticks) = read_recording(data)?;
+        let mut build: Box<dyn FnMut() -> Engine> = Box::new(build);
+        let engine = start(&mut build, &header)?;
+        Ok(Self {
+            build,
+            engine,
+            header,
+            ticks,
+            position: 0,
+        })
+    }
+
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub fn header(&self) -> &RecordingHeader {
+        &self.header
+    }
+
+    pub fn ticks(&self) -> &[RecordedTick] {
+        &self.ticks
+    }
+
+    /// Number of recorded ticks replayed so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.ticks.len()
+    }
+
+    /// Replays the next recorded tick. Returns `false` once every tick has
+    /// been replayed. The tick is replayed even when it diverges, so stepping
+    /// on shows how far the difference spreads.
+    pub fn step(&mut self) -> Result<bool, ReplayError> {
+        let Some(recorded) = self.ticks.get(self.position) else {
+            return Ok(false);
+        };
+        for request in &recorded.requests {
+            let request = edit_request(request);
+            self.engine.enqueue_edit(request.edit, request.propagation, request.origin);
+        }
+        self.engine.tick_by(recorded.step);
+        for created in &recorded.created {
+            self.engine.restore_uuid(created.node, created.uuid);
+        }
+        self.position += 1;
+
+        let replayed = self.engine.take_recording().pop();
+        match replayed {
+            Some(replayed)
+                if replayed.tick == recorded.tick
+                    && replayed.events == recorded.events
+                    && replayed.digest == recorded.digest =>
+            {
+                Ok(true)
+            }
+            replayed => Err(ReplayError::Diverged {
+                tick: recorded.tick,
+                events: replayed.map_or(0, |replayed| replayed.events),
+                expected_events: recorded.events,
+            }),
+        }
+    }
+
+    /// Replays until `position` recorded ticks have been replayed, starting
+    /// over on a freshly built engine to go backwards.
+    pub fn seek(&mut self, position: usize) -> Result<(), ReplayError> {
+        let position = position.min(self.ticks.len());
+        if position < self.position {
+            self.engine = start(&mut self.build, &self.header)?;
+            self.position = 0;
+        }
+        while self.position < position {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Replays every remaining tick, stopping at the first that diverges.
+    pub fn run(&mut self) -> Result<(), ReplayError> {
+        self.seek(self.ticks.len())
+    }
+}
+
+fn start(
+    build: &mut Box<dyn FnMut() -> Engine>,
+    header: &RecordingHeader,
+) -> Result<Engine, ReplayError> {
+    let mut engine = build();
+    if engine.time.tick != header.start_tick || engine.state_fingerprint() != header.fingerprint {
+        return Err(ReplayError::StartMismatch);
+    }
+    for node in &header.nodes {
+        engine.restore_uuid(node.node, node.uuid);
+    }
+    engine.set_recording(true);
+    Ok(engine)
+}
+
+pub(crate) fn recorded_request(request: &EditRequest) -> RecordedRequest {
+    let execution = |execution: &NodeExecution| match execution {
+        NodeExecution::Passive => RecordedExecution::Passive,
+        NodeExecution::Reactive => RecordedExecution::Reactive,
+        NodeExecution::Continuous => RecordedExecution::Continuous,
+    };
+    let edit = match &request.edit {
+        Edit::SetParam {
+            node,
+            value,
+        } => RecordedEdit::SetParam {
+            node: *node,
+            value: value.clone(),
+        },
+        Edit::RampParam {
+            node,
+            target,
+            duration,
+            easing,
+        } => RecordedEdit::RampParam {
+            node: *node,
+            target: target.clone(),
+            duration: *duration,
+            easing: match easing {
+                Easing::Linear => RecordedEasing::Linear,
+                Easing::EaseIn => RecordedEasing::EaseIn,
+                Easing::EaseOut => RecordedEasing::EaseOut,
+                Easing::EaseInOut => RecordedEasing::EaseInOut,
+            },
+        },
+        Edit::PatchParam {
+            node,
+            patch,
+        } => RecordedEdit::PatchParam {
+            node: *node,
+            patch: patch.clone(),
+        },
+        Edit::PatchMeta {
+            node,
+            patch,
+        } => RecordedEdit::PatchMeta {
+            node: *node,
+            patch: patch.clone(),
+        },
+        Edit::InstantiateChildFromManager {
+            manager,
+            node_type,
+            label,
+            execution: node_execution,
+        } => RecordedEdit::InstantiateChildFromManager {
+            manager: *manager,
+            node_type: node_type.clone(),
+            label: label.clone(),
+            execution: execution(node_execution),
+        },
+        Edit::CreateNode {
+            parent,
+            node_type,
+            label,
+            execution: node_execution,
+        } => RecordedEdit::CreateNode {
+            parent: *parent,
+            node_type: node_type.clone(),
+            label: label.clone(),
+            execution: execution(node_execution),
+        },
+        Edit::MoveNode {
+            node,
+            new_parent,
+            index,
+        } => RecordedEdit::MoveNode {
+            node: *node,
+            new_parent: *new_parent,
+            index: *index,
+        },
+        Edit::DeleteNode {
+            node,
+        } => RecordedEdit::DeleteNode {
+            node: *node,
+        },
+        Edit::ImportProject {
+            root,
+            project,
+        } => RecordedEdit::ImportProject {
+            root: *root,
+            project: project.clone(),
+        },
+        Edit::InsertSubtree {
+            parent,
+            root,
+        } => RecordedEdit::InsertSubtree {
+            parent: *parent,
+            root: root.clone(),
+        },
+    };
+    RecordedRequest {
+        edit,
+        propagation: match request.propagation {
+            Propagation::Immediate => messages::Propagation::Immediate,
+            Propagation::EndOfTick => messages::Propagation::EndOfTick,
+            Propagation::NextTick => messages::Propagation::NextTick,
+        },
+        origin: request.origin.into(),
+    }
+}
+
+fn edit_request(request: &RecordedRequest) -> EditRequest {
+    let execution = |execution: &RecordedExecution| match execution {
+        RecordedExecution::Passive => NodeExecution::Passive,
+        RecordedExecution::Reactive => NodeExecution::Reactive,
+        RecordedExecution::Continuous => NodeExecution::Continuous,
+    };
+    let edit = match &request.edit {
+        RecordedEdit::SetParam {
+            node,
+            value,
+        } => Edit::SetParam {
+            node: *node,
+            value: value.clone(),
+        },
+        RecordedEdit::RampParam {
+            node,
+            target,
+            duration,
+            easing,
+        } => Edit::RampParam {
+            node: *node,
+            target: target.clone(),
+            duration: *duration,
+            easing: match easing {
+                RecordedEasing::Linear => Easing::Linear,
+                RecordedEasing::EaseIn => Easing::EaseIn,
+                RecordedEasing::EaseOut => Easing::EaseOut,
+                RecordedEasing::EaseInOut => Easing::EaseInOut,
+            },
+        },
+        RecordedEdit::PatchParam {
+            node,
+            patch,
+        } => Edit::PatchParam {
+            node: *node,
+            patch: patch.clone(),
+        },
+        RecordedEdit::PatchMeta {
+            node,
+            patch,
+        } => Edit::PatchMeta {
+            node: *node,
+            patch: patch.clone(),
+        },
+        RecordedEdit::InstantiateChildFromManager {
+            manager,
+            node_type,
+            label,
+            execution: node_execution,
+        } => Edit::InstantiateChildFromManager {
+            manager: *manager,
+            node_type: node_type.clone(),
+            label: label.clone(),
+            execution: execution(node_execution),
+        },
+        RecordedEdit::CreateNode {
+            parent,
+            node_type,
+            label,
+            execution: node_execution,
+        } => Edit::CreateNode {
+            parent: *parent,
+            node_type: node_type.clone(),
+            label: label.clone(),
+            execution: execution(node_execution),
+        },
+        RecordedEdit::MoveNode {
+            node,
+            new_parent,
+            index,
+        } => Edit::MoveNode {
+            node: *node,
+            new_parent: *new_parent,
+            index: *index,
+        },
+        RecordedEdit::DeleteNode {
+            node,
+        } => Edit::DeleteNode {
+            node: *node,
+        },
+        RecordedEdit::ImportProject {
+            root,
+            project,
+        } => Edit::ImportProject {
+            root: *root,
+            project: project.clone(),
+        },
+        RecordedEdit::InsertSubtree {
+            parent,
+            root,
+        } => Edit::InsertSubtree {
+            parent: *parent,
+            root: root.clone(),
+        },
+    };
+    EditRequest {
+        edit,
+        propagation: match request.propagation {
+            messages::Propagation::Immediate => Propagation::Immediate,
+            messages::Propagation::EndOfTick => Propagation::EndOfTick,
+            messages::Propagation::NextTick => Propagation::NextTick,
+        },
+        origin: request.origin.clone().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::{NodeId, NodeUuid, Value};
+
+    use crate::edits::EditOrigin;
+    use crate::graph::node::NodeData;
+    use crate::persistence::load::fragment_subtree;
+    use crate::persistence::save::export_fragment;
+
+    use super::*;
+
+    /// A root holding `/group/gain`.
+    fn build() -> Engine {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let group = engine.create_child_container(root, "Container", "group");
+        engine.create_child_parameter(group, "gain", Value::Float(0.5));
+        engine
+    }
+
+    fn submit(engine: &mut Engine, edit: Edit) {
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn set(engine: &mut Engine, node: NodeId, value: f64) {
+        let edit = Edit::SetParam {
+            node,
+            value: Value::Float(value),
+        };
+        submit(engine, edit);
+    }
+
+    /// Value and uuid of every parameter, in `NodeId` order.
+    fn parameters(engine: &Engine) -> Vec<(NodeId, Value, NodeUuid)> {
+        let mut parameters: Vec<_> = engine
+            .nodes
+            .values()
+            .filter_map(|node| match &node.data {
+                NodeData::Parameter(param) => Some((node.id, param.value.clone(), node.meta.uuid)),
+                _ => None,
+            })
+            .collect();
+        parameters.sort_by_key(|(id, ..)| id.0);
+        parameters
+    }
+
+    #[test]
+    fn pasted_fragments_replay_with_their_recorded_uuids() {
+        let path = std::env::temp_dir().join(format!("golden-{}.rec", uuid::Uuid::new_v4()));
+        let mut engine = build();
+        let root = engine.root_id();
+        let group = engine.resolve_path("/group").unwrap();
+        let gain = engine.resolve_path("/group/gain").unwrap();
+        let mut recorder = Recorder::create(&path, &mut engine, "test").unwrap();
+
+        set(&mut engine, gain, 0.7);
+        recorder.tick(&mut engine).unwrap();
+        let fragment = export_fragment(&engine, group, "test");
+        let paste = Edit::InsertSubtree {
+            parent: root,
+            root: Box::new(fragment_subtree(&fragment)),
+        };
+        submit(&mut engine, paste);
+        recorder.tick(&mut engine).unwrap();
+        let (pasted_gain, ..) = *parameters(&engine).last().unwrap();
+        assert_ne!(pasted_gain, gain);
+        set(&mut engine, pasted_gain, 0.2);
+        recorder.finish(&mut engine).unwrap();
+
+        let data = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut replay = Replay::new(&data, build).unwrap();
+        assert_eq!(replay.ticks().len(), 3);
+        assert!(matches!(
+            replay.ticks()[1].requests[..],
+            [RecordedRequest {
+                edit: RecordedEdit::InsertSubtree { .. },
+                ..
+            }]
+        ));
+        replay.run().unwrap();
+        assert_eq!(parameters(replay.engine()), parameters(&engine));
+
+        // Seeking back rebuilds the engine without the paste.
+        replay.seek(1).unwrap();
+        assert_eq!(replay.engine().child_count(replay.engine().root_id()), 1);
+        replay.run().unwrap();
+        assert_eq!(parameters(replay.engine()), parameters(&engine));
+    }
+}
@@ -1,16 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
-use golden_schema::persistence::file_format::ProjectFile;
+use golden_schema::persistence::file_format::{FragmentFile, ProjectFile};
 use golden_schema::persistence::{
     ContainerDataDto, DeltaNodeRecord, FullNodeRecord, NodeDataDto, NodeDataKind, NodeRecord,
 };
-use golden_schema::{DeclId, NodeId, NodeTypeId, NodeUuid, Value};
+use golden_schema::{
+    DeclId, NodeId, NodeTypeId, NodeUuid, ParameterData, Preset, SavePolicy, Value,
+};
 use uuid::Uuid;
 
 use crate::data::{AllowedTypes, ContainerData};
 use crate::engine::Engine;
 use crate::graph::node::{Node, NodeData};
-use crate::schema::NodeSchema;
+use crate::schema::{DeclaredChild, NodeSchema, ParamDecl};
 
 enum SlotKind {
     Declared,
@@ -70,24 +72,52 @@ pub fn export_project(engine: &Engine, root: NodeId, version: &str) -> ProjectFi
     ProjectFile {
         version: version.to_string(),
         root: root_node.into_record(),
+        presets: presets_within(engine, root),
+    }
+}
+
+/// Exports `node` and its subtree as a fragment for [`import_fragment`].
+///
+/// References to nodes outside the subtree are kept as they are, so they
+/// still resolve when the fragment is pasted into the same project.
+///
+/// [`import_fragment`]: crate::persistence::load::import_fragment
+pub fn export_fragment(engine: &Engine, node: NodeId, version: &str) -> FragmentFile {
+    let mut ctx = ExportContext::new(engine);
+    let mut root = export_root_node(&mut ctx, node);
+    apply_reference_closure(&mut ctx, &mut root);
+    let NodeRecord::Full(root) = root.into_record() else {
+        unreachable!("subtree roots are exported as full records");
+    };
+    FragmentFile {
+        version: version.to_string(),
+        root,
     }
 }
 
+/// Presets captured from nodes inside the exported subtree.
+fn presets_within(engine: &Engine, root: NodeId) -> Vec<Preset> {
+    engine
+        .presets
+        .iter()
+        .filter(|preset| {
+            engine.node_by_uuid(preset.root).is_some_and(|node| engine.is_in_subtree(root, node))
+        })
+        .cloned()
+        .collect()
+}
+
 fn export_root_node(ctx: &mut ExportContext<'_>, node_id: NodeId) -> ExportNode {
     export_full_record(ctx, node_id, None).unwrap_or_else(|| missing_record(ctx.engine))
 }
 
-fn export_node(
-    ctx: &mut ExportContext<'_>,
-    node_id: NodeId,
-    parent_type: Option<&NodeTypeId>,
-) -> Option<ExportNode> {
+fn export_node(ctx: &mut ExportContext<'_>, node_id: NodeId) -> Option<ExportNode> {
     let node = ctx.engine.nodes.get(&node_id)?;
-    let slot = slot_kind(ctx.engine, parent_type, node);
+    let slot = slot_kind(ctx.engine, node);
     match slot {
         SlotKind::Dynamic => export_full_record(ctx, node_id, None),
         SlotKind::Potential => export_full_record(ctx, node_id, Some(node.meta.decl_id.clone())),
-        SlotKind::Declared => export_delta_record(ctx, node_id, parent_type),
+        SlotKind::Declared => export_delta_record(ctx, node_id),
     }
 }
 
@@ -99,7 +129,7 @@ fn export_full_record(
     let node = ctx.engine.nodes.get(&node_id)?;
     let data = node_data_to_dto(&node.data);
     let children = collect_children(ctx, node);
-    collect_references(ctx, node);
+    collect_reference(ctx, data.parameter.as_ref().map(|param| &param.value));
 
     ctx.emitted.insert(node.meta.uuid);
 
@@ -117,33 +147,23 @@ fn export_full_record(
     })
 }
 
-fn export_delta_record(
-    ctx: &mut ExportContext<'_>,
-    node_id: NodeId,
-    parent_type: Option<&NodeTypeId>,
-) -> Option<ExportNode> {
+fn export_delta_record(ctx: &mut ExportContext<'_>, node_id: NodeId) -> Option<ExportNode> {
     let node = ctx.engine.nodes.get(&node_id)?;
     let value = match &node.data {
-        NodeData::Parameter(param) => {
-            if param.default.as_ref() != Some(&param.value) {
-                Some(param.value.clone())
-            } else {
-                None
-            }
-        }
+        NodeData::Parameter(param) => delta_value(param).cloned(),
         _ => None,
     };
 
-    let schema = parent_type.and_then(|parent| ctx.engine.schema.schema_for(parent));
+    let schema = node.parent.and_then(|parent| ctx.engine.schema_of(parent));
     let declared = schema.and_then(|schema| find_declared_child(schema, node));
-    let meta = meta_patch_from_node(node, declared);
+    let meta = meta_patch_from_node(node, declared, declared_param(ctx.engine, node));
     let children = collect_children(ctx, node);
 
     if value.is_none() && meta.is_none() && children.is_empty() {
         return None;
     }
 
-    collect_references(ctx, node);
+    collect_reference(ctx, value.as_ref());
     ctx.emitted.insert(node.meta.uuid);
 
     Some(ExportNode {
@@ -159,6 +179,17 @@ fn export_delta_record(
     })
 }
 
+/// The value a declared parameter writes to a Delta record under its
+/// `SavePolicy`: nothing for `None`, overrides of the default for `Delta`,
+/// and always for `Full`.
+fn delta_value(param: &ParameterData) -> Option<&Value> {
+    match param.save {
+        SavePolicy::None => None,
+        SavePolicy::Delta => (param.default.as_ref() != Some(&param.value)).then_some(&param.value),
+        SavePolicy::Full => Some(&param.value),
+    }
+}
+
 fn node_data_to_dto(data: &NodeData) -> NodeDataDto {
     match data {
         NodeData::None => NodeDataDto {
@@ -171,11 +202,21 @@ fn node_data_to_dto(data: &NodeData) -> NodeDataDto {
             container: Some(container_to_dto(container)),
             parameter: None,
         },
-        NodeData::Parameter(param) => NodeDataDto {
-            kind: NodeDataKind::Parameter,
-            container: None,
-            parameter: Some(param.clone()),
-        },
+        NodeData::Parameter(param) => {
+            let mut param = param.clone();
+            // A record must carry a value to recreate the parameter; a
+            // runtime-only one gets its default rather than its current value.
+            if param.save == SavePolicy::None
+                && let Some(default) = &param.default
+            {
+                param.value = default.clone();
+            }
+            NodeDataDto {
+                kind: NodeDataKind::Parameter,
+                container: None,
+                parameter: Some(param),
+            }
+        }
         NodeData::Custom(_) => NodeDataDto {
             kind: NodeDataKind::Custom("Custom".to_string()),
             container: None,
@@ -205,7 +246,7 @@ fn collect_children(ctx: &mut ExportContext<'_>, node: &Node) -> Vec<ExportNode>
     let mut children = Vec::new();
     let mut current = node.first_child;
     while let Some(child_id) = current {
-        if let Some(child_record) = export_node(ctx, child_id, Some(&node.node_type)) {
+        if let Some(child_record) = export_node(ctx, child_id) {
             children.push(child_record);
         }
         current = ctx.engine.nodes.get(&child_id).and_then(|child| child.next_sibling);
@@ -215,7 +256,8 @@ fn collect_children(ctx: &mut ExportContext<'_>, node: &Node) -> Vec<ExportNode>
 
 fn meta_patch_from_node(
     node: &Node,
-    declared: Option<&crate::schema::DeclaredChild>,
+    declared: Option<&DeclaredChild>,
+    param: Option<&ParamDecl>,
 ) -> Option<golden_schema::NodeMetaPatch> {
     let mut patch = golden_schema::NodeMetaPatch::default();
     let default_label = declared
@@ -235,12 +277,19 @@ fn meta_patch_from_node(
     if !node.meta.tags.is_empty() {
         patch.tags = Some(node.meta.tags.clone());
     }
-    if node.meta.semantics != Default::default() {
+    let (default_semantics, default_presentation) = match param {
+        Some(param) => (param.semantics.clone(), param.presentation.clone()),
+        None => Default::default(),
+    };
+    if node.meta.semantics != default_semantics {
         patch.semantics = Some(node.meta.semantics.clone());
     }
-    if node.meta.presentation != Default::default() {
+    if node.meta.presentation != default_presentation {
         patch.presentation = Some(node.meta.presentation.clone());
     }
+    if node.meta.expression.is_some() {
+        patch.expression = Some(node.meta.expression.clone());
+    }
 
     if patch == golden_schema::NodeMetaPatch::default() {
         None
@@ -249,19 +298,18 @@ fn meta_patch_from_node(
     }
 }
 
-fn slot_kind(engine: &Engine, parent_type: Option<&NodeTypeId>, node: &Node) -> SlotKind {
-    let Some(parent_type) = parent_type else {
-        return SlotKind::Dynamic;
-    };
-    let Some(schema) = engine.schema.schema_for(parent_type) else {
-        return SlotKind::Dynamic;
-    };
-
-    if is_potential_slot(schema, &node.meta.decl_id, &node.node_type) {
-        return SlotKind::Potential;
+fn slot_kind(engine: &Engine, node: &Node) -> SlotKind {
+    if let Some(schema) = node.parent.and_then(|parent| engine.schema_of(parent)) {
+        if is_potential_slot(schema, &node.meta.decl_id, &node.node_type) {
+            return SlotKind::Potential;
+        }
+        if is_declared_child(schema, &node.meta.decl_id, &node.node_type) {
+            return SlotKind::Declared;
+        }
     }
-
-    if is_declared_child(schema, &node.meta.decl_id, &node.node_type) {
+    // Params and folders, possibly nested in folders, come from the schema of
+    // the nearest ancestor that has one.
+    if engine.is_declared_child(node.id) {
         return SlotKind::Declared;
     }
 
@@ -282,21 +330,32 @@ fn is_potential_slot(schema: &NodeSchema, decl_id: &DeclId, node_type: &NodeType
         .any(|slot| &slot.decl_id == decl_id && slot.allowed_types.iter().any(|t| t == node_type))
 }
 
-fn find_declared_child<'a>(
-    schema: &'a NodeSchema,
-    node: &Node,
-) -> Option<&'a crate::schema::DeclaredChild> {
+fn find_declared_child<'a>(schema: &'a NodeSchema, node: &Node) -> Option<&'a DeclaredChild> {
     schema
         .declared_children
         .iter()
         .find(|child| child.decl_id == node.meta.decl_id && child.node_type == node.node_type)
 }
 
-fn collect_references(ctx: &mut ExportContext<'_>, node: &Node) {
-    if let NodeData::Parameter(param) = &node.data {
-        if let Value::Reference(reference) = &param.value {
-            ctx.referenced.insert(reference.uuid);
+/// The declaration of a schema parameter, looked up like
+/// [`Engine::is_declared_child`].
+fn declared_param<'a>(engine: &'a Engine, node: &Node) -> Option<&'a ParamDecl> {
+    let NodeData::Parameter(_) = node.data else {
+        return None;
+    };
+    let mut current = node.parent;
+    while let Some(ancestor) = current {
+        if let Some(schema) = engine.schema_of(ancestor) {
+            return schema.params.iter().find(|param| param.decl_id == node.meta.decl_id);
         }
+        current = engine.nodes.get(&ancestor).and_then(|node| node.parent);
+    }
+    None
+}
+
+fn collect_reference(ctx: &mut ExportContext<'_>, value: Option<&Value>) {
+    if let Some(Value::Reference(reference)) = value {
+        ctx.referenced.insert(reference.uuid);
     }
 }
 
@@ -309,68 +368,67 @@ fn apply_reference_closure(ctx: &mut ExportContext<'_>, root: &mut ExportNode) {
         let Some(node_id) = ctx.uuid_map.get(&uuid).copied() else {
             continue;
         };
-        let Some(node) = ctx.engine.nodes.get(&node_id) else {
-            continue;
-        };
-        let Some(parent_id) = node.parent else {
-            continue;
-        };
-        let Some(parent) = ctx.engine.nodes.get(&parent_id) else {
-            continue;
-        };
-        let Some(schema) = ctx.engine.schema.schema_for(&parent.node_type) else {
-            continue;
-        };
-        if !is_declared_child(schema, &node.meta.decl_id, &node.node_type) {
-            continue;
+
+        // Bind the target and, for a param in an unchanged folder, each
+        // declared ancestor up to one that is already in the file.
+        let mut binding = binding_record(ctx.engine, node_id, Vec::new());
+        while let Some(node) = binding.as_ref().and_then(|b| ctx.engine.nodes.get(&b.node_id))
+            && ctx.engine.is_declared_child(node.id)
+            && let Some(parent_id) = node.parent
+        {
+            let Some(child) = binding.take() else {
+                break;
+            };
+            match exported_node(root, parent_id) {
+                Some(parent) => {
+                    merge_child(parent, child);
+                    break;
+                }
+                None => binding = binding_record(ctx.engine, parent_id, vec![child]),
+            }
         }
+    }
+}
 
-        let binding = NodeRecord::Delta(DeltaNodeRecord {
+/// A Delta record that only binds a declared node's uuid.
+fn binding_record(
+    engine: &Engine,
+    node_id: NodeId,
+    children: Vec<ExportNode>,
+) -> Option<ExportNode> {
+    let node = engine.nodes.get(&node_id)?;
+    Some(ExportNode {
+        node_id,
+        record: NodeRecord::Delta(DeltaNodeRecord {
             decl_id: node.meta.decl_id.clone(),
-            uuid: Some(uuid),
+            uuid: Some(node.meta.uuid),
             meta: None,
             value: None,
             children: Vec::new(),
-        });
-
-        insert_binding_record(root, parent_id, binding);
-    }
+        }),
+        children,
+    })
 }
 
-fn insert_binding_record(root: &mut ExportNode, parent_id: NodeId, binding: NodeRecord) -> bool {
-    if root.node_id == parent_id {
-        if !child_has_decl_id(&root.children, &binding) {
-            root.children.push(ExportNode {
-                node_id: NodeId(0),
-                record: binding,
-                children: Vec::new(),
-            });
-        }
-        return true;
+/// The record for `node_id` in the export, if it is part of it.
+fn exported_node(root: &mut ExportNode, node_id: NodeId) -> Option<&mut ExportNode> {
+    if root.node_id == node_id {
+        return Some(root);
     }
+    root.children.iter_mut().find_map(|child| exported_node(child, node_id))
+}
 
-    for child in &mut root.children {
-        if insert_binding_record(child, parent_id, binding.clone()) {
-            return true;
+/// Adds `child` under `parent`, merging it into a record already there for
+/// the same node.
+fn merge_child(parent: &mut ExportNode, child: ExportNode) {
+    match parent.children.iter_mut().find(|existing| existing.node_id == child.node_id) {
+        Some(existing) => {
+            for grandchild in child.children {
+                merge_child(existing, grandchild);
+            }
         }
+        None => parent.children.push(child),
     }
-
-    false
-}
-
-fn child_has_decl_id(children: &[ExportNode], record: &NodeRecord) -> bool {
-    let decl_id = match record {
-        NodeRecord::Full(full) => full.decl_id.as_ref(),
-        NodeRecord::Delta(delta) => Some(&delta.decl_id),
-    };
-    let Some(decl_id) = decl_id else {
-        return false;
-    };
-
-    children.iter().any(|child| match &child.record {
-        NodeRecord::Full(full) => full.decl_id.as_ref() == Some(decl_id),
-        NodeRecord::Delta(delta) => &delta.decl_id == decl_id,
-    })
 }
 
 fn missing_record(engine: &Engine) -> ExportNode {
@@ -391,3 +449,157 @@ fn missing_record(engine: &Engine) -> ExportNode {
         children: Vec::new(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::{
+        ChangePolicy, PresentationHint, ReferenceValue, SemanticsHint, UpdatePolicy,
+        ValueConstraints,
+    };
+
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::graph::node::NodeExecution;
+    use crate::persistence::load::import_fragment;
+    use crate::schema::InboxBehavior;
+
+    use super::*;
+
+    fn param(decl_id: &str, default: f64, save: SavePolicy) -> ParamDecl {
+        ParamDecl {
+            decl_id: DeclId(decl_id.to_string()),
+            default: Value::Float(default),
+            constraints: ValueConstraints::None,
+            read_only: false,
+            update: UpdatePolicy::Immediate,
+            change: ChangePolicy::ValueChange,
+            save,
+            semantics: SemanticsHint::default(),
+            presentation: PresentationHint::default(),
+            folder: None,
+            behavior: InboxBehavior::Coalesce,
+            alias: None,
+        }
+    }
+
+    /// A `Mixer` with one declared parameter per `SavePolicy`.
+    fn mixer(engine: &mut Engine) -> NodeId {
+        let mut schema = NodeSchema::new();
+        schema.params = vec![
+            param("delta", 0.5, SavePolicy::Delta),
+            param("full", 0.5, SavePolicy::Full),
+            param("none", 0.5, SavePolicy::None),
+        ];
+        let node_type = NodeTypeId("Mixer".to_string());
+        engine.register_schema(node_type.clone(), schema);
+        let root = engine.root_id();
+        engine.create_child_of_type(root, node_type, "mixer", NodeExecution::Passive).unwrap()
+    }
+
+    fn set(engine: &mut Engine, path: &str, value: Value) {
+        let edit = Edit::SetParam {
+            node: engine.resolve_path(path).unwrap(),
+            value,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn children(record: &NodeRecord) -> &[NodeRecord] {
+        match record {
+            NodeRecord::Full(record) => &record.children,
+            NodeRecord::Delta(record) => &record.children,
+        }
+    }
+
+    /// Values written for the mixer's declared parameters, by decl id.
+    fn saved_values(engine: &Engine) -> Vec<(String, Value)> {
+        let project = export_project(engine, engine.root_id(), "test");
+        let [mixer] = children(&project.root) else {
+            panic!("expected one child of the root");
+        };
+        children(mixer)
+            .iter()
+            .map(|record| match record {
+                NodeRecord::Delta(delta) => (delta.decl_id.0.clone(), delta.value.clone().unwrap()),
+                NodeRecord::Full(_) => panic!("declared parameters are saved as deltas"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn declared_parameters_are_saved_by_policy() {
+        let mut engine = Engine::new();
+        mixer(&mut engine);
+        assert_eq!(saved_values(&engine), vec![("full".to_string(), Value::Float(0.5))]);
+
+        set(&mut engine, "/mixer/delta", Value::Float(0.75));
+        set(&mut engine, "/mixer/none", Value::Float(0.75));
+        assert_eq!(
+            saved_values(&engine),
+            vec![
+                ("delta".to_string(), Value::Float(0.75)),
+                ("full".to_string(), Value::Float(0.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn runtime_only_dynamic_parameters_are_saved_with_their_default() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let meter = engine.create_child_parameter(root, "meter", Value::Float(0.0));
+        if let Some(NodeData::Parameter(param)) =
+            engine.nodes.get_mut(&meter).map(|node| &mut node.data)
+        {
+            param.save = SavePolicy::None;
+            param.default = Some(Value::Float(0.0));
+            param.value = Value::Float(0.9);
+        }
+
+        let project = export_project(&engine, root, "test");
+        let [NodeRecord::Full(record)] = children(&project.root) else {
+            panic!("expected one full record");
+        };
+        assert_eq!(record.data.parameter.as_ref().unwrap().value, Value::Float(0.0));
+    }
+
+    #[test]
+    fn pasted_fragments_get_fresh_uuids_and_keep_outside_references() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let outside = engine.create_child_parameter(root, "outside", Value::Float(0.0));
+        let outside_uuid = engine.nodes.get(&outside).unwrap().meta.uuid;
+        let group = engine.create_child_container(root, "Container", "group");
+        let target = engine.create_child_parameter(group, "target", Value::Float(0.0));
+        let target_uuid = engine.nodes.get(&target).unwrap().meta.uuid;
+        let reference = |uuid| {
+            Value::Reference(ReferenceValue {
+                uuid,
+                cached_id: None,
+            })
+        };
+        engine.create_child_parameter(group, "inner", reference(target_uuid));
+        engine.create_child_parameter(group, "outer", reference(outside_uuid));
+
+        let fragment = export_fragment(&engine, group, "test");
+        assert_eq!(fragment.root.children.len(), 3);
+        let copy = engine.create_child_container(root, "Container", "copy");
+        engine.set_journaling(true);
+        let (pasted, _) = import_fragment(&mut engine, copy, &fragment).unwrap();
+
+        let uuid_of =
+            |path: &str| engine.nodes.get(&engine.resolve_path(path).unwrap()).unwrap().meta.uuid;
+        let value_of =
+            |path: &str| match &engine.nodes.get(&engine.resolve_path(path).unwrap()).unwrap().data
+            {
+                NodeData::Parameter(param) => param.value.clone(),
+                _ => panic!("not a parameter"),
+            };
+        assert_eq!(engine.resolve_path("/copy/group"), Some(pasted));
+        assert_ne!(uuid_of("/copy/group/target"), target_uuid);
+        assert_eq!(value_of("/copy/group/inner"), reference(uuid_of("/copy/group/target")));
+        assert_eq!(value_of("/copy/group/outer"), reference(outside_uuid));
+        assert_eq!(value_of("/group/inner"), reference(target_uuid));
+        assert_eq!(engine.take_journal().len(), 1);
+    }
+}
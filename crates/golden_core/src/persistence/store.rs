@@ -0,0 +1,482 @@
+use core::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use golden_schema::NodeId;
+
+use crate::engine::Engine;
+use crate::persistence::journal::{
+    checksum, journal_header, journal_line, read_journal, replay_entry,
+};
+use crate::persistence::load::{ImportError, ImportReport, import_project, load_project};
+use crate::persistence::save::{export_project, save_project};
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    /// The project file or a journal entry could not be (de)serialized.
+    Format(serde_json::Error),
+    Import(ImportError),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(err) => write!(f, "{err}"),
+            StoreError::Format(err) => write!(f, "invalid project data: {err}"),
+            StoreError::Import(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(err: io::Error) -> Self {
+        StoreError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(err: serde_json::Error) -> Self {
+        StoreError::Format(err)
+    }
+}
+
+impl From<ImportError> for StoreError {
+    fn from(err: ImportError) -> Self {
+        StoreError::Import(err)
+    }
+}
+
+/// What [`ProjectStore::open`] found on disk.
+#[derive(Debug, Default)]
+pub struct Recovery {
+    /// Whether a project file existed and was imported.
+    pub loaded: bool,
+    pub import: ImportReport,
+    /// Journal entries replayed on top of the project file.
+    pub replayed: usize,
+    /// Journal entries naming paths that no longer resolve.
+    pub unresolved: usize,
+    /// A journal left by a save that finished writing the project file, or
+    /// one for another file, was thrown away.
+    pub discarded_journal: bool,
+}
+
+/// A project file on disk with its backups and edit journal.
+///
+/// Saves replace the file atomically and keep the previous versions as
+/// `<file>.bak.1` (newest) to `<file>.bak.N`. Between saves, committed user
+/// edits are appended to `<file>.journal`; [`ProjectStore::open`] replays them
+/// after a crash.
+pub struct ProjectStore {
+    path: PathBuf,
+    root: NodeId,
+    version: String,
+    backups: usize,
+    autosave: Option<Duration>,
+    journal: Option<File>,
+    /// Checksum of the project file as last read or written.
+    base: String,
+    saved_revision: u64,
+    /// Set while replayed journal entries are not yet saved.
+    recovered: bool,
+    /// Last time the project was known to have no unsaved changes.
+    clean_at: Instant,
+}
+
+impl ProjectStore {
+    /// A store saving the subtree at `root` to `path`, keeping three
+    /// backups and never saving on its own.
+    pub fn new(path: impl Into<PathBuf>, root: NodeId, version: &str) -> Self {
+        Self {
+            path: path.into(),
+            root,
+            version: version.to_string(),
+            backups: 3,
+            autosave: None,
+            journal: None,
+            base: checksum(b""),
+            saved_revision: 0,
+            recovered: false,
+            clean_at: Instant::now(),
+        }
+    }
+
+    /// Number of previous versions kept; 0 keeps none.
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// Saves from [`ProjectStore::tick`] once the project has had unsaved
+    /// changes for `interval`.
+    pub fn with_autosave(mut self, interval: Duration) -> Self {
+        self.autosave = Some(interval);
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn journal_path(&self) -> PathBuf {
+        sibling(&self.path, "journal")
+    }
+
+    /// Path of the `n`th most recent backup, counting from 1.
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        sibling(&self.path, &format!("bak.{n}"))
+    }
+
+    /// Loads the project file into `engine`, if there is one, and replays the
+    /// journal left by a run that ended without saving. Journaling starts
+    /// here, so call this once the graph has been built.
+    pub fn open(&mut self, engine: &mut Engine) -> Result<Recovery, StoreError> {
+        let mut recovery = Recovery::default();
+        let data = match fs::read_to_string(&self.path) {
+            Ok(data) => Some(data),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(data) = &data {
+            recovery.import = import_project(engine, self.root, &load_project(data)?)?;
+            recovery.loaded = true;
+        }
+        self.base = checksum(data.as_deref().unwrap_or_default().as_bytes());
+
+        let journal_path = self.journal_path();
+        let journal = match fs::read_to_string(&journal_path) {
+            Ok(journal) => Some(read_journal(&journal)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let mut kept = Vec::new();
+        match journal {
+            Some(Some(journal)) if journal.base == self.base => {
+                for entry in journal.entries {
+                    if replay_entry(engine, &entry) {
+                        recovery.replayed += 1;
+                        kept.push(entry);
+                    } else {
+                        recovery.unresolved += 1;
+                    }
+                }
+            }
+            Some(_) => recovery.discarded_journal = true,
+            None => {}
+        }
+
+        // Rewrite the journal without a torn tail or unresolved entries, so
+        // new entries append after whole lines.
+        if kept.is_empty() {
+            remove_if_exists(&journal_path)?;
+        } else {
+            let mut contents = journal_header(&self.base);
+            for entry in &kept {
+                contents.push_str(&journal_line(entry)?);
+            }
+            write_atomic(&journal_path, contents.as_bytes())?;
+            self.journal = Some(OpenOptions::new().append(true).open(&journal_path)?);
+        }
+
+        engine.set_journaling(true);
+        engine.take_journal();
+        self.saved_revision = engine.edit_revision();
+        self.recovered = recovery.replayed > 0;
+        self.clean_at = Instant::now();
+        Ok(recovery)
+    }
+
+    /// Whether edits were committed, or recovered from the journal, since the
+    /// project was last opened or saved.
+    pub fn is_dirty(&self, engine: &Engine) -> bool {
+        self.recovered || engine.edit_revision() != self.saved_revision
+    }
+
+    /// Writes the project file, rotating backups, and starts a new journal.
+    pub fn save(&mut self, engine: &mut Engine) -> Result<(), StoreError> {
+        let project = export_project(engine, self.root, &self.version);
+        let data = save_project(&project)?;
+        self.rotate_backups()?;
+        write_atomic(&self.path, data.as_bytes())?;
+
+        // A crash before the journal is removed leaves one whose base no
+        // longer matches the file, which `open` discards.
+        self.journal = None;
+        remove_if_exists(&self.journal_path())?;
+        self.base = checksum(data.as_bytes());
+        engine.take_journal();
+        self.saved_revision = engine.edit_revision();
+        self.recovered = false;
+        self.clean_at = Instant::now();
+        Ok(())
+    }
+
+    /// Appends edits committed since the last call to the journal, then
+    /// autosaves when due. Call after each engine tick; returns whether the
+    /// project was saved.
+    pub fn tick(&mut self, engine: &mut Engine) -> Result<bool, StoreError> {
+        self.append_journal(engine)?;
+        if !self.is_dirty(engine) {
+            self.clean_at = Instant::now();
+            return Ok(false);
+        }
+        match self.autosave {
+            Some(interval) if self.clean_at.elapsed() >= interval => {
+                self.save(engine)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn append_journal(&mut self, engine: &mut Engine) -> Result<(), StoreError> {
+        let entries = engine.take_journal();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for entry in &entries {
+            lines.push_str(&journal_line(entry)?);
+        }
+        let journal = match &mut self.journal {
+            Some(journal) => journal,
+            None => {
+                let mut journal = File::create(self.journal_path())?;
+                journal.write_all(journal_header(&self.base).as_bytes())?;
+                self.journal.insert(journal)
+            }
+        };
+        journal.write_all(lines.as_bytes())?;
+        journal.sync_data()?;
+        Ok(())
+    }
+
+    /// Shifts `<file>.bak.N-1` to `<file>.bak.N` and so on, then copies the
+    /// current file to `<file>.bak.1`. The file itself is left in place until
+    /// the new version replaces it.
+    fn rotate_backups(&self) -> io::Result<()> {
+        if self.backups == 0 || !self.path.exists() {
+            return Ok(());
+        }
+        for n in (1..self.backups).rev() {
+            let from = self.backup_path(n);
+            if from.exists() {
+                fs::rename(from, self.backup_path(n + 1))?;
+            }
+        }
+        fs::copy(&self.path, self.backup_path(1))?;
+        Ok(())
+    }
+}
+
+/// Replaces `path` with `contents` so that readers, and the file after a
+/// crash, see either the old or the new contents but never a mix: the data
+/// goes to a temporary file in the same directory, is flushed to disk, and is
+/// renamed over `path`.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = sibling(path, "tmp");
+    let mut file = File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&temp, path)?;
+    // Persist the rename itself; directories cannot be opened on every
+    // platform, so this is best effort.
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty())
+        && let Ok(dir) = File::open(dir)
+    {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// `path` with `.extension` appended to its file name.
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::Value;
+
+    use crate::edits::{Edit, EditOrigin, Propagation};
+    use crate::graph::node::NodeData;
+
+    use super::*;
+
+    /// A scratch directory removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("golden-store-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn file(&self) -> PathBuf {
+            self.0.join("show.golden")
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// The graph an application builds before opening its project.
+    fn build() -> (Engine, NodeId) {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.5));
+        (engine, gain)
+    }
+
+    fn set(engine: &mut Engine, node: NodeId, value: f64) {
+        let edit = Edit::SetParam {
+            node,
+            value: Value::Float(value),
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn value(engine: &Engine, node: NodeId) -> Value {
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn store(dir: &TempDir, engine: &Engine) -> ProjectStore {
+        ProjectStore::new(dir.file(), engine.root_id(), "test")
+    }
+
+    #[test]
+    fn saved_projects_open_with_their_values() {
+        let dir = TempDir::new();
+        let (mut engine, gain) = build();
+        let mut first = store(&dir, &engine);
+        let recovery = first.open(&mut engine).unwrap();
+        assert!(!recovery.loaded);
+
+        set(&mut engine, gain, 0.8);
+        assert!(first.is_dirty(&engine));
+        first.save(&mut engine).unwrap();
+        assert!(!first.is_dirty(&engine));
+        assert!(!first.journal_path().exists());
+
+        let (mut engine, gain) = build();
+        let recovery = store(&dir, &engine).open(&mut engine).unwrap();
+        assert!(recovery.loaded);
+        assert_eq!(recovery.replayed, 0);
+        assert_eq!(value(&engine, gain), Value::Float(0.8));
+    }
+
+    #[test]
+    fn journaled_edits_are_replayed_after_a_crash() {
+        let dir = TempDir::new();
+        let (mut engine, gain) = build();
+        let mut first = store(&dir, &engine);
+        first.open(&mut engine).unwrap();
+        first.save(&mut engine).unwrap();
+        set(&mut engine, gain, 0.1);
+        first.tick(&mut engine).unwrap();
+        set(&mut engine, gain, 0.2);
+        first.tick(&mut engine).unwrap();
+        // The process dies mid-write, without saving.
+        let journal_path = first.journal_path();
+        drop(first);
+        let mut journal = OpenOptions::new().append(true).open(journal_path).unwrap();
+        journal.write_all(b"{\"SetParam\":{\"pa").unwrap();
+
+        let (mut engine, gain) = build();
+        let mut second = store(&dir, &engine);
+        let recovery = second.open(&mut engine).unwrap();
+        assert_eq!(recovery.replayed, 2);
+        assert_eq!(recovery.unresolved, 0);
+        assert!(!recovery.discarded_journal);
+        assert_eq!(value(&engine, gain), Value::Float(0.2));
+        assert!(second.is_dirty(&engine));
+
+        // The torn tail was dropped, so the rewritten journal reads cleanly.
+        let journal = read_journal(&fs::read_to_string(second.journal_path()).unwrap()).unwrap();
+        assert_eq!(journal.entries.len(), 2);
+        assert!(!journal.torn);
+    }
+
+    #[test]
+    fn journals_for_another_file_are_discarded() {
+        let dir = TempDir::new();
+        let (mut engine, gain) = build();
+        let mut first = store(&dir, &engine);
+        first.open(&mut engine).unwrap();
+        set(&mut engine, gain, 0.9);
+        first.tick(&mut engine).unwrap();
+        // Saved by another run after this journal was started.
+        fs::write(
+            dir.file(),
+            save_project(&export_project(&engine, engine.root_id(), "test")).unwrap(),
+        )
+        .unwrap();
+
+        let (mut engine, gain) = build();
+        let mut second = store(&dir, &engine);
+        let recovery = second.open(&mut engine).unwrap();
+        assert!(recovery.discarded_journal);
+        assert_eq!(recovery.replayed, 0);
+        assert_eq!(value(&engine, gain), Value::Float(0.9));
+        assert!(!second.journal_path().exists());
+    }
+
+    #[test]
+    fn saves_keep_the_configured_number_of_backups() {
+        let dir = TempDir::new();
+        let (mut engine, gain) = build();
+        let mut store = store(&dir, &engine).with_backups(2);
+        store.open(&mut engine).unwrap();
+        for value in [0.1, 0.2, 0.3, 0.4] {
+            set(&mut engine, gain, value);
+            store.save(&mut engine).unwrap();
+        }
+        assert!(store.backup_path(1).exists() && store.backup_path(2).exists());
+        assert!(!store.backup_path(3).exists());
+
+        let backup = load_project(&fs::read_to_string(store.backup_path(2)).unwrap()).unwrap();
+        let (mut engine, gain) = build();
+        let root = engine.root_id();
+        import_project(&mut engine, root, &backup).unwrap();
+        assert_eq!(value(&engine, gain), Value::Float(0.2));
+    }
+
+    #[test]
+    fn autosave_writes_once_changes_have_waited_long_enough() {
+        let dir = TempDir::new();
+        let (mut engine, gain) = build();
+        let mut store = store(&dir, &engine).with_autosave(Duration::ZERO);
+        store.open(&mut engine).unwrap();
+        assert!(!store.tick(&mut engine).unwrap());
+        assert!(!dir.file().exists());
+
+        set(&mut engine, gain, 0.7);
+        assert!(store.tick(&mut engine).unwrap());
+        assert!(dir.file().exists());
+        assert!(!store.is_dirty(&engine));
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use golden_schema::{NodeId, NodeUuid, Preset, PresetValue, SavePolicy, Value};
+
+use crate::edits::coerce::coerce_edit;
+use crate::edits::validate::clamp_edit;
+use crate::edits::{Edit, EditError, EditOrigin, Propagation};
+use crate::engine::{Easing, Engine};
+use crate::graph::node::NodeData;
+use crate::history::sessions::EditSession;
+
+/// Presets stored with the project, looked up by name.
+#[derive(Default)]
+pub struct PresetLibrary {
+    presets: Vec<Preset>,
+}
+
+impl PresetLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// Stores a preset, replacing and returning any preset with the same name.
+    pub fn insert(&mut self, preset: Preset) -> Option<Preset> {
+        match self.presets.iter_mut().find(|existing| existing.name == preset.name) {
+            Some(existing) => Some(std::mem::replace(existing, preset)),
+            None => {
+                self.presets.push(preset);
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Preset> {
+        let index = self.presets.iter().position(|preset| preset.name == name)?;
+        Some(self.presets.remove(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Preset> {
+        self.presets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.presets.clear();
+    }
+}
+
+/// Limits a recall to part of a preset.
+///
+/// With tags set, a value is recalled when its parameter or one of its
+/// ancestors carries any of them. With path patterns set, the parameter's path
+/// must match one of them. An empty filter recalls everything.
+#[derive(Clone, Debug, Default)]
+pub struct PresetFilter {
+    tags: Vec<String>,
+    paths: Vec<String>,
+}
+
+impl PresetFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Adds a glob pattern as accepted by [`Engine::query_paths`].
+    pub fn path(mut self, pattern: &str) -> Self {
+        self.paths.push(pattern.to_string());
+        self
+    }
+}
+
+/// Outcome of a recall; values that could not be applied are listed, the rest
+/// are queued.
+#[derive(Debug, Default)]
+pub struct RecallReport {
+    pub applied: usize,
+    /// Values whose parameter no longer exists.
+    pub missing: Vec<NodeUuid>,
+    /// Values the parameter no longer accepts, e.g. after a type change.
+    pub rejected: Vec<(NodeUuid, EditError)>,
+}
+
+/// Captures the saveable parameters below `root`, in tree order.
+///
+/// Parameters with `SavePolicy::None`, read-only and expression-driven
+/// parameters and triggers are left out.
+pub fn capture_preset(engine: &Engine, root: NodeId, name: &str) -> Option<Preset> {
+    let root_uuid = engine.nodes.get(&root)?.meta.uuid;
+    let mut values = Vec::new();
+    collect_values(engine, root, &mut values);
+    Some(Preset {
+        name: name.to_string(),
+        root: root_uuid,
+        values,
+    })
+}
+
+fn collect_values(engine: &Engine, node_id: NodeId, out: &mut Vec<PresetValue>) {
+    let Some(node) = engine.nodes.get(&node_id) else {
+        return;
+    };
+    if let NodeData::Parameter(param) = &node.data
+        && param.save != SavePolicy::None
+        && !param.read_only
+        && node.meta.expression.is_none()
+        && !matches!(param.value, Value::Trigger)
+    {
+        out.push(PresetValue {
+            uuid: node.meta.uuid,
+            value: param.value.clone(),
+        });
+    }
+
+    let mut current = node.first_child;
+    while let Some(child) = current {
+        collect_values(engine, child, out);
+        current = engine.nodes.get(&child).and_then(|entry| entry.next_sibling);
+    }
+}
+
+/// Queues the preset's values as one edit session so they land in the same
+/// tick.
+///
+/// Each value is validated like any external edit; values that fail are
+/// reported and skipped rather than blocking the rest of the preset.
+pub fn recall_preset(
+    engine: &mut Engine,
+    preset: &Preset,
+    filter: &PresetFilter,
+    origin: EditOrigin,
+) -> RecallReport {
+    fade_to_preset(engine, preset, filter, origin, Duration::ZERO, Easing::Linear)
+}
+
+/// Like [`recall_preset`], but ramps every value to the preset over `fade`.
+pub fn fade_to_preset(
+    engine: &mut Engine,
+    preset: &Preset,
+    filter: &PresetFilter,
+    origin: EditOrigin,
+    fade: Duration,
+    easing: Easing,
+) -> RecallReport {
+    let mut report = RecallReport::default();
+    let by_uuid: HashMap<NodeUuid, NodeId> =
+        engine.nodes.iter().map(|(id, node)| (node.meta.uuid, id)).collect();
+    let path_matches: Option<HashSet<NodeId>> = (!filter.paths.is_empty())
+        .then(|| filter.paths.iter().flat_map(|pattern| engine.query_paths(pattern)).collect());
+
+    let mut edits = Vec::new();
+    for entry in &preset.values {
+        let Some(&node) = by_uuid.get(&entry.uuid) else {
+            report.missing.push(entry.uuid);
+            continue;
+        };
+        if path_matches.as_ref().is_some_and(|matches| !matches.contains(&node))
+            || !has_any_tag(engine, node, &filter.tags)
+        {
+            continue;
+        }
+        let mut edit = if fade.is_zero() {
+            Edit::SetParam {
+                node,
+                value: entry.value.clone(),
+            }
+        } else {
+            Edit::RampParam {
+                node,
+                target: entry.value.clone(),
+                duration: fade,
+                easing,
+            }
+        };
+        // Checked as the session will check it, so one bad value cannot
+        // reject the rest.
+        coerce_edit(engine, &mut edit, engine.coercion);
+        clamp_edit(engine, &mut edit);
+        match engine.validate_edit(&edit) {
+            Ok(()) => edits.push((entry.uuid, edit)),
+            Err(err) => report.rejected.push((entry.uuid, err)),
+        }
+    }
+
+    let session = EditSession {
+        origin,
+        label: Some(format!("recall preset '{}'", preset.name)),
+    };
+    let (uuids, edits): (Vec<_>, Vec<_>) = edits.into_iter().unzip();
+    match engine.submit_session(&session, edits, Propagation::EndOfTick) {
+        Ok(_) => report.applied = uuids.len(),
+        Err(err) => report.rejected.extend(uuids.into_iter().map(|uuid| (uuid, err.clone()))),
+    }
+    report
+}
+
+fn has_any_tag(engine: &Engine, node: NodeId, tags: &[String]) -> bool {
+    if tags.is_empty() {
+        return true;
+    }
+    let mut current = Some(node);
+    while let Some(id) = current {
+        let Some(entry) = engine.nodes.get(&id) else {
+            return false;
+        };
+        if entry.meta.tags.iter().any(|tag| tags.contains(tag)) {
+            return true;
+        }
+        current = entry.parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `/mixer/{gain, mute}` and `/fx/mix`, all saveable.
+    fn rig(engine: &mut Engine) {
+        let root = engine.root_id();
+        let mixer = engine.create_child_container(root, "Container", "mixer");
+        engine.create_child_parameter(mixer, "gain", Value::Float(0.5));
+        engine.create_child_parameter(mixer, "mute", Value::Bool(false));
+        let fx = engine.create_child_container(root, "Container", "fx");
+        engine.create_child_parameter(fx, "mix", Value::Float(0.2));
+    }
+
+    fn set(engine: &mut Engine, path: &str, value: Value) {
+        let edit = Edit::SetParam {
+            node: engine.resolve_path(path).unwrap(),
+            value,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn value(engine: &Engine, path: &str) -> Value {
+        match &engine.nodes.get(&engine.resolve_path(path).unwrap()).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn uuid(engine: &Engine, path: &str) -> NodeUuid {
+        engine.nodes.get(&engine.resolve_path(path).unwrap()).unwrap().meta.uuid
+    }
+
+    #[test]
+    fn capture_skips_values_that_are_not_saved() {
+        let mut engine = Engine::new();
+        rig(&mut engine);
+        let mixer = engine.resolve_path("/mixer").unwrap();
+        engine.create_child_parameter(mixer, "go", Value::Trigger);
+        let meter = engine.create_child_parameter(mixer, "meter", Value::Float(0.0));
+        let level = engine.create_child_parameter(mixer, "level", Value::Float(0.0));
+        for (node, read_only) in [(meter, false), (level, true)] {
+            if let Some(NodeData::Parameter(param)) =
+                engine.nodes.get_mut(&node).map(|node| &mut node.data)
+            {
+                param.save = if read_only {
+                    SavePolicy::Full
+                } else {
+                    SavePolicy::None
+                };
+                param.read_only = read_only;
+            }
+        }
+
+        let preset = capture_preset(&engine, engine.root_id(), "scene").unwrap();
+        assert_eq!(preset.name, "scene");
+        assert_eq!(preset.root, uuid(&engine, "/"));
+        let captured: Vec<_> = preset.values.iter().map(|entry| entry.uuid).collect();
+        assert_eq!(
+            captured,
+            vec![
+                uuid(&engine, "/mixer/gain"),
+                uuid(&engine, "/mixer/mute"),
+                uuid(&engine, "/fx/mix")
+            ]
+        );
+    }
+
+    #[test]
+    fn recall_restores_only_filtered_paths() {
+        let mut engine = Engine::new();
+        rig(&mut engine);
+        let preset = capture_preset(&engine, engine.root_id(), "scene").unwrap();
+        set(&mut engine, "/mixer/gain", Value::Float(0.9));
+        set(&mut engine, "/mixer/mute", Value::Bool(true));
+        set(&mut engine, "/fx/mix", Value::Float(0.8));
+
+        let filter = PresetFilter::new().path("/mixer/*");
+        let report = recall_preset(&mut engine, &preset, &filter, EditOrigin::UI);
+        engine.tick();
+
+        assert_eq!(report.applied, 2);
+        assert!(report.missing.is_empty() && report.rejected.is_empty());
+        assert_eq!(value(&engine, "/mixer/gain"), Value::Float(0.5));
+        assert_eq!(value(&engine, "/mixer/mute"), Value::Bool(false));
+        assert_eq!(value(&engine, "/fx/mix"), Value::Float(0.8));
+    }
+
+    #[test]
+    fn recall_into_a_changed_tree_reports_what_it_skipped() {
+        let mut engine = Engine::new();
+        rig(&mut engine);
+        let preset = capture_preset(&engine, engine.root_id(), "scene").unwrap();
+        let mix = uuid(&engine, "/fx/mix");
+        let mute = uuid(&engine, "/mixer/mute");
+        let edit = Edit::DeleteNode {
+            node: engine.resolve_path("/fx").unwrap(),
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        set(&mut engine, "/mixer/gain", Value::Float(0.9));
+        let node = engine.resolve_path("/mixer/mute").unwrap();
+        if let Some(NodeData::Parameter(param)) =
+            engine.nodes.get_mut(&node).map(|node| &mut node.data)
+        {
+            param.read_only = true;
+        }
+
+        let report = recall_preset(&mut engine, &preset, &PresetFilter::new(), EditOrigin::UI);
+        engine.tick();
+
+        assert_eq!(report.applied, 1);
+        assert_eq!(report.missing, vec![mix]);
+        assert_eq!(report.rejected, vec![(mute, EditError::ReadOnly(node))]);
+        assert_eq!(value(&engine, "/mixer/gain"), Value::Float(0.5));
+    }
+
+    #[test]
+    fn fade_reaches_the_preset_values() {
+        let mut engine = Engine::new();
+        rig(&mut engine);
+        let preset = capture_preset(&engine, engine.root_id(), "scene").unwrap();
+        set(&mut engine, "/mixer/gain", Value::Float(1.5));
+
+        let filter = PresetFilter::new().path("/mixer/gain");
+        let fade = Duration::from_millis(100);
+        let report =
+            fade_to_preset(&mut engine, &preset, &filter, EditOrigin::UI, fade, Easing::Linear);
+        assert_eq!(report.applied, 1);
+        engine.tick_by(Duration::ZERO);
+        engine.tick_by(Duration::from_millis(50));
+        assert_eq!(value(&engine, "/mixer/gain"), Value::Float(1.0));
+        engine.tick_by(Duration::from_millis(50));
+        assert_eq!(value(&engine, "/mixer/gain"), Value::Float(0.5));
+    }
+}
@@ -6,6 +6,7 @@ use golden_schema::{
 };
 
 use crate::data::{AllowedTypes, FolderPolicy};
+use crate::graph::node::{BindingError, NodeBehaviour, NodeBinding};
 
 #[derive(Clone, Debug)]
 pub struct DeclaredChild {
@@ -81,6 +82,17 @@ pub trait GoldenNodeDecl {
     fn node_type() -> NodeTypeId;
     fn schema() -> NodeSchema;
 
+    /// Builds the node from the children bound to its declarations.
+    fn from_binding(binding: &NodeBinding) -> Result<Self, BindingError>
+    where
+        Self: Sized;
+
+    /// Boxes the node with the callback dispatch named by its
+    /// `#[behaviour(..)]` attribute, or as its own `NodeBehaviour` without one.
+    fn into_behaviour(self) -> Box<dyn NodeBehaviour>
+    where
+        Self: Sized;
+
     fn register_schema(registry: &mut SchemaRegistry)
     where
         Self: Sized,
@@ -107,4 +119,8 @@ impl SchemaRegistry {
     pub fn schema_for(&self, node_type: &NodeTypeId) -> Option<&NodeSchema> {
         self.types.get(node_type)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&NodeTypeId, &NodeSchema)> {
+        self.types.iter()
+    }
 }
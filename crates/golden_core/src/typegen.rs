@@ -0,0 +1,128 @@
+//! Node parameter types for the schema document of
+//! [`golden_schema::typegen`].
+
+use golden_schema::ValueConstraints;
+use golden_schema::typegen::protocol_schema;
+use serde_json::{Map, Value as Json, json};
+
+use crate::schema::{NodeSchema, ParamDecl, SchemaRegistry};
+
+/// [`protocol_schema`] plus a `<NodeType>Params` definition for every node
+/// type in `registry`, mapping each declared parameter to the `Value`
+/// variant it holds, narrowed by its constraints.
+pub fn schema_document(registry: &SchemaRegistry) -> Json {
+    let mut document = protocol_schema();
+    let variants = document["$defs"]["Value"]["oneOf"].as_array().cloned().unwrap_or_default();
+    let Some(defs) = document["$defs"].as_object_mut() else {
+        return document;
+    };
+    for (node_type, schema) in registry.iter() {
+        defs.insert(format!("{}Params", node_type.0), params_schema(schema, &variants));
+    }
+    // Keep the output stable whatever order the registry and map use.
+    let mut entries: Vec<(String, Json)> = std::mem::take(defs).into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    defs.extend(entries);
+    document
+}
+
+fn params_schema(schema: &NodeSchema, variants: &[Json]) -> Json {
+    let properties: Map<String, Json> = schema
+        .params
+        .iter()
+        .map(|decl| (decl.decl_id.0.clone(), param_schema(decl, variants)))
+        .collect();
+    let required: Vec<&str> = schema.params.iter().map(|decl| decl.decl_id.0.as_str()).collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// The `Value` variant of the declared default, with the parameter's
+/// constraints, default, read-only flag and unit.
+fn param_schema(decl: &ParamDecl, variants: &[Json]) -> Json {
+    let kind = decl.default.kind_name();
+    let mut schema = variants
+        .iter()
+        .find(|variant| is_variant(variant, kind))
+        .cloned()
+        .unwrap_or_else(|| json!({}));
+    if let Some(inner) = schema.pointer_mut(&format!("/properties/{kind}")) {
+        narrow(inner, &decl.constraints);
+    }
+    let Some(object) = schema.as_object_mut() else {
+        return schema;
+    };
+    if let Ok(default) = serde_json::to_value(&decl.default) {
+        object.insert("default".to_string(), default);
+    }
+    if decl.read_only {
+        object.insert("readOnly".to_string(), Json::Bool(true));
+    }
+    if let Some(unit) = &decl.semantics.unit {
+        object.insert("description".to_string(), Json::String(format!("In {unit}.")));
+    }
+    schema
+}
+
+/// Whether `variant`, one of the `oneOf` schemas of `Value`, is `kind`.
+fn is_variant(variant: &Json, kind: &str) -> bool {
+    let kind = Json::String(kind.to_string());
+    variant.pointer("/required/0") == Some(&kind)
+        || variant.get("const") == Some(&kind)
+        || variant.get("enum").and_then(Json::as_array).is_some_and(|names| names.contains(&kind))
+}
+
+/// Adds the JSON Schema keywords matching `constraints` to a variant payload.
+fn narrow(inner: &mut Json, constraints: &ValueConstraints) {
+    let keywords = match constraints {
+        ValueConstraints::Int {
+            min,
+            max,
+            ..
+        } => [("minimum", min.map(Json::from)), ("maximum", max.map(Json::from))],
+        ValueConstraints::Float {
+            min,
+            max,
+            ..
+        } => [("minimum", min.map(Json::from)), ("maximum", max.map(Json::from))],
+        ValueConstraints::String {
+            max_len,
+            pattern,
+        } => [("maxLength", max_len.map(Json::from)), ("pattern", pattern.clone().map(Json::from))],
+        ValueConstraints::Collection {
+            min_len,
+            max_len,
+            ..
+        } => match inner.get("type").and_then(Json::as_str) {
+            Some("object") => [
+                ("minProperties", min_len.map(Json::from)),
+                ("maxProperties", max_len.map(Json::from)),
+            ],
+            _ => [("minItems", min_len.map(Json::from)), ("maxItems", max_len.map(Json::from))],
+        },
+        ValueConstraints::Enum {
+            enum_id,
+            allowed,
+        } => {
+            if let Some(properties) = inner.get_mut("properties").and_then(Json::as_object_mut) {
+                properties.insert("enum_id".to_string(), json!({ "const": enum_id.0 }));
+                let names: Vec<&str> = allowed.iter().map(|variant| variant.0.as_str()).collect();
+                properties.insert("variant".to_string(), json!({ "enum": names }));
+            }
+            return;
+        }
+        _ => return,
+    };
+    let Some(object) = inner.as_object_mut() else {
+        return;
+    };
+    for (key, value) in keywords {
+        if let Some(value) = value {
+            object.insert(key.to_string(), value);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{
     Attribute, Data, DeriveInput, Expr, ExprArray, ExprLit, ExprPath, ExprRange, Fields, Ident,
     Lit, LitBool, LitFloat, LitInt, LitStr, Result, Token, Type,
@@ -7,9 +8,21 @@ use syn::{
     parse_macro_input,
 };
 
+#[cfg(doctest)]
+mod rejected;
+
 #[proc_macro_derive(
     GoldenNode,
-    attributes(node_id, param, child, folder, container, potential_child)
+    attributes(
+        node_id,
+        param,
+        child,
+        folder,
+        container,
+        potential_child,
+        state,
+        behaviour
+    )
 )]
 pub fn golden_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -20,11 +33,23 @@ pub fn golden_node(input: TokenStream) -> TokenStream {
     let mut declared_children = Vec::new();
     let mut potential_slots = Vec::new();
     let mut container_decl = None;
+    let mut behaviour = None::<Ident>;
+    let mut field_inits = Vec::new();
+    // Handle fields bound by name, whether or not an attribute declares them.
+    let mut handle_fields = Vec::new();
+    let mut declared_fields = Vec::new();
+    let mut declared_folders = Vec::new();
 
     for attr in &input.attrs {
         if attr.path().is_ident("container") {
             container_decl = Some(parse_container_attr(attr));
         }
+        if attr.path().is_ident("behaviour") {
+            match parse_behaviour_attr(attr) {
+                Ok(kind) => behaviour = Some(kind),
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
     }
 
     let Data::Struct(data) = input.data else {
@@ -44,6 +69,36 @@ pub fn golden_node(input: TokenStream) -> TokenStream {
             continue;
         };
 
+        match field_init(&field_ident, &field.ty, &field.attrs) {
+            Ok(init) => field_inits.push(quote! { #field_ident: #init }),
+            Err(err) => return err.to_compile_error().into(),
+        }
+
+        let bound_by_attr = field.attrs.iter().any(|attr| {
+            ["node_id", "state", "child", "potential_child"]
+                .iter()
+                .any(|name| attr.path().is_ident(name))
+        });
+        if !bound_by_attr
+            && matches!(
+                type_name(&field.ty).as_deref(),
+                Some("ParameterHandle" | "FolderHandle")
+            )
+        {
+            handle_fields.push((field_ident.clone(), field.ty.clone()));
+        }
+        for attr in &field.attrs {
+            if attr.path().is_ident("param") {
+                declared_fields.push(field_ident.to_string());
+                if let Ok(folder) = slot_of(attr, "folder") {
+                    declared_folders.push(folder);
+                }
+            }
+            if attr.path().is_ident("folder") {
+                declared_fields.push(field_ident.to_string());
+            }
+        }
+
         if field
             .attrs
             .iter()
@@ -120,6 +175,88 @@ pub fn golden_node(input: TokenStream) -> TokenStream {
         }
     };
 
+    let declaration_checks = if has_attr_schema {
+        let mut errors = None::<syn::Error>;
+        for (field_ident, field_ty) in &handle_fields {
+            let name = field_ident.to_string();
+            let message = match type_name(field_ty).as_deref() {
+                Some("ParameterHandle") if !declared_fields.contains(&name) => {
+                    format!("`{name}` has no #[param] declaration")
+                }
+                Some("FolderHandle")
+                    if !declared_fields.contains(&name) && !declared_folders.contains(&name) =>
+                {
+                    format!("`{name}` has no #[folder] declaration or #[param(folder = ..)] use")
+                }
+                _ => continue,
+            };
+            let error = syn::Error::new(field_ident.span(), message);
+            match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+        if let Some(errors) = errors {
+            return errors.to_compile_error().into();
+        }
+        quote! {}
+    } else {
+        // `params!` emits one marker per declaration; naming them here turns
+        // a missing declaration into an error on the field. Parameter markers
+        // hold the declared `ParamKind`, so `i64` and `i32` handles both
+        // match an `Int` declaration.
+        let mut kind_checks = Vec::new();
+        let checks = handle_fields
+            .iter()
+            .map(|(field_ident, field_ty)| match type_name(field_ty).as_deref() {
+                Some("ParameterHandle") => {
+                    let marker =
+                        format_ident!("__golden_param_{}", field_ident, span = field_ident.span());
+                    match extract_param_kind(field_ty) {
+                        Ok(kind) => {
+                            let discriminant = kind as u8;
+                            let message = format!(
+                                "`{field_ident}` holds {kind:?} values but its params! \
+                                 declaration does not"
+                            );
+                            kind_checks.push(quote_spanned! {field_ty.span()=>
+                                const _: () = assert!(#ident::#marker == #discriminant, #message);
+                            });
+                            quote_spanned! {field_ident.span()=> let _ = Self::#marker; }
+                        }
+                        Err(err) => err.to_compile_error(),
+                    }
+                }
+                _ => {
+                    let marker =
+                        format_ident!("__golden_folder_{}", field_ident, span = field_ident.span());
+                    quote_spanned! {field_ident.span()=> Self::#marker(); }
+                }
+            })
+            .collect::<Vec<_>>();
+        quote! {
+            impl #ident {
+                #[doc(hidden)]
+                #[allow(dead_code)]
+                fn __golden_check_declarations() {
+                    #(#checks)*
+                }
+            }
+
+            #(#kind_checks)*
+        }
+    };
+
+    let behaviour_tokens = match behaviour {
+        Some(kind) if kind == "reactive" => {
+            quote! { Box::new(golden_core::graph::node::Reactive(self)) }
+        }
+        Some(kind) if kind == "continuous" => {
+            quote! { Box::new(golden_core::graph::node::Continuous(self)) }
+        }
+        _ => quote! { Box::new(self) },
+    };
+
     let expanded = quote! {
         impl golden_core::schema::GoldenNodeDecl for #ident {
             fn node_type() -> golden_schema::NodeTypeId {
@@ -129,12 +266,254 @@ pub fn golden_node(input: TokenStream) -> TokenStream {
             fn schema() -> golden_core::schema::NodeSchema {
                 #schema_tokens
             }
+
+            fn from_binding(
+                binding: &golden_core::graph::node::NodeBinding,
+            ) -> ::std::result::Result<Self, golden_core::graph::node::BindingError> {
+                Ok(Self {
+                    #(#field_inits),*
+                })
+            }
+
+            fn into_behaviour(self) -> Box<dyn golden_core::graph::node::NodeBehaviour> {
+                #behaviour_tokens
+            }
         }
+
+        #declaration_checks
     };
 
     expanded.into()
 }
 
+/// `#[behaviour(reactive)]` or `#[behaviour(continuous)]`.
+fn parse_behaviour_attr(attr: &Attribute) -> Result<Ident> {
+    let kind: Ident = attr.parse_args()?;
+    if kind != "reactive" && kind != "continuous" {
+        return Err(syn::Error::new_spanned(
+            kind,
+            "expected `reactive` or `continuous`",
+        ));
+    }
+    Ok(kind)
+}
+
+/// How `from_binding` fills a field: handles by their decl_id, which is the
+/// field name unless an attribute names a slot, and everything else from
+/// `#[state(default = ..)]` or `Default`.
+fn field_init(
+    field_ident: &Ident,
+    field_ty: &Type,
+    attrs: &[Attribute],
+) -> Result<proc_macro2::TokenStream> {
+    let name = field_ident.to_string();
+    for attr in attrs {
+        if attr.path().is_ident("node_id") {
+            return Ok(quote! { binding.node_id });
+        }
+        if attr.path().is_ident("state") {
+            let mut default = None::<Expr>;
+            if !matches!(attr.meta, syn::Meta::Path(_)) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("default") {
+                        default = Some(meta.value()?.parse()?);
+                        return Ok(());
+                    }
+                    Err(meta.error("expected `default`"))
+                })?;
+            }
+            return Ok(match default {
+                Some(default) => quote! { #default },
+                None => quote! { ::std::default::Default::default() },
+            });
+        }
+        if attr.path().is_ident("folder") {
+            let slot = slot_of(attr, "slot")?;
+            return Ok(quote! {
+                binding.folder(#slot).ok_or_else(|| {
+                    golden_core::graph::node::BindingError::MissingFolder(#slot.to_string())
+                })?
+            });
+        }
+        if attr.path().is_ident("child") {
+            let slot = slot_of(attr, "slot")?;
+            let node = quote! {
+                binding.node(#slot).ok_or_else(|| {
+                    golden_core::graph::node::BindingError::MissingChild(#slot.to_string())
+                })?
+            };
+            return Ok(match type_name(field_ty).as_deref() {
+                Some("ChildListHandle") => {
+                    quote! { golden_core::data::ChildListHandle::new(#node) }
+                }
+                _ => node,
+            });
+        }
+        if attr.path().is_ident("potential_child") {
+            let decl_id = slot_of(attr, "decl_id")?;
+            return Ok(quote! {
+                golden_core::data::PotentialSlotHandle {
+                    node_id: binding.node(#decl_id),
+                    ..golden_core::data::PotentialSlotHandle::new(
+                        golden_schema::DeclId(#decl_id.to_string()),
+                    )
+                }
+            });
+        }
+    }
+
+    Ok(match type_name(field_ty).as_deref() {
+        Some("ParameterHandle") => quote! {
+            binding.param(#name).ok_or_else(|| {
+                golden_core::graph::node::BindingError::MissingParam(#name.to_string())
+            })?
+        },
+        Some("FolderHandle") => quote! {
+            binding.folder(#name).ok_or_else(|| {
+                golden_core::graph::node::BindingError::MissingFolder(#name.to_string())
+            })?
+        },
+        _ => quote! { ::std::default::Default::default() },
+    })
+}
+
+/// Last path segment of a type, e.g. `ParameterHandle` for `ParameterHandle<f64>`.
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// `T` in `ParameterHandle<T>`.
+fn handle_argument(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let syn::PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Literal number of a default or bound, including negated literals.
+fn literal_number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(value),
+            ..
+        }) => value.base10_parse().ok(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(value),
+            ..
+        }) => value.base10_parse().ok(),
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_number(expr).map(|value| -value),
+        _ => None,
+    }
+}
+
+/// Rejects `step` on non-numeric kinds and a default (or array default
+/// element, or range bound) outside `min`/`max`. A missing scalar default is
+/// zero; `name` carries its span.
+fn check_numeric_args(kind: ParamKind, name: &Ident, args: &ParamArgs) -> Result<()> {
+    if !matches!(
+        kind,
+        ParamKind::Int
+            | ParamKind::Float
+            | ParamKind::IntArray
+            | ParamKind::FloatArray
+            | ParamKind::IntRange
+            | ParamKind::FloatRange
+    ) {
+        if let Some(step) = &args.step {
+            return Err(syn::Error::new_spanned(
+                step,
+                "`step` only applies to integer and float parameters",
+            ));
+        }
+        return Ok(());
+    }
+
+    // Array defaults are checked element by element, ranges bound by bound.
+    let defaults: Vec<&Expr> = match &args.default {
+        Some(Expr::Array(array)) => array.elems.iter().collect(),
+        Some(Expr::Repeat(repeat)) => vec![&*repeat.expr],
+        Some(Expr::Range(range)) => {
+            let bounds: Vec<&Expr> = range
+                .start
+                .iter()
+                .chain(range.end.iter())
+                .map(|bound| &**bound)
+                .collect();
+            if let [start, end] = bounds[..]
+                && let (Some(min), Some(max)) = (literal_number(start), literal_number(end))
+                && min > max
+            {
+                return Err(syn::Error::new_spanned(
+                    range,
+                    format!("range default of `{name}` has min {min} above max {max}"),
+                ));
+            }
+            bounds
+        }
+        Some(default) => vec![default],
+        None => Vec::new(),
+    };
+    let mut defaults: Vec<_> = defaults
+        .into_iter()
+        .filter_map(|expr| literal_number(expr).map(|value| (value, expr.span())))
+        .collect();
+    if args.default.is_none() && matches!(kind, ParamKind::Int | ParamKind::Float) {
+        defaults.push((0.0, name.span()));
+    }
+    for (default, span) in defaults {
+        if let Some(min) = args.min.as_ref().and_then(literal_number)
+            && default < min
+        {
+            return Err(syn::Error::new(
+                span,
+                format!("default {default} of `{name}` is below its min {min}"),
+            ));
+        }
+        if let Some(max) = args.max.as_ref().and_then(literal_number)
+            && default > max
+        {
+            return Err(syn::Error::new(
+                span,
+                format!("default {default} of `{name}` is above its max {max}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The string value of `key` in a list attribute such as `#[child(slot = "..")]`.
+fn slot_of(attr: &Attribute, key: &str) -> Result<String> {
+    let mut value = None::<LitStr>;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident(key) {
+            value = Some(meta.value()?.parse()?);
+        } else if meta.input.peek(Token![=]) {
+            meta.value()?.parse::<Expr>()?;
+        }
+        Ok(())
+    })?;
+    value
+        .map(|value| value.value())
+        .ok_or_else(|| syn::Error::new_spanned(attr, format!("{key} is required")))
+}
+
 #[proc_macro]
 pub fn params(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ParamsInput);
@@ -146,6 +525,8 @@ pub fn params(input: TokenStream) -> TokenStream {
         return err.to_compile_error().into();
     }
 
+    let markers = declaration_markers(&input.items);
+
     for item in input.items {
         collect_params_from_item(
             item,
@@ -172,11 +553,71 @@ pub fn params(input: TokenStream) -> TokenStream {
         pub fn declared_children() -> Vec<golden_core::schema::DeclaredChild> {
             vec![#(#declared_children),*]
         }
+
+        #markers
     };
 
     expanded.into()
 }
 
+/// Hidden items the `GoldenNode` derive checks its fields against: one
+/// marker per parameter (holding its `ParamKind`) and per top-level folder, plus
+/// a method touching the field each declaration binds to.
+fn declaration_markers(items: &[ParamsItem]) -> proc_macro2::TokenStream {
+    fn walk(
+        items: &[ParamsItem],
+        depth: usize,
+        markers: &mut Vec<proc_macro2::TokenStream>,
+        fields: &mut Vec<Ident>,
+    ) {
+        for item in items {
+            match item {
+                ParamsItem::Param(param) => {
+                    let name = &param.name;
+                    let marker = format_ident!("__golden_param_{}", name);
+                    if let Ok(kind) = extract_param_kind_value_type(&param.ty) {
+                        let discriminant = kind as u8;
+                        markers.push(quote! {
+                            #[doc(hidden)]
+                            #[allow(dead_code, non_upper_case_globals)]
+                            pub const #marker: u8 = #discriminant;
+                        });
+                    }
+                    fields.push(name.clone());
+                }
+                ParamsItem::Folder(folder) => {
+                    if depth == 0 {
+                        let marker = format_ident!("__golden_folder_{}", folder.name);
+                        markers.push(quote! {
+                            #[doc(hidden)]
+                            #[allow(dead_code, non_snake_case)]
+                            pub fn #marker() {}
+                        });
+                        fields.push(folder.name.clone());
+                    }
+                    walk(&folder.items, depth + 1, markers, fields);
+                }
+            }
+        }
+    }
+
+    let mut markers = Vec::new();
+    let mut fields = Vec::new();
+    walk(items, 0, &mut markers, &mut fields);
+    let field_checks = fields.iter().map(|field| {
+        quote_spanned! {field.span()=> let _ = &self.#field; }
+    });
+    quote! {
+        #(#markers)*
+
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        fn __golden_check_fields(&self) {
+            #(#field_checks)*
+        }
+    }
+}
+
 fn build_param_decl(
     field_ident: &Ident,
     field_ty: &Type,
@@ -186,8 +627,10 @@ fn build_param_decl(
     proc_macro2::TokenStream,
     Option<proc_macro2::TokenStream>,
 )> {
-    let args = parse_param_args(attr, Some(field_ident))?;
+    let mut args = parse_param_args(attr, Some(field_ident))?;
     let kind = extract_param_kind(field_ty)?;
+    args.element = handle_argument(field_ty).and_then(element_kind);
+    check_numeric_args(kind, field_ident, &args)?;
     let decl_id = field_ident.to_string();
     let default_tokens = value_tokens_from_args(&kind, &args)?;
     let constraints_tokens = constraints_tokens_from_args(&kind, &args)?;
@@ -456,6 +899,14 @@ struct ParamArgs {
     target: Option<LitStr>,
     pattern: Option<LitStr>,
     max_len: Option<LitInt>,
+    min_len: Option<LitInt>,
+    len: Option<LitInt>,
+    /// `min..max` of curve key positions or gradient stop positions.
+    domain: Option<Expr>,
+    /// `min..max` of curve key values.
+    range: Option<Expr>,
+    /// Element kind of a `Vec` or `BTreeMap` parameter, from its type.
+    element: Option<ParamKind>,
 }
 
 fn parse_param_args(attr: &Attribute, field_ident: Option<&Ident>) -> Result<ParamArgs> {
@@ -488,7 +939,7 @@ fn parse_param_args(attr: &Attribute, field_ident: Option<&Ident>) -> Result<Par
             return Ok(());
         }
         if meta.path.is_ident("save") {
-            args.save = Some(meta.value()?.parse()?);
+            args.save = Some(check_save(meta.value()?.parse()?)?);
             return Ok(());
         }
         if meta.path.is_ident("update") {
@@ -504,7 +955,7 @@ fn parse_param_args(attr: &Attribute, field_ident: Option<&Ident>) -> Result<Par
             return Ok(());
         }
         if meta.path.is_ident("unit") {
-            args.unit = Some(meta.value()?.parse()?);
+            args.unit = Some(check_unit(meta.value()?.parse()?)?);
             return Ok(());
         }
         if meta.path.is_ident("presentation") {
@@ -559,6 +1010,22 @@ fn parse_param_args(attr: &Attribute, field_ident: Option<&Ident>) -> Result<Par
             args.max_len = Some(meta.value()?.parse()?);
             return Ok(());
         }
+        if meta.path.is_ident("min_len") {
+            args.min_len = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+        if meta.path.is_ident("len") {
+            args.len = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+        if meta.path.is_ident("domain") {
+            args.domain = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
+        if meta.path.is_ident("range") {
+            args.range = Some(meta.value()?.parse()?);
+            return Ok(());
+        }
         Ok(())
     })?;
 
@@ -573,10 +1040,23 @@ enum ParamKind {
     String,
     Vec2,
     Vec3,
+    Vec4,
+    Quat,
     ColorRgba,
     Trigger,
     Enum,
     Reference,
+    List,
+    FloatArray,
+    IntArray,
+    Map,
+    FloatRange,
+    IntRange,
+    Bytes,
+    Timestamp,
+    Duration,
+    Curve,
+    Gradient,
 }
 
 fn extract_param_kind(ty: &Type) -> Result<ParamKind> {
@@ -605,41 +1085,14 @@ fn extract_param_kind(ty: &Type) -> Result<ParamKind> {
         return Err(syn::Error::new_spanned(ty, "Missing ParameterHandle type"));
     };
 
-    let syn::GenericArgument::Type(Type::Path(inner_path)) = first_arg else {
+    let syn::GenericArgument::Type(inner) = first_arg else {
         return Err(syn::Error::new_spanned(
             ty,
             "Unsupported ParameterHandle type",
         ));
     };
 
-    let ident = inner_path
-        .path
-        .segments
-        .last()
-        .map(|seg| seg.ident.to_string());
-    let Some(ident) = ident else {
-        return Err(syn::Error::new_spanned(
-            ty,
-            "Unsupported ParameterHandle type",
-        ));
-    };
-
-    let kind = match ident.as_str() {
-        "bool" | "Bool" => ParamKind::Bool,
-        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => {
-            ParamKind::Int
-        }
-        "f32" | "f64" => ParamKind::Float,
-        "String" => ParamKind::String,
-        "Vec2" => ParamKind::Vec2,
-        "Vec3" => ParamKind::Vec3,
-        "ColorRgba" => ParamKind::ColorRgba,
-        "Trigger" => ParamKind::Trigger,
-        "ReferenceValue" => ParamKind::Reference,
-        _ => ParamKind::Enum,
-    };
-
-    Ok(kind)
+    extract_param_kind_value_type(inner)
 }
 
 fn extract_param_kind_value_type(ty: &Type) -> Result<ParamKind> {
@@ -654,6 +1107,21 @@ fn extract_param_kind_value_type(ty: &Type) -> Result<ParamKind> {
         .map(|seg| seg.ident.to_string())
         .ok_or_else(|| syn::Error::new_spanned(ty, "Unsupported parameter type"))?;
 
+    if ident == "Vec" {
+        let element =
+            handle_argument(ty).ok_or_else(|| syn::Error::new_spanned(ty, "Expected Vec<T>"))?;
+        if let Type::Path(element) = element
+            && element.path.is_ident("u8")
+        {
+            return Ok(ParamKind::Bytes);
+        }
+        return Ok(match extract_param_kind_value_type(element)? {
+            ParamKind::Float => ParamKind::FloatArray,
+            ParamKind::Int => ParamKind::IntArray,
+            _ => ParamKind::List,
+        });
+    }
+
     let kind = match ident.as_str() {
         "bool" | "Bool" => ParamKind::Bool,
         "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => {
@@ -663,9 +1131,18 @@ fn extract_param_kind_value_type(ty: &Type) -> Result<ParamKind> {
         "String" => ParamKind::String,
         "Vec2" => ParamKind::Vec2,
         "Vec3" => ParamKind::Vec3,
+        "Vec4" => ParamKind::Vec4,
+        "Quat" => ParamKind::Quat,
         "ColorRgba" => ParamKind::ColorRgba,
         "Trigger" => ParamKind::Trigger,
         "ReferenceValue" => ParamKind::Reference,
+        "BTreeMap" => ParamKind::Map,
+        "FloatRange" => ParamKind::FloatRange,
+        "IntRange" => ParamKind::IntRange,
+        "Timestamp" => ParamKind::Timestamp,
+        "Duration" => ParamKind::Duration,
+        "Curve" => ParamKind::Curve,
+        "Gradient" => ParamKind::Gradient,
         _ => ParamKind::Enum,
     };
 
@@ -688,10 +1165,39 @@ fn value_tokens_from_args(kind: &ParamKind, args: &ParamArgs) -> Result<proc_mac
         ParamKind::Vec3 => {
             quote! { golden_schema::Value::Vec3(golden_schema::Vec3 { x: 0.0, y: 0.0, z: 0.0 }) }
         }
+        ParamKind::Vec4 => {
+            quote! { golden_schema::Value::Vec4(golden_schema::Vec4 { x: 0.0, y: 0.0, z: 0.0, w: 0.0 }) }
+        }
+        ParamKind::Quat => quote! { golden_schema::Value::Quat(golden_schema::Quat::IDENTITY) },
         ParamKind::ColorRgba => {
             quote! { golden_schema::Value::ColorRgba(golden_schema::ColorRgba { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }) }
         }
         ParamKind::Trigger => quote! { golden_schema::Value::Trigger },
+        ParamKind::FloatRange => {
+            quote! { golden_schema::Value::FloatRange(golden_schema::FloatRange { min: 0.0, max: 1.0 }) }
+        }
+        ParamKind::IntRange => {
+            quote! { golden_schema::Value::IntRange(golden_schema::IntRange { min: 0, max: 0 }) }
+        }
+        ParamKind::Bytes => quote! { golden_schema::Value::Bytes(Vec::new()) },
+        ParamKind::Timestamp => {
+            quote! { golden_schema::Value::Timestamp(golden_schema::Timestamp { unix_micros: 0 }) }
+        }
+        ParamKind::Duration => {
+            quote! { golden_schema::Value::Duration(::std::time::Duration::ZERO) }
+        }
+        ParamKind::Curve => {
+            quote! { golden_schema::Value::Curve(golden_schema::Curve::identity()) }
+        }
+        ParamKind::Gradient => {
+            quote! { golden_schema::Value::Gradient(golden_schema::Gradient::black_to_white()) }
+        }
+        ParamKind::List => quote! { golden_schema::Value::List(Vec::new()) },
+        ParamKind::FloatArray => quote! { golden_schema::Value::FloatArray(Vec::new()) },
+        ParamKind::IntArray => quote! { golden_schema::Value::IntArray(Vec::new()) },
+        ParamKind::Map => {
+            quote! { golden_schema::Value::Map(::std::collections::BTreeMap::new()) }
+        }
         ParamKind::Reference => {
             return Err(syn::Error::new_spanned(
                 default_error_tokens(),
@@ -729,6 +1235,113 @@ fn value_tokens_from_expr(
     expr: &Expr,
     args: &ParamArgs,
 ) -> Result<proc_macro2::TokenStream> {
+    match (kind, expr) {
+        (ParamKind::FloatArray | ParamKind::IntArray | ParamKind::List, Expr::Array(array)) => {
+            let items = array
+                .elems
+                .iter()
+                .map(|item| element_tokens(kind, item, args))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(collection_tokens(kind, quote! { vec![#(#items),*] }));
+        }
+        (ParamKind::FloatArray | ParamKind::IntArray | ParamKind::List, Expr::Repeat(repeat)) => {
+            let item = element_tokens(kind, &repeat.expr, args)?;
+            let len = &repeat.len;
+            return Ok(collection_tokens(kind, quote! { vec![#item; #len] }));
+        }
+        (ParamKind::Bytes, Expr::Array(array)) => {
+            let bytes = array
+                .elems
+                .iter()
+                .map(|item| match literal_number(item) {
+                    Some(value) if value.fract() == 0.0 && (0.0..=255.0).contains(&value) => {
+                        Ok(value as u8)
+                    }
+                    _ => Err(syn::Error::new_spanned(item, "Expected a byte")),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(quote! { golden_schema::Value::Bytes(vec![#(#bytes),*]) });
+        }
+        (ParamKind::Vec4 | ParamKind::Quat, Expr::Array(array)) => {
+            let [x, y, z, w] = vector_components(array)?;
+            let ty = match kind {
+                ParamKind::Quat => quote! { Quat },
+                _ => quote! { Vec4 },
+            };
+            return Ok(quote! {
+                golden_schema::Value::#ty(golden_schema::#ty { x: #x, y: #y, z: #z, w: #w })
+            });
+        }
+        (ParamKind::FloatRange, Expr::Range(range)) => {
+            let (min, max) = range_bounds(range)?;
+            return Ok(quote! {
+                golden_schema::Value::FloatRange(golden_schema::FloatRange { min: #min, max: #max })
+            });
+        }
+        (ParamKind::IntRange, Expr::Range(range)) => {
+            let (min, max) = range_bounds(range)?;
+            if min.fract() != 0.0 || max.fract() != 0.0 {
+                return Err(syn::Error::new_spanned(range, "Expected integer bounds"));
+            }
+            let (min, max) = (min as i64, max as i64);
+            return Ok(quote! {
+                golden_schema::Value::IntRange(golden_schema::IntRange { min: #min, max: #max })
+            });
+        }
+        (ParamKind::Timestamp, _) => {
+            return match literal_number(expr) {
+                Some(micros) if micros.fract() == 0.0 => {
+                    let micros = micros as i64;
+                    Ok(quote! {
+                        golden_schema::Value::Timestamp(golden_schema::Timestamp { unix_micros: #micros })
+                    })
+                }
+                _ => Err(syn::Error::new_spanned(expr, "Expected unix microseconds")),
+            };
+        }
+        (ParamKind::Duration, _) => {
+            let duration = duration_tokens(expr)?;
+            return Ok(quote! { golden_schema::Value::Duration(#duration) });
+        }
+        (ParamKind::Curve, Expr::Array(array)) => {
+            let points = array
+                .elems
+                .iter()
+                .map(|item| {
+                    let [x, y] = pair(item)?;
+                    let (Some(x), Some(y)) = (literal_number(x), literal_number(y)) else {
+                        return Err(syn::Error::new_spanned(item, "Expected an `(x, y)` point"));
+                    };
+                    Ok(quote! { (#x, #y) })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(quote! {
+                golden_schema::Value::Curve(golden_schema::Curve::from_points(&[#(#points),*]))
+            });
+        }
+        (ParamKind::Gradient, Expr::Array(array)) => {
+            let stops = array
+                .elems
+                .iter()
+                .map(|item| {
+                    let [t, color] = pair(item)?;
+                    let (Some(t), Expr::Array(color)) = (literal_number(t), color) else {
+                        return Err(syn::Error::new_spanned(
+                            item,
+                            "Expected a `(t, [r, g, b, a])` stop",
+                        ));
+                    };
+                    let [r, g, b, a] = vector_components(color)?;
+                    Ok(quote! { (#t, golden_schema::ColorRgba { r: #r, g: #g, b: #b, a: #a }) })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(quote! {
+                golden_schema::Value::Gradient(golden_schema::Gradient::from_colors(&[#(#stops),*]))
+            });
+        }
+        _ => {}
+    }
+
     match expr {
         Expr::Lit(ExprLit { lit, .. }) => match lit {
             Lit::Bool(value) => Ok(quote! { golden_schema::Value::Bool(#value) }),
@@ -790,59 +1403,131 @@ fn value_tokens_from_expr(
     }
 }
 
-fn constraints_tokens_from_args(
+/// One element of an array default: a bare number for float and int
+/// arrays, a `Value` for lists.
+fn element_tokens(
     kind: &ParamKind,
+    expr: &Expr,
     args: &ParamArgs,
 ) -> Result<proc_macro2::TokenStream> {
     match kind {
-        ParamKind::Int => {
-            if args.min.is_some()
-                || args.max.is_some()
-                || args.step.is_some()
-                || args.clamp.is_some()
-            {
-                let min = option_expr_tokens(&args.min);
-                let max = option_expr_tokens(&args.max);
-                let step = option_expr_tokens(&args.step);
-                let clamp = args
-                    .clamp
-                    .as_ref()
-                    .map(|value| value.value())
-                    .unwrap_or(false);
-                Ok(quote! {
-                    golden_schema::ValueConstraints::Int {
-                        min: #min,
-                        max: #max,
-                        clamp: #clamp,
-                        step: #step,
-                    }
-                })
-            } else {
-                Ok(quote! { golden_schema::ValueConstraints::None })
+        ParamKind::FloatArray => match literal_number(expr) {
+            Some(value) => Ok(quote! { #value }),
+            None => Err(syn::Error::new_spanned(expr, "Expected a number")),
+        },
+        ParamKind::IntArray => match literal_number(expr) {
+            Some(value) if value.fract() == 0.0 => {
+                let value = value as i64;
+                Ok(quote! { #value })
             }
+            _ => Err(syn::Error::new_spanned(expr, "Expected an integer")),
+        },
+        _ => value_tokens_from_expr(kind, expr, args),
+    }
+}
+
+fn collection_tokens(
+    kind: &ParamKind,
+    items: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match kind {
+        ParamKind::FloatArray => quote! { golden_schema::Value::FloatArray(#items) },
+        ParamKind::IntArray => quote! { golden_schema::Value::IntArray(#items) },
+        _ => quote! { golden_schema::Value::List(#items) },
+    }
+}
+
+/// The four components of a `[x, y, z, w]` default.
+fn vector_components(array: &ExprArray) -> Result<[f64; 4]> {
+    let components = array
+        .elems
+        .iter()
+        .map(|item| {
+            literal_number(item).ok_or_else(|| syn::Error::new_spanned(item, "Expected a number"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    components
+        .try_into()
+        .map_err(|_| syn::Error::new_spanned(array, "Expected four components"))
+}
+
+/// The two elements of a `(a, b)` tuple.
+fn pair(expr: &Expr) -> Result<[&Expr; 2]> {
+    match expr {
+        Expr::Tuple(tuple) if tuple.elems.len() == 2 => Ok([&tuple.elems[0], &tuple.elems[1]]),
+        _ => Err(syn::Error::new_spanned(expr, "Expected a pair")),
+    }
+}
+
+/// Both bounds of a `min..max` default.
+fn range_bounds(range: &syn::ExprRange) -> Result<(f64, f64)> {
+    let bound = |bound: &Option<Box<Expr>>| bound.as_deref().and_then(literal_number);
+    match (bound(&range.start), bound(&range.end)) {
+        (Some(min), Some(max)) => Ok((min, max)),
+        _ => Err(syn::Error::new_spanned(
+            range,
+            "Expected a `min..max` range",
+        )),
+    }
+}
+
+/// A `std::time::Duration` from a literal with an `s`, `ms`, `us` or `ns`
+/// suffix, such as `250ms` or `1.5s`.
+fn duration_tokens(expr: &Expr) -> Result<proc_macro2::TokenStream> {
+    let (digits, suffix) = match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(value),
+            ..
+        }) => (
+            value.base10_digits().to_string(),
+            value.suffix().to_string(),
+        ),
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(value),
+            ..
+        }) => (
+            value.base10_digits().to_string(),
+            value.suffix().to_string(),
+        ),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "Expected a duration such as `250ms`",
+            ));
         }
-        ParamKind::Float => {
+    };
+    let scale = match suffix.as_str() {
+        "s" => 1e9,
+        "ms" => 1e6,
+        "us" => 1e3,
+        "ns" => 1.0,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "Durations need an `s`, `ms`, `us` or `ns` suffix",
+            ));
+        }
+    };
+    let nanos = digits
+        .parse::<f64>()
+        .map_err(|err| syn::Error::new_spanned(expr, err))?
+        * scale;
+    let nanos = nanos.round() as u64;
+    Ok(quote! { ::std::time::Duration::from_nanos(#nanos) })
+}
+
+fn constraints_tokens_from_args(
+    kind: &ParamKind,
+    args: &ParamArgs,
+) -> Result<proc_macro2::TokenStream> {
+    match kind {
+        ParamKind::Int | ParamKind::Float | ParamKind::IntRange | ParamKind::FloatRange => {
             if args.min.is_some()
                 || args.max.is_some()
                 || args.step.is_some()
                 || args.clamp.is_some()
             {
-                let min = option_expr_tokens(&args.min);
-                let max = option_expr_tokens(&args.max);
-                let step = option_expr_tokens(&args.step);
-                let clamp = args
-                    .clamp
-                    .as_ref()
-                    .map(|value| value.value())
-                    .unwrap_or(false);
-                Ok(quote! {
-                    golden_schema::ValueConstraints::Float {
-                        min: #min,
-                        max: #max,
-                        clamp: #clamp,
-                        step: #step,
-                    }
-                })
+                Ok(numeric_constraints_tokens(kind, args))
             } else {
                 Ok(quote! { golden_schema::ValueConstraints::None })
             }
@@ -903,10 +1588,220 @@ fn constraints_tokens_from_args(
                 .unwrap_or_else(|| quote! { None });
             Ok(quote! { golden_schema::ValueConstraints::Reference { target: #target } })
         }
+        ParamKind::Duration => {
+            if args.min.is_none() && args.max.is_none() && args.clamp.is_none() {
+                return Ok(quote! { golden_schema::ValueConstraints::None });
+            }
+            let bound = |value: &Option<Expr>| -> Result<proc_macro2::TokenStream> {
+                match value {
+                    Some(value) => {
+                        let duration = duration_tokens(value)?;
+                        Ok(quote! { Some(#duration) })
+                    }
+                    None => Ok(quote! { None }),
+                }
+            };
+            let min = bound(&args.min)?;
+            let max = bound(&args.max)?;
+            let clamp = args
+                .clamp
+                .as_ref()
+                .map(|value| value.value())
+                .unwrap_or(false);
+            Ok(quote! {
+                golden_schema::ValueConstraints::Duration {
+                    min: #min,
+                    max: #max,
+                    clamp: #clamp,
+                }
+            })
+        }
+        ParamKind::Curve | ParamKind::Gradient => {
+            let bounds = |value: &Option<Expr>| -> Result<proc_macro2::TokenStream> {
+                match value {
+                    Some(Expr::Range(range)) => {
+                        let (min, max) = range_bounds(range)?;
+                        Ok(quote! { Some(golden_schema::FloatRange { min: #min, max: #max }) })
+                    }
+                    Some(value) => Err(syn::Error::new_spanned(value, "Expected `min..max`")),
+                    None => Ok(quote! { None }),
+                }
+            };
+            if args.domain.is_none() && args.range.is_none() {
+                return Ok(quote! { golden_schema::ValueConstraints::None });
+            }
+            let domain = bounds(&args.domain)?;
+            let clamp = args
+                .clamp
+                .as_ref()
+                .map(|value| value.value())
+                .unwrap_or(false);
+            if *kind == ParamKind::Gradient {
+                if let Some(range) = &args.range {
+                    return Err(syn::Error::new_spanned(
+                        range,
+                        "`range` only applies to curves",
+                    ));
+                }
+                return Ok(quote! {
+                    golden_schema::ValueConstraints::Gradient {
+                        domain: #domain,
+                        clamp: #clamp,
+                    }
+                });
+            }
+            let range = bounds(&args.range)?;
+            Ok(quote! {
+                golden_schema::ValueConstraints::Curve {
+                    domain: #domain,
+                    range: #range,
+                    clamp: #clamp,
+                }
+            })
+        }
+        ParamKind::Bytes
+            if args.min_len.is_none() && args.max_len.is_none() && args.len.is_none() =>
+        {
+            Ok(quote! { golden_schema::ValueConstraints::None })
+        }
+        ParamKind::List
+        | ParamKind::FloatArray
+        | ParamKind::IntArray
+        | ParamKind::Map
+        | ParamKind::Bytes => {
+            let element = match kind {
+                ParamKind::FloatArray => Some(ParamKind::Float),
+                ParamKind::IntArray => Some(ParamKind::Int),
+                ParamKind::Bytes => None,
+                _ => args.element,
+            };
+            // Typed elements always get a constraint so their kind is checked.
+            let element = match element {
+                Some(element @ (ParamKind::Int | ParamKind::Float)) => {
+                    numeric_constraints_tokens(&element, args)
+                }
+                Some(ParamKind::String) => {
+                    let pattern = args
+                        .pattern
+                        .as_ref()
+                        .map(|value| {
+                            let value = value.value();
+                            quote! { Some(#value.to_string()) }
+                        })
+                        .unwrap_or_else(|| quote! { None });
+                    quote! {
+                        golden_schema::ValueConstraints::String {
+                            max_len: None,
+                            pattern: #pattern,
+                        }
+                    }
+                }
+                Some(element @ (ParamKind::Reference | ParamKind::Enum)) => {
+                    constraints_tokens_from_args(&element, args)?
+                }
+                _ => quote! { golden_schema::ValueConstraints::None },
+            };
+            let len = |value: &Option<LitInt>| -> Result<proc_macro2::TokenStream> {
+                match value {
+                    Some(value) => {
+                        let value = value.base10_parse::<usize>()?;
+                        Ok(quote! { Some(#value) })
+                    }
+                    None => Ok(quote! { None }),
+                }
+            };
+            let (min_len, max_len) = match &args.len {
+                Some(_) => (len(&args.len)?, len(&args.len)?),
+                None => (len(&args.min_len)?, len(&args.max_len)?),
+            };
+            Ok(quote! {
+                golden_schema::ValueConstraints::Collection {
+                    min_len: #min_len,
+                    max_len: #max_len,
+                    element: Box::new(#element),
+                }
+            })
+        }
         _ => Ok(quote! { golden_schema::ValueConstraints::None }),
     }
 }
 
+fn numeric_constraints_tokens(kind: &ParamKind, args: &ParamArgs) -> proc_macro2::TokenStream {
+    let min = option_expr_tokens(&args.min);
+    let max = option_expr_tokens(&args.max);
+    let step = option_expr_tokens(&args.step);
+    let clamp = args
+        .clamp
+        .as_ref()
+        .map(|value| value.value())
+        .unwrap_or(false);
+    let variant = match kind {
+        ParamKind::Int | ParamKind::IntRange => quote! { Int },
+        _ => quote! { Float },
+    };
+    quote! {
+        golden_schema::ValueConstraints::#variant {
+            min: #min,
+            max: #max,
+            clamp: #clamp,
+            step: #step,
+        }
+    }
+}
+
+/// Element kind of a `Vec<T>` or `BTreeMap<String, T>` parameter type.
+fn element_kind(ty: &Type) -> Option<ParamKind> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let index = match segment.ident.to_string().as_str() {
+        "Vec" => 0,
+        "BTreeMap" => 1,
+        _ => return None,
+    };
+    match args.args.iter().nth(index)? {
+        syn::GenericArgument::Type(element) => extract_param_kind_value_type(element).ok(),
+        _ => None,
+    }
+}
+
+/// Rejects unit strings missing from `golden_schema::units`, which the engine
+/// could not convert.
+fn check_unit(unit: LitStr) -> syn::Result<LitStr> {
+    if golden_schema::units::unit(&unit.value()).is_some() {
+        return Ok(unit);
+    }
+    let known: Vec<&str> = golden_schema::units::UNITS
+        .iter()
+        .map(|unit| unit.symbol)
+        .collect();
+    Err(syn::Error::new(
+        unit.span(),
+        format!(
+            "unknown unit \"{}\"; expected one of {}",
+            unit.value(),
+            known.join(", ")
+        ),
+    ))
+}
+
+fn check_save(save: LitStr) -> syn::Result<LitStr> {
+    if ["None", "Delta", "Full"].contains(&save.value().as_str()) {
+        return Ok(save);
+    }
+    Err(syn::Error::new(
+        save.span(),
+        format!(
+            "unknown save policy \"{}\"; expected None, Delta or Full",
+            save.value()
+        ),
+    ))
+}
+
 fn semantics_tokens(semantics: &Option<LitStr>, unit: &Option<LitStr>) -> proc_macro2::TokenStream {
     let intent = semantics
         .as_ref()
@@ -1011,10 +1906,16 @@ struct ParamOptions {
     min: Option<Expr>,
     max: Option<Expr>,
     step: Option<Expr>,
+    min_len: Option<LitInt>,
+    max_len: Option<LitInt>,
+    len: Option<LitInt>,
+    domain: Option<Expr>,
+    range: Option<Expr>,
     sem: Option<LitStr>,
     unit: Option<LitStr>,
     behavior: Option<LitStr>,
     alias: Option<LitStr>,
+    save: Option<LitStr>,
     direct_access: bool,
 }
 
@@ -1120,14 +2021,26 @@ fn parse_param_item(input: ParseStream, name: Ident) -> Result<ParamItem> {
                     options.max = Some(content.parse()?);
                 } else if key == "step" {
                     options.step = Some(content.parse()?);
+                } else if key == "min_len" {
+                    options.min_len = Some(content.parse()?);
+                } else if key == "max_len" {
+                    options.max_len = Some(content.parse()?);
+                } else if key == "len" {
+                    options.len = Some(content.parse()?);
+                } else if key == "domain" {
+                    options.domain = Some(content.parse()?);
+                } else if key == "range" {
+                    options.range = Some(content.parse()?);
                 } else if key == "sem" || key == "semantics" {
                     options.sem = Some(content.parse()?);
                 } else if key == "unit" {
-                    options.unit = Some(content.parse()?);
+                    options.unit = Some(check_unit(content.parse()?)?);
                 } else if key == "behavior" {
                     options.behavior = Some(content.parse()?);
                 } else if key == "alias" {
                     options.alias = Some(content.parse()?);
+                } else if key == "save" {
+                    options.save = Some(check_save(content.parse()?)?);
                 } else {
                     let _skip: Expr = content.parse()?;
                 }
@@ -1148,8 +2061,28 @@ fn parse_param_item(input: ParseStream, name: Ident) -> Result<ParamItem> {
 }
 
 fn parse_simple_expr(input: ParseStream) -> Result<Expr> {
+    if input.peek(syn::token::Bracket) {
+        // `[x; n]` or `[a, b, ..]`; parsed on their own so a following
+        // `[min..max]` is not read as an index.
+        if input.fork().parse::<syn::ExprRepeat>().is_ok() {
+            return Ok(Expr::Repeat(input.parse()?));
+        }
+        return Ok(Expr::Array(input.parse()?));
+    }
+
     if input.peek(LitBool) || input.peek(LitInt) || input.peek(LitFloat) || input.peek(LitStr) {
         let literal: ExprLit = input.parse()?;
+        if input.peek(Token![..]) {
+            // `min..max` range default.
+            let limits: syn::RangeLimits = input.parse()?;
+            let end: ExprLit = input.parse()?;
+            return Ok(Expr::Range(syn::ExprRange {
+                attrs: Vec::new(),
+                start: Some(Box::new(Expr::Lit(literal))),
+                limits,
+                end: Some(Box::new(Expr::Lit(end))),
+            }));
+        }
         return Ok(Expr::Lit(literal));
     }
 
@@ -1174,10 +2107,17 @@ fn collect_params_from_item(
                 min: param.options.min.clone(),
                 max: param.options.max.clone(),
                 step: param.options.step.clone(),
+                min_len: param.options.min_len.clone(),
+                max_len: param.options.max_len.clone(),
+                len: param.options.len.clone(),
+                domain: param.options.domain.clone(),
+                range: param.options.range.clone(),
+                element: element_kind(&param.ty),
                 semantics: param.options.sem.clone(),
                 unit: param.options.unit.clone(),
                 behavior: param.options.behavior.clone(),
                 alias: param.options.alias.clone(),
+                save: param.options.save.clone(),
                 read_only: false,
                 ..Default::default()
             };
@@ -1190,6 +2130,11 @@ fn collect_params_from_item(
                 }
             };
 
+            if let Err(err) = check_numeric_args(kind, &param.name, &args) {
+                param_decls.push(err.to_compile_error());
+                return;
+            }
+
             let default_tokens = match value_tokens_from_args(&kind, &args) {
                 Ok(tokens) => tokens,
                 Err(err) => {
@@ -1213,6 +2158,7 @@ fn collect_params_from_item(
 
             let behavior_tokens = behavior_tokens(&args.behavior);
             let change_tokens = change_policy_tokens_with_kind(&args.change, &kind);
+            let save_tokens = save_policy_tokens(&args.save);
             let semantics_tokens = semantics_tokens(&args.semantics, &args.unit);
             let alias_tokens = if param.options.direct_access {
                 let mut alias = param.name.to_string();
@@ -1235,7 +2181,7 @@ fn collect_params_from_item(
                     read_only: false,
                     update: golden_schema::UpdatePolicy::Immediate,
                     change: #change_tokens,
-                    save: golden_schema::SavePolicy::Delta,
+                    save: #save_tokens,
                     semantics: #semantics_tokens,
                     presentation: golden_schema::PresentationHint { widget: None },
                     folder: #folder_tokens,
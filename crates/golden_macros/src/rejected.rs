@@ -0,0 +1,142 @@
+//! Declarations the `GoldenNode` derive and `params!` must reject, kept as
+//! `compile_fail` doc-tests. Each one differs from this accepted node in a
+//! single place:
+//!
+//! ```
+//! use golden_core::data::{FolderHandle, ParameterHandle};
+//! use golden_core::graph::node::NodeReactive;
+//! use golden_macros::{GoldenNode, params};
+//! use golden_schema::NodeId;
+//!
+//! #[derive(GoldenNode)]
+//! #[behaviour(reactive)]
+//! pub struct Delay {
+//!     #[node_id]
+//!     id: NodeId,
+//!     timing: FolderHandle,
+//!     time: ParameterHandle<f64>,
+//!     feedback: ParameterHandle<f64>,
+//! }
+//!
+//! impl Delay {
+//!     params! {
+//!         feedback: f64 = 0.5 [0.0..1.0];
+//!         folder(timing) {
+//!             time: f64 = 120.0 (unit = "ms", min = 0.0);
+//!         }
+//!     }
+//! }
+//!
+//! impl NodeReactive for Delay {}
+//! ```
+//!
+//! A `params!` entry without a field:
+//!
+//! ```compile_fail
+//! # use golden_core::data::{FolderHandle, ParameterHandle};
+//! # use golden_core::graph::node::NodeReactive;
+//! # use golden_macros::{GoldenNode, params};
+//! # use golden_schema::NodeId;
+//! #[derive(GoldenNode)]
+//! #[behaviour(reactive)]
+//! pub struct Delay {
+//!     #[node_id]
+//!     id: NodeId,
+//!     timing: FolderHandle,
+//!     time: ParameterHandle<f64>,
+//! }
+//!
+//! impl Delay {
+//!     params! {
+//!         feedback: f64 = 0.5 [0.0..1.0];
+//!         folder(timing) {
+//!             time: f64 = 120.0 (unit = "ms", min = 0.0);
+//!         }
+//!     }
+//! }
+//! # impl NodeReactive for Delay {}
+//! ```
+//!
+//! A field holding a different kind than its declaration:
+//!
+//! ```compile_fail
+//! # use golden_core::data::{FolderHandle, ParameterHandle};
+//! # use golden_core::graph::node::NodeReactive;
+//! # use golden_macros::{GoldenNode, params};
+//! # use golden_schema::NodeId;
+//! #[derive(GoldenNode)]
+//! #[behaviour(reactive)]
+//! pub struct Delay {
+//!     #[node_id]
+//!     id: NodeId,
+//!     timing: FolderHandle,
+//!     time: ParameterHandle<f64>,
+//!     feedback: ParameterHandle<bool>,
+//! }
+//!
+//! impl Delay {
+//!     params! {
+//!         feedback: f64 = 0.5 [0.0..1.0];
+//!         folder(timing) {
+//!             time: f64 = 120.0 (unit = "ms", min = 0.0);
+//!         }
+//!     }
+//! }
+//! # impl NodeReactive for Delay {}
+//! ```
+//!
+//! A folder field without a folder of that name:
+//!
+//! ```compile_fail
+//! # use golden_core::data::{FolderHandle, ParameterHandle};
+//! # use golden_core::graph::node::NodeReactive;
+//! # use golden_macros::{GoldenNode, params};
+//! # use golden_schema::NodeId;
+//! #[derive(GoldenNode)]
+//! #[behaviour(reactive)]
+//! pub struct Delay {
+//!     #[node_id]
+//!     id: NodeId,
+//!     timing: FolderHandle,
+//!     time: ParameterHandle<f64>,
+//!     feedback: ParameterHandle<f64>,
+//! }
+//!
+//! impl Delay {
+//!     params! {
+//!         feedback: f64 = 0.5 [0.0..1.0];
+//!         folder(clock) {
+//!             time: f64 = 120.0 (unit = "ms", min = 0.0);
+//!         }
+//!     }
+//! }
+//! # impl NodeReactive for Delay {}
+//! ```
+//!
+//! A unit `golden_schema::units` does not know:
+//!
+//! ```compile_fail
+//! # use golden_core::data::{FolderHandle, ParameterHandle};
+//! # use golden_core::graph::node::NodeReactive;
+//! # use golden_macros::{GoldenNode, params};
+//! # use golden_schema::NodeId;
+//! #[derive(GoldenNode)]
+//! #[behaviour(reactive)]
+//! pub struct Delay {
+//!     #[node_id]
+//!     id: NodeId,
+//!     timing: FolderHandle,
+//!     time: ParameterHandle<f64>,
+//!     feedback: ParameterHandle<f64>,
+//! }
+//!
+//! impl Delay {
+//!     params! {
+//!         feedback: f64 = 0.5 [0.0..1.0];
+//!         folder(timing) {
+//!             time: f64 = 120.0 (unit = "msec", min = 0.0);
+//!         }
+//!     }
+//! }
+//! # impl NodeReactive for Delay {}
+//! ```
@@ -11,13 +11,11 @@ use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tower_http::services::{ServeDir, ServeFile};
 
-use golden_core::edits::{Edit, EditOrigin, Propagation};
 use golden_core::Engine;
-use golden_schema::ui::messages::{
-    EventBatch, GetSnapshot, MessageEnvelope, SetParam, Snapshot, Subscribe,
-};
+use golden_schema::ui::codecs::Frame;
 
-use crate::snapshot::build_snapshot;
+use crate::rest::rest_router;
+use crate::session::run_session;
 
 #[derive(Clone, Debug)]
 pub struct AppServerConfig {
@@ -39,6 +37,7 @@ pub async fn start_app_server(
 
     let app = Router::new()
         .route("/ws", get(ws_handler))
+        .nest("/api", rest_router(Arc::clone(&engine)))
         .fallback_service(static_service)
         .with_state(AppState { engine });
 
@@ -56,136 +55,42 @@ async fn ws_handler(
 
 async fn handle_socket(engine: Arc<Mutex<Engine>>, socket: WebSocket) {
     let (mut ws_write, mut ws_read) = socket.split();
-    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
+    let (in_tx, in_rx) = mpsc::unbounded_channel::<Frame>();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Frame>();
 
+    let session = tokio::spawn(run_session(engine, in_rx, out_tx));
     let writer = tokio::spawn(async move {
-        while let Some(text) = out_rx.recv().await {
-            if ws_write.send(Message::Text(text)).await.is_err() {
+        while let Some(frame) = out_rx.recv().await {
+            let msg = match frame {
+                Frame::Text(text) => Message::Text(text),
+                Frame::Binary(bytes) => Message::Binary(bytes),
+            };
+            if ws_write.send(msg).await.is_err() {
                 break;
             }
         }
     });
 
-    let snapshot = build_snapshot(&engine.lock().unwrap());
-    if send_snapshot(&out_tx, snapshot).is_err() {
-        return;
-    }
-
-    let mut subscription_task: Option<tokio::task::JoinHandle<()>> = None;
-
     while let Some(result) = ws_read.next().await {
         let msg = match result {
             Ok(msg) => msg,
             Err(_) => break,
         };
 
-        let Message::Text(text) = msg else {
-            continue;
+        let frame = match msg {
+            Message::Text(text) => Frame::Text(text),
+            Message::Binary(bytes) => Frame::Binary(bytes),
+            _ => continue,
         };
 
-        if let Ok(envelope) = serde_json::from_str::<MessageEnvelope<serde_json::Value>>(&text) {
-            let payload = envelope.payload;
-            match envelope.msg.as_str() {
-                "GetSnapshot" => {
-                    let _ = serde_json::from_value::<GetSnapshot>(payload);
-                    let snapshot = build_snapshot(&engine.lock().unwrap());
-                    if send_snapshot(&out_tx, snapshot).is_err() {
-                        break;
-                    }
-                }
-                "SetParam" => {
-                    if let Ok(set_param) = serde_json::from_value::<SetParam>(payload) {
-                        let snapshot = {
-                            let mut engine = engine.lock().unwrap();
-                            let propagation = match set_param.propagation {
-                                golden_schema::ui::messages::Propagation::Immediate => {
-                                    Propagation::Immediate
-                                }
-                                golden_schema::ui::messages::Propagation::EndOfTick => {
-                                    Propagation::EndOfTick
-                                }
-                                golden_schema::ui::messages::Propagation::NextTick => {
-                                    Propagation::NextTick
-                                }
-                            };
-                            engine.enqueue_edit(
-                                Edit::SetParam {
-                                    node: set_param.param_node_id,
-                                    value: set_param.value,
-                                },
-                                propagation,
-                                EditOrigin::Network,
-                            );
-                            engine.tick();
-                            build_snapshot(&engine)
-                        };
-                        if send_snapshot(&out_tx, snapshot).is_err() {
-                            break;
-                        }
-                    }
-                }
-                "Subscribe" => {
-                    if let Ok(subscribe) = serde_json::from_value::<Subscribe>(payload) {
-                        if let Some(task) = subscription_task.take() {
-                            task.abort();
-                        }
-                        let engine = Arc::clone(&engine);
-                        let out_tx = out_tx.clone();
-                        subscription_task = Some(tokio::spawn(async move {
-                            let mut last_time = subscribe.from;
-                            let mut interval =
-                                tokio::time::interval(std::time::Duration::from_millis(8));
-                            loop {
-                                interval.tick().await;
-                                let events = {
-                                    let engine = engine.lock().unwrap();
-                                    engine.events_since(last_time)
-                                };
-                                if let Some(last) = events.last() {
-                                    last_time = last.time;
-                                }
-                                if events.is_empty() {
-                                    continue;
-                                }
-                                let batch = EventBatch { events };
-                                if send_event_batch(&out_tx, batch).is_err() {
-                                    break;
-                                }
-                            }
-                        }));
-                    }
-                }
-                _ => {}
-            }
+        if in_tx.send(frame).is_err() {
+            break;
         }
     }
 
-    if let Some(task) = subscription_task {
-        task.abort();
+    drop(in_tx);
+    if let Ok(Err(err)) = session.await {
+        eprintln!("app session error: {err}");
     }
     let _ = writer.await;
 }
-
-fn send_snapshot(tx: &mpsc::UnboundedSender<String>, snapshot: Snapshot) -> anyhow::Result<()> {
-    let envelope = MessageEnvelope {
-        msg: "Snapshot".to_string(),
-        req_id: None,
-        payload: snapshot,
-    };
-    let text = serde_json::to_string(&envelope)?;
-    tx.send(text)
-        .map_err(|_| anyhow::anyhow!("ws send failed"))?;
-    Ok(())
-}
-
-fn send_event_batch(tx: &mpsc::UnboundedSender<String>, batch: EventBatch) -> anyhow::Result<()> {
-    let envelope = MessageEnvelope {
-        msg: "EventBatch".to_string(),
-        req_id: None,
-        payload: batch,
-    };
-    let text = serde_json::to_string(&envelope)?;
-    tx.send(text)
-        .map_err(|_| anyhow::anyhow!("ws send failed"))?;
-    Ok(())
-}
@@ -258,14 +258,44 @@ const JS_BUNDLE: &str = r#"(() => {
     return map;
   };
 
+  // Durations decode to seconds, timestamps to ISO strings and bytes to hex.
   const decodeValue = (value) => {
     if (value && typeof value === 'object') {
       const key = Object.keys(value)[0];
-      return { kind: key, value: value[key] };
+      const inner = value[key];
+      if (key === 'Duration') {
+        return { kind: key, value: inner.secs + inner.nanos / 1e9 };
+      }
+      if (key === 'Timestamp') {
+        return { kind: key, value: new Date(inner.unix_micros / 1000).toISOString() };
+      }
+      if (key === 'Bytes') {
+        return { kind: key, value: inner.map((byte) => byte.toString(16).padStart(2, '0')).join(' ') };
+      }
+      return { kind: key, value: inner };
     }
     return { kind: 'Unknown', value };
   };
 
+  // Applies a ValuePatch to a List, FloatArray, IntArray or Map value.
+  const applyValuePatch = (value, patch) => {
+    const decoded = decodeValue(value);
+    const op = Object.keys(patch)[0];
+    const args = patch[op];
+    if (decoded.kind === 'Map') {
+      const entries = { ...decoded.value };
+      if (op === 'SetKey') entries[args.key] = args.value;
+      if (op === 'RemoveKey') delete entries[args.key];
+      return { Map: entries };
+    }
+    const element = decoded.kind === 'List' ? args.value : decodeValue(args.value).value;
+    const items = [...decoded.value];
+    if (op === 'SetIndex') items[args.index] = element;
+    if (op === 'Insert') items.splice(args.index, 0, element);
+    if (op === 'Remove') items.splice(args.index, 1);
+    return { [decoded.kind]: items };
+  };
+
   const decodeConstraints = (constraints) => {
     if (constraints && typeof constraints === 'object') {
       const key = Object.keys(constraints)[0];
@@ -498,6 +528,14 @@ const JS_BUNDLE: &str = r#"(() => {
           return;
         }
 
+        if (kind === 'ParamPatched') {
+          const param = state.params.find((p) => p.param_node_id === payload.param);
+          if (!param) return;
+          param.value = applyValuePatch(param.value, payload.patch);
+          updateParamDisplay(payload.param, param.value);
+          return;
+        }
+
         if (kind === 'MetaChanged') {
           const node = state.nodes.find((n) => n.node_id === payload.node);
           if (node && payload.patch) {
@@ -508,6 +546,8 @@ const JS_BUNDLE: &str = r#"(() => {
             if (patch.tags !== undefined) node.meta.tags = patch.tags;
             if (patch.semantics !== undefined) node.meta.semantics = patch.semantics;
             if (patch.presentation !== undefined) node.meta.presentation = patch.presentation;
+            if (patch.expression !== undefined) node.meta.expression = patch.expression;
+            if (patch.expression_error !== undefined) node.meta.expression_error = patch.expression_error;
           }
           return;
         }
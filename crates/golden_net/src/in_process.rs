@@ -0,0 +1,45 @@
+use std::sync::{Arc, Mutex};
+
+use golden_core::Engine;
+use golden_schema::ui::codecs::Frame;
+use tokio::sync::mpsc;
+
+use crate::session::run_session;
+
+/// A UI protocol client connected to the engine without a socket.
+///
+/// Frames are the same envelopes a WebSocket client would exchange, so
+/// embedded tools and tests can speak the protocol directly.
+pub struct InProcessClient {
+    tx: mpsc::UnboundedSender<Frame>,
+    rx: mpsc::UnboundedReceiver<Frame>,
+}
+
+impl InProcessClient {
+    /// Spawns a session task on the current tokio runtime.
+    pub fn connect(engine: Arc<Mutex<Engine>>) -> Self {
+        let (in_tx, in_rx) = mpsc::unbounded_channel::<Frame>();
+        let (out_tx, out_rx) = mpsc::unbounded_channel::<Frame>();
+        tokio::spawn(run_session(engine, in_rx, out_tx));
+        Self {
+            tx: in_tx,
+            rx: out_rx,
+        }
+    }
+
+    pub fn send(&self, frame: Frame) -> anyhow::Result<()> {
+        self.tx.send(frame).map_err(|_| anyhow::anyhow!("in-process session closed"))
+    }
+
+    pub fn send_text(&self, text: impl Into<String>) -> anyhow::Result<()> {
+        self.send(Frame::Text(text.into()))
+    }
+
+    pub async fn recv(&mut self) -> Option<Frame> {
+        self.rx.recv().await
+    }
+
+    pub fn try_recv(&mut self) -> Option<Frame> {
+        self.rx.try_recv().ok()
+    }
+}
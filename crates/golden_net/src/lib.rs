@@ -1,8 +1,16 @@
 pub mod app_server;
 pub mod http_server;
+pub mod in_process;
+pub mod osc;
+pub mod rest;
+pub mod session;
 pub mod snapshot;
 pub mod ws_server;
 
 pub use app_server::{start_app_server, AppServerConfig};
 pub use http_server::{HttpServerConfig, start_http_server};
+pub use in_process::InProcessClient;
+pub use osc::{OscBridgeConfig, OscQueryConfig, OscSender, start_osc_bridge, start_oscquery_server};
+pub use rest::rest_router;
+pub use session::{ClientSession, run_session};
 pub use ws_server::{WsServerConfig, start_ws_server};
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use golden_core::edits::{Edit, EditOrigin, Propagation};
+use golden_core::{Engine, NodeData};
+use golden_schema::{EventKind, NodeId, Value};
+
+use crate::osc::mapping::{OscAddressMap, args_to_value, value_to_args, with_whole_value};
+use crate::osc::packet::{OscMessage, decode_packet, encode_message};
+
+/// Blocking OSC sender usable from node behaviours.
+///
+/// Sends never wait for the network; failures are reported but not retried.
+pub struct OscSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl OscSender {
+    pub fn connect(host: &str, port: u16) -> std::io::Result<Self> {
+        let target = (host, port).to_socket_addrs()?.next().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, format!("cannot resolve {host}"))
+        })?;
+        let bind: SocketAddr = if target.is_ipv4() {
+            SocketAddr::from(([0, 0, 0, 0], 0))
+        } else {
+            SocketAddr::from(([0u16; 8], 0))
+        };
+        let socket = UdpSocket::bind(bind)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            target,
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub fn send(&self, message: &OscMessage) -> std::io::Result<()> {
+        self.socket.send_to(&encode_message(message), self.target).map(|_| ())
+    }
+
+    pub fn send_value(&self, address: &str, value: &Value) -> std::io::Result<()> {
+        self.send(&OscMessage::new(address, value_to_args(value)))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OscBridgeConfig {
+    /// Address to receive OSC on; `None` disables input.
+    pub listen: Option<SocketAddr>,
+    /// Peer that receives every parameter change; `None` disables output.
+    pub send_to: Option<SocketAddr>,
+    /// Subtree exposed over OSC; defaults to the engine root.
+    pub root: Option<NodeId>,
+    pub poll_interval: Duration,
+}
+
+impl Default for OscBridgeConfig {
+    fn default() -> Self {
+        Self {
+            listen: None,
+            send_to: None,
+            root: None,
+            poll_interval: Duration::from_millis(8),
+        }
+    }
+}
+
+/// Mirrors a subtree of the engine over OSC.
+///
+/// Incoming messages become `SetParam` edits with [`EditOrigin::Network`];
+/// parameter changes from the event log are sent to `send_to`, except those
+/// the bridge applied itself, which would only echo back to the sender.
+pub async fn start_osc_bridge(
+    engine: Arc<Mutex<Engine>>,
+    config: OscBridgeConfig,
+) -> anyhow::Result<()> {
+    let bind = config.listen.unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+    let socket = tokio::net::UdpSocket::bind(bind).await?;
+
+    let (mut addresses, mut last_time) = {
+        let engine = engine.lock().unwrap();
+        let mut addresses = OscAddressMap::new(config.root.unwrap_or(engine.root_id()));
+        addresses.rebuild(&engine);
+        (addresses, engine.time)
+    };
+
+    // Values written from OSC input per parameter, not yet seen as events.
+    let mut echoes: HashMap<NodeId, Vec<Value>> = HashMap::new();
+    let mut interval = tokio::time::interval(config.poll_interval);
+    let mut buffer = vec![0u8; 65536];
+    loop {
+        tokio::select! {
+            received = socket.recv_from(&mut buffer), if config.listen.is_some() => {
+                let size = match received {
+                    Ok((size, _)) => size,
+                    // Such as ECONNRESET after sending to a closed port; the
+                    // socket stays usable.
+                    Err(err) => {
+                        eprintln!("osc receive error: {err}");
+                        continue;
+                    }
+                };
+                let packet = match decode_packet(&buffer[..size]) {
+                    Ok(packet) => packet,
+                    Err(err) => {
+                        eprintln!("osc decode error: {err}");
+                        continue;
+                    }
+                };
+                let mut engine = engine.lock().unwrap();
+                addresses.sync(&engine);
+                for message in packet.into_messages() {
+                    if let Some((node, value)) = apply_message(&mut engine, &addresses, message) {
+                        let written = echoes.entry(node).or_default();
+                        if written.last() != Some(&value) {
+                            written.push(value);
+                        }
+                    }
+                }
+            }
+            _ = interval.tick() => {
+                let events = {
+                    let engine = engine.lock().unwrap();
+                    addresses.sync(&engine);
+                    engine
+                        .events_since(last_time)
+                        .into_iter()
+                        .map(|event| with_whole_value(&engine, event))
+                        .collect::<Vec<_>>()
+                };
+                let Some(last) = events.last() else {
+                    continue;
+                };
+                last_time = last.time;
+
+                let Some(target) = config.send_to else {
+                    echoes.clear();
+                    continue;
+                };
+                for event in events {
+                    let EventKind::ParamChanged { param, value } = event.kind else {
+                        continue;
+                    };
+                    if is_echo(&mut echoes, param, &value) {
+                        continue;
+                    }
+                    let Some(address) = addresses.address_of(param) else {
+                        continue;
+                    };
+                    let message = OscMessage::new(address, value_to_args(&value));
+                    if let Err(err) = socket.send_to(&encode_message(&message), target).await {
+                        eprintln!("osc send error: {err}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Whether a change of `param` to `value` is one the bridge applied from
+/// OSC input. Any change of `param` forgets the values written before it, so
+/// a write that was clamped or rejected does not hide a later one.
+fn is_echo(echoes: &mut HashMap<NodeId, Vec<Value>>, param: NodeId, value: &Value) -> bool {
+    let Some(written) = echoes.get_mut(&param) else {
+        return false;
+    };
+    let echo = match written.iter().position(|written| written == value) {
+        Some(index) => {
+            written.drain(..=index);
+            true
+        }
+        None => {
+            written.clear();
+            false
+        }
+    };
+    if written.is_empty() {
+        echoes.remove(&param);
+    }
+    echo
+}
+
+/// Submits `message` as a `SetParam` edit and returns the parameter and the
+/// value written, or `None` if the address is unknown or the edit rejected.
+pub(crate) fn apply_message(
+    engine: &mut Engine,
+    addresses: &OscAddressMap,
+    message: OscMessage,
+) -> Option<(NodeId, Value)> {
+    let node = addresses.resolve(&message.address)?;
+    let current = engine.nodes.get(&node).and_then(|node| match &node.data {
+        NodeData::Parameter(param) => Some(param.value.clone()),
+        _ => None,
+    })?;
+    let Some(value) = args_to_value(&message.args, &current) else {
+        eprintln!("osc: cannot apply {:?} to {}", message.args, message.address);
+        return None;
+    };
+    let edit = Edit::SetParam {
+        node,
+        value: value.clone(),
+    };
+    match engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::Network) {
+        Ok(warnings) => {
+            for warning in warnings {
+                eprintln!("osc: {}: {warning}", message.address);
+            }
+            Some((node, value))
+        }
+        Err(err) => {
+            eprintln!("osc: rejected {}: {err}", message.address);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::osc::packet::OscArg;
+
+    use super::*;
+
+    fn param(engine: &Arc<Mutex<Engine>>, node: NodeId) -> Value {
+        match &engine.lock().unwrap().nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    #[tokio::test]
+    async fn round_trips_over_localhost_udp() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let mixer = engine.create_child_container(root, "Container", "mixer");
+        let gain = engine.create_child_parameter(mixer, "gain", Value::Float(0.5));
+        let mute = engine.create_child_parameter(mixer, "mute", Value::Bool(false));
+        let engine = Arc::new(Mutex::new(engine));
+
+        let peer = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let listen = UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let config = OscBridgeConfig {
+            listen: Some(listen),
+            send_to: Some(peer.local_addr().unwrap()),
+            root: None,
+            poll_interval: Duration::from_millis(1),
+        };
+        tokio::spawn(start_osc_bridge(Arc::clone(&engine), config));
+
+        // In: resent until the bridge is bound and the edit lands.
+        let message = OscMessage::new("/mixer/gain", vec![OscArg::Float(0.75)]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while param(&engine, gain) != Value::Float(0.75) {
+            assert!(Instant::now() < deadline, "OSC input was not applied");
+            peer.send_to(&encode_message(&message), listen).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            engine.lock().unwrap().tick();
+        }
+
+        // Out: the OSC write is not echoed, so the first message back is the
+        // change made from elsewhere.
+        {
+            let mut engine = engine.lock().unwrap();
+            let edit = Edit::SetParam {
+                node: mute,
+                value: Value::Bool(true),
+            };
+            engine.submit_edit(edit, Propagation::Immediate, EditOrigin::UI).unwrap();
+            engine.tick();
+        }
+        let mut buffer = [0u8; 1024];
+        let received = tokio::time::timeout(Duration::from_secs(5), peer.recv_from(&mut buffer));
+        let (size, _) = received.await.expect("no OSC output").unwrap();
+        let messages = decode_packet(&buffer[..size]).unwrap().into_messages();
+        assert_eq!(messages, vec![OscMessage::new("/mixer/mute", vec![OscArg::Bool(true)])]);
+    }
+
+    #[test]
+    fn skips_only_values_the_bridge_wrote() {
+        let gain = NodeId(1);
+        let mut echoes = HashMap::from([(gain, vec![Value::Float(0.25), Value::Float(0.5)])]);
+
+        // A write that was clamped never comes back; later ones still match.
+        assert!(is_echo(&mut echoes, gain, &Value::Float(0.5)));
+        assert!(echoes.is_empty());
+
+        // A change from elsewhere forgets the pending writes and is sent.
+        echoes.insert(gain, vec![Value::Float(0.75)]);
+        assert!(!is_echo(&mut echoes, gain, &Value::Float(0.1)));
+        assert!(!is_echo(&mut echoes, gain, &Value::Float(0.75)));
+        assert!(!is_echo(&mut echoes, NodeId(2), &Value::Float(0.75)));
+    }
+}
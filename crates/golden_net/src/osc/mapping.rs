@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use golden_core::{Engine, NodeData};
+use std::time::Duration;
+
+use golden_schema::{
+    ColorRgba, Event, EventKind, EventTime, FloatRange, IntRange, NodeId, Quat, Timestamp, Value,
+    Vec2, Vec3, Vec4,
+};
+
+use crate::osc::packet::OscArg;
+
+/// Converts a parameter value into OSC arguments.
+///
+/// Vectors, colours, ranges and arrays are sent as consecutive numbers,
+/// triggers as an argument-less message, enums as their variant id and
+/// references as the target uuid. Bytes are a blob, timestamps microseconds
+/// since the Unix epoch and durations seconds. Curves send `x y` per key and
+/// gradients `t r g b a` per stop. Lists concatenate their elements and maps
+/// send each key followed by its value.
+pub fn value_to_args(value: &Value) -> Vec<OscArg> {
+    match value {
+        Value::Bool(v) => vec![OscArg::Bool(*v)],
+        Value::Int(v) => match i32::try_from(*v) {
+            Ok(v) => vec![OscArg::Int(v)],
+            Err(_) => vec![OscArg::Long(*v)],
+        },
+        Value::Float(v) => vec![OscArg::Float(*v as f32)],
+        Value::String(v) => vec![OscArg::String(v.clone())],
+        Value::Vec2(v) => vec![OscArg::Float(v.x as f32), OscArg::Float(v.y as f32)],
+        Value::Vec3(v) => {
+            vec![OscArg::Float(v.x as f32), OscArg::Float(v.y as f32), OscArg::Float(v.z as f32)]
+        }
+        Value::Vec4(v) => [v.x, v.y, v.z, v.w].map(|c| OscArg::Float(c as f32)).to_vec(),
+        Value::Quat(q) => [q.x, q.y, q.z, q.w].map(|c| OscArg::Float(c as f32)).to_vec(),
+        Value::ColorRgba(c) => vec![
+            OscArg::Float(c.r as f32),
+            OscArg::Float(c.g as f32),
+            OscArg::Float(c.b as f32),
+            OscArg::Float(c.a as f32),
+        ],
+        Value::FloatRange(r) => vec![OscArg::Float(r.min as f32), OscArg::Float(r.max as f32)],
+        Value::IntRange(r) => {
+            [r.min, r.max].iter().flat_map(|v| value_to_args(&Value::Int(*v))).collect()
+        }
+        Value::Bytes(bytes) => vec![OscArg::Blob(bytes.clone())],
+        Value::Timestamp(t) => vec![OscArg::Long(t.unix_micros)],
+        Value::Duration(d) => vec![OscArg::Double(d.as_secs_f64())],
+        Value::Curve(curve) => curve
+            .keys
+            .iter()
+            .flat_map(|key| [key.x, key.y].map(|v| OscArg::Float(v as f32)))
+            .collect(),
+        Value::Gradient(gradient) => gradient
+            .stops
+            .iter()
+            .flat_map(|stop| {
+                let c = stop.color;
+                [stop.t, c.r, c.g, c.b, c.a].map(|v| OscArg::Float(v as f32))
+            })
+            .collect(),
+        Value::Trigger => Vec::new(),
+        Value::Enum {
+            variant,
+            ..
+        } => vec![OscArg::String(variant.0.clone())],
+        Value::Reference(reference) => vec![OscArg::String(reference.uuid.0.to_string())],
+        Value::List(items) => items.iter().flat_map(value_to_args).collect(),
+        Value::FloatArray(items) => items.iter().map(|v| OscArg::Float(*v as f32)).collect(),
+        Value::IntArray(items) => {
+            items.iter().flat_map(|v| value_to_args(&Value::Int(*v))).collect()
+        }
+        Value::Map(entries) => entries
+            .iter()
+            .flat_map(|(key, value)| {
+                std::iter::once(OscArg::String(key.clone())).chain(value_to_args(value))
+            })
+            .collect(),
+    }
+}
+
+/// Converts incoming OSC arguments into a value shaped like `current`.
+///
+/// Numeric tags are accepted interchangeably, so a controller sending `i 1`
+/// to a float parameter still works. Returns `None` when the arguments cannot
+/// represent the parameter's kind.
+pub fn args_to_value(args: &[OscArg], current: &Value) -> Option<Value> {
+    match current {
+        Value::Bool(_) => match args.first()? {
+            OscArg::Bool(v) => Some(Value::Bool(*v)),
+            other => other.as_f64().map(|v| Value::Bool(v >= 0.5)),
+        },
+        Value::Int(_) => args.first()?.as_i64().map(Value::Int),
+        Value::Float(_) => args.first()?.as_f64().map(Value::Float),
+        Value::String(_) => match args.first()? {
+            OscArg::String(v) => Some(Value::String(v.clone())),
+            other => other.as_f64().map(|v| Value::String(v.to_string())),
+        },
+        Value::Vec2(_) => {
+            let [x, y] = floats::<2>(args)?;
+            Some(Value::Vec2(Vec2 {
+                x,
+                y,
+            }))
+        }
+        Value::Vec3(_) => {
+            let [x, y, z] = floats::<3>(args)?;
+            Some(Value::Vec3(Vec3 {
+                x,
+                y,
+                z,
+            }))
+        }
+        Value::Vec4(_) => {
+            let [x, y, z, w] = floats::<4>(args)?;
+            Some(Value::Vec4(Vec4 {
+                x,
+                y,
+                z,
+                w,
+            }))
+        }
+        Value::Quat(_) => {
+            let [x, y, z, w] = floats::<4>(args)?;
+            Some(Value::Quat(Quat {
+                x,
+                y,
+                z,
+                w,
+            }))
+        }
+        Value::ColorRgba(_) => color_from_args(args).map(Value::ColorRgba),
+        Value::FloatRange(_) => {
+            let [min, max] = floats::<2>(args)?;
+            Some(Value::FloatRange(FloatRange {
+                min,
+                max,
+            }))
+        }
+        Value::IntRange(_) => {
+            let [min, max] = [args.first()?.as_i64()?, args.get(1)?.as_i64()?];
+            Some(Value::IntRange(IntRange {
+                min,
+                max,
+            }))
+        }
+        Value::Bytes(_) => match args.first()? {
+            OscArg::Blob(bytes) => Some(Value::Bytes(bytes.clone())),
+            _ => None,
+        },
+        Value::Timestamp(_) => args.first()?.as_i64().map(|unix_micros| {
+            Value::Timestamp(Timestamp {
+                unix_micros,
+            })
+        }),
+        Value::Duration(_) => {
+            let secs = args.first()?.as_f64()?;
+            Duration::try_from_secs_f64(secs).ok().map(Value::Duration)
+        }
+        Value::Trigger => Some(Value::Trigger),
+        Value::Enum {
+            enum_id,
+            ..
+        } => match args.first()? {
+            OscArg::String(variant) => Some(Value::Enum {
+                enum_id: enum_id.clone(),
+                variant: golden_schema::EnumVariantId(variant.clone()),
+            }),
+            _ => None,
+        },
+        Value::FloatArray(_) => {
+            args.iter().map(OscArg::as_f64).collect::<Option<_>>().map(Value::FloatArray)
+        }
+        Value::IntArray(_) => {
+            args.iter().map(OscArg::as_i64).collect::<Option<_>>().map(Value::IntArray)
+        }
+        Value::Reference(_)
+        | Value::List(_)
+        | Value::Map(_)
+        | Value::Curve(_)
+        | Value::Gradient(_) => None,
+    }
+}
+
+/// OSC always carries whole values, so a `ParamPatched` event becomes a
+/// `ParamChanged` with the parameter's current value.
+pub fn with_whole_value(engine: &Engine, event: Event) -> Event {
+    let EventKind::ParamPatched {
+        param,
+        ..
+    } = event.kind
+    else {
+        return event;
+    };
+    match engine.nodes.get(&param).map(|node| &node.data) {
+        Some(NodeData::Parameter(data)) => Event {
+            time: event.time,
+            kind: EventKind::ParamChanged {
+                param,
+                value: data.value.clone(),
+            },
+        },
+        _ => event,
+    }
+}
+
+fn floats<const N: usize>(args: &[OscArg]) -> Option<[f64; N]> {
+    if args.len() < N {
+        return None;
+    }
+    let mut out = [0.0; N];
+    for (slot, arg) in out.iter_mut().zip(args) {
+        *slot = arg.as_f64()?;
+    }
+    Some(out)
+}
+
+fn color_from_args(args: &[OscArg]) -> Option<ColorRgba> {
+    if let Some(OscArg::Color(rgba)) = args.first() {
+        let [r, g, b, a] = rgba.to_be_bytes();
+        return Some(ColorRgba {
+            r: r as f64 / 255.0,
+            g: g as f64 / 255.0,
+            b: b as f64 / 255.0,
+            a: a as f64 / 255.0,
+        });
+    }
+    if args.len() == 3 {
+        let [r, g, b] = floats::<3>(args)?;
+        return Some(ColorRgba {
+            r,
+            g,
+            b,
+            a: 1.0,
+        });
+    }
+    let [r, g, b, a] = floats::<4>(args)?;
+    Some(ColorRgba {
+        r,
+        g,
+        b,
+        a,
+    })
+}
+
+/// OSC address of `node` relative to `root`, i.e. its engine path with the
+/// root's path stripped.
+///
+/// Returns `None` when `node` is not below `root`.
+pub fn osc_address(engine: &Engine, root: NodeId, node: NodeId) -> Option<String> {
+    let path = engine.path_of(node)?;
+    let root_path = engine.path_of(root)?;
+    if root_path == "/" {
+        return Some(path.to_string());
+    }
+    match path.strip_prefix(root_path) {
+        Some("") => Some("/".to_string()),
+        Some(rest) if rest.starts_with('/') => Some(rest.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether an event can add, remove or rename an OSC address.
+pub fn changes_addresses(kind: &EventKind) -> bool {
+    matches!(
+        kind,
+        EventKind::ChildAdded { .. }
+            | EventKind::ChildRemoved { .. }
+            | EventKind::ChildReplaced { .. }
+            | EventKind::ChildMoved { .. }
+            | EventKind::NodeDeleted { .. }
+            | EventKind::MetaChanged { .. }
+    )
+}
+
+/// Address lookup for every parameter below a root node.
+#[derive(Default)]
+pub struct OscAddressMap {
+    root: Option<NodeId>,
+    by_address: HashMap<String, NodeId>,
+    by_node: HashMap<NodeId, String>,
+    /// Time of the last event seen by [`OscAddressMap::sync`].
+    synced: Option<EventTime>,
+}
+
+impl OscAddressMap {
+    pub fn new(root: NodeId) -> Self {
+        Self {
+            root: Some(root),
+            by_address: HashMap::new(),
+            by_node: HashMap::new(),
+            synced: None,
+        }
+    }
+
+    pub fn rebuild(&mut self, engine: &Engine) {
+        let root = *self.root.get_or_insert(engine.root_id());
+        self.synced = engine.event_log.back().map(|event| event.time);
+        self.by_address.clear();
+        self.by_node.clear();
+        for (node_id, node) in engine.nodes.iter() {
+            if !matches!(node.data, NodeData::Parameter(_)) {
+                continue;
+            }
+            if let Some(address) = osc_address(engine, root, node_id) {
+                self.by_address.insert(address.clone(), node_id);
+                self.by_node.insert(node_id, address);
+            }
+        }
+    }
+
+    /// Rebuilds if the engine logged a structure change since the last
+    /// rebuild or sync, or dropped events that might have held one. Only the
+    /// new events are scanned, so this is cheap to call per packet.
+    pub fn sync(&mut self, engine: &Engine) {
+        let synced = self.synced;
+        let is_new = |event: &&Event| synced.is_none_or(|synced| event.time > synced);
+        let missed = synced.is_some_and(|synced| {
+            engine.event_log.front().is_some_and(|first| first.time > synced)
+        });
+        if missed
+            || engine
+                .event_log
+                .iter()
+                .rev()
+                .take_while(is_new)
+                .any(|event| changes_addresses(&event.kind))
+        {
+            self.rebuild(engine);
+        } else if let Some(last) = engine.event_log.back() {
+            self.synced = Some(last.time);
+        }
+    }
+
+    /// Node at an address as of the last rebuild or sync. Unknown addresses
+    /// do not trigger a rebuild, so a flood of them costs lookups only.
+    pub fn resolve(&self, address: &str) -> Option<NodeId> {
+        self.by_address.get(address).copied()
+    }
+
+    /// Address of a parameter as of the last rebuild.
+    pub fn address_of(&self, node: NodeId) -> Option<&str> {
+        self.by_node.get(&node).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_core::edits::{Edit, EditOrigin, Propagation};
+
+    use super::*;
+
+    #[test]
+    fn values_round_trip_through_args() {
+        let values = [
+            Value::Int(-7),
+            Value::Int(i64::from(i32::MAX) + 1),
+            Value::Float(0.25),
+            Value::String("cue 1".to_string()),
+            Value::Bool(true),
+            Value::Vec2(Vec2 {
+                x: 1.0,
+                y: -2.0,
+            }),
+            Value::Vec3(Vec3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            }),
+            Value::ColorRgba(ColorRgba {
+                r: 1.0,
+                g: 0.5,
+                b: 0.0,
+                a: 1.0,
+            }),
+            Value::Trigger,
+        ];
+        for value in values {
+            assert_eq!(args_to_value(&value_to_args(&value), &value), Some(value));
+        }
+    }
+
+    #[test]
+    fn numeric_args_are_accepted_interchangeably() {
+        assert_eq!(args_to_value(&[OscArg::Int(1)], &Value::Float(0.0)), Some(Value::Float(1.0)));
+        assert_eq!(
+            args_to_value(&[OscArg::Float(1.0)], &Value::Bool(false)),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(args_to_value(&[OscArg::String("x".into())], &Value::Int(0)), None);
+        let rgb = [OscArg::Float(1.0), OscArg::Float(0.0), OscArg::Float(0.0)];
+        assert_eq!(
+            args_to_value(
+                &rgb,
+                &Value::ColorRgba(ColorRgba {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.0
+                })
+            ),
+            Some(Value::ColorRgba(ColorRgba {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            }))
+        );
+    }
+
+    #[test]
+    fn address_map_follows_structure_changes_only() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let mixer = engine.create_child_container(root, "Container", "mixer");
+        let gain = engine.create_child_parameter(mixer, "gain", Value::Float(0.5));
+        let mut addresses = OscAddressMap::new(mixer);
+        addresses.rebuild(&engine);
+        assert_eq!(addresses.resolve("/gain"), Some(gain));
+        assert_eq!(addresses.address_of(gain), Some("/gain"));
+        assert_eq!(addresses.resolve("/nothing"), None);
+
+        // A value change leaves the map alone; a new parameter rebuilds it.
+        let edit = Edit::SetParam {
+            node: gain,
+            value: Value::Float(0.1),
+        };
+        engine.submit_edit(edit, Propagation::Immediate, EditOrigin::UI).unwrap();
+        engine.tick();
+        addresses.sync(&engine);
+        assert_eq!(addresses.resolve("/gain"), Some(gain));
+
+        let bus = engine.create_child_container(mixer, "Container", "bus");
+        assert!(engine.move_node(gain, bus, 0));
+        assert_eq!(addresses.resolve("/bus/gain"), None);
+        addresses.sync(&engine);
+        assert_eq!(addresses.resolve("/bus/gain"), Some(gain));
+        assert_eq!(addresses.resolve("/gain"), None);
+    }
+}
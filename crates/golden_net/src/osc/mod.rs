@@ -0,0 +1,9 @@
+pub mod bridge;
+pub mod mapping;
+pub mod packet;
+pub mod query;
+
+pub use bridge::{OscBridgeConfig, OscSender, start_osc_bridge};
+pub use mapping::{OscAddressMap, args_to_value, changes_addresses, osc_address, value_to_args};
+pub use packet::{OscArg, OscError, OscMessage, OscPacket, decode_packet, encode_message};
+pub use query::{OscQueryConfig, build_namespace, start_oscquery_server};
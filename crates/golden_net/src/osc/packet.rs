@@ -0,0 +1,270 @@
+use core::fmt;
+
+/// A single OSC argument, tagged as in the OSC 1.0 type tag string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Impulse,
+    /// 32-bit RGBA colour, one byte per channel.
+    Color(u32),
+}
+
+impl OscArg {
+    pub fn type_tag(&self) -> char {
+        match self {
+            OscArg::Int(_) => 'i',
+            OscArg::Long(_) => 'h',
+            OscArg::Float(_) => 'f',
+            OscArg::Double(_) => 'd',
+            OscArg::String(_) => 's',
+            OscArg::Blob(_) => 'b',
+            OscArg::Bool(true) => 'T',
+            OscArg::Bool(false) => 'F',
+            OscArg::Nil => 'N',
+            OscArg::Impulse => 'I',
+            OscArg::Color(_) => 'r',
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(v) => Some(*v as f64),
+            OscArg::Long(v) => Some(*v as f64),
+            OscArg::Float(v) => Some(*v as f64),
+            OscArg::Double(v) => Some(*v),
+            OscArg::Bool(v) => Some(f64::from(u8::from(*v))),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            OscArg::Int(v) => Some(*v as i64),
+            OscArg::Long(v) => Some(*v),
+            OscArg::Float(v) => Some(v.round() as i64),
+            OscArg::Double(v) => Some(v.round() as i64),
+            OscArg::Bool(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscPacket {
+    Message(OscMessage),
+    /// Bundle contents; time tags are ignored and elements apply immediately.
+    Bundle(Vec<OscPacket>),
+}
+
+impl OscPacket {
+    /// Flattens nested bundles into their messages, in order.
+    pub fn into_messages(self) -> Vec<OscMessage> {
+        match self {
+            OscPacket::Message(message) => vec![message],
+            OscPacket::Bundle(packets) => {
+                packets.into_iter().flat_map(OscPacket::into_messages).collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OscError {
+    Truncated,
+    InvalidString,
+    InvalidAddress,
+    MissingTypeTags,
+    UnsupportedTag(char),
+}
+
+impl fmt::Display for OscError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscError::Truncated => write!(f, "packet is truncated"),
+            OscError::InvalidString => write!(f, "string is not valid UTF-8"),
+            OscError::InvalidAddress => write!(f, "address must start with '/'"),
+            OscError::MissingTypeTags => write!(f, "type tag string must start with ','"),
+            OscError::UnsupportedTag(tag) => write!(f, "unsupported type tag '{tag}'"),
+        }
+    }
+}
+
+impl std::error::Error for OscError {}
+
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+pub fn encode_message(message: &OscMessage) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_string(&mut out, &message.address);
+
+    let mut tags = String::from(",");
+    for arg in &message.args {
+        tags.push(arg.type_tag());
+    }
+    write_string(&mut out, &tags);
+
+    for arg in &message.args {
+        match arg {
+            OscArg::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+            OscArg::String(v) => write_string(&mut out, v),
+            OscArg::Blob(bytes) => {
+                out.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+                out.extend_from_slice(bytes);
+                pad(&mut out);
+            }
+            OscArg::Color(v) => out.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Bool(_) | OscArg::Nil | OscArg::Impulse => {}
+        }
+    }
+    out
+}
+
+pub fn decode_packet(data: &[u8]) -> Result<OscPacket, OscError> {
+    if data.starts_with(BUNDLE_TAG) {
+        return decode_bundle(data);
+    }
+    decode_message(data).map(OscPacket::Message)
+}
+
+fn decode_bundle(data: &[u8]) -> Result<OscPacket, OscError> {
+    // "#bundle\0" followed by an 8 byte time tag.
+    let mut reader = Reader {
+        data,
+        pos: BUNDLE_TAG.len() + 8,
+    };
+    if data.len() < reader.pos {
+        return Err(OscError::Truncated);
+    }
+
+    let mut packets = Vec::new();
+    while reader.pos < data.len() {
+        let size = reader.read_i32()? as usize;
+        let element = reader.take(size)?;
+        packets.push(decode_packet(element)?);
+    }
+    Ok(OscPacket::Bundle(packets))
+}
+
+fn decode_message(data: &[u8]) -> Result<OscMessage, OscError> {
+    let mut reader = Reader {
+        data,
+        pos: 0,
+    };
+    let address = reader.read_string()?;
+    if !address.starts_with('/') {
+        return Err(OscError::InvalidAddress);
+    }
+
+    // Very old senders omit the type tag string entirely.
+    if reader.pos >= data.len() {
+        return Ok(OscMessage::new(address, Vec::new()));
+    }
+    let tags = reader.read_string()?;
+    let Some(tags) = tags.strip_prefix(',') else {
+        return Err(OscError::MissingTypeTags);
+    };
+
+    let mut args = Vec::with_capacity(tags.len());
+    for tag in tags.chars() {
+        let arg = match tag {
+            'i' => OscArg::Int(reader.read_i32()?),
+            'h' => OscArg::Long(i64::from_be_bytes(reader.read_array()?)),
+            'f' => OscArg::Float(f32::from_be_bytes(reader.read_array()?)),
+            'd' => OscArg::Double(f64::from_be_bytes(reader.read_array()?)),
+            's' | 'S' => OscArg::String(reader.read_string()?),
+            'b' => {
+                let size = reader.read_i32()? as usize;
+                let bytes = reader.take(size)?.to_vec();
+                reader.align();
+                OscArg::Blob(bytes)
+            }
+            'r' => OscArg::Color(u32::from_be_bytes(reader.read_array()?)),
+            'c' => {
+                let code = u32::from_be_bytes(reader.read_array()?);
+                OscArg::String(char::from_u32(code).map(String::from).unwrap_or_default())
+            }
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'N' => OscArg::Nil,
+            'I' => OscArg::Impulse,
+            other => return Err(OscError::UnsupportedTag(other)),
+        };
+        args.push(arg);
+    }
+
+    Ok(OscMessage::new(address, args))
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(value.as_bytes());
+    out.push(0);
+    pad(out);
+}
+
+fn pad(out: &mut Vec<u8>) {
+    while !out.len().is_multiple_of(4) {
+        out.push(0);
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], OscError> {
+        let end = self.pos.checked_add(len).ok_or(OscError::Truncated)?;
+        let slice = self.data.get(self.pos..end).ok_or(OscError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], OscError> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    fn read_i32(&mut self) -> Result<i32, OscError> {
+        Ok(i32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_string(&mut self) -> Result<String, OscError> {
+        let rest = self.data.get(self.pos..).ok_or(OscError::Truncated)?;
+        let len = rest.iter().position(|byte| *byte == 0).ok_or(OscError::Truncated)?;
+        let value = std::str::from_utf8(&rest[..len]).map_err(|_| OscError::InvalidString)?;
+        self.pos += len + 1;
+        self.align();
+        Ok(value.to_string())
+    }
+
+    fn align(&mut self) {
+        self.pos = self.pos.div_ceil(4) * 4;
+    }
+}
@@ -0,0 +1,580 @@
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::Router;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{RawQuery, State};
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Map, Value as Json, json};
+
+use golden_core::Engine;
+use golden_schema::ui::dtos::{NodeDto, ParamDto};
+use golden_schema::{EventKind, NodeId, Value, ValueConstraints};
+
+use crate::osc::bridge::apply_message;
+use crate::osc::mapping::{OscAddressMap, changes_addresses, value_to_args, with_whole_value};
+use crate::osc::packet::{OscArg, OscMessage, decode_packet, encode_message};
+use crate::snapshot::build_snapshot;
+
+/// OSCQuery `ACCESS` values.
+const ACCESS_NONE: u8 = 0;
+const ACCESS_READ: u8 = 1;
+const ACCESS_WRITE: u8 = 2;
+const ACCESS_READ_WRITE: u8 = 3;
+
+#[derive(Clone, Debug)]
+pub struct OscQueryConfig {
+    pub addr: SocketAddr,
+    pub name: String,
+    /// UDP port of a running OSC bridge, advertised in `HOST_INFO`.
+    pub osc_port: Option<u16>,
+    /// Subtree exposed over OSCQuery; defaults to the engine root.
+    pub root: Option<NodeId>,
+    pub poll_interval: Duration,
+}
+
+impl Default for OscQueryConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(([0, 0, 0, 0], 9010)),
+            name: "golden".to_string(),
+            osc_port: None,
+            root: None,
+            poll_interval: Duration::from_millis(8),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct QueryState {
+    engine: Arc<Mutex<Engine>>,
+    config: Arc<OscQueryConfig>,
+}
+
+/// Serves the node tree as an OSCQuery namespace.
+///
+/// `GET <path>` returns the namespace below `path`, `GET <path>?ATTR` a single
+/// attribute and `GET /?HOST_INFO` the server description. A WebSocket on the
+/// same port accepts `LISTEN`/`IGNORE` commands and streams value changes as
+/// binary OSC packets; binary packets sent by the client are applied as edits.
+pub async fn start_oscquery_server(
+    engine: Arc<Mutex<Engine>>,
+    config: OscQueryConfig,
+) -> anyhow::Result<()> {
+    let addr = config.addr;
+    let app = Router::new().fallback(query_handler).with_state(QueryState {
+        engine,
+        config: Arc::new(config),
+    });
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app.into_make_service()).await?;
+    Ok(())
+}
+
+async fn query_handler(
+    State(state): State<QueryState>,
+    uri: Uri,
+    RawQuery(query): RawQuery,
+    ws: Option<WebSocketUpgrade>,
+) -> Response {
+    if let Some(ws) = ws {
+        return ws.on_upgrade(move |socket| handle_socket(state, socket)).into_response();
+    }
+
+    if query.as_deref() == Some("HOST_INFO") {
+        return axum::Json(host_info(&state.config)).into_response();
+    }
+
+    let namespace = {
+        let engine = state.engine.lock().unwrap();
+        build_namespace(&engine, state.config.root.unwrap_or(engine.root_id()))
+    };
+    let Some(node) = find_path(&namespace, uri.path()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match query.as_deref() {
+        None | Some("") => axum::Json(node.clone()).into_response(),
+        Some(attribute) => match node.get(attribute) {
+            Some(value) => axum::Json(json!({ attribute: value })).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        },
+    }
+}
+
+fn host_info(config: &OscQueryConfig) -> Json {
+    let mut info = json!({
+        "NAME": config.name,
+        "OSC_TRANSPORT": "UDP",
+        "EXTENSIONS": {
+            "ACCESS": true,
+            "VALUE": true,
+            "RANGE": true,
+            "DESCRIPTION": true,
+            "TYPE": true,
+            "LISTEN": true,
+            "PATH_CHANGED": true,
+        },
+    });
+    if let Some(port) = config.osc_port {
+        info["OSC_PORT"] = json!(port);
+    }
+    info
+}
+
+/// Builds the OSCQuery namespace for the subtree below `root`.
+///
+/// Addresses match the OSC bridge: the `short_name`s of every node below
+/// `root`, joined with `/`.
+pub fn build_namespace(engine: &Engine, root: NodeId) -> Json {
+    let snapshot = build_snapshot(engine);
+    let nodes: HashMap<NodeId, &NodeDto> =
+        snapshot.nodes.iter().map(|node| (node.node_id, node)).collect();
+    let params: HashMap<NodeId, &ParamDto> =
+        snapshot.params.iter().map(|param| (param.param_node_id, param)).collect();
+    namespace_node(&nodes, &params, root, "/")
+}
+
+fn namespace_node(
+    nodes: &HashMap<NodeId, &NodeDto>,
+    params: &HashMap<NodeId, &ParamDto>,
+    node_id: NodeId,
+    path: &str,
+) -> Json {
+    let mut out = Map::new();
+    out.insert("FULL_PATH".to_string(), json!(path));
+
+    let Some(node) = nodes.get(&node_id) else {
+        return Json::Object(out);
+    };
+    out.insert(
+        "DESCRIPTION".to_string(),
+        json!(node.meta.description.clone().unwrap_or_else(|| node.meta.label.clone())),
+    );
+
+    match params.get(&node_id) {
+        Some(param) => param_attributes(param, &mut out),
+        None => {
+            out.insert("ACCESS".to_string(), json!(ACCESS_NONE));
+        }
+    }
+
+    if !node.children.is_empty() {
+        let mut contents = Map::new();
+        for child in &node.children {
+            let Some(child_node) = nodes.get(child) else {
+                continue;
+            };
+            let name = child_node.meta.short_name.0.as_str();
+            let child_path = if path == "/" {
+                format!("/{name}")
+            } else {
+                format!("{path}/{name}")
+            };
+            contents.insert(name.to_string(), namespace_node(nodes, params, *child, &child_path));
+        }
+        out.insert("CONTENTS".to_string(), Json::Object(contents));
+    }
+
+    Json::Object(out)
+}
+
+fn param_attributes(param: &ParamDto, out: &mut Map<String, Json>) {
+    let args = value_to_args(&param.value);
+    let type_tags = match &param.value {
+        Value::Trigger => "I".to_string(),
+        _ => args.iter().map(OscArg::type_tag).collect(),
+    };
+    out.insert("TYPE".to_string(), json!(type_tags));
+
+    let access = match (&param.value, param.read_only) {
+        (Value::Trigger, _) => ACCESS_WRITE,
+        (_, true) => ACCESS_READ,
+        (_, false) => ACCESS_READ_WRITE,
+    };
+    out.insert("ACCESS".to_string(), json!(access));
+
+    if !args.is_empty() {
+        out.insert("VALUE".to_string(), Json::Array(args.iter().map(arg_to_json).collect()));
+    }
+
+    if let Some(range) = constraint_range(&param.constraints) {
+        // One range entry per argument, as OSCQuery expects.
+        out.insert("RANGE".to_string(), Json::Array(vec![range; args.len().max(1)]));
+    }
+}
+
+fn constraint_range(constraints: &ValueConstraints) -> Option<Json> {
+    let mut range = Map::new();
+    match constraints {
+        ValueConstraints::Int {
+            min,
+            max,
+            ..
+        } => {
+            if let Some(min) = min {
+                range.insert("MIN".to_string(), json!(min));
+            }
+            if let Some(max) = max {
+                range.insert("MAX".to_string(), json!(max));
+            }
+        }
+        ValueConstraints::Float {
+            min,
+            max,
+            ..
+        } => {
+            if let Some(min) = min {
+                range.insert("MIN".to_string(), json!(min));
+            }
+            if let Some(max) = max {
+                range.insert("MAX".to_string(), json!(max));
+            }
+        }
+        ValueConstraints::Enum {
+            allowed,
+            ..
+        } => {
+            let values: Vec<&str> = allowed.iter().map(|variant| variant.0.as_str()).collect();
+            range.insert("VALS".to_string(), json!(values));
+        }
+        _ => {}
+    }
+    if range.is_empty() {
+        None
+    } else {
+        Some(Json::Object(range))
+    }
+}
+
+fn arg_to_json(arg: &OscArg) -> Json {
+    match arg {
+        OscArg::Int(v) => json!(v),
+        OscArg::Long(v) => json!(v),
+        OscArg::Float(v) => json!(v),
+        OscArg::Double(v) => json!(v),
+        OscArg::String(v) => json!(v),
+        OscArg::Bool(v) => json!(v),
+        OscArg::Color(v) => json!(format!("#{v:08x}")),
+        OscArg::Blob(_) | OscArg::Nil | OscArg::Impulse => Json::Null,
+    }
+}
+
+fn find_path<'a>(namespace: &'a Json, path: &str) -> Option<&'a Json> {
+    let mut node = namespace;
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        node = node.get("CONTENTS")?.get(segment)?;
+    }
+    Some(node)
+}
+
+async fn handle_socket(state: QueryState, socket: WebSocket) {
+    let (mut ws_write, mut ws_read) = socket.split();
+    let (mut addresses, mut last_time) = {
+        let engine = state.engine.lock().unwrap();
+        let mut addresses = OscAddressMap::new(state.config.root.unwrap_or(engine.root_id()));
+        addresses.rebuild(&engine);
+        (addresses, engine.time)
+    };
+    let mut listening: HashSet<String> = HashSet::new();
+    let mut interval = tokio::time::interval(state.config.poll_interval);
+
+    loop {
+        let outgoing = tokio::select! {
+            msg = ws_read.next() => {
+                let Some(Ok(msg)) = msg else {
+                    break;
+                };
+                match msg {
+                    Message::Text(text) => handle_command(&text, &mut listening),
+                    Message::Binary(bytes) => match decode_packet(&bytes) {
+                        Ok(packet) => {
+                            let mut engine = state.engine.lock().unwrap();
+                            addresses.sync(&engine);
+                            for message in packet.into_messages() {
+                                apply_message(&mut engine, &addresses, message);
+                            }
+                        }
+                        Err(err) => eprintln!("oscquery decode error: {err}"),
+                    },
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+                Vec::new()
+            }
+            _ = interval.tick() => {
+                let events = {
+                    let engine = state.engine.lock().unwrap();
+                    addresses.sync(&engine);
+                    engine
+                        .events_since(last_time)
+                        .into_iter()
+                        .map(|event| with_whole_value(&engine, event))
+                        .collect::<Vec<_>>()
+                };
+                let Some(last) = events.last() else {
+                    continue;
+                };
+                last_time = last.time;
+
+                let mut outgoing = Vec::new();
+                if events.iter().any(|event| changes_addresses(&event.kind)) {
+                    let notice = json!({ "COMMAND": "PATH_CHANGED", "DATA": "/" });
+                    outgoing.push(Message::Text(notice.to_string()));
+                }
+                for event in events {
+                    let EventKind::ParamChanged { param, value } = event.kind else {
+                        continue;
+                    };
+                    let Some(address) = addresses.address_of(param) else {
+                        continue;
+                    };
+                    if !listening.contains(address) {
+                        continue;
+                    }
+                    let message = OscMessage::new(address, value_to_args(&value));
+                    outgoing.push(Message::Binary(encode_message(&message)));
+                }
+                outgoing
+            }
+        };
+
+        for msg in outgoing {
+            if ws_write.send(msg).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn handle_command(text: &str, listening: &mut HashSet<String>) {
+    let Ok(command) = serde_json::from_str::<Json>(text) else {
+        return;
+    };
+    let Some(path) = command.get("DATA").and_then(Json::as_str) else {
+        return;
+    };
+    match command.get("COMMAND").and_then(Json::as_str) {
+        Some("LISTEN") => {
+            listening.insert(path.to_string());
+        }
+        Some("IGNORE") => {
+            listening.remove(path);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use golden_core::edits::{Edit, EditOrigin, Propagation};
+    use golden_core::graph::node::NodeData;
+    use golden_schema::{EnumId, EnumVariantId, ParameterData, Vec3};
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    use super::*;
+
+    fn param_mut(engine: &mut Engine, node: NodeId) -> &mut ParameterData {
+        match &mut engine.nodes.get_mut(&node).unwrap().data {
+            NodeData::Parameter(param) => param,
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn namespace(engine: &Engine) -> Json {
+        build_namespace(engine, engine.root_id())
+    }
+
+    #[test]
+    fn nests_contents_by_full_path() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let mixer = engine.create_child_container(root, "Container", "mixer");
+        let channel = engine.create_child_container(mixer, "Container", "channel");
+        engine.create_child_parameter(channel, "gain", Value::Float(0.5));
+
+        let namespace = namespace(&engine);
+        assert_eq!(namespace["FULL_PATH"], "/");
+        assert_eq!(namespace["ACCESS"], ACCESS_NONE);
+        let mixer = &namespace["CONTENTS"]["mixer"];
+        assert_eq!(mixer["FULL_PATH"], "/mixer");
+        assert_eq!(mixer["CONTENTS"]["channel"]["FULL_PATH"], "/mixer/channel");
+        let gain = &mixer["CONTENTS"]["channel"]["CONTENTS"]["gain"];
+        assert_eq!(gain["FULL_PATH"], "/mixer/channel/gain");
+        assert!(gain.get("CONTENTS").is_none());
+        assert_eq!(find_path(&namespace, "/mixer/channel/gain"), Some(gain));
+        assert_eq!(find_path(&namespace, "/mixer/missing"), None);
+    }
+
+    #[test]
+    fn type_tags_follow_the_value_kind() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let kinds = [
+            ("bool", Value::Bool(true), "T"),
+            ("int", Value::Int(3), "i"),
+            ("long", Value::Int(i64::MAX), "h"),
+            ("float", Value::Float(0.5), "f"),
+            ("string", Value::String("a".into()), "s"),
+            (
+                "vec3",
+                Value::Vec3(Vec3 {
+                    x: 0.0,
+                    y: 1.0,
+                    z: 2.0,
+                }),
+                "fff",
+            ),
+            ("duration", Value::Duration(Duration::from_secs(1)), "d"),
+            ("go", Value::Trigger, "I"),
+        ];
+        for (label, value, _) in &kinds {
+            engine.create_child_parameter(root, label, value.clone());
+        }
+
+        let namespace = namespace(&engine);
+        for (label, _, tags) in kinds {
+            assert_eq!(namespace["CONTENTS"][label]["TYPE"], tags, "{label}");
+        }
+        assert_eq!(namespace["CONTENTS"]["vec3"]["VALUE"], json!([0.0, 1.0, 2.0]));
+        assert!(namespace["CONTENTS"]["go"].get("VALUE").is_none());
+    }
+
+    #[test]
+    fn ranges_come_from_constraints() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.5));
+        param_mut(&mut engine, gain).constraints = ValueConstraints::Float {
+            min: Some(0.0),
+            max: Some(2.0),
+            clamp: true,
+            step: None,
+        };
+        let steps = engine.create_child_parameter(root, "steps", Value::Int(4));
+        param_mut(&mut engine, steps).constraints = ValueConstraints::Int {
+            min: None,
+            max: Some(16),
+            clamp: false,
+            step: None,
+        };
+        let mode = engine.create_child_parameter(root, "mode", Value::String("add".into()));
+        param_mut(&mut engine, mode).constraints = ValueConstraints::Enum {
+            enum_id: EnumId("blend".into()),
+            allowed: vec![EnumVariantId("add".into()), EnumVariantId("multiply".into())],
+        };
+        engine.create_child_parameter(root, "free", Value::Float(0.5));
+
+        let contents = &namespace(&engine)["CONTENTS"];
+        assert_eq!(contents["gain"]["RANGE"], json!([{ "MIN": 0.0, "MAX": 2.0 }]));
+        assert_eq!(contents["steps"]["RANGE"], json!([{ "MAX": 16 }]));
+        assert_eq!(contents["mode"]["RANGE"], json!([{ "VALS": ["add", "multiply"] }]));
+        assert!(contents["free"].get("RANGE").is_none());
+    }
+
+    #[test]
+    fn access_and_description_follow_the_node() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.5));
+        let level = engine.create_child_parameter(root, "level", Value::Float(0.0));
+        param_mut(&mut engine, level).read_only = true;
+        engine.nodes.get_mut(&level).unwrap().meta.description = Some("Output level".into());
+        engine.create_child_parameter(root, "go", Value::Trigger);
+
+        let contents = &namespace(&engine)["CONTENTS"];
+        assert_eq!(contents["gain"]["ACCESS"], ACCESS_READ_WRITE);
+        assert_eq!(contents["level"]["ACCESS"], ACCESS_READ);
+        assert_eq!(contents["go"]["ACCESS"], ACCESS_WRITE);
+        assert_eq!(contents["level"]["DESCRIPTION"], "Output level");
+        let label = engine.nodes.get(&gain).unwrap().meta.label.clone();
+        assert_eq!(contents["gain"]["DESCRIPTION"], json!(label));
+    }
+
+    #[test]
+    fn host_info_advertises_the_osc_port() {
+        let mut config = OscQueryConfig {
+            name: "stage".to_string(),
+            ..OscQueryConfig::default()
+        };
+        let info = host_info(&config);
+        assert_eq!(info["NAME"], "stage");
+        assert_eq!(info["OSC_TRANSPORT"], "UDP");
+        assert_eq!(info["EXTENSIONS"]["LISTEN"], true);
+        assert!(info.get("OSC_PORT").is_none());
+
+        config.osc_port = Some(9000);
+        assert_eq!(host_info(&config)["OSC_PORT"], 9000);
+    }
+
+    #[test]
+    fn listen_and_ignore_track_paths() {
+        let mut listening = HashSet::new();
+        handle_command(r#"{"COMMAND":"LISTEN","DATA":"/gain"}"#, &mut listening);
+        handle_command(r#"{"COMMAND":"LISTEN","DATA":"/mute"}"#, &mut listening);
+        handle_command(r#"{"COMMAND":"IGNORE","DATA":"/mute"}"#, &mut listening);
+        handle_command("not json", &mut listening);
+        assert_eq!(listening, HashSet::from(["/gain".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn listening_sockets_receive_changed_values() {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.5));
+        engine.create_child_parameter(root, "mute", Value::Bool(false));
+        let engine = Arc::new(Mutex::new(engine));
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let config = OscQueryConfig {
+            addr,
+            poll_interval: Duration::from_millis(1),
+            ..OscQueryConfig::default()
+        };
+        tokio::spawn(start_oscquery_server(Arc::clone(&engine), config));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let url = format!("ws://{addr}/");
+        let (mut socket, _) = loop {
+            match tokio_tungstenite::connect_async(url.as_str()).await {
+                Ok(connected) => break connected,
+                Err(err) => assert!(Instant::now() < deadline, "cannot connect: {err}"),
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let listen = json!({ "COMMAND": "LISTEN", "DATA": "/gain" });
+        socket.send(WsMessage::Text(listen.to_string())).await.unwrap();
+
+        // Changed until the first one after the LISTEN reaches the socket.
+        let mut next = 0.0;
+        let message = loop {
+            assert!(Instant::now() < deadline, "no value after LISTEN");
+            next += 0.25;
+            {
+                let mut engine = engine.lock().unwrap();
+                let edit = Edit::SetParam {
+                    node: gain,
+                    value: Value::Float(next),
+                };
+                engine.submit_edit(edit, Propagation::Immediate, EditOrigin::UI).unwrap();
+                engine.tick();
+            }
+            let received = tokio::time::timeout(Duration::from_millis(50), socket.next()).await;
+            if let Ok(Some(Ok(WsMessage::Binary(bytes)))) = received {
+                break decode_packet(&bytes).unwrap().into_messages().remove(0);
+            }
+        };
+        assert_eq!(message.address, "/gain");
+        let [OscArg::Float(value)] = message.args[..] else {
+            panic!("unexpected arguments {:?}", message.args);
+        };
+        assert!(value > 0.0 && value as f64 <= next);
+    }
+}
@@ -0,0 +1,611 @@
+use std::sync::{Arc, Mutex};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use golden_core::edits::{Edit, EditError, EditOrigin, EditWarning, Propagation};
+use golden_core::persistence::load::fragment_subtree;
+use golden_core::persistence::{ImportReport, export_fragment, export_project};
+use golden_core::{Engine, NodeData, NodeExecution};
+use golden_schema::persistence::file_format::{FragmentFile, ProjectFile};
+use golden_schema::ui::messages::ErrorInfo;
+use golden_schema::{
+    EventKind, EventTime, NodeId, NodeMeta, NodeMetaPatch, NodeTypeId, NodeUuid, ParameterData,
+    Value,
+};
+
+pub const PROJECT_VERSION: &str = "1";
+
+/// A node and its subtree as returned by the HTTP API.
+#[derive(Clone, Debug, Serialize)]
+pub struct TreeNode {
+    pub node_id: NodeId,
+    pub uuid: NodeUuid,
+    pub node_type: NodeTypeId,
+    pub path: String,
+    pub meta: NodeMeta,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<ParameterData>,
+    pub children: Vec<TreeNode>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValueBody {
+    pub value: Value,
+    /// Unit of `value` in requests, converted to the parameter's own unit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Set in replies when the written value had to be converted lossily.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ErrorInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CreateNodeBody {
+    pub node_type: NodeTypeId,
+    pub label: Option<String>,
+    /// `passive`, `reactive` (default) or `continuous`.
+    pub execution: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MoveNodeBody {
+    /// Uuid, numeric id or path of the new parent.
+    pub new_parent: String,
+    /// Position among the new siblings; appends when omitted.
+    pub index: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportSummary {
+    pub skipped: Vec<String>,
+    pub rejected: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct ErrorBody {
+    error: ErrorInfo,
+}
+
+/// An HTTP error reply with a protocol-style error body.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    info: ErrorInfo,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            info: ErrorInfo {
+                code: code.to_string(),
+                message: message.into(),
+            },
+        }
+    }
+
+    fn not_found(selector: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "unknown_node", format!("no node matches '{selector}'"))
+    }
+}
+
+impl From<EditError> for ApiError {
+    fn from(err: EditError) -> Self {
+        let status = match &err {
+            EditError::UnknownNode(_) => StatusCode::NOT_FOUND,
+            EditError::ReadOnly(_)
+            | EditError::DeclaredChild(_)
+            | EditError::RootNode
+            | EditError::EngineManagedField {
+                ..
+            } => StatusCode::FORBIDDEN,
+            EditError::ContainerFull(_)
+            | EditError::ExpressionDriven(_)
+            | EditError::CyclicMove {
+                ..
+            } => StatusCode::CONFLICT,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, err.code(), err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(ErrorBody {
+                error: self.info,
+            }),
+        )
+            .into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+type SharedEngine = Arc<Mutex<Engine>>;
+
+/// Routes of the HTTP API, meant to be nested under `/api`.
+///
+/// Nodes are addressed by uuid, numeric id, or a percent-encoded path such as
+/// `%2Foutputs%2Fosc_output_a`; `/tree/*path` takes the path unencoded.
+pub fn rest_router<S>(engine: SharedEngine) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/tree", get(get_tree))
+        .route("/tree/*path", get(get_tree_at_path))
+        .route("/nodes/:node", get(get_node).delete(delete_node))
+        .route("/nodes/:node/value", get(get_value).put(put_value))
+        .route("/nodes/:node/meta", patch(patch_meta))
+        .route("/nodes/:node/children", post(create_child))
+        .route("/nodes/:node/move", post(move_node))
+        .route("/nodes/:node/fragment", get(get_fragment).post(paste_fragment))
+        .route("/project", get(export).put(import))
+        .with_state(engine)
+}
+
+async fn get_tree(State(engine): State<SharedEngine>) -> ApiResult<Json<TreeNode>> {
+    let engine = engine.lock().unwrap();
+    Ok(Json(build_tree(&engine, engine.root_id())?))
+}
+
+async fn get_tree_at_path(
+    State(engine): State<SharedEngine>,
+    Path(path): Path<String>,
+) -> ApiResult<Json<TreeNode>> {
+    let engine = engine.lock().unwrap();
+    // The wildcard captures the path without its leading slash.
+    let path = format!("/{}", path.trim_start_matches('/'));
+    let node = engine.resolve_path(&path).ok_or_else(|| ApiError::not_found(&path))?;
+    Ok(Json(build_tree(&engine, node)?))
+}
+
+async fn get_node(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+) -> ApiResult<Json<TreeNode>> {
+    let engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    Ok(Json(build_tree(&engine, node)?))
+}
+
+async fn get_value(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+) -> ApiResult<Json<ValueBody>> {
+    let engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    match engine.nodes.get(&node).map(|entry| &entry.data) {
+        Some(NodeData::Parameter(param)) => Ok(Json(ValueBody {
+            value: param.value.clone(),
+            unit: None,
+            warnings: Vec::new(),
+        })),
+        _ => Err(EditError::NotAParameter(node).into()),
+    }
+}
+
+async fn put_value(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+    Json(body): Json<ValueBody>,
+) -> ApiResult<Json<ValueBody>> {
+    let mut engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    let value = match &body.unit {
+        Some(unit) => engine.convert_to_param_unit(node, &body.value, unit)?,
+        None => body.value,
+    };
+    let warnings = apply(
+        &mut engine,
+        Edit::SetParam {
+            node,
+            value,
+        },
+    )?;
+    // Coercion or constraints may have changed the value.
+    match engine.nodes.get(&node).map(|entry| &entry.data) {
+        Some(NodeData::Parameter(param)) => Ok(Json(ValueBody {
+            value: param.value.clone(),
+            unit: None,
+            warnings: warnings
+                .iter()
+                .map(|warning| ErrorInfo {
+                    code: warning.code().to_string(),
+                    message: warning.to_string(),
+                })
+                .collect(),
+        })),
+        _ => Err(ApiError::not_found(&selector)),
+    }
+}
+
+async fn patch_meta(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+    Json(patch): Json<NodeMetaPatch>,
+) -> ApiResult<Json<NodeMeta>> {
+    let mut engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    apply(
+        &mut engine,
+        Edit::PatchMeta {
+            node,
+            patch,
+        },
+    )?;
+    engine
+        .nodes
+        .get(&node)
+        .map(|entry| Json(entry.meta.clone()))
+        .ok_or_else(|| ApiError::not_found(&selector))
+}
+
+async fn create_child(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+    Json(body): Json<CreateNodeBody>,
+) -> ApiResult<(StatusCode, Json<TreeNode>)> {
+    let mut engine = engine.lock().unwrap();
+    let parent = resolve_selector(&engine, &selector)?;
+    let execution = match body.execution.as_deref() {
+        None | Some("reactive") => NodeExecution::Reactive,
+        Some("passive") => NodeExecution::Passive,
+        Some("continuous") => NodeExecution::Continuous,
+        Some(other) => {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_execution",
+                format!("unknown execution mode '{other}'"),
+            ));
+        }
+    };
+    let label = body.label.unwrap_or_else(|| body.node_type.0.to_lowercase());
+
+    let before = engine.time;
+    apply(
+        &mut engine,
+        Edit::CreateNode {
+            parent,
+            node_type: body.node_type,
+            label,
+            execution,
+        },
+    )?;
+    let created = created_child(&engine, before, parent)?;
+    Ok((StatusCode::CREATED, Json(build_tree(&engine, created)?)))
+}
+
+async fn move_node(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+    Json(body): Json<MoveNodeBody>,
+) -> ApiResult<Json<TreeNode>> {
+    let mut engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    let new_parent = resolve_selector(&engine, &body.new_parent)?;
+    apply(
+        &mut engine,
+        Edit::MoveNode {
+            node,
+            new_parent,
+            index: body.index.unwrap_or(usize::MAX),
+        },
+    )?;
+    Ok(Json(build_tree(&engine, node)?))
+}
+
+async fn delete_node(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+) -> ApiResult<StatusCode> {
+    let mut engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    apply(
+        &mut engine,
+        Edit::DeleteNode {
+            node,
+        },
+    )?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn export(State(engine): State<SharedEngine>) -> Json<ProjectFile> {
+    let engine = engine.lock().unwrap();
+    Json(export_project(&engine, engine.root_id(), PROJECT_VERSION))
+}
+
+async fn import(
+    State(engine): State<SharedEngine>,
+    Json(project): Json<ProjectFile>,
+) -> ApiResult<Json<ImportSummary>> {
+    let mut engine = engine.lock().unwrap();
+    let root = engine.root_id();
+    apply(
+        &mut engine,
+        Edit::ImportProject {
+            root,
+            project: Box::new(project),
+        },
+    )?;
+    let report = engine.take_import_report().ok_or_else(not_applied)?;
+    Ok(Json(summarize(report)))
+}
+
+async fn get_fragment(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+) -> ApiResult<Json<FragmentFile>> {
+    let engine = engine.lock().unwrap();
+    let node = resolve_selector(&engine, &selector)?;
+    Ok(Json(export_fragment(&engine, node, PROJECT_VERSION)))
+}
+
+/// Pastes a fragment under the node and returns the created subtree.
+async fn paste_fragment(
+    State(engine): State<SharedEngine>,
+    Path(selector): Path<String>,
+    Json(fragment): Json<FragmentFile>,
+) -> ApiResult<(StatusCode, Json<TreeNode>)> {
+    let mut engine = engine.lock().unwrap();
+    let parent = resolve_selector(&engine, &selector)?;
+    let before = engine.time;
+    apply(
+        &mut engine,
+        Edit::InsertSubtree {
+            parent,
+            root: Box::new(fragment_subtree(&fragment)),
+        },
+    )?;
+    let created = created_child(&engine, before, parent)?;
+    Ok((StatusCode::CREATED, Json(build_tree(&engine, created)?)))
+}
+
+fn summarize(report: ImportReport) -> ImportSummary {
+    ImportSummary {
+        skipped: report.skipped.into_iter().map(|decl| decl.0).collect(),
+        rejected: report.rejected.into_iter().map(|node_type| node_type.0).collect(),
+    }
+}
+
+/// Validates and queues an edit like a WebSocket client would, then ticks so
+/// the response reflects the result.
+fn apply(engine: &mut Engine, edit: Edit) -> ApiResult<Vec<EditWarning>> {
+    let warnings = engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::Network)?;
+    engine.tick();
+    Ok(warnings)
+}
+
+/// The child an applied edit added to `parent` since `before`.
+fn created_child(engine: &Engine, before: EventTime, parent: NodeId) -> ApiResult<NodeId> {
+    engine
+        .events_since(before)
+        .into_iter()
+        .find_map(|event| match event.kind {
+            EventKind::ChildAdded {
+                parent: added_to,
+                child,
+            } if added_to == parent => Some(child),
+            _ => None,
+        })
+        .ok_or_else(not_applied)
+}
+
+fn not_applied() -> ApiError {
+    ApiError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "not_applied",
+        "the edit was accepted but not applied",
+    )
+}
+
+fn resolve_selector(engine: &Engine, selector: &str) -> ApiResult<NodeId> {
+    let found = if selector.starts_with('/') {
+        engine.resolve_path(selector)
+    } else if let Ok(uuid) = Uuid::parse_str(selector) {
+        engine.node_by_uuid(NodeUuid(uuid))
+    } else if let Ok(id) = selector.parse::<u64>() {
+        Some(NodeId(id)).filter(|id| engine.nodes.get(id).is_some())
+    } else {
+        None
+    };
+    found.ok_or_else(|| ApiError::not_found(selector))
+}
+
+/// The node and its subtree, or a 404 if the node no longer exists.
+pub fn build_tree(engine: &Engine, node_id: NodeId) -> ApiResult<TreeNode> {
+    let node =
+        engine.nodes.get(&node_id).ok_or_else(|| ApiError::not_found(&node_id.0.to_string()))?;
+    let mut children = Vec::new();
+    let mut current = node.first_child;
+    while let Some(child) = current {
+        children.push(build_tree(engine, child)?);
+        current = engine.nodes.get(&child).and_then(|entry| entry.next_sibling);
+    }
+
+    Ok(TreeNode {
+        node_id,
+        uuid: node.meta.uuid,
+        node_type: node.node_type.clone(),
+        path: engine.path_of(node_id).unwrap_or_default().to_string(),
+        meta: node.meta.clone(),
+        parameter: match &node.data {
+            NodeData::Parameter(param) => Some(param.clone()),
+            _ => None,
+        },
+        children,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::persistence::NodeRecord;
+    use golden_schema::{JournalEntry, RecordedEdit};
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+
+    struct Fixture {
+        engine: SharedEngine,
+        mixer: NodeId,
+        gain: NodeId,
+    }
+
+    fn fixture() -> Fixture {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let outputs = engine.create_child_container(root, "Container", "outputs");
+        let mixer = engine.create_child_container(outputs, "Container", "mixer");
+        let gain = engine.create_child_parameter(mixer, "gain", Value::Float(0.5));
+        Fixture {
+            engine: Arc::new(Mutex::new(engine)),
+            mixer,
+            gain,
+        }
+    }
+
+    /// Sends one request to the router over localhost and returns the status
+    /// and JSON body.
+    async fn request(
+        engine: &SharedEngine,
+        method: &str,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> (u16, serde_json::Value) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router: Router = rest_router(Arc::clone(engine));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let body = body.map(|body| body.to_string()).unwrap_or_default();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let head = format!(
+            "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\
+             Content-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(body.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, serde_json::from_str(body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn tree_path_resolves_nested_nodes() {
+        let fixture = fixture();
+        let (status, body) =
+            request(&fixture.engine, "GET", "/tree/outputs/mixer/gain", None).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["node_id"], json!(fixture.gain.0));
+        assert_eq!(body["path"], "/outputs/mixer/gain");
+
+        let (status, body) = request(&fixture.engine, "GET", "/tree/outputs/mixer", None).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["children"][0]["path"], "/outputs/mixer/gain");
+
+        let (status, body) = request(&fixture.engine, "GET", "/tree/outputs/nope", None).await;
+        assert_eq!(status, 404);
+        assert_eq!(body["error"]["message"], "no node matches '/outputs/nope'");
+    }
+
+    #[test]
+    fn build_tree_reports_missing_nodes() {
+        let fixture = fixture();
+        let mut engine = fixture.engine.lock().unwrap();
+        engine.delete_node(fixture.mixer);
+        let err = build_tree(&engine, fixture.gain).unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn paste_is_queued_journaled_and_recorded() {
+        let fixture = fixture();
+        let (fragment, root) = {
+            let mut engine = fixture.engine.lock().unwrap();
+            engine.set_journaling(true);
+            engine.set_recording(true);
+            (export_fragment(&engine, fixture.mixer, PROJECT_VERSION), engine.root_id())
+        };
+        let path = format!("/nodes/{}/fragment", root.0);
+        let (status, body) = request(&fixture.engine, "POST", &path, Some(json!(fragment))).await;
+        assert_eq!(status, 201);
+        assert_eq!(body["path"], "/mixer");
+        assert_eq!(body["children"][0]["parameter"]["value"], json!({ "Float": 0.5 }));
+        assert_ne!(body["uuid"], json!(fragment.root.uuid));
+
+        let mut engine = fixture.engine.lock().unwrap();
+        let journal = engine.take_journal();
+        assert!(matches!(
+            journal.as_slice(),
+            [JournalEntry::InsertSubtree { parent, .. }] if parent == "/"
+        ));
+        let recorded: Vec<_> =
+            engine.take_recording().into_iter().flat_map(|tick| tick.requests).collect();
+        assert!(matches!(
+            recorded.as_slice(),
+            [request] if matches!(request.edit, RecordedEdit::InsertSubtree { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn paste_rejects_incompatible_parents() {
+        let fixture = fixture();
+        let fragment = export_fragment(&fixture.engine.lock().unwrap(), fixture.mixer, "1");
+        let path = format!("/nodes/{}/fragment", fixture.gain.0);
+        let (status, body) = request(&fixture.engine, "POST", &path, Some(json!(fragment))).await;
+        assert_eq!(status, 422);
+        assert_eq!(body["error"]["code"], "not_a_container");
+        assert!(fixture.engine.lock().unwrap().pending_edits.is_empty());
+    }
+
+    #[tokio::test]
+    async fn project_import_is_queued_and_validated() {
+        let fixture = fixture();
+        let mut project = {
+            let mut engine = fixture.engine.lock().unwrap();
+            let project = export_project(&engine, engine.root_id(), PROJECT_VERSION);
+            engine
+                .submit_edit(
+                    Edit::SetParam {
+                        node: fixture.gain,
+                        value: Value::Float(0.9),
+                    },
+                    Propagation::Immediate,
+                    EditOrigin::UI,
+                )
+                .unwrap();
+            engine.tick();
+            project
+        };
+
+        let (status, body) =
+            request(&fixture.engine, "PUT", "/project", Some(json!(project))).await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "skipped": [], "rejected": [] }));
+        let (_, value) = request(&fixture.engine, "GET", "/tree/outputs/mixer/gain", None).await;
+        assert_eq!(value["parameter"]["value"], json!({ "Float": 0.5 }));
+
+        let NodeRecord::Full(root) = &mut project.root else {
+            panic!("the root is saved in full");
+        };
+        root.node_type = NodeTypeId("Container".to_string());
+        let (status, body) =
+            request(&fixture.engine, "PUT", "/project", Some(json!(project))).await;
+        assert_eq!(status, 422);
+        assert_eq!(body["error"]["code"], "root_type_mismatch");
+    }
+}
@@ -0,0 +1,549 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use golden_core::edits::{Edit, EditOrigin, Propagation};
+use golden_core::{Engine, NodeExecution};
+use golden_schema::events::EventTime;
+use golden_schema::ui::codecs::{
+    Codec, CodecError, Frame, ProtocolCodec, validate_protocol_version,
+};
+use golden_schema::ui::messages::{
+    Ack, ClientMessage, Envelope, ErrorInfo, EventBatch, Hello, HelloAck, ServerMessage,
+};
+use tokio::sync::mpsc;
+
+use crate::snapshot::build_snapshot;
+
+pub const PROTOCOL_VERSION: &str = "1";
+pub const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(8);
+
+/// Per-connection protocol state, independent of the socket carrying it.
+///
+/// Transports feed incoming frames to [`ClientSession::handle_frame`] and
+/// periodically call [`ClientSession::poll_subscription`]; every returned frame
+/// must be sent to the client in order. An error means a reply could not be
+/// encoded, leaving the client without an answer it may be waiting for; the
+/// transport should close the connection.
+pub struct ClientSession {
+    engine: Arc<Mutex<Engine>>,
+    codec: ProtocolCodec,
+    client_name: Option<String>,
+    subscription: Option<EventTime>,
+}
+
+impl ClientSession {
+    pub fn new(engine: Arc<Mutex<Engine>>) -> Self {
+        Self {
+            engine,
+            codec: ProtocolCodec::Json,
+            client_name: None,
+            subscription: None,
+        }
+    }
+
+    pub fn client_name(&self) -> Option<&str> {
+        self.client_name.as_deref()
+    }
+
+    pub fn codec(&self) -> ProtocolCodec {
+        self.codec
+    }
+
+    pub fn is_subscribed(&self) -> bool {
+        self.subscription.is_some()
+    }
+
+    /// Frames sent as soon as the connection is established.
+    pub fn open(&mut self) -> Result<Vec<Frame>, CodecError> {
+        let snapshot = build_snapshot(&self.engine.lock().unwrap());
+        let mut out = Vec::new();
+        self.push(&mut out, None, ServerMessage::Snapshot(snapshot))?;
+        Ok(out)
+    }
+
+    pub fn handle_text(&mut self, text: &str) -> Result<Vec<Frame>, CodecError> {
+        self.handle_frame(&Frame::Text(text.to_string()))
+    }
+
+    pub fn handle_frame(&mut self, frame: &Frame) -> Result<Vec<Frame>, CodecError> {
+        let mut out = Vec::new();
+        match ProtocolCodec::decode_any::<ClientMessage>(frame) {
+            Ok(envelope) => self.handle_message(envelope.req_id, envelope.message, &mut out)?,
+            Err(err) => self.push_ack(
+                &mut out,
+                None,
+                Some(ErrorInfo {
+                    code: "invalid_message".to_string(),
+                    message: err.to_string(),
+                }),
+            )?,
+        }
+        Ok(out)
+    }
+
+    /// Returns an `EventBatch` frame when the subscribed event stream advanced.
+    pub fn poll_subscription(&mut self) -> Result<Vec<Frame>, CodecError> {
+        let Some(last_time) = self.subscription else {
+            return Ok(Vec::new());
+        };
+        let events = self.engine.lock().unwrap().events_since(last_time);
+        let Some(last) = events.last() else {
+            return Ok(Vec::new());
+        };
+        self.subscription = Some(last.time);
+
+        let mut out = Vec::new();
+        self.push(
+            &mut out,
+            None,
+            ServerMessage::EventBatch(EventBatch {
+                events,
+            }),
+        )?;
+        Ok(out)
+    }
+
+    fn handle_message(
+        &mut self,
+        req_id: Option<String>,
+        message: ClientMessage,
+        out: &mut Vec<Frame>,
+    ) -> Result<(), CodecError> {
+        match message {
+            ClientMessage::Hello(hello) => self.handle_hello(hello, req_id, out)?,
+            ClientMessage::GetSnapshot(_) => {
+                let snapshot = build_snapshot(&self.engine.lock().unwrap());
+                self.push(out, req_id, ServerMessage::Snapshot(snapshot))?;
+            }
+            ClientMessage::Subscribe(subscribe) => {
+                self.subscription = Some(subscribe.from);
+                self.push_ack(out, req_id, None)?;
+            }
+            ClientMessage::SetParam(set_param) => {
+                let node = set_param.param_node_id;
+                let value = match &set_param.unit {
+                    Some(unit) => {
+                        let converted = self.engine.lock().unwrap().convert_to_param_unit(
+                            node,
+                            &set_param.value,
+                            unit,
+                        );
+                        match converted {
+                            Ok(value) => value,
+                            Err(err) => {
+                                let error = ErrorInfo {
+                                    code: err.code().to_string(),
+                                    message: err.to_string(),
+                                };
+                                return self.push_ack(out, req_id, Some(error));
+                            }
+                        }
+                    }
+                    None => set_param.value,
+                };
+                let edit = Edit::SetParam {
+                    node,
+                    value,
+                };
+                if self.submit(edit, &set_param.propagation, req_id, out)? {
+                    let snapshot = build_snapshot(&self.engine.lock().unwrap());
+                    self.push(out, None, ServerMessage::Snapshot(snapshot))?;
+                }
+            }
+            ClientMessage::PatchParam(patch_param) => {
+                let edit = Edit::PatchParam {
+                    node: patch_param.param_node_id,
+                    patch: patch_param.patch,
+                };
+                self.submit(edit, &patch_param.propagation, req_id, out)?;
+            }
+            ClientMessage::PatchMeta(patch_meta) => {
+                let edit = Edit::PatchMeta {
+                    node: patch_meta.node_id,
+                    patch: patch_meta.patch,
+                };
+                self.submit(edit, &patch_meta.propagation, req_id, out)?;
+            }
+            ClientMessage::CreateNode(create) => {
+                let label = create.label.unwrap_or_else(|| create.node_type.0.to_lowercase());
+                let edit = Edit::CreateNode {
+                    parent: create.parent_id,
+                    node_type: create.node_type,
+                    label,
+                    execution: NodeExecution::Reactive,
+                };
+                self.submit(edit, &create.propagation, req_id, out)?;
+            }
+            ClientMessage::MoveNode(move_node) => {
+                let edit = Edit::MoveNode {
+                    node: move_node.node_id,
+                    new_parent: move_node.new_parent_id,
+                    index: move_node.new_index,
+                };
+                self.submit(edit, &move_node.propagation, req_id, out)?;
+            }
+            ClientMessage::DeleteNode(delete) => {
+                let edit = Edit::DeleteNode {
+                    node: delete.node_id,
+                };
+                self.submit(edit, &delete.propagation, req_id, out)?;
+            }
+            ClientMessage::BeginEdit(_) | ClientMessage::EndEdit(_) => self.push_ack(
+                out,
+                req_id,
+                Some(ErrorInfo {
+                    code: "unsupported".to_string(),
+                    message: "message is not supported by this server".to_string(),
+                }),
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Validates and applies an edit, acknowledging the outcome.
+    fn submit(
+        &mut self,
+        edit: Edit,
+        propagation: &golden_schema::ui::messages::Propagation,
+        req_id: Option<String>,
+        out: &mut Vec<Frame>,
+    ) -> Result<bool, CodecError> {
+        let result = {
+            let mut engine = self.engine.lock().unwrap();
+            let result = engine.submit_edit(
+                edit,
+                propagation_from_message(propagation),
+                EditOrigin::Network,
+            );
+            if result.is_ok() {
+                engine.tick();
+            }
+            result
+        };
+        match result {
+            Ok(warnings) if !warnings.is_empty() => {
+                let warnings = warnings
+                    .iter()
+                    .map(|warning| ErrorInfo {
+                        code: warning.code().to_string(),
+                        message: warning.to_string(),
+                    })
+                    .collect();
+                self.push(
+                    out,
+                    req_id,
+                    ServerMessage::Ack(Ack {
+                        ok: true,
+                        error: None,
+                        warnings,
+                    }),
+                )?;
+                Ok(true)
+            }
+            Ok(_) => {
+                self.push_ack(out, req_id, None)?;
+                Ok(true)
+            }
+            Err(err) => {
+                let error = ErrorInfo {
+                    code: err.code().to_string(),
+                    message: err.to_string(),
+                };
+                self.push_ack(out, req_id, Some(error))?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn handle_hello(
+        &mut self,
+        hello: Hello,
+        req_id: Option<String>,
+        out: &mut Vec<Frame>,
+    ) -> Result<(), CodecError> {
+        if !validate_protocol_version(&hello.protocol_version) {
+            return self.push_ack(
+                out,
+                req_id,
+                Some(ErrorInfo {
+                    code: "unsupported_protocol".to_string(),
+                    message: format!(
+                        "protocol version '{}' is not supported",
+                        hello.protocol_version
+                    ),
+                }),
+            );
+        }
+
+        self.client_name = Some(hello.client_name);
+        let codec = ProtocolCodec::negotiate(&hello.codecs);
+        let ack = HelloAck {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            server_name: "golden".to_string(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: vec!["snapshot".to_string(), "subscribe".to_string(), "ack".to_string()],
+            codec: codec.name().to_string(),
+        };
+        // The acknowledgement still uses the codec the client started with.
+        self.push(out, req_id, ServerMessage::HelloAck(ack))?;
+        self.codec = codec;
+        Ok(())
+    }
+
+    fn push(
+        &self,
+        out: &mut Vec<Frame>,
+        req_id: Option<String>,
+        message: ServerMessage,
+    ) -> Result<(), CodecError> {
+        let envelope = Envelope {
+            req_id,
+            message,
+        };
+        out.push(self.codec.encode(&envelope)?);
+        Ok(())
+    }
+
+    fn push_ack(
+        &self,
+        out: &mut Vec<Frame>,
+        req_id: Option<String>,
+        error: Option<ErrorInfo>,
+    ) -> Result<(), CodecError> {
+        // Fire-and-forget messages only get an answer when they fail.
+        if req_id.is_none() && error.is_none() {
+            return Ok(());
+        }
+        self.push(
+            out,
+            req_id,
+            ServerMessage::Ack(Ack {
+                ok: error.is_none(),
+                error,
+                warnings: Vec::new(),
+            }),
+        )
+    }
+}
+
+/// Drives a [`ClientSession`] from a pair of frame channels until the incoming
+/// side closes, or until a reply cannot be encoded.
+pub async fn run_session(
+    engine: Arc<Mutex<Engine>>,
+    mut incoming: mpsc::UnboundedReceiver<Frame>,
+    outgoing: mpsc::UnboundedSender<Frame>,
+) -> Result<(), CodecError> {
+    let mut session = ClientSession::new(engine);
+    if !send_all(&outgoing, session.open()?) {
+        return Ok(());
+    }
+
+    let mut interval = tokio::time::interval(SUBSCRIPTION_POLL_INTERVAL);
+    loop {
+        let frames = tokio::select! {
+            frame = incoming.recv() => match frame {
+                Some(frame) => session.handle_frame(&frame),
+                None => break,
+            },
+            _ = interval.tick() => session.poll_subscription(),
+        }?;
+        if !send_all(&outgoing, frames) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn send_all(tx: &mpsc::UnboundedSender<Frame>, frames: Vec<Frame>) -> bool {
+    frames.into_iter().all(|frame| tx.send(frame).is_ok())
+}
+
+pub(crate) fn propagation_from_message(
+    propagation: &golden_schema::ui::messages::Propagation,
+) -> Propagation {
+    match propagation {
+        golden_schema::ui::messages::Propagation::Immediate => Propagation::Immediate,
+        golden_schema::ui::messages::Propagation::EndOfTick => Propagation::EndOfTick,
+        golden_schema::ui::messages::Propagation::NextTick => Propagation::NextTick,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_schema::ui::codecs::JsonCodec;
+    use golden_schema::ui::messages::{
+        BeginEdit, EditOrigin as MessageOrigin, Propagation as MessagePropagation, Scope,
+        ScopeMode, SetParam, Subscribe,
+    };
+    use golden_schema::{NodeId, Value};
+
+    use super::*;
+
+    fn session() -> (ClientSession, Arc<Mutex<Engine>>, NodeId) {
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let gain = engine.create_child_parameter(root, "gain", Value::Float(0.5));
+        let engine = Arc::new(Mutex::new(engine));
+        (ClientSession::new(Arc::clone(&engine)), engine, gain)
+    }
+
+    fn send(
+        session: &mut ClientSession,
+        req_id: Option<&str>,
+        message: ClientMessage,
+    ) -> Vec<Envelope<ServerMessage>> {
+        let frame = JsonCodec
+            .encode(&Envelope {
+                req_id: req_id.map(str::to_string),
+                message,
+            })
+            .unwrap();
+        decode(session.handle_frame(&frame).unwrap())
+    }
+
+    fn decode(frames: Vec<Frame>) -> Vec<Envelope<ServerMessage>> {
+        frames.iter().map(|frame| ProtocolCodec::decode_any(frame).unwrap()).collect()
+    }
+
+    fn hello(codecs: &[&str]) -> ClientMessage {
+        ClientMessage::Hello(Hello {
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            client_name: "test".to_string(),
+            client_version: "0".to_string(),
+            root_scope: None,
+            codecs: codecs.iter().map(|codec| codec.to_string()).collect(),
+        })
+    }
+
+    fn set_param(node: NodeId, value: Value) -> ClientMessage {
+        ClientMessage::SetParam(SetParam {
+            edit_session_id: None,
+            param_node_id: node,
+            value,
+            unit: None,
+            propagation: MessagePropagation::Immediate,
+        })
+    }
+
+    fn ack(envelope: &Envelope<ServerMessage>) -> &Ack {
+        match &envelope.message {
+            ServerMessage::Ack(ack) => ack,
+            other => panic!("expected an ack, got {other:?}"),
+        }
+    }
+
+    fn value_of(engine: &Arc<Mutex<Engine>>, node: NodeId) -> Value {
+        match &engine.lock().unwrap().nodes.get(&node).unwrap().data {
+            golden_core::NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    #[test]
+    fn open_sends_a_snapshot() {
+        let (mut session, _, gain) = session();
+        let replies = decode(session.open().unwrap());
+        assert_eq!(replies.len(), 1);
+        let ServerMessage::Snapshot(snapshot) = &replies[0].message else {
+            panic!("expected a snapshot");
+        };
+        assert!(snapshot.params.iter().any(|param| param.param_node_id == gain));
+    }
+
+    #[test]
+    fn hello_switches_codec_after_the_acknowledgement() {
+        let (mut session, _, gain) = session();
+        let replies = send(&mut session, Some("1"), hello(&["cbor", "msgpack"]));
+        let ServerMessage::HelloAck(ack) = &replies[0].message else {
+            panic!("expected a hello ack");
+        };
+        assert_eq!(ack.codec, "msgpack");
+        assert_eq!(replies[0].req_id.as_deref(), Some("1"));
+        assert_eq!(session.client_name(), Some("test"));
+        assert_eq!(session.codec(), ProtocolCodec::MessagePack);
+
+        let frame = JsonCodec
+            .encode(&Envelope {
+                req_id: Some("2".to_string()),
+                message: set_param(gain, Value::Float(0.25)),
+            })
+            .unwrap();
+        let frames = session.handle_frame(&frame).unwrap();
+        assert!(frames.iter().all(|frame| matches!(frame, Frame::Binary(_))));
+    }
+
+    #[test]
+    fn set_param_is_acknowledged_and_applied() {
+        let (mut session, engine, gain) = session();
+        let replies = send(&mut session, Some("7"), set_param(gain, Value::Float(0.75)));
+        assert!(ack(&replies[0]).ok);
+        assert_eq!(replies[0].req_id.as_deref(), Some("7"));
+        assert!(matches!(replies[1].message, ServerMessage::Snapshot(_)));
+        assert_eq!(value_of(&engine, gain), Value::Float(0.75));
+    }
+
+    #[test]
+    fn successful_fire_and_forget_edits_are_not_acknowledged() {
+        let (mut session, engine, gain) = session();
+        let replies = send(&mut session, None, set_param(gain, Value::Float(0.1)));
+        assert!(replies.iter().all(|reply| !matches!(reply.message, ServerMessage::Ack(_))));
+        assert_eq!(value_of(&engine, gain), Value::Float(0.1));
+    }
+
+    #[test]
+    fn rejected_edits_report_the_error() {
+        let (mut session, engine, gain) = session();
+        let root = engine.lock().unwrap().root_id();
+        let replies = send(&mut session, None, set_param(root, Value::Float(1.0)));
+        let ack = ack(&replies[0]);
+        assert!(!ack.ok);
+        assert!(ack.error.is_some());
+        assert_eq!(value_of(&engine, gain), Value::Float(0.5));
+    }
+
+    #[test]
+    fn undecodable_frames_are_answered_with_an_error() {
+        let (mut session, _, _) = session();
+        let replies = decode(session.handle_text("{\"msg\":\"Nonsense\"}").unwrap());
+        assert_eq!(ack(&replies[0]).error.as_ref().unwrap().code, "invalid_message");
+    }
+
+    #[test]
+    fn unsupported_messages_are_rejected() {
+        let (mut session, _, _) = session();
+        let message = ClientMessage::BeginEdit(BeginEdit {
+            origin: MessageOrigin::UI,
+            label: None,
+        });
+        let replies = send(&mut session, Some("3"), message);
+        assert_eq!(ack(&replies[0]).error.as_ref().unwrap().code, "unsupported");
+    }
+
+    #[test]
+    fn subscription_streams_new_events() {
+        let (mut session, _, gain) = session();
+        assert!(session.poll_subscription().unwrap().is_empty());
+
+        let ServerMessage::Snapshot(snapshot) = &decode(session.open().unwrap())[0].message else {
+            panic!("expected a snapshot");
+        };
+        let subscribe = ClientMessage::Subscribe(Subscribe {
+            scope: Scope {
+                mode: ScopeMode::Root,
+                root_uuid: None,
+            },
+            from: snapshot.as_of,
+        });
+        assert!(ack(&send(&mut session, Some("s"), subscribe)[0]).ok);
+        assert!(session.is_subscribed());
+        assert!(session.poll_subscription().unwrap().is_empty());
+
+        send(&mut session, None, set_param(gain, Value::Float(0.9)));
+        let replies = decode(session.poll_subscription().unwrap());
+        let ServerMessage::EventBatch(batch) = &replies[0].message else {
+            panic!("expected an event batch");
+        };
+        assert!(!batch.events.is_empty());
+        assert!(session.poll_subscription().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,367 @@
+use core::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::values::ColorRgba;
+
+/// How a curve moves from a key to the next one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum CurveInterpolation {
+    /// Holds the key's value until the next key.
+    Step,
+    #[default]
+    Linear,
+    /// Cubic Hermite using the keys' tangents.
+    Smooth,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+/// One keyframe of a [`Curve`]. `id` stays the same across edits and
+/// save/load, so editors and merges can follow a key that moved.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CurveKey {
+    pub id: Uuid,
+    pub x: f64,
+    pub y: f64,
+    /// Interpolation towards the next key.
+    pub interpolation: CurveInterpolation,
+    /// Slope (dy/dx) arriving at this key, used by `Smooth`.
+    pub in_tangent: f64,
+    /// Slope (dy/dx) leaving this key, used by `Smooth`.
+    pub out_tangent: f64,
+}
+
+impl CurveKey {
+    /// A linear key with a fresh id and flat tangents.
+    pub fn new(x: f64, y: f64) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            x,
+            y,
+            interpolation: CurveInterpolation::Linear,
+            in_tangent: 0.0,
+            out_tangent: 0.0,
+        }
+    }
+}
+
+/// Transfer function made of keys sorted by `x`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Curve {
+    pub keys: Vec<CurveKey>,
+}
+
+impl Curve {
+    /// Linear keys through `points`. Ids are numbered from 1 so a declared
+    /// default compares equal from one run to the next.
+    pub fn from_points(points: &[(f64, f64)]) -> Self {
+        let mut keys: Vec<CurveKey> = points
+            .iter()
+            .enumerate()
+            .map(|(index, &(x, y))| CurveKey {
+                id: Uuid::from_u128(index as u128 + 1),
+                ..CurveKey::new(x, y)
+            })
+            .collect();
+        keys.sort_by(|a, b| a.x.total_cmp(&b.x));
+        Self {
+            keys,
+        }
+    }
+
+    /// `y = x` over `0..=1`.
+    pub fn identity() -> Self {
+        Self::from_points(&[(0.0, 0.0), (1.0, 1.0)])
+    }
+
+    /// Inserts `key` after any key at the same `x` and returns its index.
+    pub fn insert(&mut self, key: CurveKey) -> usize {
+        let index = self.keys.partition_point(|other| other.x <= key.x);
+        self.keys.insert(index, key);
+        index
+    }
+
+    /// The key with `id`, if any.
+    pub fn key(&self, id: Uuid) -> Option<&CurveKey> {
+        self.keys.iter().find(|key| key.id == id)
+    }
+
+    /// Value at `x`; held flat before the first and after the last key, and
+    /// 0 for an empty curve. NaN samples the first key.
+    pub fn sample(&self, x: f64) -> f64 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else {
+            return 0.0;
+        };
+        if x.is_nan() || x <= first.x {
+            return first.y;
+        }
+        if x >= last.x {
+            return last.y;
+        }
+        let next = self.keys.partition_point(|key| key.x <= x);
+        let (a, b) = (&self.keys[next - 1], &self.keys[next]);
+        let span = b.x - a.x;
+        if span <= 0.0 {
+            return b.y;
+        }
+        let t = (x - a.x) / span;
+        let eased = match a.interpolation {
+            CurveInterpolation::Step => return a.y,
+            CurveInterpolation::Linear => t,
+            CurveInterpolation::Smooth => {
+                let (t2, t3) = (t * t, t * t * t);
+                return (2.0 * t3 - 3.0 * t2 + 1.0) * a.y
+                    + (t3 - 2.0 * t2 + t) * span * a.out_tangent
+                    + (-2.0 * t3 + 3.0 * t2) * b.y
+                    + (t3 - t2) * span * b.in_tangent;
+            }
+            CurveInterpolation::EaseIn => t * t,
+            CurveInterpolation::EaseOut => t * (2.0 - t),
+            CurveInterpolation::EaseInOut => t * t * (3.0 - 2.0 * t),
+        };
+        a.y + (b.y - a.y) * eased
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Curve(")?;
+        for (index, key) in self.keys.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", key.x, key.y)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// One colour stop of a [`Gradient`]; `id` is stable like [`CurveKey::id`].
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GradientStop {
+    pub id: Uuid,
+    pub t: f64,
+    pub color: ColorRgba,
+}
+
+impl GradientStop {
+    /// A stop with a fresh id.
+    pub fn new(t: f64, color: ColorRgba) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            t,
+            color,
+        }
+    }
+}
+
+/// Colour stops sorted by `t`, blended linearly in RGBA.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Gradient {
+    pub stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// Stops at `colors`, with ids numbered from 1 as in
+    /// [`Curve::from_points`].
+    pub fn from_colors(colors: &[(f64, ColorRgba)]) -> Self {
+        let mut stops: Vec<GradientStop> = colors
+            .iter()
+            .enumerate()
+            .map(|(index, &(t, color))| GradientStop {
+                id: Uuid::from_u128(index as u128 + 1),
+                t,
+                color,
+            })
+            .collect();
+        stops.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Self {
+            stops,
+        }
+    }
+
+    /// Opaque black at 0 to opaque white at 1.
+    pub fn black_to_white() -> Self {
+        let grey = |v| ColorRgba {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.0,
+        };
+        Self::from_colors(&[(0.0, grey(0.0)), (1.0, grey(1.0))])
+    }
+
+    /// Inserts `stop` after any stop at the same `t` and returns its index.
+    pub fn insert(&mut self, stop: GradientStop) -> usize {
+        let index = self.stops.partition_point(|other| other.t <= stop.t);
+        self.stops.insert(index, stop);
+        index
+    }
+
+    /// Colour at `t`; held before the first and after the last stop, and
+    /// transparent black for an empty gradient. NaN samples the first stop.
+    pub fn sample(&self, t: f64) -> ColorRgba {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return ColorRgba {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 0.0,
+            };
+        };
+        if t.is_nan() || t <= first.t {
+            return first.color;
+        }
+        if t >= last.t {
+            return last.color;
+        }
+        let next = self.stops.partition_point(|stop| stop.t <= t);
+        let (a, b) = (&self.stops[next - 1], &self.stops[next]);
+        let span = b.t - a.t;
+        if span <= 0.0 {
+            return b.color;
+        }
+        let u = (t - a.t) / span;
+        let mix = |from: f64, to: f64| from + (to - from) * u;
+        ColorRgba {
+            r: mix(a.color.r, b.color.r),
+            g: mix(a.color.g, b.color.g),
+            b: mix(a.color.b, b.color.b),
+            a: mix(a.color.a, b.color.a),
+        }
+    }
+}
+
+impl fmt::Display for Gradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gradient(")?;
+        for (index, stop) in self.stops.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            let c = stop.color;
+            write!(f, "{}: rgba({}, {}, {}, {})", stop.t, c.r, c.g, c.b, c.a)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grey(v: f64) -> ColorRgba {
+        ColorRgba {
+            r: v,
+            g: v,
+            b: v,
+            a: 1.0,
+        }
+    }
+
+    /// Two keys from (0, 0) to (1, 1) using `interpolation`.
+    fn ramp(interpolation: CurveInterpolation) -> Curve {
+        let mut curve = Curve::identity();
+        curve.keys[0].interpolation = interpolation;
+        curve
+    }
+
+    #[test]
+    fn empty_curves_and_gradients_sample_to_zero() {
+        assert_eq!(Curve::default().sample(0.5), 0.0);
+        assert_eq!(
+            Gradient::default().sample(0.5),
+            ColorRgba {
+                a: 0.0,
+                ..grey(0.0)
+            }
+        );
+    }
+
+    #[test]
+    fn samples_outside_the_keys_hold_the_end_values() {
+        let curve = Curve::from_points(&[(1.0, 2.0), (3.0, 4.0)]);
+        assert_eq!(curve.sample(0.0), 2.0);
+        assert_eq!(curve.sample(f64::NEG_INFINITY), 2.0);
+        assert_eq!(curve.sample(5.0), 4.0);
+        assert_eq!(curve.sample(f64::INFINITY), 4.0);
+
+        let gradient = Gradient::black_to_white();
+        assert_eq!(gradient.sample(-1.0), grey(0.0));
+        assert_eq!(gradient.sample(2.0), grey(1.0));
+    }
+
+    #[test]
+    fn nan_samples_the_first_key() {
+        let curve = Curve::from_points(&[(0.0, 0.25), (1.0, 1.0)]);
+        assert_eq!(curve.sample(f64::NAN), 0.25);
+        assert_eq!(Gradient::black_to_white().sample(f64::NAN), grey(0.0));
+    }
+
+    #[test]
+    fn each_interpolation_mode_shapes_the_segment() {
+        assert_eq!(ramp(CurveInterpolation::Step).sample(0.75), 0.0);
+        assert_eq!(ramp(CurveInterpolation::Linear).sample(0.25), 0.25);
+        assert_eq!(ramp(CurveInterpolation::EaseIn).sample(0.5), 0.25);
+        assert_eq!(ramp(CurveInterpolation::EaseOut).sample(0.5), 0.75);
+        assert_eq!(ramp(CurveInterpolation::EaseInOut).sample(0.25), 0.15625);
+        assert_eq!(ramp(CurveInterpolation::EaseInOut).sample(0.5), 0.5);
+
+        // Flat tangents give a smoothstep; matching slopes give a line.
+        let mut smooth = ramp(CurveInterpolation::Smooth);
+        assert_eq!(smooth.sample(0.25), 0.15625);
+        smooth.keys[0].out_tangent = 1.0;
+        smooth.keys[1].in_tangent = 1.0;
+        assert!((smooth.sample(0.25) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn keys_at_the_same_x_jump_to_the_later_one() {
+        let curve = Curve::from_points(&[(0.0, 0.0), (1.0, 1.0), (1.0, 5.0), (2.0, 5.0)]);
+        assert_eq!(curve.sample(0.5), 0.5);
+        assert_eq!(curve.sample(1.0), 5.0);
+        assert_eq!(curve.sample(1.5), 5.0);
+
+        let gradient =
+            Gradient::from_colors(&[(0.0, grey(0.0)), (0.5, grey(0.0)), (0.5, grey(1.0))]);
+        assert_eq!(gradient.sample(0.5), grey(1.0));
+        assert_eq!(gradient.sample(0.25), grey(0.0));
+    }
+
+    #[test]
+    fn gradients_blend_each_channel() {
+        let gradient = Gradient::from_colors(&[
+            (
+                0.0,
+                ColorRgba {
+                    r: 1.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.0,
+                },
+            ),
+            (
+                1.0,
+                ColorRgba {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 1.0,
+                    a: 1.0,
+                },
+            ),
+        ]);
+        assert_eq!(
+            gradient.sample(0.25),
+            ColorRgba {
+                r: 0.75,
+                g: 0.0,
+                b: 0.25,
+                a: 0.25,
+            }
+        );
+    }
+}
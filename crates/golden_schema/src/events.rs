@@ -1,28 +1,37 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::ids::NodeId;
 use crate::meta::NodeMetaPatch;
-use crate::values::Value;
+use crate::values::{Value, ValuePatch};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
 pub struct EventTime {
     pub tick: u64,
     pub micro: u32,
     pub seq: u32,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Event {
     pub time: EventTime,
     pub kind: EventKind,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum EventKind {
     ParamChanged {
         param: NodeId,
         value: Value,
     },
+    /// One element of a collection parameter changed; `patch` applied to the
+    /// previous value gives the new one.
+    ParamPatched {
+        param: NodeId,
+        patch: ValuePatch,
+    },
     ChildAdded {
         parent: NodeId,
         child: NodeId,
@@ -55,4 +64,12 @@ pub enum EventKind {
         node: NodeId,
         patch: NodeMetaPatch,
     },
+    /// A ramp reached its target.
+    RampFinished {
+        param: NodeId,
+    },
+    /// A ramp was interrupted by another write to its parameter.
+    RampCancelled {
+        param: NodeId,
+    },
 }
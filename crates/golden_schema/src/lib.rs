@@ -1,18 +1,30 @@
+pub mod curves;
 pub mod events;
 pub mod ids;
 pub mod meta;
 pub mod persistence;
+pub mod typegen;
 pub mod ui;
+pub mod units;
 pub mod values;
 
+pub use curves::{Curve, CurveInterpolation, CurveKey, Gradient, GradientStop};
 pub use events::{Event, EventKind, EventTime};
 pub use ids::{DeclId, EnumId, EnumVariantId, NodeId, NodeTypeId, NodeUuid, ShortName};
 pub use meta::{NodeMeta, NodeMetaPatch, PresentationHint, SemanticsHint};
-pub use persistence::file_format::ProjectFile;
+pub use persistence::file_format::{FragmentFile, ProjectFile};
+pub use persistence::journal::JournalEntry;
+pub use persistence::recording::{
+    RecordedEasing, RecordedEdit, RecordedExecution, RecordedNode, RecordedRequest, RecordedTick,
+    RecordingHeader,
+};
 pub use persistence::{
     ContainerDataDto, DeltaNodeRecord, FullNodeRecord, NodeDataDto, NodeDataKind, NodeRecord,
+    Preset, PresetValue,
 };
+pub use units::{Dimension, Unit, UnitError};
 pub use values::{
-    ChangePolicy, ColorRgba, ParameterData, ReferenceValue, SavePolicy, Trigger, UpdatePolicy,
-    Value, ValueConstraints, Vec2, Vec3,
+    ChangePolicy, Coerced, ColorRgba, FloatRange, IntRange, ParameterData, Quat, ReferenceValue,
+    SavePolicy, Timestamp, Trigger, UpdatePolicy, Value, ValueConstraints, ValuePatch, Vec2, Vec3,
+    Vec4,
 };
@@ -1,19 +1,20 @@
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::ids::{DeclId, NodeUuid, ShortName};
 
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SemanticsHint {
     pub intent: Option<String>,
     pub unit: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PresentationHint {
     pub widget: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NodeMeta {
     pub uuid: NodeUuid,
     pub decl_id: DeclId,
@@ -24,14 +25,74 @@ pub struct NodeMeta {
     pub tags: Vec<String>,
     pub semantics: SemanticsHint,
     pub presentation: PresentationHint,
+    /// Expression computing a parameter's value, e.g. `sin(time) * /inputs/fader`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// Why `expression` cannot be evaluated; maintained by the engine.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression_error: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+/// Changes to a node's metadata; fields left out are kept.
+///
+/// For the optional fields, `null` clears the value while leaving the field
+/// out keeps it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NodeMetaPatch {
     pub enabled: Option<bool>,
     pub label: Option<String>,
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
     pub description: Option<Option<String>>,
     pub tags: Option<Vec<String>>,
     pub semantics: Option<SemanticsHint>,
     pub presentation: Option<PresentationHint>,
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    pub expression: Option<Option<String>>,
+    /// Set by the engine in `MetaChanged` events; edits that set it are
+    /// rejected.
+    #[serde(default, deserialize_with = "present", skip_serializing_if = "Option::is_none")]
+    pub expression_error: Option<Option<String>>,
+}
+
+/// Reads a field that is present, `null` included, as `Some`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn null_clears_and_absence_keeps_optional_fields() {
+        let patch: NodeMetaPatch =
+            serde_json::from_value(json!({ "expression": null, "description": "fader" })).unwrap();
+        assert_eq!(patch.expression, Some(None));
+        assert_eq!(patch.description, Some(Some("fader".to_string())));
+        assert_eq!(patch.expression_error, None);
+        assert_eq!(patch.label, None);
+
+        let empty: NodeMetaPatch = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty, NodeMetaPatch::default());
+    }
+
+    #[test]
+    fn optional_fields_round_trip() {
+        for field in [None, Some(None), Some(Some("sin(time)".to_string()))] {
+            let patch = NodeMetaPatch {
+                description: field.clone(),
+                expression: field.clone(),
+                expression_error: field,
+                ..NodeMetaPatch::default()
+            };
+            let json = serde_json::to_string(&patch).unwrap();
+            assert_eq!(serde_json::from_str::<NodeMetaPatch>(&json).unwrap(), patch, "{json}");
+        }
+    }
 }
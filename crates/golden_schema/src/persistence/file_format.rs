@@ -1,9 +1,21 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::persistence::NodeRecord;
+use crate::persistence::{FullNodeRecord, NodeRecord, Preset};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectFile {
     pub version: String,
     pub root: NodeRecord,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<Preset>,
+}
+
+/// One node and its subtree, exported on its own to be pasted under another
+/// compatible parent or kept as a template. Importing gives every node in it a
+/// fresh uuid.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FragmentFile {
+    pub version: String,
+    pub root: FullNodeRecord,
 }
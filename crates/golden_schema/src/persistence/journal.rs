@@ -0,0 +1,45 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{NodeTypeId, NodeUuid};
+use crate::meta::NodeMetaPatch;
+use crate::persistence::FullNodeRecord;
+use crate::values::Value;
+
+/// One committed edit, written to the journal kept beside a project file.
+///
+/// Nodes are addressed by path rather than `NodeId`, so entries replay onto a
+/// graph rebuilt from the last save by a later run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum JournalEntry {
+    /// The value a parameter ended up with, after clamping or patching.
+    SetParam {
+        path: String,
+        value: Value,
+    },
+    PatchMeta {
+        path: String,
+        patch: NodeMetaPatch,
+    },
+    /// A node created under `parent`; `uuid` is restored so references to it
+    /// keep resolving.
+    CreateNode {
+        parent: String,
+        node_type: NodeTypeId,
+        label: String,
+        uuid: NodeUuid,
+    },
+    MoveNode {
+        path: String,
+        new_parent: String,
+        index: usize,
+    },
+    DeleteNode {
+        path: String,
+    },
+    /// A pasted fragment, with the uuids it was given when pasted.
+    InsertSubtree {
+        parent: String,
+        root: Box<FullNodeRecord>,
+    },
+}
@@ -1,12 +1,15 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 pub mod file_format;
+pub mod journal;
+pub mod recording;
 
 use crate::ids::{DeclId, NodeTypeId, NodeUuid};
 use crate::meta::{NodeMeta, NodeMetaPatch};
 use crate::values::{ParameterData, Value};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum NodeDataKind {
     None,
     Container,
@@ -14,13 +17,13 @@ pub enum NodeDataKind {
     Custom(String),
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ContainerDataDto {
     pub allowed_types: Vec<NodeTypeId>,
     pub folders_allowed: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NodeDataDto {
     pub kind: NodeDataKind,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,14 +32,14 @@ pub struct NodeDataDto {
     pub parameter: Option<ParameterData>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum NodeRecord {
     Full(FullNodeRecord),
     Delta(DeltaNodeRecord),
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct FullNodeRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub decl_id: Option<DeclId>,
@@ -49,7 +52,7 @@ pub struct FullNodeRecord {
     pub children: Vec<NodeRecord>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DeltaNodeRecord {
     pub decl_id: DeclId,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -61,3 +64,21 @@ pub struct DeltaNodeRecord {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<NodeRecord>,
 }
+
+/// A named snapshot of parameter values below a node.
+///
+/// Values are keyed by node uuid so a preset still applies after the graph has
+/// been rebuilt with fresh `NodeId`s.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Preset {
+    pub name: String,
+    /// Node the preset was captured from.
+    pub root: NodeUuid,
+    pub values: Vec<PresetValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PresetValue {
+    pub uuid: NodeUuid,
+    pub value: Value,
+}
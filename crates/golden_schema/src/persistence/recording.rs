@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{NodeId, NodeTypeId, NodeUuid};
+use crate::meta::NodeMetaPatch;
+use crate::persistence::FullNodeRecord;
+use crate::persistence::file_format::ProjectFile;
+use crate::ui::messages::{EditOrigin, Propagation};
+use crate::values::{Value, ValuePatch};
+
+/// The first line of a recording file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RecordingHeader {
+    pub version: String,
+    /// Engine tick the recording starts after.
+    pub start_tick: u64,
+    /// Fingerprint of the graph when recording started; replay needs an
+    /// engine in the same state.
+    pub fingerprint: String,
+    /// Every node's uuid when recording started. Replay restores them, as a
+    /// project file leaves out those of declared nodes nothing references.
+    pub nodes: Vec<RecordedNode>,
+}
+
+/// One engine tick: the input it consumed and a digest of what it emitted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedTick {
+    pub tick: u64,
+    /// The clock step the tick advanced by.
+    pub step: Duration,
+    /// External edits the tick applied, in the order they were queued.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requests: Vec<RecordedRequest>,
+    /// Nodes the tick created, with the uuids they were given; replay gives
+    /// them the same ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub created: Vec<RecordedNode>,
+    /// Number of events emitted since the previous tick, this one included.
+    pub events: usize,
+    /// Checksum of those events.
+    pub digest: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedNode {
+    pub node: NodeId,
+    pub uuid: NodeUuid,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RecordedRequest {
+    pub edit: RecordedEdit,
+    pub propagation: Propagation,
+    pub origin: EditOrigin,
+}
+
+/// An external edit as queued on the engine. Nodes are named by `NodeId`, as
+/// on the wire, since replay rebuilds the same graph.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum RecordedEdit {
+    SetParam {
+        node: NodeId,
+        value: Value,
+    },
+    RampParam {
+        node: NodeId,
+        target: Value,
+        duration: Duration,
+        easing: RecordedEasing,
+    },
+    PatchParam {
+        node: NodeId,
+        patch: ValuePatch,
+    },
+    PatchMeta {
+        node: NodeId,
+        patch: NodeMetaPatch,
+    },
+    InstantiateChildFromManager {
+        manager: NodeId,
+        node_type: NodeTypeId,
+        label: String,
+        execution: RecordedExecution,
+    },
+    CreateNode {
+        parent: NodeId,
+        node_type: NodeTypeId,
+        label: String,
+        execution: RecordedExecution,
+    },
+    MoveNode {
+        node: NodeId,
+        new_parent: NodeId,
+        index: usize,
+    },
+    DeleteNode {
+        node: NodeId,
+    },
+    ImportProject {
+        root: NodeId,
+        project: Box<ProjectFile>,
+    },
+    /// A pasted subtree, with the uuids it was given.
+    InsertSubtree {
+        parent: NodeId,
+        root: Box<FullNodeRecord>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RecordedEasing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum RecordedExecution {
+    Passive,
+    Reactive,
+    Continuous,
+}
@@ -0,0 +1,271 @@
+//! JSON Schema and TypeScript definitions derived from the wire types, so
+//! clients stop decoding `Value` and the protocol messages by hand.
+
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value as Json, json};
+
+use crate::persistence::file_format::ProjectFile;
+use crate::ui::messages::{ClientMessage, Envelope, ServerMessage};
+use crate::values::{Value, ValueConstraints};
+
+/// JSON Schema (draft 2020-12) whose `$defs` hold the client and server
+/// envelopes, `Value`, `ValueConstraints`, the project file and every type
+/// they reach. More definitions, such as node parameters, may be added to
+/// `$defs` before calling [`typescript`].
+pub fn protocol_schema() -> Json {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    generator.subschema_for::<Envelope<ClientMessage>>();
+    generator.subschema_for::<Envelope<ServerMessage>>();
+    generator.subschema_for::<ClientMessage>();
+    generator.subschema_for::<ServerMessage>();
+    generator.subschema_for::<Value>();
+    generator.subschema_for::<ValueConstraints>();
+    generator.subschema_for::<ProjectFile>();
+    let mut defs = generator.take_definitions(true);
+    // `Envelope` flattens its message; refer to the message definition
+    // instead of repeating every variant.
+    for message in ["ClientMessage", "ServerMessage"] {
+        let variants = defs.get(message).and_then(|def| def.get("oneOf")).cloned();
+        if let Some(envelope) =
+            defs.get_mut(&format!("{message}Envelope")).and_then(Json::as_object_mut)
+            && envelope.get("oneOf") == variants.as_ref()
+        {
+            envelope.remove("oneOf");
+            envelope.insert("allOf".to_string(), json!([{ "$ref": format!("#/$defs/{message}") }]));
+        }
+    }
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Golden protocol",
+        "$defs": defs,
+    })
+}
+
+/// TypeScript declarations for the `$defs` of `schema`: an interface per
+/// plain object and a type alias for everything else, sorted by name.
+pub fn typescript(schema: &Json) -> String {
+    let mut out =
+        String::from("// Generated from the Rust types by golden_typegen; do not edit.\n");
+    let Some(defs) = schema.get("$defs").and_then(Json::as_object) else {
+        return out;
+    };
+    let mut names: Vec<&String> = defs.keys().collect();
+    names.sort();
+    for name in names {
+        let def = &defs[name];
+        out.push('\n');
+        out.push_str(&doc_comment(def, ""));
+        let name = type_name(name);
+        match def.as_object().filter(|object| is_plain_object(object)) {
+            Some(object) => {
+                out.push_str(&format!("export interface {name} {}\n", object_body(object, "")))
+            }
+            None => out.push_str(&format!("export type {name} ={};\n", spaced(&ts_type(def, "")))),
+        }
+    }
+    out
+}
+
+/// An object with named properties and no variants, written as an interface.
+fn is_plain_object(object: &Map<String, Json>) -> bool {
+    object.get("type").and_then(Json::as_str) == Some("object")
+        && object.contains_key("properties")
+        && !["oneOf", "anyOf", "allOf"].iter().any(|key| object.contains_key(*key))
+}
+
+fn ts_type(schema: &Json, indent: &str) -> String {
+    let Some(object) = schema.as_object() else {
+        return match schema {
+            Json::Bool(false) => "never".to_string(),
+            _ => "unknown".to_string(),
+        };
+    };
+    if let Some(reference) = object.get("$ref").and_then(Json::as_str) {
+        return type_name(reference.rsplit('/').next().unwrap_or(reference));
+    }
+    if let Some(value) = object.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = object.get("enum").and_then(Json::as_array) {
+        return values.iter().map(Json::to_string).collect::<Vec<_>>().join(" | ");
+    }
+
+    let mut parts = Vec::new();
+    if object.contains_key("properties") || object.contains_key("type") {
+        parts.push(base_type(object, indent));
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = object.get(key).and_then(Json::as_array) {
+            parts.push(union(variants, indent));
+        }
+    }
+    if let Some(all) = object.get("allOf").and_then(Json::as_array) {
+        parts.extend(all.iter().map(|schema| ts_type(schema, indent)));
+    }
+    match parts.len() {
+        0 => "unknown".to_string(),
+        1 => parts.remove(0),
+        _ => parts.iter().map(|part| group(part)).collect::<Vec<_>>().join(" & "),
+    }
+}
+
+/// The type named by `type`, which may list several (e.g. `["string", "null"]`).
+fn base_type(object: &Map<String, Json>, indent: &str) -> String {
+    let types: Vec<&str> = match object.get("type") {
+        Some(Json::String(single)) => vec![single.as_str()],
+        Some(Json::Array(many)) => many.iter().filter_map(Json::as_str).collect(),
+        _ => vec!["object"],
+    };
+    types
+        .into_iter()
+        .map(|kind| match kind {
+            "string" => "string".to_string(),
+            "integer" | "number" => "number".to_string(),
+            "boolean" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            "array" => array_type(object, indent),
+            "object" => object_type(object, indent),
+            _ => "unknown".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn array_type(object: &Map<String, Json>, indent: &str) -> String {
+    if let Some(items) = object.get("prefixItems").and_then(Json::as_array) {
+        let items: Vec<String> = items.iter().map(|item| ts_type(item, indent)).collect();
+        return format!("[{}]", items.join(", "));
+    }
+    match object.get("items") {
+        Some(items) => format!("{}[]", group(&ts_type(items, indent))),
+        None => "unknown[]".to_string(),
+    }
+}
+
+fn object_type(object: &Map<String, Json>, indent: &str) -> String {
+    if object.contains_key("properties") {
+        let body = object_body(object, indent);
+        return inline(&body).unwrap_or(body);
+    }
+    match object.get("additionalProperties") {
+        Some(values @ Json::Object(_)) => format!("Record<string, {}>", ts_type(values, indent)),
+        _ => "Record<string, unknown>".to_string(),
+    }
+}
+
+/// `{ ... }` with one line per property, indented below `indent`.
+fn object_body(object: &Map<String, Json>, indent: &str) -> String {
+    let empty = Map::new();
+    let properties = object.get("properties").and_then(Json::as_object).unwrap_or(&empty);
+    let required: Vec<&str> = object
+        .get("required")
+        .and_then(Json::as_array)
+        .map(|names| names.iter().filter_map(Json::as_str).collect())
+        .unwrap_or_default();
+    let inner = format!("{indent}  ");
+    let mut body = String::from("{\n");
+    for (name, property) in properties {
+        body.push_str(&doc_comment(property, &inner));
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let read_only = if property.get("readOnly") == Some(&Json::Bool(true)) {
+            "readonly "
+        } else {
+            ""
+        };
+        body.push_str(&format!(
+            "{inner}{read_only}{}{optional}:{};\n",
+            property_name(name),
+            spaced(&ts_type(property, &inner))
+        ));
+    }
+    body.push_str(indent);
+    body.push('}');
+    body
+}
+
+/// `variants` on one line, or one per line, each led by `|`, when long.
+fn union(variants: &[Json], indent: &str) -> String {
+    let line: Vec<String> = variants.iter().map(|variant| ts_type(variant, indent)).collect();
+    let line = line.join(" | ");
+    if line.len() <= 80 && !line.contains('\n') {
+        return line;
+    }
+    let inner = format!("{indent}  ");
+    variants.iter().map(|variant| format!("\n{inner}| {}", ts_type(variant, &inner))).collect()
+}
+
+/// `ty` after a `:` or `=`; unions split over lines start on the next line.
+fn spaced(ty: &str) -> String {
+    if ty.starts_with('\n') {
+        ty.to_string()
+    } else {
+        format!(" {ty}")
+    }
+}
+
+/// `body` on one line, when it is short and has no doc comments.
+fn inline(body: &str) -> Option<String> {
+    let fields: Vec<&str> = body.lines().map(str::trim).collect();
+    let fields = &fields[1..fields.len() - 1];
+    if fields.is_empty() || fields.iter().any(|field| field.starts_with("/*")) {
+        return None;
+    }
+    let line = format!("{{ {} }}", fields.join(" ").trim_end_matches(';'));
+    (line.len() <= 72).then_some(line)
+}
+
+fn doc_comment(schema: &Json, indent: &str) -> String {
+    let Some(description) = schema.get("description").and_then(Json::as_str) else {
+        return String::new();
+    };
+    let description = description.replace("*/", "*\\/");
+    let lines: Vec<&str> = description.lines().collect();
+    if let [line] = lines.as_slice() {
+        return format!("{indent}/** {line} */\n");
+    }
+    let mut out = format!("{indent}/**\n");
+    for line in lines {
+        out.push_str(&format!("{indent} * {line}\n").replace(" * \n", " *\n"));
+    }
+    out.push_str(&format!("{indent} */\n"));
+    out
+}
+
+/// Wraps a top-level union or intersection in parentheses before it is
+/// combined further.
+fn group(ty: &str) -> String {
+    let mut depth = 0i32;
+    let mut compound = false;
+    for c in ty.chars() {
+        match c {
+            '{' | '[' | '(' | '<' => depth += 1,
+            '}' | ']' | ')' | '>' => depth -= 1,
+            '|' | '&' if depth == 0 => compound = true,
+            _ => {}
+        }
+    }
+    if compound {
+        format!("({ty})")
+    } else {
+        ty.to_string()
+    }
+}
+
+/// A definition name as a TypeScript identifier.
+fn type_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_').collect()
+}
+
+fn property_name(name: &str) -> String {
+    let identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if identifier {
+        name.to_string()
+    } else {
+        Json::String(name.to_string()).to_string()
+    }
+}
@@ -1,3 +1,580 @@
+use core::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::ui::messages::Envelope;
+
+pub const JSON_CODEC: &str = "json";
+pub const MSGPACK_CODEC: &str = "msgpack";
+
 pub fn validate_protocol_version(_version: &str) -> bool {
     true
 }
+
+/// A single transport frame, mirroring WebSocket text and binary messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Debug)]
+pub enum CodecError {
+    Json(serde_json::Error),
+    Encode(rmp_serde::encode::Error),
+    Decode(rmp_serde::decode::Error),
+    UnexpectedFrame(&'static str),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Json(err) => write!(f, "json: {err}"),
+            CodecError::Encode(err) => write!(f, "msgpack encode: {err}"),
+            CodecError::Decode(err) => write!(f, "msgpack decode: {err}"),
+            CodecError::UnexpectedFrame(expected) => write!(f, "expected a {expected} frame"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Encodes and decodes protocol envelopes to and from transport frames.
+pub trait Codec {
+    fn name(&self) -> &'static str;
+    fn encode<M: Serialize>(&self, envelope: &Envelope<M>) -> Result<Frame, CodecError>;
+    fn decode<M: DeserializeOwned>(&self, frame: &Frame) -> Result<Envelope<M>, CodecError>;
+}
+
+/// Human-readable JSON carried in text frames.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn name(&self) -> &'static str {
+        JSON_CODEC
+    }
+
+    fn encode<M: Serialize>(&self, envelope: &Envelope<M>) -> Result<Frame, CodecError> {
+        serde_json::to_string(envelope).map(Frame::Text).map_err(CodecError::Json)
+    }
+
+    fn decode<M: DeserializeOwned>(&self, frame: &Frame) -> Result<Envelope<M>, CodecError> {
+        match frame {
+            Frame::Text(text) => serde_json::from_str(text).map_err(CodecError::Json),
+            Frame::Binary(bytes) => serde_json::from_slice(bytes).map_err(CodecError::Json),
+        }
+    }
+}
+
+/// MessagePack carried in binary frames.
+///
+/// Structs are written as maps so the envelope stays self-describing and
+/// decodes into the same types as JSON.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MessagePackCodec;
+
+impl Codec for MessagePackCodec {
+    fn name(&self) -> &'static str {
+        MSGPACK_CODEC
+    }
+
+    fn encode<M: Serialize>(&self, envelope: &Envelope<M>) -> Result<Frame, CodecError> {
+        rmp_serde::to_vec_named(envelope).map(Frame::Binary).map_err(CodecError::Encode)
+    }
+
+    fn decode<M: DeserializeOwned>(&self, frame: &Frame) -> Result<Envelope<M>, CodecError> {
+        match frame {
+            Frame::Binary(bytes) => rmp_serde::from_slice(bytes).map_err(CodecError::Decode),
+            Frame::Text(_) => Err(CodecError::UnexpectedFrame("binary")),
+        }
+    }
+}
+
+/// The codec chosen for a connection during the `Hello` handshake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProtocolCodec {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl ProtocolCodec {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            JSON_CODEC => Some(ProtocolCodec::Json),
+            MSGPACK_CODEC => Some(ProtocolCodec::MessagePack),
+            _ => None,
+        }
+    }
+
+    /// Picks the first codec offered by the client that the server supports,
+    /// falling back to JSON.
+    pub fn negotiate<S: AsRef<str>>(offered: &[S]) -> Self {
+        offered.iter().find_map(|name| Self::from_name(name.as_ref())).unwrap_or_default()
+    }
+
+    /// Decodes with whichever codec matches the frame type, so clients may
+    /// send either form regardless of the negotiated output codec.
+    pub fn decode_any<M: DeserializeOwned>(frame: &Frame) -> Result<Envelope<M>, CodecError> {
+        match frame {
+            Frame::Text(_) => JsonCodec.decode(frame),
+            Frame::Binary(_) => MessagePackCodec.decode(frame),
+        }
+    }
+}
+
+impl Codec for ProtocolCodec {
+    fn name(&self) -> &'static str {
+        match self {
+            ProtocolCodec::Json => JsonCodec.name(),
+            ProtocolCodec::MessagePack => MessagePackCodec.name(),
+        }
+    }
+
+    fn encode<M: Serialize>(&self, envelope: &Envelope<M>) -> Result<Frame, CodecError> {
+        match self {
+            ProtocolCodec::Json => JsonCodec.encode(envelope),
+            ProtocolCodec::MessagePack => MessagePackCodec.encode(envelope),
+        }
+    }
+
+    fn decode<M: DeserializeOwned>(&self, frame: &Frame) -> Result<Envelope<M>, CodecError> {
+        match self {
+            ProtocolCodec::Json => JsonCodec.decode(frame),
+            ProtocolCodec::MessagePack => MessagePackCodec.decode(frame),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::curves::{Curve, Gradient};
+    use crate::events::{Event, EventKind, EventTime};
+    use crate::ids::{DeclId, EnumId, EnumVariantId, NodeId, NodeTypeId, NodeUuid, ShortName};
+    use crate::meta::{NodeMeta, NodeMetaPatch, PresentationHint, SemanticsHint};
+    use crate::persistence::{NodeDataDto, NodeDataKind};
+    use crate::ui::dtos::{EnumDef, EnumVariantDef, NodeDto, NodeTypeDef, ParamDto};
+    use crate::ui::messages::*;
+    use crate::values::{
+        ChangePolicy, ColorRgba, FloatRange, IntRange, ParameterData, Quat, ReferenceValue,
+        SavePolicy, Timestamp, UpdatePolicy, Value, ValueConstraints, ValuePatch, Vec2, Vec3, Vec4,
+    };
+
+    const RED: ColorRgba = ColorRgba {
+        r: 1.0,
+        g: 0.0,
+        b: 0.0,
+        a: 1.0,
+    };
+
+    fn uuid(n: u128) -> NodeUuid {
+        NodeUuid(Uuid::from_u128(n))
+    }
+
+    /// One value of every kind.
+    fn values() -> Vec<Value> {
+        let values = vec![
+            Value::Bool(true),
+            Value::Int(-42),
+            Value::Float(0.25),
+            Value::String("hello".to_string()),
+            Value::Vec2(Vec2 {
+                x: 1.0,
+                y: 2.0,
+            }),
+            Value::Vec3(Vec3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+            }),
+            Value::Vec4(Vec4 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0,
+                w: 4.0,
+            }),
+            Value::Quat(Quat::IDENTITY),
+            Value::ColorRgba(RED),
+            Value::FloatRange(FloatRange {
+                min: -1.0,
+                max: 1.0,
+            }),
+            Value::IntRange(IntRange {
+                min: 1,
+                max: 10,
+            }),
+            Value::Bytes(vec![0xf0, 0x7e, 0xf7]),
+            Value::Timestamp(Timestamp {
+                unix_micros: 1_714_566_600_250_000,
+            }),
+            Value::Duration(Duration::from_millis(1500)),
+            Value::Curve(Curve::from_points(&[(0.0, 0.0), (0.5, 1.0), (1.0, 0.0)])),
+            Value::Gradient(Gradient::from_colors(&[(0.0, RED), (1.0, RED)])),
+            Value::Trigger,
+            Value::Enum {
+                enum_id: EnumId("Waveform".to_string()),
+                variant: EnumVariantId("Sine".to_string()),
+            },
+            Value::Reference(ReferenceValue {
+                uuid: uuid(7),
+                cached_id: Some(NodeId(3)),
+            }),
+            Value::List(vec![Value::Int(1), Value::String("two".to_string())]),
+            Value::FloatArray(vec![0.0, 0.5, 1.0]),
+            Value::IntArray(vec![1, 2, 3]),
+            Value::Map(BTreeMap::from([
+                ("a".to_string(), Value::Float(1.0)),
+                ("b".to_string(), Value::Bool(false)),
+            ])),
+        ];
+        let kinds: HashSet<&str> = values.iter().map(Value::kind_name).collect();
+        assert_eq!(kinds.len(), 23, "a value kind is missing from the samples");
+        values
+    }
+
+    fn scope() -> Scope {
+        Scope {
+            mode: ScopeMode::Subtree,
+            root_uuid: Some(uuid(1)),
+        }
+    }
+
+    fn client_messages() -> Vec<ClientMessage> {
+        let mut messages = vec![
+            ClientMessage::Hello(Hello {
+                protocol_version: "1".to_string(),
+                client_name: "ui".to_string(),
+                client_version: "0.1".to_string(),
+                root_scope: Some(scope()),
+                codecs: vec![MSGPACK_CODEC.to_string(), JSON_CODEC.to_string()],
+            }),
+            ClientMessage::GetSnapshot(GetSnapshot {
+                scope: scope(),
+                include_schema: true,
+            }),
+            ClientMessage::Subscribe(Subscribe {
+                scope: scope(),
+                from: EventTime {
+                    tick: 4,
+                    micro: 5,
+                    seq: 6,
+                },
+            }),
+            ClientMessage::BeginEdit(BeginEdit {
+                origin: EditOrigin::UI,
+                label: Some("drag".to_string()),
+            }),
+            ClientMessage::EndEdit(EndEdit {
+                edit_session_id: "s1".to_string(),
+            }),
+            ClientMessage::PatchParam(PatchParam {
+                edit_session_id: None,
+                param_node_id: NodeId(2),
+                patch: ValuePatch::SetKey {
+                    key: "gain".to_string(),
+                    value: Value::Float(0.5),
+                },
+                propagation: Propagation::EndOfTick,
+            }),
+            ClientMessage::PatchMeta(PatchMeta {
+                edit_session_id: None,
+                node_id: NodeId(2),
+                patch: NodeMetaPatch {
+                    label: Some("Gain".to_string()),
+                    description: Some(None),
+                    expression: Some(Some("time * 2".to_string())),
+                    ..NodeMetaPatch::default()
+                },
+                propagation: Propagation::Immediate,
+            }),
+            ClientMessage::CreateNode(CreateNode {
+                edit_session_id: None,
+                parent_id: NodeId(1),
+                node_type: NodeTypeId("Lfo".to_string()),
+                label: Some("lfo".to_string()),
+                propagation: Propagation::NextTick,
+            }),
+            ClientMessage::MoveNode(MoveNode {
+                edit_session_id: Some("s1".to_string()),
+                node_id: NodeId(2),
+                new_parent_id: NodeId(1),
+                new_index: 0,
+                propagation: Propagation::Immediate,
+            }),
+            ClientMessage::DeleteNode(DeleteNode {
+                edit_session_id: None,
+                node_id: NodeId(2),
+                propagation: Propagation::Immediate,
+            }),
+        ];
+        messages.extend(values().into_iter().map(|value| {
+            ClientMessage::SetParam(SetParam {
+                edit_session_id: None,
+                param_node_id: NodeId(2),
+                value,
+                unit: Some("ms".to_string()),
+                propagation: Propagation::Immediate,
+            })
+        }));
+        // Exhaustive, so a new message type cannot go without a sample.
+        let variant = |message: &ClientMessage| match message {
+            ClientMessage::Hello(_) => 0,
+            ClientMessage::GetSnapshot(_) => 1,
+            ClientMessage::Subscribe(_) => 2,
+            ClientMessage::BeginEdit(_) => 3,
+            ClientMessage::EndEdit(_) => 4,
+            ClientMessage::SetParam(_) => 5,
+            ClientMessage::PatchParam(_) => 6,
+            ClientMessage::PatchMeta(_) => 7,
+            ClientMessage::CreateNode(_) => 8,
+            ClientMessage::MoveNode(_) => 9,
+            ClientMessage::DeleteNode(_) => 10,
+        };
+        assert_eq!(messages.iter().map(variant).collect::<HashSet<_>>().len(), 11);
+        messages
+    }
+
+    fn snapshot() -> Snapshot {
+        let parameter = ParameterData {
+            value: Value::Float(0.5),
+            default: Some(Value::Float(0.0)),
+            read_only: false,
+            update: UpdatePolicy::Immediate,
+            save: SavePolicy::Delta,
+            change: ChangePolicy::ValueChange,
+            constraints: ValueConstraints::Float {
+                min: Some(0.0),
+                max: Some(1.0),
+                clamp: true,
+                step: None,
+            },
+        };
+        Snapshot {
+            as_of: EventTime {
+                tick: 1,
+                micro: 0,
+                seq: 2,
+            },
+            nodes: vec![NodeDto {
+                node_id: NodeId(2),
+                uuid: uuid(2),
+                node_type: NodeTypeId("Parameter".to_string()),
+                decl_id: Some(DeclId("gain".to_string())),
+                meta: NodeMeta {
+                    uuid: uuid(2),
+                    decl_id: DeclId("gain".to_string()),
+                    short_name: ShortName("gain".to_string()),
+                    enabled: true,
+                    label: "Gain".to_string(),
+                    description: None,
+                    tags: vec!["audio".to_string()],
+                    semantics: SemanticsHint::default(),
+                    presentation: PresentationHint::default(),
+                    expression: Some("time".to_string()),
+                    expression_error: None,
+                },
+                data: NodeDataDto {
+                    kind: NodeDataKind::Parameter,
+                    container: None,
+                    parameter: Some(parameter.clone()),
+                },
+                children: Vec::new(),
+            }],
+            params: vec![ParamDto {
+                param_node_id: NodeId(2),
+                value: parameter.value,
+                read_only: parameter.read_only,
+                update_policy: parameter.update,
+                change_policy: parameter.change,
+                constraints: parameter.constraints,
+                presentation: PresentationHint {
+                    widget: Some("slider".to_string()),
+                },
+                semantics: SemanticsHint {
+                    intent: None,
+                    unit: Some("dB".to_string()),
+                },
+            }],
+            enums: vec![EnumDef {
+                enum_id: EnumId("Waveform".to_string()),
+                variants: vec![EnumVariantDef {
+                    variant_id: EnumVariantId("Sine".to_string()),
+                    label: "Sine".to_string(),
+                }],
+            }],
+            node_types: vec![NodeTypeDef {
+                node_type: NodeTypeId("Lfo".to_string()),
+                label: "LFO".to_string(),
+                palette_allowed_children: Vec::new(),
+            }],
+        }
+    }
+
+    fn server_messages() -> Vec<ServerMessage> {
+        let time = |seq| EventTime {
+            tick: 9,
+            micro: 0,
+            seq,
+        };
+        let events = vec![
+            Event {
+                time: time(0),
+                kind: EventKind::ParamPatched {
+                    param: NodeId(2),
+                    patch: ValuePatch::Remove {
+                        index: 1,
+                    },
+                },
+            },
+            Event {
+                time: time(1),
+                kind: EventKind::MetaChanged {
+                    node: NodeId(2),
+                    patch: NodeMetaPatch {
+                        enabled: Some(false),
+                        expression_error: Some(None),
+                        ..NodeMetaPatch::default()
+                    },
+                },
+            },
+            Event {
+                time: time(2),
+                kind: EventKind::ChildMoved {
+                    child: NodeId(2),
+                    old_parent: NodeId(1),
+                    new_parent: NodeId(3),
+                },
+            },
+        ];
+        let mut messages = vec![
+            ServerMessage::HelloAck(HelloAck {
+                protocol_version: "1".to_string(),
+                server_name: "golden".to_string(),
+                server_version: "0.1.0".to_string(),
+                features: vec!["snapshot".to_string()],
+                codec: MSGPACK_CODEC.to_string(),
+            }),
+            ServerMessage::Snapshot(snapshot()),
+            ServerMessage::EventBatch(EventBatch {
+                events,
+            }),
+            ServerMessage::BeginEditAck(BeginEditAck {
+                edit_session_id: "s1".to_string(),
+            }),
+            ServerMessage::Ack(Ack {
+                ok: false,
+                error: Some(ErrorInfo {
+                    code: "not_found".to_string(),
+                    message: "no such node".to_string(),
+                }),
+                warnings: vec![ErrorInfo {
+                    code: "lossy".to_string(),
+                    message: "rounded".to_string(),
+                }],
+            }),
+        ];
+        messages.extend(values().into_iter().enumerate().map(|(seq, value)| {
+            ServerMessage::EventBatch(EventBatch {
+                events: vec![Event {
+                    time: time(seq as u32),
+                    kind: EventKind::ParamChanged {
+                        param: NodeId(2),
+                        value,
+                    },
+                }],
+            })
+        }));
+        let variant = |message: &ServerMessage| match message {
+            ServerMessage::HelloAck(_) => 0,
+            ServerMessage::Snapshot(_) => 1,
+            ServerMessage::EventBatch(_) => 2,
+            ServerMessage::BeginEditAck(_) => 3,
+            ServerMessage::Ack(_) => 4,
+        };
+        assert_eq!(messages.iter().map(variant).collect::<HashSet<_>>().len(), 5);
+        messages
+    }
+
+    fn round_trip<C: Codec, M>(codec: &C, messages: Vec<M>)
+    where
+        M: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+    {
+        for (index, message) in messages.into_iter().enumerate() {
+            let envelope = Envelope {
+                req_id: Some(index.to_string()),
+                message,
+            };
+            let frame = codec.encode(&envelope).unwrap();
+            assert_eq!(codec.decode::<M>(&frame).unwrap(), envelope, "{}", codec.name());
+            assert_eq!(ProtocolCodec::decode_any::<M>(&frame).unwrap(), envelope);
+        }
+    }
+
+    #[test]
+    fn json_round_trips_every_message() {
+        round_trip(&JsonCodec, client_messages());
+        round_trip(&JsonCodec, server_messages());
+    }
+
+    #[test]
+    fn msgpack_round_trips_every_message() {
+        round_trip(&MessagePackCodec, client_messages());
+        round_trip(&MessagePackCodec, server_messages());
+    }
+
+    #[test]
+    fn codecs_use_their_own_frame_type() {
+        let envelope = Envelope {
+            req_id: None,
+            message: ServerMessage::BeginEditAck(BeginEditAck {
+                edit_session_id: "s1".to_string(),
+            }),
+        };
+        assert!(matches!(JsonCodec.encode(&envelope).unwrap(), Frame::Text(_)));
+        let binary = MessagePackCodec.encode(&envelope).unwrap();
+        assert!(matches!(binary, Frame::Binary(_)));
+        assert!(matches!(
+            MessagePackCodec.decode::<ServerMessage>(&Frame::Text("{}".to_string())),
+            Err(CodecError::UnexpectedFrame("binary"))
+        ));
+        assert!(matches!(JsonCodec.decode::<ServerMessage>(&binary), Err(CodecError::Json(_))));
+    }
+
+    #[test]
+    fn negotiate_picks_the_first_supported_codec() {
+        assert_eq!(ProtocolCodec::negotiate(&["msgpack", "json"]), ProtocolCodec::MessagePack);
+        assert_eq!(ProtocolCodec::negotiate(&["cbor", "json", "msgpack"]), ProtocolCodec::Json);
+        assert_eq!(ProtocolCodec::negotiate(&["cbor", "bson"]), ProtocolCodec::Json);
+        assert_eq!(ProtocolCodec::negotiate::<&str>(&[]), ProtocolCodec::Json);
+        assert_eq!(ProtocolCodec::MessagePack.name(), MSGPACK_CODEC);
+        assert_eq!(ProtocolCodec::from_name("MSGPACK"), None);
+    }
+
+    #[test]
+    fn decode_any_dispatches_on_frame_type() {
+        let envelope = Envelope {
+            req_id: Some("1".to_string()),
+            message: client_messages().remove(0),
+        };
+        let text = ProtocolCodec::Json.encode(&envelope).unwrap();
+        let binary = ProtocolCodec::MessagePack.encode(&envelope).unwrap();
+        assert_eq!(ProtocolCodec::decode_any::<ClientMessage>(&text).unwrap(), envelope);
+        assert_eq!(ProtocolCodec::decode_any::<ClientMessage>(&binary).unwrap(), envelope);
+        assert!(matches!(
+            ProtocolCodec::decode_any::<ClientMessage>(&Frame::Binary(b"{}".to_vec())),
+            Err(CodecError::Decode(_))
+        ));
+        assert!(matches!(
+            ProtocolCodec::decode_any::<ClientMessage>(&Frame::Text("[".to_string())),
+            Err(CodecError::Json(_))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::ids::{DeclId, EnumId, EnumVariantId, NodeId, NodeTypeId, NodeUuid};
@@ -5,7 +6,7 @@ use crate::meta::{NodeMeta, PresentationHint, SemanticsHint};
 use crate::persistence::NodeDataDto;
 use crate::values::{ChangePolicy, UpdatePolicy, Value, ValueConstraints};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NodeDto {
     pub node_id: NodeId,
     pub uuid: NodeUuid,
@@ -17,7 +18,7 @@ pub struct NodeDto {
     pub children: Vec<NodeId>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ParamDto {
     pub param_node_id: NodeId,
     pub value: Value,
@@ -29,19 +30,19 @@ pub struct ParamDto {
     pub semantics: SemanticsHint,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EnumVariantDef {
     pub variant_id: EnumVariantId,
     pub label: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EnumDef {
     pub enum_id: EnumId,
     pub variants: Vec<EnumVariantDef>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NodeTypeDef {
     pub node_type: NodeTypeId,
     pub label: String,
@@ -1,12 +1,13 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::events::{Event, EventTime};
 use crate::ids::{NodeId, NodeTypeId, NodeUuid};
 use crate::meta::NodeMetaPatch;
 use crate::ui::dtos::{EnumDef, NodeDto, NodeTypeDef, ParamDto};
-use crate::values::Value;
+use crate::values::{Value, ValuePatch};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MessageEnvelope<T> {
     pub msg: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -14,43 +15,53 @@ pub struct MessageEnvelope<T> {
     pub payload: T,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum ScopeMode {
     Root,
     Subtree,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Scope {
     pub mode: ScopeMode,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_uuid: Option<NodeUuid>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Hello {
     pub protocol_version: String,
     pub client_name: String,
     pub client_version: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_scope: Option<Scope>,
+    /// Codec names the client can decode, most preferred first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codecs: Vec<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct HelloAck {
     pub protocol_version: String,
     pub server_name: String,
     pub server_version: String,
     pub features: Vec<String>,
+    /// Codec used for every server frame after this acknowledgement.
+    #[serde(default = "default_codec")]
+    pub codec: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+fn default_codec() -> String {
+    "json".to_string()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct GetSnapshot {
     pub scope: Scope,
     pub include_schema: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Snapshot {
     pub as_of: EventTime,
     pub nodes: Vec<NodeDto>,
@@ -59,18 +70,18 @@ pub struct Snapshot {
     pub node_types: Vec<NodeTypeDef>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Subscribe {
     pub scope: Scope,
     pub from: EventTime,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EventBatch {
     pub events: Vec<Event>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum EditOrigin {
     UI,
     Network,
@@ -78,38 +89,51 @@ pub enum EditOrigin {
     Internal,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum Propagation {
     Immediate,
     EndOfTick,
     NextTick,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BeginEdit {
     pub origin: EditOrigin,
     pub label: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BeginEditAck {
     pub edit_session_id: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct EndEdit {
     pub edit_session_id: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct SetParam {
     pub edit_session_id: Option<String>,
     pub param_node_id: NodeId,
     pub value: Value,
+    /// Unit `value` is written in, converted to the parameter's own unit
+    /// before the edit is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub propagation: Propagation,
+}
+
+/// Changes one element of a collection parameter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PatchParam {
+    pub edit_session_id: Option<String>,
+    pub param_node_id: NodeId,
+    pub patch: ValuePatch,
     pub propagation: Propagation,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct PatchMeta {
     pub edit_session_id: Option<String>,
     pub node_id: NodeId,
@@ -117,7 +141,7 @@ pub struct PatchMeta {
     pub propagation: Propagation,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct CreateNode {
     pub edit_session_id: Option<String>,
     pub parent_id: NodeId,
@@ -126,7 +150,7 @@ pub struct CreateNode {
     pub propagation: Propagation,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MoveNode {
     pub edit_session_id: Option<String>,
     pub node_id: NodeId,
@@ -135,22 +159,63 @@ pub struct MoveNode {
     pub propagation: Propagation,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct DeleteNode {
     pub edit_session_id: Option<String>,
     pub node_id: NodeId,
     pub propagation: Propagation,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Ack {
     pub ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ErrorInfo>,
+    /// Accepted with caveats, e.g. a value converted with loss of precision.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ErrorInfo>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ErrorInfo {
     pub code: String,
     pub message: String,
 }
+
+/// Messages a client may send, keyed by the envelope `msg` field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "msg", content = "payload")]
+pub enum ClientMessage {
+    Hello(Hello),
+    GetSnapshot(GetSnapshot),
+    Subscribe(Subscribe),
+    BeginEdit(BeginEdit),
+    EndEdit(EndEdit),
+    SetParam(SetParam),
+    PatchParam(PatchParam),
+    PatchMeta(PatchMeta),
+    CreateNode(CreateNode),
+    MoveNode(MoveNode),
+    DeleteNode(DeleteNode),
+}
+
+/// Messages the server sends, keyed by the envelope `msg` field.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "msg", content = "payload")]
+pub enum ServerMessage {
+    HelloAck(HelloAck),
+    Snapshot(Snapshot),
+    EventBatch(EventBatch),
+    BeginEditAck(BeginEditAck),
+    Ack(Ack),
+}
+
+/// Wire form of a message: the tagged message plus the optional request id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename = "{M}Envelope")]
+pub struct Envelope<M> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub req_id: Option<String>,
+    #[serde(flatten)]
+    pub message: M,
+}
@@ -0,0 +1,277 @@
+use core::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::values::{FloatRange, IntRange, Value, Vec2, Vec3};
+
+/// What a unit measures. Values convert between units of the same dimension
+/// only.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum Dimension {
+    Time,
+    Frequency,
+    Angle,
+    /// Dimensionless amounts: plain factors, percentages and decibels.
+    /// Decibels are logarithmic, so they convert through [`Scale::Decibel`]
+    /// rather than a factor.
+    Ratio,
+}
+
+impl Dimension {
+    /// The unit every other unit of the dimension is converted through.
+    pub fn base_unit(self) -> &'static Unit {
+        UNITS
+            .iter()
+            .find(|unit| unit.dimension == self && unit.scale == Scale::Linear(1.0))
+            .expect("every dimension has a base unit")
+    }
+}
+
+/// How a unit relates to the base unit of its dimension.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scale {
+    /// One of this unit is this many base units.
+    Linear(f64),
+    /// Decibels of an amplitude ratio: `base = 10^(dB / 20)`. A ratio of
+    /// zero or below is `-inf` dB.
+    Decibel,
+}
+
+/// A known unit, as written in `SemanticsHint::unit`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,
+    /// Other spellings [`unit`] accepts.
+    pub aliases: &'static [&'static str],
+    pub dimension: Dimension,
+    pub scale: Scale,
+}
+
+impl Unit {
+    /// `value` in this unit, expressed in the dimension's base unit.
+    pub fn to_base(&self, value: f64) -> f64 {
+        match self.scale {
+            Scale::Linear(factor) => value * factor,
+            Scale::Decibel => 10f64.powf(value / 20.0),
+        }
+    }
+
+    /// `value` in the dimension's base unit, expressed in this unit.
+    pub fn from_base(&self, value: f64) -> f64 {
+        match self.scale {
+            Scale::Linear(factor) => value / factor,
+            Scale::Decibel => 20.0 * value.max(0.0).log10(),
+        }
+    }
+
+    fn matches(&self, symbol: &str) -> bool {
+        self.symbol == symbol || self.aliases.contains(&symbol)
+    }
+}
+
+const fn linear(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        aliases,
+        dimension,
+        scale: Scale::Linear(factor),
+    }
+}
+
+/// Every unit the engine knows. `#[param]` and `params!` reject any other
+/// unit string at compile time; one set at runtime, e.g. by a meta edit, is
+/// kept as a display label but cannot be converted.
+pub static UNITS: &[Unit] = &[
+    linear("s", &["sec", "seconds"], Dimension::Time, 1.0),
+    linear("ms", &["milliseconds"], Dimension::Time, 1e-3),
+    linear("us", &["µs", "microseconds"], Dimension::Time, 1e-6),
+    linear("min", &["minutes"], Dimension::Time, 60.0),
+    linear("Hz", &["hz"], Dimension::Frequency, 1.0),
+    linear("kHz", &["khz"], Dimension::Frequency, 1e3),
+    linear("BPM", &["bpm"], Dimension::Frequency, 1.0 / 60.0),
+    linear("rad", &["radians"], Dimension::Angle, 1.0),
+    linear("deg", &["°", "degrees"], Dimension::Angle, std::f64::consts::PI / 180.0),
+    linear("turn", &["turns"], Dimension::Angle, std::f64::consts::TAU),
+    linear("x", &["ratio"], Dimension::Ratio, 1.0),
+    linear("%", &["percent"], Dimension::Ratio, 0.01),
+    Unit {
+        symbol: "dB",
+        aliases: &["db"],
+        dimension: Dimension::Ratio,
+        scale: Scale::Decibel,
+    },
+];
+
+/// The unit written as `symbol` or one of its aliases.
+pub fn unit(symbol: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.matches(symbol))
+}
+
+/// The units of `dimension`, base unit first.
+pub fn units_of(dimension: Dimension) -> impl Iterator<Item = &'static Unit> {
+    UNITS.iter().filter(move |unit| unit.dimension == dimension)
+}
+
+/// Why a value could not be converted between two units.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnitError {
+    Unknown(String),
+    Incompatible {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// The value kind has no numeric components to convert.
+    NotNumeric(&'static str),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::Unknown(symbol) => write!(f, "unknown unit '{symbol}'"),
+            UnitError::Incompatible {
+                from,
+                to,
+            } => write!(f, "cannot convert {from} to {to}"),
+            UnitError::NotNumeric(kind) => write!(f, "{kind} values have no unit"),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+/// Converts `value` from the unit `from` to the unit `to`.
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, UnitError> {
+    let (from, to) = units_for(from, to)?;
+    Ok(to.from_base(from.to_base(value)))
+}
+
+/// Converts every numeric component of `value` from `from` to `to`. Int
+/// kinds are rounded to the nearest integer; other kinds are rejected.
+pub fn convert_value(value: &Value, from: &str, to: &str) -> Result<Value, UnitError> {
+    let (from, to) = units_for(from, to)?;
+    let f = |v: f64| to.from_base(from.to_base(v));
+    let i = |v: i64| f(v as f64).round() as i64;
+    Ok(match value {
+        Value::Float(v) => Value::Float(f(*v)),
+        Value::Int(v) => Value::Int(i(*v)),
+        Value::FloatArray(items) => Value::FloatArray(items.iter().map(|v| f(*v)).collect()),
+        Value::IntArray(items) => Value::IntArray(items.iter().map(|v| i(*v)).collect()),
+        Value::FloatRange(r) => Value::FloatRange(FloatRange {
+            min: f(r.min),
+            max: f(r.max),
+        }),
+        Value::IntRange(r) => Value::IntRange(IntRange {
+            min: i(r.min),
+            max: i(r.max),
+        }),
+        Value::Vec2(v) => Value::Vec2(Vec2 {
+            x: f(v.x),
+            y: f(v.y),
+        }),
+        Value::Vec3(v) => Value::Vec3(Vec3 {
+            x: f(v.x),
+            y: f(v.y),
+            z: f(v.z),
+        }),
+        other => return Err(UnitError::NotNumeric(other.kind_name())),
+    })
+}
+
+fn units_for(from: &str, to: &str) -> Result<(&'static Unit, &'static Unit), UnitError> {
+    let from = unit(from).ok_or_else(|| UnitError::Unknown(from.to_string()))?;
+    let to = unit(to).ok_or_else(|| UnitError::Unknown(to.to_string()))?;
+    if from.dimension != to.dimension {
+        return Err(UnitError::Incompatible {
+            from: from.symbol,
+            to: to.symbol,
+        });
+    }
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn converts_time_units() {
+        assert_near(convert(250.0, "ms", "s").unwrap(), 0.25);
+        assert_near(convert(1.5, "s", "ms").unwrap(), 1500.0);
+        assert_near(convert(2.0, "min", "seconds").unwrap(), 120.0);
+        assert_near(convert(1.0, "ms", "µs").unwrap(), 1000.0);
+    }
+
+    #[test]
+    fn converts_frequency_units() {
+        assert_near(convert(120.0, "BPM", "Hz").unwrap(), 2.0);
+        assert_near(convert(1.5, "kHz", "hz").unwrap(), 1500.0);
+        assert_near(convert(4.0, "Hz", "bpm").unwrap(), 240.0);
+    }
+
+    #[test]
+    fn converts_angle_units() {
+        assert_near(convert(180.0, "deg", "rad").unwrap(), std::f64::consts::PI);
+        assert_near(convert(std::f64::consts::FRAC_PI_2, "radians", "°").unwrap(), 90.0);
+        assert_near(convert(0.25, "turn", "deg").unwrap(), 90.0);
+    }
+
+    #[test]
+    fn decibels_are_amplitude_ratios() {
+        assert_near(convert(0.0, "dB", "x").unwrap(), 1.0);
+        assert_near(convert(20.0, "dB", "x").unwrap(), 10.0);
+        assert_near(convert(-20.0, "dB", "%").unwrap(), 10.0);
+        assert_near(convert(0.5, "x", "dB").unwrap(), -6.020599913279624);
+        assert_near(convert(-6.0, "db", "dB").unwrap(), -6.0);
+        assert_eq!(convert(0.0, "x", "dB").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(convert(-1.0, "x", "dB").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(Dimension::Ratio.base_unit().symbol, "x");
+    }
+
+    #[test]
+    fn rejects_unknown_units_and_other_dimensions() {
+        assert_eq!(convert(1.0, "msec", "s"), Err(UnitError::Unknown("msec".to_string())));
+        assert_eq!(
+            convert(1.0, "s", "Hz"),
+            Err(UnitError::Incompatible {
+                from: "s",
+                to: "Hz",
+            })
+        );
+        assert_eq!(convert(1.0, "dB", "ms").unwrap_err().to_string(), "cannot convert dB to ms");
+    }
+
+    #[test]
+    fn converts_every_numeric_component() {
+        assert_eq!(convert_value(&Value::Int(1499), "ms", "s"), Ok(Value::Int(1)));
+        assert_eq!(
+            convert_value(
+                &Value::FloatRange(FloatRange {
+                    min: 0.5,
+                    max: 2.0,
+                }),
+                "s",
+                "ms"
+            ),
+            Ok(Value::FloatRange(FloatRange {
+                min: 500.0,
+                max: 2000.0,
+            }))
+        );
+        assert_eq!(
+            convert_value(&Value::Bool(true), "s", "ms"),
+            Err(UnitError::NotNumeric(Value::Bool(true).kind_name()))
+        );
+        let units: Vec<_> = units_of(Dimension::Time).map(|unit| unit.symbol).collect();
+        assert_eq!(units, ["s", "ms", "us", "min"]);
+    }
+}
@@ -1,23 +1,121 @@
 use core::fmt;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::curves::{Curve, Gradient};
 use crate::ids::{EnumId, EnumVariantId, NodeId, NodeUuid};
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Vec2 {
     pub x: f64,
     pub y: f64,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,
     pub z: f64,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Vec4 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+/// Rotation quaternion; `w` is the scalar part.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Quat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Quat {
+    pub const IDENTITY: Quat = Quat {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+        w: 1.0,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FloatRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct IntRange {
+    pub min: i64,
+    pub max: i64,
+}
+
+/// Wall-clock time as microseconds since the Unix epoch (UTC).
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema,
+)]
+pub struct Timestamp {
+    pub unix_micros: i64,
+}
+
+impl Timestamp {
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        let unix_micros = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => after.as_micros() as i64,
+            Err(before) => -(before.duration().as_micros() as i64),
+        };
+        Self {
+            unix_micros,
+        }
+    }
+}
+
+/// RFC 3339 in UTC, e.g. `2024-05-01T12:30:00.250000Z`.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.unix_micros.div_euclid(1_000_000);
+        let micros = self.unix_micros.rem_euclid(1_000_000);
+        let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+        // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 {
+            mp + 3
+        } else {
+            mp - 9
+        };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{micros:06}Z",
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ColorRgba {
     pub r: f64,
     pub g: f64,
@@ -25,17 +123,17 @@ pub struct ColorRgba {
     pub a: f64,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ReferenceValue {
     pub uuid: NodeUuid,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cached_id: Option<NodeId>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Trigger;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum Value {
     Bool(bool),
     Int(i64),
@@ -43,13 +141,304 @@ pub enum Value {
     String(String),
     Vec2(Vec2),
     Vec3(Vec3),
+    Vec4(Vec4),
+    Quat(Quat),
     ColorRgba(ColorRgba),
+    FloatRange(FloatRange),
+    IntRange(IntRange),
+    /// Binary blob, e.g. a MIDI sysex message.
+    Bytes(Vec<u8>),
+    Timestamp(Timestamp),
+    Duration(Duration),
+    /// Keyframed transfer function, sampled with [`Curve::sample`].
+    Curve(Curve),
+    Gradient(Gradient),
     Trigger,
     Enum {
         enum_id: EnumId,
         variant: EnumVariantId,
     },
     Reference(ReferenceValue),
+    /// Ordered values, usually of one kind (e.g. a group of references).
+    List(Vec<Value>),
+    /// Dense floats, e.g. a DMX universe or a spectrum.
+    FloatArray(Vec<f64>),
+    IntArray(Vec<i64>),
+    /// Small string-keyed map, kept sorted by key.
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Name of the variant, as used in error messages.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "Bool",
+            Value::Int(_) => "Int",
+            Value::Float(_) => "Float",
+            Value::String(_) => "String",
+            Value::Vec2(_) => "Vec2",
+            Value::Vec3(_) => "Vec3",
+            Value::Vec4(_) => "Vec4",
+            Value::Quat(_) => "Quat",
+            Value::ColorRgba(_) => "ColorRgba",
+            Value::FloatRange(_) => "FloatRange",
+            Value::IntRange(_) => "IntRange",
+            Value::Bytes(_) => "Bytes",
+            Value::Timestamp(_) => "Timestamp",
+            Value::Duration(_) => "Duration",
+            Value::Curve(_) => "Curve",
+            Value::Gradient(_) => "Gradient",
+            Value::Trigger => "Trigger",
+            Value::Enum {
+                ..
+            } => "Enum",
+            Value::Reference(_) => "Reference",
+            Value::List(_) => "List",
+            Value::FloatArray(_) => "FloatArray",
+            Value::IntArray(_) => "IntArray",
+            Value::Map(_) => "Map",
+        }
+    }
+
+    /// Number of elements of a collection value.
+    pub fn collection_len(&self) -> Option<usize> {
+        match self {
+            Value::List(items) => Some(items.len()),
+            Value::FloatArray(items) => Some(items.len()),
+            Value::IntArray(items) => Some(items.len()),
+            Value::Bytes(bytes) => Some(bytes.len()),
+            Value::Map(entries) => Some(entries.len()),
+            _ => None,
+        }
+    }
+
+    /// True when both values have the same variant (and enum type).
+    pub fn same_kind(&self, other: &Value) -> bool {
+        match (self, other) {
+            (
+                Value::Enum {
+                    enum_id: a,
+                    ..
+                },
+                Value::Enum {
+                    enum_id: b,
+                    ..
+                },
+            ) => a == b,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+/// A value converted by [`Value::coerce_to`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coerced {
+    pub value: Value,
+    /// The conversion lost information, e.g. a fraction was rounded away.
+    pub lossy: bool,
+}
+
+impl Value {
+    /// Converts this value to the kind of `target`, whose own value only
+    /// matters for its enum id. Values of the same kind are returned as is.
+    ///
+    /// | From | To | Lossy when |
+    /// |---|---|---|
+    /// | Int | Float | beyond ±2^53 |
+    /// | Float | Int | rounded or saturated; NaN and infinities fail |
+    /// | Bool | Int, Float | never (`false` is 0, `true` is 1) |
+    /// | Int, Float | Bool | not 0 or 1 (non-zero is `true`) |
+    /// | String | Bool, Int, Float | as above once parsed as `true`, `false` or a number; other strings fail |
+    /// | Bool, Int, Float | String | never |
+    /// | Enum | String | never (the variant id) |
+    /// | String | Enum | never (read as a variant id of the target's enum) |
+    /// | Vec2, Vec3 | a wider vector | never (new components are 0) |
+    /// | Vec3, Vec4 | a narrower vector | a dropped component is not 0 |
+    /// | Vec3 | ColorRgba | never (alpha is 1) |
+    /// | Vec4 | ColorRgba, and back | never |
+    /// | ColorRgba | Vec3 | alpha is not 1 |
+    /// | IntArray, IntRange | FloatArray, FloatRange | as Int to Float, per element |
+    /// | FloatArray, FloatRange | IntArray, IntRange | as Float to Int, per element |
+    ///
+    /// Any other pair returns `None`.
+    pub fn coerce_to(&self, target: &Value) -> Option<Coerced> {
+        if self.same_kind(target) {
+            return Some(Coerced {
+                value: self.clone(),
+                lossy: false,
+            });
+        }
+        let (value, lossy) = match (self, target) {
+            (Value::Int(v), Value::Float(_)) => {
+                let (v, lossy) = int_to_float(*v);
+                (Value::Float(v), lossy)
+            }
+            (Value::Float(v), Value::Int(_)) => {
+                let (v, lossy) = float_to_int(*v)?;
+                (Value::Int(v), lossy)
+            }
+            (Value::Bool(v), Value::Int(_)) => (Value::Int(i64::from(*v)), false),
+            (Value::Bool(v), Value::Float(_)) => (Value::Float(f64::from(u8::from(*v))), false),
+            (Value::Int(v), Value::Bool(_)) => (Value::Bool(*v != 0), *v != 0 && *v != 1),
+            (Value::Float(v), Value::Bool(_)) => (Value::Bool(*v != 0.0), *v != 0.0 && *v != 1.0),
+            (Value::String(text), Value::Bool(_) | Value::Int(_) | Value::Float(_)) => {
+                return parse_scalar(text.trim())?.coerce_to(target);
+            }
+            (Value::Bool(_) | Value::Int(_) | Value::Float(_), Value::String(_)) => {
+                (Value::String(self.to_string()), false)
+            }
+            (
+                Value::Enum {
+                    variant,
+                    ..
+                },
+                Value::String(_),
+            ) => (Value::String(variant.0.clone()), false),
+            (
+                Value::String(text),
+                Value::Enum {
+                    enum_id,
+                    ..
+                },
+            ) => (
+                Value::Enum {
+                    enum_id: enum_id.clone(),
+                    variant: EnumVariantId(text.clone()),
+                },
+                false,
+            ),
+            (Value::Vec3(v), Value::ColorRgba(_)) => (
+                Value::ColorRgba(ColorRgba {
+                    r: v.x,
+                    g: v.y,
+                    b: v.z,
+                    a: 1.0,
+                }),
+                false,
+            ),
+            (Value::Vec4(v), Value::ColorRgba(_)) => (
+                Value::ColorRgba(ColorRgba {
+                    r: v.x,
+                    g: v.y,
+                    b: v.z,
+                    a: v.w,
+                }),
+                false,
+            ),
+            (Value::ColorRgba(c), Value::Vec3(_)) => (
+                Value::Vec3(Vec3 {
+                    x: c.r,
+                    y: c.g,
+                    z: c.b,
+                }),
+                c.a != 1.0,
+            ),
+            (Value::ColorRgba(c), Value::Vec4(_)) => (
+                Value::Vec4(Vec4 {
+                    x: c.r,
+                    y: c.g,
+                    z: c.b,
+                    w: c.a,
+                }),
+                false,
+            ),
+            (Value::Vec2(_) | Value::Vec3(_) | Value::Vec4(_), _) => {
+                let (components, _) = vector_components(self)?;
+                let (_, len) = vector_components(target)?;
+                let lossy = components[len..].iter().any(|c| *c != 0.0);
+                (vector_value(components, len), lossy)
+            }
+            (Value::IntArray(items), Value::FloatArray(_)) => {
+                let items: Vec<_> = items.iter().map(|v| int_to_float(*v)).collect();
+                let lossy = items.iter().any(|(_, lossy)| *lossy);
+                (Value::FloatArray(items.into_iter().map(|(v, _)| v).collect()), lossy)
+            }
+            (Value::FloatArray(items), Value::IntArray(_)) => {
+                let items: Vec<_> =
+                    items.iter().map(|v| float_to_int(*v)).collect::<Option<_>>()?;
+                let lossy = items.iter().any(|(_, lossy)| *lossy);
+                (Value::IntArray(items.into_iter().map(|(v, _)| v).collect()), lossy)
+            }
+            (Value::IntRange(r), Value::FloatRange(_)) => {
+                let ((min, min_lossy), (max, max_lossy)) =
+                    (int_to_float(r.min), int_to_float(r.max));
+                (
+                    Value::FloatRange(FloatRange {
+                        min,
+                        max,
+                    }),
+                    min_lossy || max_lossy,
+                )
+            }
+            (Value::FloatRange(r), Value::IntRange(_)) => {
+                let ((min, min_lossy), (max, max_lossy)) =
+                    (float_to_int(r.min)?, float_to_int(r.max)?);
+                (
+                    Value::IntRange(IntRange {
+                        min,
+                        max,
+                    }),
+                    min_lossy || max_lossy,
+                )
+            }
+            _ => return None,
+        };
+        Some(Coerced {
+            value,
+            lossy,
+        })
+    }
+}
+
+fn int_to_float(v: i64) -> (f64, bool) {
+    (v as f64, v.unsigned_abs() > 1 << 53)
+}
+
+fn float_to_int(v: f64) -> Option<(i64, bool)> {
+    if !v.is_finite() {
+        return None;
+    }
+    let rounded = v.round();
+    let int = rounded as i64;
+    Some((int, rounded != v || int as f64 != rounded))
+}
+
+fn parse_scalar(text: &str) -> Option<Value> {
+    match text {
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => text.parse().map(Value::Int).or_else(|_| text.parse().map(Value::Float)).ok(),
+    }
+}
+
+/// Components of a vector value padded with zeros, and how many it has.
+fn vector_components(value: &Value) -> Option<([f64; 4], usize)> {
+    match value {
+        Value::Vec2(v) => Some(([v.x, v.y, 0.0, 0.0], 2)),
+        Value::Vec3(v) => Some(([v.x, v.y, v.z, 0.0], 3)),
+        Value::Vec4(v) => Some(([v.x, v.y, v.z, v.w], 4)),
+        _ => None,
+    }
+}
+
+fn vector_value([x, y, z, w]: [f64; 4], len: usize) -> Value {
+    match len {
+        2 => Value::Vec2(Vec2 {
+            x,
+            y,
+        }),
+        3 => Value::Vec3(Vec3 {
+            x,
+            y,
+            z,
+        }),
+        _ => Value::Vec4(Vec4 {
+            x,
+            y,
+            z,
+            w,
+        }),
+    }
 }
 
 impl fmt::Display for Value {
@@ -61,7 +450,25 @@ impl fmt::Display for Value {
             Value::String(v) => write!(f, "\"{v}\""),
             Value::Vec2(v) => write!(f, "Vec2({}, {})", v.x, v.y),
             Value::Vec3(v) => write!(f, "Vec3({}, {}, {})", v.x, v.y, v.z),
+            Value::Vec4(v) => write!(f, "Vec4({}, {}, {}, {})", v.x, v.y, v.z, v.w),
+            Value::Quat(v) => write!(f, "Quat({}, {}, {}, {})", v.x, v.y, v.z, v.w),
             Value::ColorRgba(v) => write!(f, "ColorRgba({}, {}, {}, {})", v.r, v.g, v.b, v.a),
+            Value::FloatRange(v) => write!(f, "{}..{}", v.min, v.max),
+            Value::IntRange(v) => write!(f, "{}..{}", v.min, v.max),
+            Value::Bytes(bytes) => {
+                write!(f, "Bytes(")?;
+                for (index, byte) in bytes.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{byte:02x}")?;
+                }
+                write!(f, ")")
+            }
+            Value::Timestamp(v) => write!(f, "{v}"),
+            Value::Duration(v) => write!(f, "{v:?}"),
+            Value::Curve(v) => write!(f, "{v}"),
+            Value::Gradient(v) => write!(f, "{v}"),
             Value::Trigger => write!(f, "Trigger"),
             Value::Enum {
                 enum_id,
@@ -72,31 +479,191 @@ impl fmt::Display for Value {
                 "Reference(uuid: {:?}, cached_id: {:?})",
                 reference.uuid, reference.cached_id
             ),
+            Value::List(items) => write_list(f, items),
+            Value::FloatArray(items) => write_list(f, items),
+            Value::IntArray(items) => write_list(f, items),
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T]) -> fmt::Result {
+    write!(f, "[")?;
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{item}")?;
+    }
+    write!(f, "]")
+}
+
+/// A change to one element of a collection value, so large arrays do not
+/// travel whole for a single edit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum ValuePatch {
+    /// Replaces the element at `index` of a list or array.
+    SetIndex {
+        index: usize,
+        value: Value,
+    },
+    /// Inserts before `index`; `index == len` appends.
+    Insert {
+        index: usize,
+        value: Value,
+    },
+    Remove {
+        index: usize,
+    },
+    /// Inserts or replaces a map entry.
+    SetKey {
+        key: String,
+        value: Value,
+    },
+    RemoveKey {
+        key: String,
+    },
+}
+
+impl ValuePatch {
+    /// Applies the patch in place. Array elements must be `Float` for
+    /// `FloatArray` and `Int` for `IntArray`.
+    pub fn apply(&self, target: &mut Value) -> Result<(), String> {
+        match self {
+            ValuePatch::SetIndex {
+                index,
+                value,
+            } => match target {
+                Value::List(items) => set_at(items, *index, value.clone()),
+                Value::FloatArray(items) => set_at(items, *index, element(value, float_element)?),
+                Value::IntArray(items) => set_at(items, *index, element(value, int_element)?),
+                other => Err(not_indexable(other)),
+            },
+            ValuePatch::Insert {
+                index,
+                value,
+            } => match target {
+                Value::List(items) => insert_at(items, *index, value.clone()),
+                Value::FloatArray(items) => {
+                    insert_at(items, *index, element(value, float_element)?)
+                }
+                Value::IntArray(items) => insert_at(items, *index, element(value, int_element)?),
+                other => Err(not_indexable(other)),
+            },
+            ValuePatch::Remove {
+                index,
+            } => match target {
+                Value::List(items) => remove_at(items, *index),
+                Value::FloatArray(items) => remove_at(items, *index),
+                Value::IntArray(items) => remove_at(items, *index),
+                other => Err(not_indexable(other)),
+            },
+            ValuePatch::SetKey {
+                key,
+                value,
+            } => match target {
+                Value::Map(entries) => {
+                    entries.insert(key.clone(), value.clone());
+                    Ok(())
+                }
+                other => Err(no_keys(other)),
+            },
+            ValuePatch::RemoveKey {
+                key,
+            } => match target {
+                Value::Map(entries) => match entries.remove(key) {
+                    Some(_) => Ok(()),
+                    None => Err(format!("no entry '{key}'")),
+                },
+                other => Err(no_keys(other)),
+            },
+        }
+    }
+}
+
+fn set_at<T>(items: &mut [T], index: usize, value: T) -> Result<(), String> {
+    let len = items.len();
+    let slot = items.get_mut(index).ok_or_else(|| out_of_bounds(index, len))?;
+    *slot = value;
+    Ok(())
+}
+
+fn insert_at<T>(items: &mut Vec<T>, index: usize, value: T) -> Result<(), String> {
+    if index > items.len() {
+        return Err(out_of_bounds(index, items.len()));
+    }
+    items.insert(index, value);
+    Ok(())
+}
+
+fn remove_at<T>(items: &mut Vec<T>, index: usize) -> Result<(), String> {
+    if index >= items.len() {
+        return Err(out_of_bounds(index, items.len()));
+    }
+    items.remove(index);
+    Ok(())
+}
+
+fn out_of_bounds(index: usize, len: usize) -> String {
+    format!("index {index} is out of bounds for length {len}")
+}
+
+fn no_keys(value: &Value) -> String {
+    format!("{} values have no keys", value.kind_name())
+}
+
+fn not_indexable(value: &Value) -> String {
+    format!("{} values cannot be indexed", value.kind_name())
+}
+
+fn element<T>(value: &Value, convert: fn(&Value) -> Option<T>) -> Result<T, String> {
+    convert(value).ok_or_else(|| format!("array elements cannot be {}", value.kind_name()))
+}
+
+fn float_element(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn int_element(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum UpdatePolicy {
     Immediate,
     EndOfTick,
     NextTick,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ChangePolicy {
     ValueChange,
     Always,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum SavePolicy {
     None,
     Delta,
     Full,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum ValueConstraints {
     None,
     Int {
@@ -122,9 +689,33 @@ pub enum ValueConstraints {
     Reference {
         target: Option<String>,
     },
+    Duration {
+        min: Option<Duration>,
+        max: Option<Duration>,
+        clamp: bool,
+    },
+    /// Key positions must lie in `domain` and key values in `range`.
+    Curve {
+        domain: Option<FloatRange>,
+        range: Option<FloatRange>,
+        clamp: bool,
+    },
+    /// Stop positions must lie in `domain`.
+    Gradient {
+        domain: Option<FloatRange>,
+        clamp: bool,
+    },
+    /// Applies to `List`, `FloatArray`, `IntArray`, `Map` and `Bytes` values.
+    /// `element` constrains every element (every value of a map); equal
+    /// `min_len` and `max_len` make a fixed-size array.
+    Collection {
+        min_len: Option<usize>,
+        max_len: Option<usize>,
+        element: Box<ValueConstraints>,
+    },
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ParameterData {
     pub value: Value,
     pub default: Option<Value>,
@@ -134,3 +725,198 @@ pub struct ParameterData {
     pub change: ChangePolicy,
     pub constraints: ValueConstraints,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+
+    use super::*;
+
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn collection_values_round_trip_through_json() {
+        let values = [
+            Value::List(vec![Value::Int(1), Value::String("two".to_string()), Value::List(vec![])]),
+            Value::FloatArray(vec![0.0, -1.5, 1e-9]),
+            Value::IntArray(vec![i64::MIN, 0, i64::MAX]),
+            Value::Map(BTreeMap::from([
+                ("gain".to_string(), Value::Float(0.5)),
+                ("tags".to_string(), Value::List(vec![Value::Bool(true)])),
+            ])),
+            Value::Map(BTreeMap::new()),
+        ];
+        for value in values {
+            assert_eq!(round_trip(&value), value);
+        }
+    }
+
+    #[test]
+    fn collection_values_keep_their_json_shape() {
+        assert_eq!(
+            serde_json::to_value(Value::FloatArray(vec![1.0, 2.5])).unwrap(),
+            json!({ "FloatArray": [1.0, 2.5] })
+        );
+        assert_eq!(
+            serde_json::to_value(Value::Map(BTreeMap::from([(
+                "a".to_string(),
+                Value::IntArray(vec![3])
+            )])))
+            .unwrap(),
+            json!({ "Map": { "a": { "IntArray": [3] } } })
+        );
+    }
+
+    fn coerce(value: Value, target: Value) -> Option<(Value, bool)> {
+        value.coerce_to(&target).map(|coerced| (coerced.value, coerced.lossy))
+    }
+
+    #[test]
+    fn scalars_coerce_and_flag_lost_information() {
+        assert_eq!(coerce(Value::Int(2), Value::Float(0.0)), Some((Value::Float(2.0), false)));
+        assert!(coerce(Value::Int(1 << 60), Value::Float(0.0)).unwrap().1);
+        assert_eq!(coerce(Value::Float(2.0), Value::Int(0)), Some((Value::Int(2), false)));
+        assert_eq!(coerce(Value::Float(-1.5), Value::Int(0)), Some((Value::Int(-2), true)));
+        assert_eq!(coerce(Value::Float(1e300), Value::Int(0)), Some((Value::Int(i64::MAX), true)));
+        assert_eq!(coerce(Value::Float(f64::NAN), Value::Int(0)), None);
+        assert_eq!(coerce(Value::Bool(true), Value::Float(0.0)), Some((Value::Float(1.0), false)));
+        assert_eq!(coerce(Value::Int(3), Value::Bool(false)), Some((Value::Bool(true), true)));
+        assert_eq!(
+            coerce(Value::String("0.5".to_string()), Value::Float(0.0)),
+            Some((Value::Float(0.5), false))
+        );
+        assert_eq!(
+            coerce(Value::String("true".to_string()), Value::Int(0)),
+            Some((Value::Int(1), false))
+        );
+        assert_eq!(coerce(Value::String("loud".to_string()), Value::Int(0)), None);
+        assert_eq!(
+            coerce(Value::Float(0.25), Value::String(String::new())),
+            Some((Value::String("0.25".to_string()), false))
+        );
+    }
+
+    #[test]
+    fn enums_coerce_through_their_variant_id() {
+        let target = Value::Enum {
+            enum_id: EnumId("mode".to_string()),
+            variant: EnumVariantId("off".to_string()),
+        };
+        let on = Value::Enum {
+            enum_id: EnumId("mode".to_string()),
+            variant: EnumVariantId("on".to_string()),
+        };
+        assert_eq!(coerce(Value::String("on".to_string()), target), Some((on.clone(), false)));
+        assert_eq!(
+            coerce(on, Value::String(String::new())),
+            Some((Value::String("on".to_string()), false))
+        );
+    }
+
+    #[test]
+    fn vectors_and_colors_coerce_by_component() {
+        let vec3 = |x, y, z| {
+            Value::Vec3(Vec3 {
+                x,
+                y,
+                z,
+            })
+        };
+        let vec2 = Value::Vec2(Vec2 {
+            x: 1.0,
+            y: 2.0,
+        });
+        assert_eq!(coerce(vec2.clone(), vec3(0.0, 0.0, 0.0)), Some((vec3(1.0, 2.0, 0.0), false)));
+        assert_eq!(coerce(vec3(1.0, 2.0, 0.0), vec2.clone()), Some((vec2.clone(), false)));
+        assert_eq!(coerce(vec3(1.0, 2.0, 3.0), vec2.clone()), Some((vec2, true)));
+
+        let color = ColorRgba {
+            r: 0.1,
+            g: 0.2,
+            b: 0.3,
+            a: 0.5,
+        };
+        let (converted, lossy) = coerce(vec3(0.1, 0.2, 0.3), Value::ColorRgba(color)).unwrap();
+        assert_eq!(
+            (converted, lossy),
+            (
+                Value::ColorRgba(ColorRgba {
+                    a: 1.0,
+                    ..color
+                }),
+                false
+            )
+        );
+        assert!(coerce(Value::ColorRgba(color), vec3(0.0, 0.0, 0.0)).unwrap().1);
+        assert_eq!(coerce(Value::Bool(true), vec3(0.0, 0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn arrays_and_ranges_coerce_per_element() {
+        assert_eq!(
+            coerce(Value::IntArray(vec![1, 2]), Value::FloatArray(vec![])),
+            Some((Value::FloatArray(vec![1.0, 2.0]), false))
+        );
+        assert_eq!(
+            coerce(Value::FloatArray(vec![1.0, 2.4]), Value::IntArray(vec![])),
+            Some((Value::IntArray(vec![1, 2]), true))
+        );
+        assert_eq!(coerce(Value::FloatArray(vec![f64::INFINITY]), Value::IntArray(vec![])), None);
+        assert_eq!(
+            coerce(
+                Value::FloatRange(FloatRange {
+                    min: 0.0,
+                    max: 10.0,
+                }),
+                Value::IntRange(IntRange {
+                    min: 0,
+                    max: 0,
+                })
+            ),
+            Some((
+                Value::IntRange(IntRange {
+                    min: 0,
+                    max: 10,
+                }),
+                false
+            ))
+        );
+    }
+
+    #[test]
+    fn value_patches_round_trip_through_json() {
+        let patches = [
+            ValuePatch::SetIndex {
+                index: 2,
+                value: Value::Float(0.25),
+            },
+            ValuePatch::Insert {
+                index: 0,
+                value: Value::Map(BTreeMap::from([("k".to_string(), Value::Int(1))])),
+            },
+            ValuePatch::Remove {
+                index: 7,
+            },
+            ValuePatch::SetKey {
+                key: "gain".to_string(),
+                value: Value::IntArray(vec![1, 2]),
+            },
+            ValuePatch::RemoveKey {
+                key: "gain".to_string(),
+            },
+        ];
+        for patch in patches {
+            assert_eq!(round_trip(&patch), patch);
+        }
+        assert_eq!(
+            serde_json::to_value(ValuePatch::Remove {
+                index: 1
+            })
+            .unwrap(),
+            json!({ "Remove": { "index": 1 } })
+        );
+    }
+}
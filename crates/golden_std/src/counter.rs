@@ -0,0 +1,85 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::NodeReactive;
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Integer counter stepped by triggers.
+///
+/// `count` stays within `min..=max`; with `wrap` set, stepping past one end
+/// continues from the other instead of stopping. `reset` returns to `min`.
+#[derive(GoldenNode)]
+#[behaviour(reactive)]
+pub struct Counter {
+    #[node_id]
+    id: NodeId,
+    #[param(behavior = "Append")]
+    increment: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    decrement: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    reset: ParameterHandle<Trigger>,
+    #[param(default = 1, min = 1, clamp = true)]
+    step: ParameterHandle<i64>,
+    #[param(default = 0)]
+    min: ParameterHandle<i64>,
+    #[param(default = 100)]
+    max: ParameterHandle<i64>,
+    #[param(default = false)]
+    wrap: ParameterHandle<bool>,
+    #[param(default = 0, read_only = true, save = "None")]
+    count: ParameterHandle<i64>,
+    value: i64,
+}
+
+impl Counter {
+    fn bounds(&self, ctx: &ProcessCtx) -> (i64, i64) {
+        let min = self.min.get(ctx).unwrap_or(0);
+        let max = self.max.get(ctx).unwrap_or(min);
+        (min.min(max), min.max(max))
+    }
+
+    fn step_by(&mut self, ctx: &mut ProcessCtx, delta: i64) {
+        let (min, max) = self.bounds(ctx);
+        let next = self.value.saturating_add(delta);
+        let next = if self.wrap.get(ctx).unwrap_or(false) {
+            let span = max - min + 1;
+            min + (next - min).rem_euclid(span)
+        } else {
+            next.clamp(min, max)
+        };
+        self.store(ctx, next);
+    }
+
+    fn store(&mut self, ctx: &mut ProcessCtx, value: i64) {
+        if value != self.value {
+            self.value = value;
+            self.count.set(ctx, value);
+        }
+    }
+}
+
+impl NodeReactive for Counter {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            let (min, max) = self.bounds(ctx);
+            self.value = self.count.get(ctx).unwrap_or(0);
+            self.store(ctx, self.value.clamp(min, max));
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        let step = self.step.get(ctx).unwrap_or(1);
+        if param == self.increment.node_id {
+            self.step_by(ctx, step);
+        } else if param == self.decrement.node_id {
+            self.step_by(ctx, -step);
+        } else if param == self.reset.node_id {
+            let (min, _) = self.bounds(ctx);
+            self.store(ctx, min);
+        } else if param == self.min.node_id || param == self.max.node_id {
+            let (min, max) = self.bounds(ctx);
+            self.store(ctx, self.value.clamp(min, max));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Re-fires `input` on `output` after `delay` seconds.
+///
+/// Every input is delayed independently, so bursts keep their spacing;
+/// `cancel` drops the pending ones. The delay is taken when the input
+/// arrives. Runs as `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct TriggerDelay {
+    #[param(behavior = "Append")]
+    input: ParameterHandle<Trigger>,
+    #[param(default = 0.5, min = 0.0, clamp = true, unit = "s")]
+    delay: ParameterHandle<f64>,
+    #[param(behavior = "Append")]
+    cancel: ParameterHandle<Trigger>,
+    #[param(read_only = true, save = "None")]
+    output: ParameterHandle<Trigger>,
+    /// Engine times at which pending inputs fire, in order.
+    pending: VecDeque<Duration>,
+}
+
+impl NodeReactive for TriggerDelay {
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.input.node_id {
+            let delay = self.delay.get(ctx).unwrap_or(0.0).max(0.0);
+            let due = ctx.now + Duration::from_secs_f64(delay);
+            // A shortened delay can put this input ahead of earlier ones.
+            let index = self.pending.partition_point(|pending| *pending <= due);
+            self.pending.insert(index, due);
+        } else if param == self.cancel.node_id {
+            self.pending.clear();
+        }
+    }
+}
+
+impl NodeContinuous for TriggerDelay {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        while self.pending.front().is_some_and(|due| *due <= ctx.now) {
+            self.pending.pop_front();
+            self.output.set(ctx, Trigger);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+std_enum! {
+    /// Shape of one oscillator cycle.
+    pub enum Waveform = "Waveform" {
+        Sine,
+        Triangle,
+        Saw,
+        Square,
+    }
+}
+
+impl Waveform {
+    /// Samples the waveform at cycle position `t` in `0..1`, in `-1..=1`.
+    pub fn sample(self, t: f64) -> f64 {
+        let t = t.rem_euclid(1.0);
+        match self {
+            Waveform::Sine => (t * std::f64::consts::TAU).sin(),
+            Waveform::Triangle => {
+                if t < 0.5 {
+                    4.0 * t - 1.0
+                } else {
+                    3.0 - 4.0 * t
+                }
+            }
+            Waveform::Saw => 2.0 * t - 1.0,
+            Waveform::Square => {
+                if t < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+/// Low-frequency oscillator.
+///
+/// `output` is `offset + amplitude * wave`, so the defaults swing between 0
+/// and 1. Runs as `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Lfo {
+    #[node_id]
+    id: NodeId,
+    #[param(default = 1.0, min = 0.0, clamp = true, unit = "Hz")]
+    frequency: ParameterHandle<f64>,
+    #[param(
+        default = Waveform::Sine,
+        enum_id = "Waveform",
+        allowed = ["Sine", "Triangle", "Saw", "Square"]
+    )]
+    waveform: ParameterHandle<Waveform>,
+    #[param(default = 0.5)]
+    amplitude: ParameterHandle<f64>,
+    #[param(default = 0.5)]
+    offset: ParameterHandle<f64>,
+    /// Cycle offset in `0..1`.
+    #[param(default = 0.0, min = 0.0, max = 1.0, clamp = true)]
+    phase: ParameterHandle<f64>,
+    #[param(default = true)]
+    running: ParameterHandle<bool>,
+    #[param(behavior = "Append")]
+    reset: ParameterHandle<Trigger>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    output: ParameterHandle<f64>,
+    cycle: f64,
+    last_output: Option<f64>,
+}
+
+impl Lfo {
+    fn write_output(&mut self, ctx: &mut ProcessCtx) {
+        let waveform = self.waveform.get(ctx).unwrap_or(Waveform::Sine);
+        let phase = self.phase.get(ctx).unwrap_or(0.0);
+        let amplitude = self.amplitude.get(ctx).unwrap_or(0.5);
+        let offset = self.offset.get(ctx).unwrap_or(0.5);
+        let value = offset + amplitude * waveform.sample(self.cycle + phase);
+        if self.last_output != Some(value) {
+            self.last_output = Some(value);
+            self.output.set(ctx, value);
+        }
+    }
+}
+
+impl NodeReactive for Lfo {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.write_output(ctx);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.reset.node_id {
+            self.cycle = 0.0;
+            self.write_output(ctx);
+        }
+    }
+}
+
+impl NodeContinuous for Lfo {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        if !self.running.get(ctx).unwrap_or(true) {
+            return;
+        }
+        let frequency = self.frequency.get(ctx).unwrap_or(0.0);
+        self.cycle = (self.cycle + frequency * ctx.delta.as_secs_f64()).rem_euclid(1.0);
+        self.write_output(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use golden_core::Engine;
+    use golden_core::edits::{Edit, EditOrigin, Propagation};
+    use golden_core::graph::node::{ManagerData, NodeData, NodeExecution};
+    use golden_core::schema::GoldenNodeDecl;
+    use golden_schema::{EnumId, EnumVariantId};
+
+    use super::*;
+
+    /// An LFO at `/lfos/lfo`.
+    fn rig() -> Engine {
+        let mut manager = ManagerData::new();
+        manager.register::<Lfo>();
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let lfos = engine.create_child_manager(root, "Manager", "lfos", manager);
+        engine.create_child_of_type(lfos, Lfo::node_type(), "lfo", NodeExecution::Continuous);
+        engine.tick_by(Duration::ZERO);
+        engine
+    }
+
+    fn set(engine: &mut Engine, param: &str, value: Value) {
+        let edit = Edit::SetParam {
+            node: engine.resolve_path(&format!("/lfos/lfo/{param}")).unwrap(),
+            value,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick_by(Duration::ZERO);
+    }
+
+    fn waveform(waveform: &str) -> Value {
+        Value::Enum {
+            enum_id: EnumId("Waveform".to_string()),
+            variant: EnumVariantId(waveform.to_string()),
+        }
+    }
+
+    fn output(engine: &Engine) -> f64 {
+        let node = engine.resolve_path("/lfos/lfo/output").unwrap();
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => match param.value {
+                Value::Float(value) => value,
+                ref other => panic!("unexpected output {other:?}"),
+            },
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn waveforms_at_known_phases() {
+        let cases = [
+            (Waveform::Sine, [0.0, 1.0, 0.0, -1.0]),
+            (Waveform::Triangle, [-1.0, 0.0, 1.0, 0.0]),
+            (Waveform::Saw, [-1.0, -0.5, 0.0, 0.5]),
+            (Waveform::Square, [1.0, 1.0, -1.0, -1.0]),
+        ];
+        for (waveform, expected) in cases {
+            for (quarter, expected) in expected.into_iter().enumerate() {
+                let t = quarter as f64 / 4.0;
+                assert_near(waveform.sample(t), expected);
+                // Whole cycles and negative positions wrap.
+                assert_near(waveform.sample(t + 3.0), expected);
+                assert_near(waveform.sample(t - 1.0), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn output_follows_frequency_amplitude_and_offset() {
+        let mut engine = rig();
+        assert_near(output(&engine), 0.5);
+        engine.tick_by(Duration::from_millis(250));
+        assert_near(output(&engine), 1.0);
+        engine.tick_by(Duration::from_millis(500));
+        assert_near(output(&engine), 0.0);
+
+        set(&mut engine, "frequency", Value::Float(2.0));
+        set(&mut engine, "amplitude", Value::Float(2.0));
+        set(&mut engine, "offset", Value::Float(1.0));
+        set(&mut engine, "waveform", waveform("Saw"));
+        // The cycle is at 0.75: a saw there is 0.5.
+        assert_near(output(&engine), 2.0);
+        engine.tick_by(Duration::from_millis(125));
+        assert_near(output(&engine), -1.0);
+    }
+
+    #[test]
+    fn phase_shifts_and_reset_restarts_the_cycle() {
+        let mut engine = rig();
+        set(&mut engine, "waveform", waveform("Square"));
+        set(&mut engine, "phase", Value::Float(0.5));
+        engine.tick_by(Duration::from_millis(100));
+        assert_near(output(&engine), 0.0);
+
+        set(&mut engine, "phase", Value::Float(0.0));
+        engine.tick_by(Duration::from_millis(500));
+        assert_near(output(&engine), 0.0);
+        set(&mut engine, "reset", Value::Trigger);
+        assert_near(output(&engine), 1.0);
+    }
+
+    #[test]
+    fn stops_while_not_running() {
+        let mut engine = rig();
+        set(&mut engine, "running", Value::Bool(false));
+        engine.tick_by(Duration::from_millis(250));
+        assert_near(output(&engine), 0.5);
+        set(&mut engine, "running", Value::Bool(true));
+        engine.tick_by(Duration::from_millis(250));
+        assert_near(output(&engine), 1.0);
+    }
+}
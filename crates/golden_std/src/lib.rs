@@ -0,0 +1,120 @@
+//! Standard node library built on the `GoldenNode` authoring surface.
+//!
+//! [`register_all`] adds every node type to a manager. Nodes that advance
+//! with engine time (see [`execution_for`]) must be instantiated as
+//! `NodeExecution::Continuous`; the rest only react to parameter changes.
+
+use golden_core::graph::node::{ManagerData, NodeExecution};
+use golden_core::nodes::Mapping;
+use golden_core::schema::GoldenNodeDecl;
+use golden_schema::NodeTypeId;
+
+macro_rules! std_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident = $enum_id:literal {
+            $($variant:ident),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub const ENUM_ID: &'static str = $enum_id;
+            pub const VARIANTS: &'static [$name] = &[$($name::$variant),+];
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),+
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::VARIANTS.iter().copied().find(|variant| variant.name() == name)
+            }
+        }
+
+        impl golden_core::data::ParameterValue for $name {
+            fn into_value(self) -> golden_schema::Value {
+                golden_schema::Value::Enum {
+                    enum_id: golden_schema::EnumId(Self::ENUM_ID.to_string()),
+                    variant: golden_schema::EnumVariantId(self.name().to_string()),
+                }
+            }
+
+            fn from_value(value: &golden_schema::Value) -> Option<Self> {
+                match value {
+                    golden_schema::Value::Enum {
+                        variant,
+                        ..
+                    } => Self::from_name(&variant.0),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+pub mod counter;
+pub mod delay;
+pub mod lfo;
+pub mod logic;
+pub mod math;
+pub mod random;
+pub mod recorder;
+pub mod script;
+pub mod smoother;
+pub mod timer;
+pub mod toggle;
+
+pub use counter::Counter;
+pub use delay::TriggerDelay;
+pub use lfo::{Lfo, Waveform};
+pub use logic::{LogicGate, LogicOp};
+pub use math::MathExpression;
+pub use random::Random;
+pub use recorder::ValueRecorder;
+pub use script::Script;
+pub use smoother::Smoother;
+pub use timer::{Metronome, Timer};
+pub use toggle::Toggle;
+
+/// Registers every standard node type, including the core `Mapping` node.
+pub fn register_all(manager: &mut ManagerData) {
+    manager.register::<Lfo>();
+    manager.register::<Counter>();
+    manager.register::<Timer>();
+    manager.register::<Metronome>();
+    manager.register::<Random>();
+    manager.register::<Smoother>();
+    manager.register::<MathExpression>();
+    manager.register::<LogicGate>();
+    manager.register::<Toggle>();
+    manager.register::<TriggerDelay>();
+    manager.register::<ValueRecorder>();
+    manager.register::<Script>();
+    Mapping::register(manager);
+}
+
+/// Execution mode a standard node type needs to behave as documented.
+pub fn execution_for(node_type: &NodeTypeId) -> NodeExecution {
+    let continuous = [
+        Lfo::node_type(),
+        Timer::node_type(),
+        Metronome::node_type(),
+        Random::node_type(),
+        Smoother::node_type(),
+        TriggerDelay::node_type(),
+        ValueRecorder::node_type(),
+        Script::node_type(),
+        Mapping::node_type(),
+    ];
+    if continuous.contains(node_type) {
+        NodeExecution::Continuous
+    } else {
+        NodeExecution::Reactive
+    }
+}
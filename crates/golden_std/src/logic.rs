@@ -0,0 +1,81 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::NodeReactive;
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Value};
+
+std_enum! {
+    /// Boolean operation of a [`LogicGate`].
+    pub enum LogicOp = "LogicOp" {
+        And,
+        Or,
+        Xor,
+        Nand,
+        Nor,
+        Xnor,
+        Not,
+    }
+}
+
+impl LogicOp {
+    /// `Not` ignores `b`.
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            LogicOp::And => a && b,
+            LogicOp::Or => a || b,
+            LogicOp::Xor => a != b,
+            LogicOp::Nand => !(a && b),
+            LogicOp::Nor => !(a || b),
+            LogicOp::Xnor => a == b,
+            LogicOp::Not => !a,
+        }
+    }
+}
+
+/// Combines two booleans with a [`LogicOp`].
+#[derive(GoldenNode)]
+#[behaviour(reactive)]
+pub struct LogicGate {
+    #[node_id]
+    id: NodeId,
+    #[param(
+        default = LogicOp::And,
+        enum_id = "LogicOp",
+        allowed = ["And", "Or", "Xor", "Nand", "Nor", "Xnor", "Not"]
+    )]
+    op: ParameterHandle<LogicOp>,
+    #[param(default = false)]
+    a: ParameterHandle<bool>,
+    #[param(default = false)]
+    b: ParameterHandle<bool>,
+    #[param(default = false, read_only = true, save = "None")]
+    output: ParameterHandle<bool>,
+    last_output: Option<bool>,
+}
+
+impl LogicGate {
+    fn evaluate(&mut self, ctx: &mut ProcessCtx) {
+        let op = self.op.get(ctx).unwrap_or(LogicOp::And);
+        let a = self.a.get(ctx).unwrap_or(false);
+        let b = self.b.get(ctx).unwrap_or(false);
+        let output = op.apply(a, b);
+        if self.last_output != Some(output) {
+            self.last_output = Some(output);
+            self.output.set(ctx, output);
+        }
+    }
+}
+
+impl NodeReactive for LogicGate {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.evaluate(ctx);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param != self.output.node_id {
+            self.evaluate(ctx);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::expr::{self, Expr};
+use golden_core::graph::node::NodeReactive;
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Value};
+
+/// Evaluates `expression` over the inputs `a` to `d`.
+///
+/// The syntax is that of [`golden_core::expr`]. Parse and evaluation errors
+/// are reported in `error`, which is empty while the expression is valid;
+/// `output` keeps its last value meanwhile.
+#[derive(GoldenNode)]
+#[behaviour(reactive)]
+pub struct MathExpression {
+    #[node_id]
+    id: NodeId,
+    #[param(default = "a + b")]
+    expression: ParameterHandle<String>,
+    #[param(default = 0.0)]
+    a: ParameterHandle<f64>,
+    #[param(default = 0.0)]
+    b: ParameterHandle<f64>,
+    #[param(default = 0.0)]
+    c: ParameterHandle<f64>,
+    #[param(default = 0.0)]
+    d: ParameterHandle<f64>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    output: ParameterHandle<f64>,
+    #[param(default = "", read_only = true, save = "None")]
+    error: ParameterHandle<String>,
+    #[state(default = Err(String::new()))]
+    parsed: Result<Expr, String>,
+    last_error: String,
+}
+
+impl MathExpression {
+    fn compile(&mut self, ctx: &ProcessCtx) {
+        let source = self.expression.get(ctx).unwrap_or_default();
+        self.parsed = expr::parse(&source).map_err(|err| err.to_string());
+    }
+
+    fn evaluate(&mut self, ctx: &mut ProcessCtx) {
+        let inputs = [
+            ("a", self.a.get(ctx)),
+            ("b", self.b.get(ctx)),
+            ("c", self.c.get(ctx)),
+            ("d", self.d.get(ctx)),
+        ];
+        let lookup = |name: &str| {
+            inputs.iter().find(|(input, _)| *input == name).and_then(|(_, value)| *value)
+        };
+        let result = match &self.parsed {
+            Ok(parsed) => parsed.eval(&lookup).map_err(|err| err.to_string()),
+            Err(err) => Err(err.clone()),
+        };
+        let error = match result {
+            Ok(value) => {
+                self.output.set(ctx, value);
+                String::new()
+            }
+            Err(err) => err,
+        };
+        if error != self.last_error {
+            self.error.set(ctx, error.clone());
+            self.last_error = error;
+        }
+    }
+}
+
+impl NodeReactive for MathExpression {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.compile(ctx);
+            self.evaluate(ctx);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.output.node_id || param == self.error.node_id {
+            return;
+        }
+        if param == self.expression.node_id {
+            self.compile(ctx);
+        }
+        self.evaluate(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use golden_core::Engine;
+    use golden_core::edits::{Edit, EditOrigin, Propagation};
+    use golden_core::graph::node::{ManagerData, NodeData, NodeExecution};
+    use golden_core::schema::GoldenNodeDecl;
+
+    use super::*;
+
+    /// A math node at `/math/expr`.
+    fn rig() -> Engine {
+        let mut manager = ManagerData::new();
+        manager.register::<MathExpression>();
+        let mut engine = Engine::new();
+        let root = engine.root_id();
+        let math = engine.create_child_manager(root, "Manager", "math", manager);
+        let node_type = MathExpression::node_type();
+        engine.create_child_of_type(math, node_type, "expr", NodeExecution::Reactive);
+        engine
+    }
+
+    fn set(engine: &mut Engine, param: &str, value: Value) {
+        let edit = Edit::SetParam {
+            node: engine.resolve_path(&format!("/math/expr/{param}")).unwrap(),
+            value,
+        };
+        engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+        engine.tick();
+    }
+
+    fn get(engine: &Engine, param: &str) -> Value {
+        let node = engine.resolve_path(&format!("/math/expr/{param}")).unwrap();
+        match &engine.nodes.get(&node).unwrap().data {
+            NodeData::Parameter(param) => param.value.clone(),
+            _ => panic!("not a parameter"),
+        }
+    }
+
+    fn expression(engine: &mut Engine, source: &str) {
+        set(engine, "expression", Value::String(source.to_string()));
+    }
+
+    #[test]
+    fn evaluates_over_the_inputs() {
+        let mut engine = rig();
+        set(&mut engine, "a", Value::Float(2.0));
+        set(&mut engine, "b", Value::Float(3.0));
+        assert_eq!(get(&engine, "output"), Value::Float(5.0));
+
+        set(&mut engine, "c", Value::Float(4.0));
+        set(&mut engine, "d", Value::Float(-1.0));
+        let cases = [
+            ("a * b - c", 2.0),
+            ("(a + b) / d", -5.0),
+            ("a ^ c % 5", 1.0),
+            ("max(a, c) + abs(d)", 5.0),
+            ("clamp(b, 0, 1)", 1.0),
+            ("a < b ? c : d", 4.0),
+            ("a == 2 && !(d > 0)", 1.0),
+        ];
+        for (source, expected) in cases {
+            expression(&mut engine, source);
+            assert_eq!(get(&engine, "output"), Value::Float(expected), "{source}");
+            assert_eq!(get(&engine, "error"), Value::String(String::new()), "{source}");
+        }
+    }
+
+    #[test]
+    fn errors_keep_the_last_output() {
+        let mut engine = rig();
+        set(&mut engine, "a", Value::Float(1.0));
+        assert_eq!(get(&engine, "output"), Value::Float(1.0));
+
+        expression(&mut engine, "a +");
+        assert_ne!(get(&engine, "error"), Value::String(String::new()));
+        assert_eq!(get(&engine, "output"), Value::Float(1.0));
+
+        expression(&mut engine, "x + 1");
+        assert_eq!(get(&engine, "error"), Value::String("unknown variable 'x'".into()));
+        assert_eq!(get(&engine, "output"), Value::Float(1.0));
+
+        expression(&mut engine, "a * 10");
+        assert_eq!(get(&engine, "error"), Value::String(String::new()));
+        assert_eq!(get(&engine, "output"), Value::Float(10.0));
+    }
+}
@@ -0,0 +1,102 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Uniform random values between `min` and `max`.
+///
+/// A new value is drawn on `generate` and, with `rate` above zero, that many
+/// times per second. The sequence is determined by `seed` and restarts when
+/// the seed changes. Automatic draws need `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Random {
+    #[node_id]
+    id: NodeId,
+    #[param(behavior = "Append")]
+    generate: ParameterHandle<Trigger>,
+    #[param(default = 0.0)]
+    min: ParameterHandle<f64>,
+    #[param(default = 1.0)]
+    max: ParameterHandle<f64>,
+    #[param(default = 0.0, min = 0.0, clamp = true, unit = "Hz")]
+    rate: ParameterHandle<f64>,
+    #[param(default = 0)]
+    seed: ParameterHandle<i64>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    output: ParameterHandle<f64>,
+    #[state(default = SplitMix64::new(0))]
+    rng: SplitMix64,
+    /// Seconds until the next automatic draw.
+    until_next: f64,
+}
+
+impl Random {
+    fn draw(&mut self, ctx: &mut ProcessCtx) {
+        let min = self.min.get(ctx).unwrap_or(0.0);
+        let max = self.max.get(ctx).unwrap_or(1.0);
+        let value = min + (max - min) * self.rng.next_f64();
+        self.output.set(ctx, value);
+    }
+}
+
+impl NodeReactive for Random {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.rng = SplitMix64::new(self.seed.get(ctx).unwrap_or(0) as u64);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, value: Value) {
+        if param == self.generate.node_id {
+            self.draw(ctx);
+        } else if param == self.seed.node_id
+            && let Value::Int(seed) = value
+        {
+            self.rng = SplitMix64::new(seed as u64);
+        }
+    }
+}
+
+impl NodeContinuous for Random {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        let rate = self.rate.get(ctx).unwrap_or(0.0);
+        if rate <= 0.0 {
+            self.until_next = 0.0;
+            return;
+        }
+        self.until_next -= ctx.delta.as_secs_f64();
+        if self.until_next <= 0.0 {
+            self.draw(ctx);
+            // Skip draws missed during a long tick rather than bursting.
+            self.until_next = (self.until_next + 1.0 / rate).max(0.0);
+        }
+    }
+}
+
+/// Small deterministic generator; quality is ample for control values.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..1`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,122 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Records changes of `input` and plays them back on `output`.
+///
+/// Turning `record` on replaces the recording; turning it off fixes its
+/// `length`. While `play` is on the recording is replayed from the start,
+/// holding each value until the next change, and repeats when `looping` is
+/// set. The recording lives in memory only. Runs as
+/// `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct ValueRecorder {
+    #[param(default = 0.0)]
+    input: ParameterHandle<f64>,
+    #[param(default = false)]
+    record: ParameterHandle<bool>,
+    #[param(default = false)]
+    play: ParameterHandle<bool>,
+    #[param(default = true)]
+    looping: ParameterHandle<bool>,
+    #[param(behavior = "Append")]
+    clear: ParameterHandle<Trigger>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    output: ParameterHandle<f64>,
+    #[param(default = 0.0, read_only = true, save = "None", unit = "s")]
+    length: ParameterHandle<f64>,
+    /// `(seconds since recording started, value)` in time order.
+    samples: Vec<(f64, f64)>,
+    recording_since: Option<f64>,
+    duration: f64,
+    playhead: Option<f64>,
+    last_output: Option<f64>,
+}
+
+impl ValueRecorder {
+    fn start_recording(&mut self, ctx: &mut ProcessCtx) {
+        let now = ctx.now.as_secs_f64();
+        self.samples.clear();
+        if let Some(input) = self.input.get(ctx) {
+            self.samples.push((0.0, input));
+        }
+        self.recording_since = Some(now);
+        self.playhead = None;
+    }
+
+    fn stop_recording(&mut self, ctx: &mut ProcessCtx) {
+        if let Some(since) = self.recording_since.take() {
+            self.duration = ctx.now.as_secs_f64() - since;
+            self.length.set(ctx, self.duration);
+        }
+    }
+
+    /// The value held at `time`: the last sample at or before it.
+    fn value_at(&self, time: f64) -> Option<f64> {
+        let index = self.samples.partition_point(|(at, _)| *at <= time);
+        index.checked_sub(1).map(|index| self.samples[index].1)
+    }
+
+    fn write_output(&mut self, ctx: &mut ProcessCtx, value: f64) {
+        if self.last_output != Some(value) {
+            self.last_output = Some(value);
+            self.output.set(ctx, value);
+        }
+    }
+}
+
+impl NodeReactive for ValueRecorder {
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, value: Value) {
+        if param == self.input.node_id {
+            if let (Some(since), Value::Float(input)) = (self.recording_since, value) {
+                self.samples.push((ctx.now.as_secs_f64() - since, input));
+            }
+        } else if param == self.record.node_id {
+            match value {
+                Value::Bool(true) => self.start_recording(ctx),
+                Value::Bool(false) => self.stop_recording(ctx),
+                _ => {}
+            }
+        } else if param == self.play.node_id {
+            // Restart from the top each time playback is switched on.
+            self.playhead = None;
+        } else if param == self.clear.node_id {
+            self.samples.clear();
+            self.recording_since = self.recording_since.map(|_| ctx.now.as_secs_f64());
+            self.duration = 0.0;
+            self.playhead = None;
+            self.length.set(ctx, 0.0);
+        }
+    }
+}
+
+impl NodeContinuous for ValueRecorder {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        if self.recording_since.is_some()
+            || self.samples.is_empty()
+            || !self.play.get(ctx).unwrap_or(false)
+        {
+            return;
+        }
+
+        let mut playhead = match self.playhead {
+            Some(playhead) => playhead + ctx.delta.as_secs_f64(),
+            None => 0.0,
+        };
+        if playhead > self.duration {
+            if self.looping.get(ctx).unwrap_or(true) && self.duration > 0.0 {
+                playhead %= self.duration;
+            } else {
+                playhead = self.duration;
+                self.play.set(ctx, false);
+            }
+        }
+        self.playhead = Some(playhead);
+        if let Some(value) = self.value_at(playhead) {
+            self.write_output(ctx, value);
+        }
+    }
+}
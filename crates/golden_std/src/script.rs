@@ -0,0 +1,720 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use golden_core::data::ParameterHandle;
+use golden_core::edits::{Edit, EditOrigin, Propagation};
+use golden_core::engine::ProcessCtx;
+use golden_core::events::routing::subscriptions::ListenerSpec;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_core::graph::paths::{PathIndex, child_path};
+use golden_macros::GoldenNode;
+use golden_schema::{
+    ColorRgba, Curve, EnumVariantId, FloatRange, Gradient, IntRange, NodeId, NodeMeta, Quat,
+    Timestamp, Value, Vec2, Vec3, Vec4,
+};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, CallFnOptions, Dynamic, EvalAltResult, FuncArgs, Map, Scope};
+
+type ScriptEngine = rhai::Engine;
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Runs a [Rhai](https://rhai.rs) script against the parameter graph.
+///
+/// The script is compiled whenever `script` changes; its top-level statements
+/// then run once, followed by `fn init()` if defined. A script that fails to
+/// compile leaves the previous one running, with the compile error shown in
+/// `error` until the source is fixed. The engine calls the optional
+/// callbacks
+///
+/// - `fn on_param_change(path, value)` for every parameter passed to
+///   `watch(path)`, and
+/// - `fn update(dt)` every tick, with the clock step in seconds, when the
+///   node runs as `NodeExecution::Continuous`.
+///
+/// Callbacks see `this`, an object map kept until the script is reloaded.
+/// `get(path)`, `set(path, value)` and `fire(path)` read and write
+/// parameters, and `time()` returns the engine clock in seconds. Relative
+/// paths start at the script node's parent. Vectors and colours are object
+/// maps (`#{x: 1.0, y: 2.0}`), enums are variant names and triggers `()`.
+/// Curves and gradients keep their type and have `sample(x)`.
+///
+/// Writes are applied at the end of the tick as `EditOrigin::Script` edits,
+/// so they are validated like edits from the UI; a callback that fails makes
+/// none of its writes. All callbacks of one tick share a budget of
+/// `max_operations`. The last error is shown in `error` and the last printed
+/// line in `log`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Script {
+    #[node_id]
+    id: NodeId,
+    #[param(default = "", presentation = "code")]
+    script: ParameterHandle<String>,
+    #[param(default = true)]
+    enabled: ParameterHandle<bool>,
+    #[param(default = 100000, min = 1, clamp = true)]
+    max_operations: ParameterHandle<i64>,
+    #[param(default = "", read_only = true, save = "None")]
+    error: ParameterHandle<String>,
+    #[param(default = "", read_only = true, save = "None")]
+    log: ParameterHandle<String>,
+    sandbox: Sandbox,
+    ast: Option<AST>,
+    #[state(default = Dynamic::from_map(Map::new()))]
+    this: Dynamic,
+    watching: Vec<NodeId>,
+    budget_tick: Option<u64>,
+    budget_left: u64,
+    last_error: String,
+    /// Why the current source did not compile, while an older script runs.
+    compile_error: String,
+}
+
+/// The Rhai engine, without `eval` or module imports and with bounded nesting
+/// and collection sizes, and the state its registered functions share with
+/// the node.
+struct Sandbox {
+    engine: ScriptEngine,
+    host: Arc<Mutex<Host>>,
+    /// Operations used by the running call, as reported by Rhai.
+    operations: Arc<AtomicU64>,
+}
+
+/// What script functions can see and do during one call.
+#[derive(Default)]
+struct Host {
+    params: Arc<HashMap<NodeId, Value>>,
+    meta: Arc<HashMap<NodeId, NodeMeta>>,
+    paths: Arc<PathIndex>,
+    /// Path relative paths are resolved against.
+    base: String,
+    now: f64,
+    writes: Vec<(NodeId, Value)>,
+    watches: Vec<NodeId>,
+    log: Option<String>,
+}
+
+impl Host {
+    fn resolve(&self, path: &str) -> ScriptResult<NodeId> {
+        let full = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            child_path(&self.base, path)
+        };
+        self.paths.resolve(&full).ok_or_else(|| format!("unknown path '{path}'").into())
+    }
+
+    /// The value as this call last wrote it, or as of the start of the call.
+    fn read(&self, node: NodeId) -> Option<&Value> {
+        let written = self.writes.iter().rev().find(|(target, _)| *target == node);
+        written.map(|(_, value)| value).or_else(|| self.params.get(&node))
+    }
+
+    fn get(&self, path: &str) -> ScriptResult<Dynamic> {
+        let node = self.resolve(path)?;
+        let value = self.read(node).ok_or_else(|| format!("'{path}' is not a parameter"))?;
+        Ok(to_dynamic(value))
+    }
+
+    fn set(&mut self, path: &str, value: Dynamic) -> ScriptResult<()> {
+        let node = self.resolve(path)?;
+        let current = self.read(node).ok_or_else(|| format!("'{path}' is not a parameter"))?;
+        if self.meta.get(&node).is_some_and(|meta| meta.expression.is_some()) {
+            return Err(format!("'{path}' is driven by an expression").into());
+        }
+        let value = from_dynamic(current, &value).ok_or_else(|| {
+            format!("'{path}' expects {}, got {}", current.kind_name(), value.type_name())
+        })?;
+        self.writes.push((node, value));
+        Ok(())
+    }
+
+    fn watch(&mut self, path: &str) -> ScriptResult<()> {
+        let node = self.resolve(path)?;
+        if !self.params.contains_key(&node) {
+            return Err(format!("'{path}' is not a parameter").into());
+        }
+        self.watches.push(node);
+        Ok(())
+    }
+}
+
+fn lock(host: &Mutex<Host>) -> MutexGuard<'_, Host> {
+    host.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Script {
+    /// Compiles the script afresh, dropping the previous one's state and
+    /// watches, and runs its top level and `init`. On a compile error the
+    /// previous script keeps running.
+    fn load(&mut self, ctx: &mut ProcessCtx) {
+        let ast = if self.enabled.get(ctx).unwrap_or(true) {
+            let source = self.script.get(ctx).unwrap_or_default();
+            match self.sandbox.engine.compile(&source) {
+                Ok(ast) => Some(ast),
+                Err(err) => {
+                    self.compile_error = err.to_string();
+                    self.report(ctx, self.compile_error.clone());
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+
+        self.ast = ast;
+        self.compile_error.clear();
+        self.this = Dynamic::from_map(Map::new());
+        for node in self.watching.drain(..) {
+            ctx.unsubscribe(ListenerSpec::on_param_change(self.id, node));
+        }
+        if self.ast.is_none() {
+            self.report(ctx, String::new());
+            return;
+        }
+        let loaded =
+            self.run(ctx, |engine, ast, _| engine.run_ast_with_scope(&mut Scope::new(), ast));
+        if loaded && self.has_fn("init", 0) {
+            self.call(ctx, "init", ());
+        }
+    }
+
+    fn has_fn(&self, name: &str, arity: usize) -> bool {
+        self.ast.as_ref().is_some_and(|ast| {
+            ast.iter_functions().any(|func| func.name == name && func.params.len() == arity)
+        })
+    }
+
+    fn call(&mut self, ctx: &mut ProcessCtx, name: &str, args: impl FuncArgs) {
+        let _ = self.run(ctx, |engine, ast, this| {
+            let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+            engine
+                .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, name, args)
+                .map(drop)
+        });
+    }
+
+    /// Runs script code within this tick's operation budget and applies what
+    /// it did if it succeeded, which is returned.
+    fn run(
+        &mut self,
+        ctx: &mut ProcessCtx,
+        body: impl FnOnce(&ScriptEngine, &AST, &mut Dynamic) -> ScriptResult<()>,
+    ) -> bool {
+        let limit = self.max_operations.get(ctx).unwrap_or(100_000).max(1) as u64;
+        if self.budget_tick != Some(ctx.time.tick) {
+            self.budget_tick = Some(ctx.time.tick);
+            self.budget_left = limit;
+        }
+        if self.budget_left == 0 {
+            return false;
+        }
+
+        let base = match ctx.path_of(self.id) {
+            Some(path) => match path.rsplit_once('/') {
+                Some(("", _)) | None => "/".to_string(),
+                Some((parent, _)) => parent.to_string(),
+            },
+            None => "/".to_string(),
+        };
+        *lock(&self.sandbox.host) = Host {
+            params: Arc::clone(&ctx.param_values),
+            meta: Arc::clone(&ctx.meta_values),
+            paths: Arc::clone(&ctx.paths),
+            base,
+            now: ctx.now.as_secs_f64(),
+            ..Host::default()
+        };
+        self.sandbox.engine.set_max_operations(self.budget_left);
+        self.sandbox.operations.store(0, Ordering::Relaxed);
+
+        let Some(ast) = self.ast.as_ref() else {
+            return false;
+        };
+        let result = body(&self.sandbox.engine, ast, &mut self.this);
+        let used = self.sandbox.operations.load(Ordering::Relaxed).max(1);
+        self.budget_left = self.budget_left.saturating_sub(used);
+
+        let host = std::mem::take(&mut *lock(&self.sandbox.host));
+        if let Some(line) = host.log {
+            self.log.set(ctx, line);
+        }
+        match result {
+            Ok(()) => {
+                for (node, value) in host.writes {
+                    ctx.edits.push(
+                        Edit::SetParam {
+                            node,
+                            value,
+                        },
+                        Propagation::EndOfTick,
+                        EditOrigin::Script,
+                    );
+                }
+                for node in host.watches {
+                    if !self.watching.contains(&node) {
+                        self.watching.push(node);
+                        ctx.subscribe(ListenerSpec::on_param_change(self.id, node));
+                    }
+                }
+                self.report(ctx, self.compile_error.clone());
+                true
+            }
+            Err(err) => {
+                let message = match *err {
+                    EvalAltResult::ErrorTooManyOperations(_) => {
+                        self.budget_left = 0;
+                        format!("operation limit of {limit} per tick reached")
+                    }
+                    err => err.to_string(),
+                };
+                self.report(ctx, message);
+                false
+            }
+        }
+    }
+
+    fn report(&mut self, ctx: &mut ProcessCtx, error: String) {
+        if error != self.last_error {
+            self.error.set(ctx, error.clone());
+            self.last_error = error;
+        }
+    }
+}
+
+impl NodeReactive for Script {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        if node == self.id {
+            self.load(ctx);
+        }
+    }
+
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, value: Value) {
+        if param == self.script.node_id || param == self.enabled.node_id {
+            self.load(ctx);
+        } else if self.watching.contains(&param) && self.has_fn("on_param_change", 2) {
+            let path = ctx.path_of(param).unwrap_or_default().to_string();
+            self.call(ctx, "on_param_change", (path, to_dynamic(&value)));
+        }
+    }
+}
+
+impl NodeContinuous for Script {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        if self.has_fn("update", 1) {
+            let dt = ctx.delta.as_secs_f64();
+            self.call(ctx, "update", (dt,));
+        }
+    }
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        let host = Arc::new(Mutex::new(Host::default()));
+        let operations = Arc::new(AtomicU64::new(0));
+        let mut engine = ScriptEngine::new();
+        engine
+            .disable_symbol("eval")
+            .set_module_resolver(DummyModuleResolver::new())
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(64 * 1024)
+            .set_max_array_size(10_000)
+            .set_max_map_size(10_000);
+
+        let counter = Arc::clone(&operations);
+        engine.on_progress(move |count| {
+            counter.store(count, Ordering::Relaxed);
+            None
+        });
+        let print = Arc::clone(&host);
+        engine.on_print(move |text| lock(&print).log = Some(text.to_string()));
+        let debug = Arc::clone(&host);
+        engine.on_debug(move |text, _, _| lock(&debug).log = Some(text.to_string()));
+
+        let get = Arc::clone(&host);
+        engine.register_fn("get", move |path: &str| lock(&get).get(path));
+        let set = Arc::clone(&host);
+        engine.register_fn("set", move |path: &str, value: Dynamic| lock(&set).set(path, value));
+        let fire = Arc::clone(&host);
+        engine.register_fn("fire", move |path: &str| lock(&fire).set(path, Dynamic::UNIT));
+        let watch = Arc::clone(&host);
+        engine.register_fn("watch", move |path: &str| lock(&watch).watch(path));
+        let time = Arc::clone(&host);
+        engine.register_fn("time", move || lock(&time).now);
+        engine
+            .register_type_with_name::<Curve>("Curve")
+            .register_fn("sample", |curve: &mut Curve, x: f64| curve.sample(x))
+            .register_type_with_name::<Gradient>("Gradient")
+            .register_fn("sample", |gradient: &mut Gradient, t: f64| {
+                to_dynamic(&Value::ColorRgba(gradient.sample(t)))
+            });
+
+        Self {
+            engine,
+            host,
+            operations,
+        }
+    }
+}
+
+fn to_dynamic(value: &Value) -> Dynamic {
+    match value {
+        Value::Bool(v) => Dynamic::from(*v),
+        Value::Int(v) => Dynamic::from(*v),
+        Value::Float(v) => Dynamic::from(*v),
+        Value::String(v) => Dynamic::from(v.clone()),
+        Value::Vec2(v) => object(&[("x", v.x), ("y", v.y)]),
+        Value::Vec3(v) => object(&[("x", v.x), ("y", v.y), ("z", v.z)]),
+        Value::Vec4(v) => object(&[("x", v.x), ("y", v.y), ("z", v.z), ("w", v.w)]),
+        Value::Quat(q) => object(&[("x", q.x), ("y", q.y), ("z", q.z), ("w", q.w)]),
+        Value::ColorRgba(c) => object(&[("r", c.r), ("g", c.g), ("b", c.b), ("a", c.a)]),
+        Value::FloatRange(r) => object(&[("min", r.min), ("max", r.max)]),
+        Value::IntRange(r) => Dynamic::from_map(Map::from([
+            ("min".into(), Dynamic::from(r.min)),
+            ("max".into(), Dynamic::from(r.max)),
+        ])),
+        Value::Bytes(bytes) => Dynamic::from_blob(bytes.clone()),
+        Value::Timestamp(t) => Dynamic::from(t.unix_micros),
+        Value::Duration(d) => Dynamic::from(d.as_secs_f64()),
+        Value::Curve(curve) => Dynamic::from(curve.clone()),
+        Value::Gradient(gradient) => Dynamic::from(gradient.clone()),
+        Value::Trigger => Dynamic::UNIT,
+        Value::Enum {
+            variant,
+            ..
+        } => Dynamic::from(variant.0.clone()),
+        Value::Reference(reference) => Dynamic::from(reference.uuid.0.to_string()),
+        Value::List(items) => Dynamic::from_array(items.iter().map(to_dynamic).collect()),
+        Value::FloatArray(items) => {
+            Dynamic::from_array(items.iter().map(|v| Dynamic::from(*v)).collect())
+        }
+        Value::IntArray(items) => {
+            Dynamic::from_array(items.iter().map(|v| Dynamic::from(*v)).collect())
+        }
+        Value::Map(entries) => Dynamic::from_map(
+            entries.iter().map(|(key, value)| (key.as_str().into(), to_dynamic(value))).collect(),
+        ),
+    }
+}
+
+/// Converts a script value to the kind of `current`; numbers convert between
+/// Int and Float and anything fires a trigger. List elements take the kind of
+/// the current first element and map values that of the current entry, or
+/// the script value's own kind when there is none.
+fn from_dynamic(current: &Value, value: &Dynamic) -> Option<Value> {
+    Some(match current {
+        Value::Bool(_) => Value::Bool(value.as_bool().ok()?),
+        Value::Int(_) => Value::Int(match value.as_int() {
+            Ok(v) => v,
+            Err(_) => value.as_float().ok()?.round() as i64,
+        }),
+        Value::Float(_) => Value::Float(number(value)?),
+        Value::String(_) => Value::String(value.clone().into_string().ok()?),
+        Value::Vec2(_) => {
+            let [x, y] = fields(value, ["x", "y"])?;
+            Value::Vec2(Vec2 {
+                x,
+                y,
+            })
+        }
+        Value::Vec3(_) => {
+            let [x, y, z] = fields(value, ["x", "y", "z"])?;
+            Value::Vec3(Vec3 {
+                x,
+                y,
+                z,
+            })
+        }
+        Value::Vec4(_) => {
+            let [x, y, z, w] = fields(value, ["x", "y", "z", "w"])?;
+            Value::Vec4(Vec4 {
+                x,
+                y,
+                z,
+                w,
+            })
+        }
+        Value::Quat(_) => {
+            let [x, y, z, w] = fields(value, ["x", "y", "z", "w"])?;
+            Value::Quat(Quat {
+                x,
+                y,
+                z,
+                w,
+            })
+        }
+        Value::ColorRgba(_) => {
+            let [r, g, b, a] = fields(value, ["r", "g", "b", "a"])?;
+            Value::ColorRgba(ColorRgba {
+                r,
+                g,
+                b,
+                a,
+            })
+        }
+        Value::FloatRange(_) => {
+            let [min, max] = fields(value, ["min", "max"])?;
+            Value::FloatRange(FloatRange {
+                min,
+                max,
+            })
+        }
+        Value::IntRange(_) => {
+            let [min, max] = fields(value, ["min", "max"])?;
+            Value::IntRange(IntRange {
+                min: min.round() as i64,
+                max: max.round() as i64,
+            })
+        }
+        Value::Bytes(_) => Value::Bytes(value.clone().into_blob().ok()?),
+        Value::Timestamp(_) => Value::Timestamp(Timestamp {
+            unix_micros: value.as_int().ok()?,
+        }),
+        Value::Duration(_) => {
+            Value::Duration(std::time::Duration::try_from_secs_f64(number(value)?).ok()?)
+        }
+        Value::Curve(_) => Value::Curve(value.clone().try_cast()?),
+        Value::Gradient(_) => Value::Gradient(value.clone().try_cast()?),
+        Value::Trigger => Value::Trigger,
+        Value::Enum {
+            enum_id,
+            ..
+        } => Value::Enum {
+            enum_id: enum_id.clone(),
+            variant: EnumVariantId(value.clone().into_string().ok()?),
+        },
+        Value::Reference(_) => return None,
+        Value::List(items) => {
+            let array = value.read_lock::<rhai::Array>()?;
+            let items = array
+                .iter()
+                .map(|item| match items.first() {
+                    Some(shape) => from_dynamic(shape, item),
+                    None => untyped(item),
+                })
+                .collect::<Option<_>>()?;
+            Value::List(items)
+        }
+        Value::FloatArray(_) => {
+            let array = value.read_lock::<rhai::Array>()?;
+            Value::FloatArray(array.iter().map(number).collect::<Option<_>>()?)
+        }
+        Value::IntArray(_) => {
+            let array = value.read_lock::<rhai::Array>()?;
+            let items = array.iter().map(|item| number(item).map(|v| v.round() as i64));
+            Value::IntArray(items.collect::<Option<_>>()?)
+        }
+        Value::Map(entries) => {
+            let map = value.read_lock::<Map>()?;
+            let entries = map
+                .iter()
+                .map(|(key, item)| {
+                    let item = match entries.get(key.as_str()) {
+                        Some(shape) => from_dynamic(shape, item),
+                        None => untyped(item),
+                    };
+                    item.map(|item| (key.to_string(), item))
+                })
+                .collect::<Option<_>>()?;
+            Value::Map(entries)
+        }
+    })
+}
+
+/// A script value with no parameter to shape it: scalars, strings, arrays
+/// and maps.
+fn untyped(value: &Dynamic) -> Option<Value> {
+    if let Ok(v) = value.as_bool() {
+        return Some(Value::Bool(v));
+    }
+    if let Ok(v) = value.as_int() {
+        return Some(Value::Int(v));
+    }
+    if let Ok(v) = value.as_float() {
+        return Some(Value::Float(v));
+    }
+    if value.is_string() {
+        return value.clone().into_string().ok().map(Value::String);
+    }
+    if let Some(array) = value.read_lock::<rhai::Array>() {
+        return array.iter().map(untyped).collect::<Option<_>>().map(Value::List);
+    }
+    let map = value.read_lock::<Map>()?;
+    let entries = map.iter().map(|(key, item)| untyped(item).map(|item| (key.to_string(), item)));
+    entries.collect::<Option<_>>().map(Value::Map)
+}
+
+fn number(value: &Dynamic) -> Option<f64> {
+    value.as_float().ok().or_else(|| value.as_int().ok().map(|v| v as f64))
+}
+
+fn object(entries: &[(&str, f64)]) -> Dynamic {
+    let map: Map =
+        entries.iter().map(|(key, value)| ((*key).into(), Dynamic::from(*value))).collect();
+    Dynamic::from_map(map)
+}
+
+fn fields<const N: usize>(value: &Dynamic, keys: [&str; N]) -> Option<[f64; N]> {
+    let map = value.read_lock::<Map>()?;
+    let mut out = [0.0; N];
+    for (slot, key) in out.iter_mut().zip(keys) {
+        *slot = number(map.get(key)?)?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use golden_core::Engine;
+    use golden_core::graph::node::{ManagerData, NodeData, NodeExecution};
+    use golden_core::schema::GoldenNodeDecl;
+
+    use super::*;
+
+    struct Rig {
+        engine: Engine,
+    }
+
+    impl Rig {
+        /// A script node at `/scripts/script` next to `/scripts/input` and
+        /// `/scripts/output`, running `source`.
+        fn new(source: &str) -> Self {
+            let mut manager = ManagerData::new();
+            manager.register::<Script>();
+            let mut engine = Engine::new();
+            let root = engine.root_id();
+            let scripts = engine.create_child_manager(root, "Manager", "scripts", manager);
+            engine.create_child_parameter(scripts, "input", Value::Float(1.0));
+            engine.create_child_parameter(scripts, "output", Value::Float(0.0));
+            engine
+                .create_child_of_type(
+                    scripts,
+                    Script::node_type(),
+                    "script",
+                    NodeExecution::Continuous,
+                )
+                .unwrap();
+            let mut rig = Self {
+                engine,
+            };
+            rig.set("/scripts/script/script", Value::String(source.to_string()));
+            rig
+        }
+
+        fn set(&mut self, path: &str, value: Value) {
+            let edit = Edit::SetParam {
+                node: self.engine.resolve_path(path).unwrap(),
+                value,
+            };
+            self.engine.submit_edit(edit, Propagation::EndOfTick, EditOrigin::UI).unwrap();
+            self.tick();
+        }
+
+        /// Two ticks, so script writes queued in the first land.
+        fn tick(&mut self) {
+            self.engine.tick_by(Duration::from_millis(10));
+            self.engine.tick_by(Duration::from_millis(10));
+        }
+
+        fn get(&self, path: &str) -> Value {
+            let node = self.engine.resolve_path(path).unwrap();
+            match &self.engine.nodes.get(&node).unwrap().data {
+                NodeData::Parameter(param) => param.value.clone(),
+                _ => panic!("not a parameter"),
+            }
+        }
+
+        fn error(&self) -> String {
+            match self.get("/scripts/script/error") {
+                Value::String(error) => error,
+                other => panic!("unexpected error value {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn reads_and_writes_parameters_by_path() {
+        let mut rig = Rig::new(
+            r#"
+            watch("input");
+            fn on_param_change(path, value) {
+                set("/scripts/output", value * 2.0);
+                print(path);
+            }
+            "#,
+        );
+        assert_eq!(rig.error(), "");
+        rig.set("/scripts/input", Value::Float(3.0));
+        rig.tick();
+        assert_eq!(rig.get("/scripts/output"), Value::Float(6.0));
+        assert_eq!(rig.get("/scripts/script/log"), Value::String("/scripts/input".into()));
+
+        rig.set("/scripts/script/script", Value::String(r#"set("missing", 1.0);"#.into()));
+        assert!(rig.error().contains("unknown path 'missing'"), "{}", rig.error());
+        rig.set("/scripts/script/script", Value::String(r#"set("input", "text");"#.into()));
+        assert!(rig.error().contains("'input' expects"), "{}", rig.error());
+    }
+
+    #[test]
+    fn reloads_when_the_source_changes() {
+        let mut rig = Rig::new("fn update(dt) { set(\"output\", 1.0); }");
+        rig.tick();
+        assert_eq!(rig.get("/scripts/output"), Value::Float(1.0));
+
+        rig.set(
+            "/scripts/script/script",
+            Value::String("fn update(dt) { set(\"output\", 2.0); }".into()),
+        );
+        rig.tick();
+        assert_eq!(rig.get("/scripts/output"), Value::Float(2.0));
+        assert_eq!(rig.error(), "");
+
+        // A compile error is reported and the last good script keeps running.
+        rig.set("/scripts/output", Value::Float(0.0));
+        rig.set("/scripts/script/script", Value::String("fn update(dt) { set(".into()));
+        assert_ne!(rig.error(), "");
+        rig.tick();
+        assert_eq!(rig.get("/scripts/output"), Value::Float(2.0));
+    }
+
+    #[test]
+    fn operation_budget_stops_runaway_loops() {
+        let mut rig = Rig::new("fn update(dt) { loop { } }");
+        rig.set("/scripts/script/max_operations", Value::Int(1000));
+        rig.tick();
+        assert_eq!(rig.error(), "operation limit of 1000 per tick reached");
+
+        // Each tick gets a fresh budget, so a fixed script recovers.
+        rig.set(
+            "/scripts/script/script",
+            Value::String("fn update(dt) { set(\"output\", dt); }".into()),
+        );
+        rig.tick();
+        assert_eq!(rig.error(), "");
+        assert_eq!(rig.get("/scripts/output"), Value::Float(0.01));
+    }
+
+    #[test]
+    fn sandbox_rejects_eval_and_module_imports() {
+        let module = std::env::temp_dir().join(format!("golden-script-{}.rhai", unique_suffix()));
+        std::fs::write(&module, "fn secret() { 42 }").unwrap();
+        let path = module.with_extension("");
+        let source = format!(r#"import "{}" as m; set("output", m::secret());"#, path.display());
+
+        let mut rig = Rig::new(&source);
+        std::fs::remove_file(&module).unwrap();
+        assert!(rig.error().contains("Module not found"), "{}", rig.error());
+        assert_eq!(rig.get("/scripts/output"), Value::Float(0.0));
+
+        rig.set("/scripts/script/script", Value::String(r#"eval("1")"#.into()));
+        assert_ne!(rig.error(), "");
+    }
+
+    fn unique_suffix() -> u128 {
+        std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()
+    }
+}
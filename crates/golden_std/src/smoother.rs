@@ -0,0 +1,73 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::NodeId;
+
+/// Low-pass filter following `input`.
+///
+/// `time` is the one-pole time constant in seconds; `max_rate` additionally
+/// limits the slope in units per second, with zero meaning unlimited. Runs as
+/// `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Smoother {
+    #[node_id]
+    id: NodeId,
+    #[param(default = 0.0)]
+    input: ParameterHandle<f64>,
+    #[param(default = 0.1, min = 0.0, clamp = true, unit = "s")]
+    time: ParameterHandle<f64>,
+    #[param(default = 0.0, min = 0.0, clamp = true)]
+    max_rate: ParameterHandle<f64>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    output: ParameterHandle<f64>,
+    current: Option<f64>,
+}
+
+impl NodeReactive for Smoother {
+    fn on_node_created(&mut self, ctx: &mut ProcessCtx, node: NodeId) {
+        // Start settled on the input instead of sweeping up from zero.
+        if node == self.id
+            && let Some(input) = self.input.get(ctx)
+        {
+            self.current = Some(input);
+            self.output.set(ctx, input);
+        }
+    }
+}
+
+impl NodeContinuous for Smoother {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        let Some(goal) = self.input.get(ctx) else {
+            return;
+        };
+        let Some(current) = self.current else {
+            self.current = Some(goal);
+            self.output.set(ctx, goal);
+            return;
+        };
+        if current == goal {
+            return;
+        }
+
+        let dt = ctx.delta.as_secs_f64();
+        let tau = self.time.get(ctx).unwrap_or(0.0);
+        let mut step = if tau > 0.0 {
+            (goal - current) * (1.0 - (-dt / tau).exp())
+        } else {
+            goal - current
+        };
+        let max_rate = self.max_rate.get(ctx).unwrap_or(0.0);
+        if max_rate > 0.0 {
+            let limit = max_rate * dt;
+            step = step.clamp(-limit, limit);
+        }
+        let mut next = current + step;
+        if (goal - next).abs() < 1e-6 {
+            next = goal;
+        }
+        self.current = Some(next);
+        self.output.set(ctx, next);
+    }
+}
@@ -0,0 +1,165 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::{NodeContinuous, NodeReactive};
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Counts up to `duration` and fires `done`.
+///
+/// `start` resumes, `stop` pauses and `reset` rewinds to zero without
+/// changing whether the timer runs. With `looping` set the timer restarts on
+/// completion. Runs as `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Timer {
+    #[param(default = 1.0, min = 0.0, clamp = true, unit = "s")]
+    duration: ParameterHandle<f64>,
+    #[param(default = false)]
+    looping: ParameterHandle<bool>,
+    #[param(behavior = "Append")]
+    start: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    stop: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    reset: ParameterHandle<Trigger>,
+    #[param(default = false, read_only = true, save = "None")]
+    running: ParameterHandle<bool>,
+    #[param(default = 0.0, read_only = true, save = "None", unit = "s")]
+    elapsed: ParameterHandle<f64>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    progress: ParameterHandle<f64>,
+    #[param(read_only = true, save = "None")]
+    done: ParameterHandle<Trigger>,
+    is_running: bool,
+    time: f64,
+}
+
+impl Timer {
+    fn set_running(&mut self, ctx: &mut ProcessCtx, running: bool) {
+        if self.is_running != running {
+            self.is_running = running;
+            self.running.set(ctx, running);
+        }
+    }
+
+    fn write_time(&self, ctx: &mut ProcessCtx) {
+        let duration = self.duration.get(ctx).unwrap_or(0.0);
+        let progress = if duration > 0.0 {
+            (self.time / duration).min(1.0)
+        } else {
+            1.0
+        };
+        self.elapsed.set(ctx, self.time);
+        self.progress.set(ctx, progress);
+    }
+}
+
+impl NodeReactive for Timer {
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.start.node_id {
+            let duration = self.duration.get(ctx).unwrap_or(0.0);
+            // Starting a finished one-shot timer runs it again.
+            if self.time >= duration {
+                self.time = 0.0;
+                self.write_time(ctx);
+            }
+            self.set_running(ctx, true);
+        } else if param == self.stop.node_id {
+            self.set_running(ctx, false);
+        } else if param == self.reset.node_id {
+            self.time = 0.0;
+            self.write_time(ctx);
+        }
+    }
+}
+
+impl NodeContinuous for Timer {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        if !self.is_running {
+            return;
+        }
+        let duration = self.duration.get(ctx).unwrap_or(0.0);
+        self.time += ctx.delta.as_secs_f64();
+        if self.time >= duration {
+            self.done.set(ctx, Trigger);
+            if self.looping.get(ctx).unwrap_or(false) && duration > 0.0 {
+                self.time %= duration;
+            } else {
+                self.time = duration;
+                self.set_running(ctx, false);
+            }
+        }
+        self.write_time(ctx);
+    }
+}
+
+/// Beat clock firing `beat` at `bpm` and `bar` on the first beat of each bar.
+///
+/// `beat_index` counts from zero within the bar and `phase` runs from 0 to 1
+/// across each beat. Runs as `NodeExecution::Continuous`.
+#[derive(GoldenNode)]
+#[behaviour(continuous)]
+pub struct Metronome {
+    #[param(default = 120.0, min = 0.0, clamp = true, unit = "bpm")]
+    bpm: ParameterHandle<f64>,
+    #[param(default = 4, min = 1, clamp = true)]
+    beats_per_bar: ParameterHandle<i64>,
+    #[param(default = true)]
+    running: ParameterHandle<bool>,
+    #[param(behavior = "Append")]
+    reset: ParameterHandle<Trigger>,
+    #[param(read_only = true, save = "None")]
+    beat: ParameterHandle<Trigger>,
+    #[param(read_only = true, save = "None")]
+    bar: ParameterHandle<Trigger>,
+    #[param(default = 0, read_only = true, save = "None")]
+    beat_index: ParameterHandle<i64>,
+    #[param(default = 0.0, read_only = true, save = "None")]
+    phase: ParameterHandle<f64>,
+    /// Beats since the last reset, including the fraction of the current one.
+    position: f64,
+    started: bool,
+}
+
+impl Metronome {
+    fn fire_beat(&self, ctx: &mut ProcessCtx, beat: u64) {
+        let beats_per_bar = self.beats_per_bar.get(ctx).unwrap_or(4).max(1) as u64;
+        let index = beat % beats_per_bar;
+        self.beat.set(ctx, Trigger);
+        if index == 0 {
+            self.bar.set(ctx, Trigger);
+        }
+        self.beat_index.set(ctx, index as i64);
+    }
+}
+
+impl NodeReactive for Metronome {
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.reset.node_id {
+            self.position = 0.0;
+            self.started = false;
+            self.phase.set(ctx, 0.0);
+        }
+    }
+}
+
+impl NodeContinuous for Metronome {
+    fn update(&mut self, ctx: &mut ProcessCtx) {
+        if !self.running.get(ctx).unwrap_or(true) {
+            return;
+        }
+        // The first beat sounds as soon as the metronome runs.
+        if !self.started {
+            self.started = true;
+            self.fire_beat(ctx, 0);
+        }
+        let bpm = self.bpm.get(ctx).unwrap_or(0.0);
+        let previous = self.position;
+        self.position += bpm / 60.0 * ctx.delta.as_secs_f64();
+        let crossed = self.position.floor() as u64;
+        if crossed > previous.floor() as u64 {
+            self.fire_beat(ctx, crossed);
+        }
+        self.phase.set(ctx, self.position.fract());
+    }
+}
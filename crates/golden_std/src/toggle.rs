@@ -0,0 +1,43 @@
+use golden_core::data::ParameterHandle;
+use golden_core::engine::ProcessCtx;
+use golden_core::graph::node::NodeReactive;
+use golden_macros::GoldenNode;
+use golden_schema::{NodeId, Trigger, Value};
+
+/// Flip-flop holding a boolean `state`.
+///
+/// `toggle` inverts it, `set` and `reset` force it on and off.
+#[derive(GoldenNode)]
+#[behaviour(reactive)]
+pub struct Toggle {
+    #[param(behavior = "Append")]
+    toggle: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    set: ParameterHandle<Trigger>,
+    #[param(behavior = "Append")]
+    reset: ParameterHandle<Trigger>,
+    #[param(default = false, read_only = true, save = "None")]
+    state: ParameterHandle<bool>,
+    on: bool,
+}
+
+impl Toggle {
+    fn store(&mut self, ctx: &mut ProcessCtx, on: bool) {
+        if self.on != on {
+            self.on = on;
+            self.state.set(ctx, on);
+        }
+    }
+}
+
+impl NodeReactive for Toggle {
+    fn on_param_change(&mut self, ctx: &mut ProcessCtx, param: NodeId, _value: Value) {
+        if param == self.toggle.node_id {
+            self.store(ctx, !self.on);
+        } else if param == self.set.node_id {
+            self.store(ctx, true);
+        } else if param == self.reset.node_id {
+            self.store(ctx, false);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Compares and merges project files.
+//!
+//! `golden_diff <old> <new>` prints what changed between two versions of a
+//! project, one line per change. `golden_diff merge <base> <ours> <theirs>`
+//! writes the three-way merge over `<ours>` and prints conflicts, exiting with
+//! status 1 if there were any, which makes it usable as a git merge driver:
+//!
+//! ```text
+//! # .gitattributes
+//! *.golden.json merge=golden
+//! # .git/config
+//! [merge "golden"]
+//!     driver = golden_diff merge %O %A %B
+//! ```
+
+use std::process::ExitCode;
+
+use golden_core::persistence::diff::{diff, merge};
+use golden_core::persistence::{load_project, save_project, write_atomic};
+use golden_schema::ProjectFile;
+
+const USAGE: &str =
+    "usage: golden_diff <old> <new>\n       golden_diff merge <base> <ours> <theirs>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.as_slice() {
+        [command, base, ours, theirs] if command == "merge" => run_merge(base, ours, theirs),
+        [old, new] => run_diff(old, new),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("golden_diff: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read(path: &str) -> Result<ProjectFile, String> {
+    let data = std::fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    load_project(&data).map_err(|err| format!("{path}: {err}"))
+}
+
+fn run_diff(old: &str, new: &str) -> Result<bool, String> {
+    for change in diff(&read(old)?, &read(new)?) {
+        println!("{change}");
+    }
+    Ok(true)
+}
+
+/// Returns whether the merge was clean.
+fn run_merge(base: &str, ours: &str, theirs: &str) -> Result<bool, String> {
+    let merged = merge(&read(base)?, &read(ours)?, &read(theirs)?);
+    let data = save_project(&merged.project).map_err(|err| err.to_string())?;
+    write_atomic(ours.as_ref(), data.as_bytes()).map_err(|err| format!("{ours}: {err}"))?;
+    for conflict in &merged.conflicts {
+        println!("{conflict}");
+    }
+    Ok(merged.conflicts.is_empty())
+}
@@ -0,0 +1,22 @@
+//! Writes `golden.schema.json` and `golden.ts`, describing the protocol, the
+//! value types and every standard node type's parameters, to the directory
+//! given as the first argument (default `crates/golden_ui/src/lib/generated`).
+//!
+//! Applications with their own node types can build the same files with
+//! `golden_core::typegen::schema_document` and `golden_schema::typegen::typescript`.
+
+use std::path::PathBuf;
+
+use golden_tools::{GENERATED_DIR, generated_files};
+
+fn main() -> std::io::Result<()> {
+    let out_dir =
+        std::env::args().nth(1).map(PathBuf::from).unwrap_or_else(|| PathBuf::from(GENERATED_DIR));
+
+    std::fs::create_dir_all(&out_dir)?;
+    for (name, contents) in generated_files() {
+        std::fs::write(out_dir.join(name), contents)?;
+    }
+    println!("wrote {}", out_dir.display());
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! Command-line tools for working on the workspace, kept out of the runtime
+//! crates.
+
+use golden_core::graph::node::ManagerData;
+use golden_core::schema::SchemaRegistry;
+use golden_core::typegen::schema_document;
+use golden_schema::typegen::typescript;
+
+/// Where `golden_typegen` writes by default, relative to the workspace root.
+pub const GENERATED_DIR: &str = "crates/golden_ui/src/lib/generated";
+
+/// The generated files for the standard node library, by file name.
+pub fn generated_files() -> Vec<(&'static str, String)> {
+    let mut manager = ManagerData::new();
+    golden_std::register_all(&mut manager);
+    let mut registry = SchemaRegistry::new();
+    for (node_type, schema) in manager.node_types() {
+        registry.register(node_type.clone(), schema.clone());
+    }
+
+    let document = schema_document(&registry);
+    let json = serde_json::to_string_pretty(&document).expect("schema documents serialize");
+    vec![("golden.schema.json", json + "\n"), ("golden.ts", typescript(&document))]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn committed_files_are_up_to_date() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(GENERATED_DIR);
+        for (name, contents) in generated_files() {
+            let committed = std::fs::read_to_string(dir.join(name)).unwrap();
+            assert!(
+                committed == contents,
+                "{name} is out of date; run `cargo run -p golden_tools --bin golden_typegen`"
+            );
+        }
+    }
+}
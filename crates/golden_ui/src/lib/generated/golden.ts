@@ -0,0 +1,668 @@
+// Generated from the Rust types by golden_typegen; do not edit.
+
+export interface Ack {
+  error?: ErrorInfo | null;
+  ok: boolean;
+  /** Accepted with caveats, e.g. a value converted with loss of precision. */
+  warnings?: ErrorInfo[];
+}
+
+export interface BeginEdit {
+  label?: string | null;
+  origin: EditOrigin;
+}
+
+export interface BeginEditAck {
+  edit_session_id: string;
+}
+
+export type ChangePolicy = "ValueChange" | "Always";
+
+/** Messages a client may send, keyed by the envelope `msg` field. */
+export type ClientMessage =
+  | { msg: "Hello"; payload: Hello }
+  | { msg: "GetSnapshot"; payload: GetSnapshot }
+  | { msg: "Subscribe"; payload: Subscribe }
+  | { msg: "BeginEdit"; payload: BeginEdit }
+  | { msg: "EndEdit"; payload: EndEdit }
+  | { msg: "SetParam"; payload: SetParam }
+  | { msg: "PatchParam"; payload: PatchParam }
+  | { msg: "PatchMeta"; payload: PatchMeta }
+  | { msg: "CreateNode"; payload: CreateNode }
+  | { msg: "MoveNode"; payload: MoveNode }
+  | { msg: "DeleteNode"; payload: DeleteNode };
+
+/** Wire form of a message: the tagged message plus the optional request id. */
+export type ClientMessageEnvelope = { req_id?: string | null } & ClientMessage;
+
+export interface ColorRgba {
+  a: number;
+  b: number;
+  g: number;
+  r: number;
+}
+
+export interface ContainerDataDto {
+  allowed_types: NodeTypeId[];
+  folders_allowed: boolean;
+}
+
+export interface CounterParams {
+  readonly count: { Int: number };
+  decrement: "Trigger";
+  increment: "Trigger";
+  max: { Int: number };
+  min: { Int: number };
+  reset: "Trigger";
+  step: { Int: number };
+  wrap: { Bool: boolean };
+}
+
+export interface CreateNode {
+  edit_session_id?: string | null;
+  label?: string | null;
+  node_type: NodeTypeId;
+  parent_id: NodeId;
+  propagation: Propagation;
+}
+
+/** Transfer function made of keys sorted by `x`. */
+export interface Curve {
+  keys: CurveKey[];
+}
+
+/** How a curve moves from a key to the next one. */
+export type CurveInterpolation = "Linear" | "EaseIn" | "EaseOut" | "EaseInOut" | "Step" | "Smooth";
+
+/**
+ * One keyframe of a [`Curve`]. `id` stays the same across edits and
+ * save/load, so editors and merges can follow a key that moved.
+ */
+export interface CurveKey {
+  id: string;
+  /** Slope (dy/dx) arriving at this key, used by `Smooth`. */
+  in_tangent: number;
+  /** Interpolation towards the next key. */
+  interpolation: CurveInterpolation;
+  /** Slope (dy/dx) leaving this key, used by `Smooth`. */
+  out_tangent: number;
+  x: number;
+  y: number;
+}
+
+export type DeclId = string;
+
+export interface DeleteNode {
+  edit_session_id?: string | null;
+  node_id: NodeId;
+  propagation: Propagation;
+}
+
+export interface DeltaNodeRecord {
+  children?: NodeRecord[];
+  decl_id: DeclId;
+  meta?: NodeMetaPatch | null;
+  uuid?: NodeUuid | null;
+  value?: Value | null;
+}
+
+export interface Duration {
+  nanos: number;
+  secs: number;
+}
+
+export type EditOrigin = "UI" | "Network" | "Script" | "Internal";
+
+export interface EndEdit {
+  edit_session_id: string;
+}
+
+export interface EnumDef {
+  enum_id: EnumId;
+  variants: EnumVariantDef[];
+}
+
+export type EnumId = string;
+
+export interface EnumVariantDef {
+  label: string;
+  variant_id: EnumVariantId;
+}
+
+export type EnumVariantId = string;
+
+export interface ErrorInfo {
+  code: string;
+  message: string;
+}
+
+export interface Event {
+  kind: EventKind;
+  time: EventTime;
+}
+
+export interface EventBatch {
+  events: Event[];
+}
+
+export type EventKind =
+  | { ParamChanged: { param: NodeId; value: Value } }
+  | { ParamPatched: { param: NodeId; patch: ValuePatch } }
+  | { ChildAdded: { child: NodeId; parent: NodeId } }
+  | { ChildRemoved: { child: NodeId; parent: NodeId } }
+  | { ChildReplaced: { new: NodeId; old: NodeId; parent: NodeId } }
+  | {
+    ChildMoved: { child: NodeId; new_parent: NodeId; old_parent: NodeId };
+  }
+  | { ChildReordered: { child: NodeId; parent: NodeId } }
+  | { NodeCreated: { node: NodeId } }
+  | { NodeDeleted: { node: NodeId } }
+  | { MetaChanged: { node: NodeId; patch: NodeMetaPatch } }
+  | { RampFinished: { param: NodeId } }
+  | { RampCancelled: { param: NodeId } };
+
+export interface EventTime {
+  micro: number;
+  seq: number;
+  tick: number;
+}
+
+export interface FloatRange {
+  max: number;
+  min: number;
+}
+
+export interface FullNodeRecord {
+  children?: NodeRecord[];
+  data: NodeDataDto;
+  decl_id?: DeclId | null;
+  meta: NodeMeta;
+  type: NodeTypeId;
+  uuid: NodeUuid;
+}
+
+export interface GetSnapshot {
+  include_schema: boolean;
+  scope: Scope;
+}
+
+/** Colour stops sorted by `t`, blended linearly in RGBA. */
+export interface Gradient {
+  stops: GradientStop[];
+}
+
+/** One colour stop of a [`Gradient`]; `id` is stable like [`CurveKey::id`]. */
+export interface GradientStop {
+  color: ColorRgba;
+  id: string;
+  t: number;
+}
+
+export interface Hello {
+  client_name: string;
+  client_version: string;
+  /** Codec names the client can decode, most preferred first. */
+  codecs?: string[];
+  protocol_version: string;
+  root_scope?: Scope | null;
+}
+
+export interface HelloAck {
+  /** Codec used for every server frame after this acknowledgement. */
+  codec?: string;
+  features: string[];
+  protocol_version: string;
+  server_name: string;
+  server_version: string;
+}
+
+export interface IntRange {
+  max: number;
+  min: number;
+}
+
+export interface LfoParams {
+  amplitude: { Float: number };
+  /** In Hz. */
+  frequency: { Float: number };
+  offset: { Float: number };
+  readonly output: { Float: number };
+  phase: { Float: number };
+  reset: "Trigger";
+  running: { Bool: boolean };
+  waveform: {
+    Enum: { enum_id: "Waveform"; variant: "Sine" | "Triangle" | "Saw" | "Square" };
+  };
+}
+
+export interface LogicGateParams {
+  a: { Bool: boolean };
+  b: { Bool: boolean };
+  op: {
+    Enum: {
+      enum_id: "LogicOp";
+      variant: "And" | "Or" | "Xor" | "Nand" | "Nor" | "Xnor" | "Not";
+    };
+  };
+  readonly output: { Bool: boolean };
+}
+
+export interface MappingParams {
+  clamp: { Bool: boolean };
+  color_high: { ColorRgba: ColorRgba };
+  color_low: { ColorRgba: ColorRgba };
+  convert_units: { Bool: boolean };
+  curve: {
+    Enum: {
+      enum_id: "MappingCurve";
+      variant: "Linear" | "EaseIn" | "EaseOut" | "EaseInOut";
+    };
+  };
+  in_max: { Float: number };
+  in_min: { Float: number };
+  invert: { Bool: boolean };
+  out_max: { Float: number };
+  out_min: { Float: number };
+  /** In s. */
+  smoothing: { Float: number };
+  source: { Reference: ReferenceValue };
+  target: { Reference: ReferenceValue };
+  threshold: { Float: number };
+}
+
+export interface MathExpressionParams {
+  a: { Float: number };
+  b: { Float: number };
+  c: { Float: number };
+  d: { Float: number };
+  readonly error: { String: string };
+  expression: { String: string };
+  readonly output: { Float: number };
+}
+
+export interface MetronomeParams {
+  readonly bar: "Trigger";
+  readonly beat: "Trigger";
+  readonly beat_index: { Int: number };
+  beats_per_bar: { Int: number };
+  /** In bpm. */
+  bpm: { Float: number };
+  readonly phase: { Float: number };
+  reset: "Trigger";
+  running: { Bool: boolean };
+}
+
+export interface MoveNode {
+  edit_session_id?: string | null;
+  new_index: number;
+  new_parent_id: NodeId;
+  node_id: NodeId;
+  propagation: Propagation;
+}
+
+export interface NodeDataDto {
+  container?: ContainerDataDto | null;
+  kind: NodeDataKind;
+  parameter?: ParameterData | null;
+}
+
+export type NodeDataKind = "None" | "Container" | "Parameter" | { Custom: string };
+
+export interface NodeDto {
+  children: NodeId[];
+  data: NodeDataDto;
+  decl_id?: DeclId | null;
+  meta: NodeMeta;
+  node_id: NodeId;
+  node_type: NodeTypeId;
+  uuid: NodeUuid;
+}
+
+export type NodeId = number;
+
+export interface NodeMeta {
+  decl_id: DeclId;
+  description?: string | null;
+  enabled: boolean;
+  /** Expression computing a parameter's value, e.g. `sin(time) * /inputs/fader`. */
+  expression?: string | null;
+  /** Why `expression` cannot be evaluated; maintained by the engine. */
+  expression_error?: string | null;
+  label: string;
+  presentation: PresentationHint;
+  semantics: SemanticsHint;
+  short_name: ShortName;
+  tags: string[];
+  uuid: NodeUuid;
+}
+
+/**
+ * Changes to a node's metadata; fields left out are kept.
+ *
+ * For the optional fields, `null` clears the value while leaving the field
+ * out keeps it.
+ */
+export interface NodeMetaPatch {
+  description?: string | null;
+  enabled?: boolean | null;
+  expression?: string | null;
+  /**
+   * Set by the engine in `MetaChanged` events; edits that set it are
+   * rejected.
+   */
+  expression_error?: string | null;
+  label?: string | null;
+  presentation?: PresentationHint | null;
+  semantics?: SemanticsHint | null;
+  tags?: string[] | null;
+}
+
+export type NodeRecord = FullNodeRecord | DeltaNodeRecord;
+
+export interface NodeTypeDef {
+  label: string;
+  node_type: NodeTypeId;
+  palette_allowed_children: NodeTypeId[];
+}
+
+export type NodeTypeId = string;
+
+export type NodeUuid = string;
+
+export interface ParamDto {
+  change_policy: ChangePolicy;
+  constraints: ValueConstraints;
+  param_node_id: NodeId;
+  presentation: PresentationHint;
+  read_only: boolean;
+  semantics: SemanticsHint;
+  update_policy: UpdatePolicy;
+  value: Value;
+}
+
+export interface ParameterData {
+  change: ChangePolicy;
+  constraints: ValueConstraints;
+  default?: Value | null;
+  read_only: boolean;
+  save: SavePolicy;
+  update: UpdatePolicy;
+  value: Value;
+}
+
+export interface PatchMeta {
+  edit_session_id?: string | null;
+  node_id: NodeId;
+  patch: NodeMetaPatch;
+  propagation: Propagation;
+}
+
+/** Changes one element of a collection parameter. */
+export interface PatchParam {
+  edit_session_id?: string | null;
+  param_node_id: NodeId;
+  patch: ValuePatch;
+  propagation: Propagation;
+}
+
+export interface PresentationHint {
+  widget?: string | null;
+}
+
+/**
+ * A named snapshot of parameter values below a node.
+ *
+ * Values are keyed by node uuid so a preset still applies after the graph has
+ * been rebuilt with fresh `NodeId`s.
+ */
+export interface Preset {
+  name: string;
+  /** Node the preset was captured from. */
+  root: NodeUuid;
+  values: PresetValue[];
+}
+
+export interface PresetValue {
+  uuid: NodeUuid;
+  value: Value;
+}
+
+export interface ProjectFile {
+  presets?: Preset[];
+  root: NodeRecord;
+  version: string;
+}
+
+export type Propagation = "Immediate" | "EndOfTick" | "NextTick";
+
+/** Rotation quaternion; `w` is the scalar part. */
+export interface Quat {
+  w: number;
+  x: number;
+  y: number;
+  z: number;
+}
+
+export interface RandomParams {
+  generate: "Trigger";
+  max: { Float: number };
+  min: { Float: number };
+  readonly output: { Float: number };
+  /** In Hz. */
+  rate: { Float: number };
+  seed: { Int: number };
+}
+
+export interface ReferenceValue {
+  cached_id?: NodeId | null;
+  uuid: NodeUuid;
+}
+
+export type SavePolicy = "None" | "Delta" | "Full";
+
+export interface Scope {
+  mode: ScopeMode;
+  root_uuid?: NodeUuid | null;
+}
+
+export type ScopeMode = "Root" | "Subtree";
+
+export interface ScriptParams {
+  enabled: { Bool: boolean };
+  readonly error: { String: string };
+  readonly log: { String: string };
+  max_operations: { Int: number };
+  script: { String: string };
+}
+
+export interface SemanticsHint {
+  intent?: string | null;
+  unit?: string | null;
+}
+
+/** Messages the server sends, keyed by the envelope `msg` field. */
+export type ServerMessage =
+  | { msg: "HelloAck"; payload: HelloAck }
+  | { msg: "Snapshot"; payload: Snapshot }
+  | { msg: "EventBatch"; payload: EventBatch }
+  | { msg: "BeginEditAck"; payload: BeginEditAck }
+  | { msg: "Ack"; payload: Ack };
+
+/** Wire form of a message: the tagged message plus the optional request id. */
+export type ServerMessageEnvelope = { req_id?: string | null } & ServerMessage;
+
+export interface SetParam {
+  edit_session_id?: string | null;
+  param_node_id: NodeId;
+  propagation: Propagation;
+  /**
+   * Unit `value` is written in, converted to the parameter's own unit
+   * before the edit is applied.
+   */
+  unit?: string | null;
+  value: Value;
+}
+
+export type ShortName = string;
+
+export interface SmootherParams {
+  input: { Float: number };
+  max_rate: { Float: number };
+  readonly output: { Float: number };
+  /** In s. */
+  time: { Float: number };
+}
+
+export interface Snapshot {
+  as_of: EventTime;
+  enums: EnumDef[];
+  node_types: NodeTypeDef[];
+  nodes: NodeDto[];
+  params: ParamDto[];
+}
+
+export interface Subscribe {
+  from: EventTime;
+  scope: Scope;
+}
+
+export interface TimerParams {
+  readonly done: "Trigger";
+  /** In s. */
+  duration: { Float: number };
+  /** In s. */
+  readonly elapsed: { Float: number };
+  looping: { Bool: boolean };
+  readonly progress: { Float: number };
+  reset: "Trigger";
+  readonly running: { Bool: boolean };
+  start: "Trigger";
+  stop: "Trigger";
+}
+
+/** Wall-clock time as microseconds since the Unix epoch (UTC). */
+export interface Timestamp {
+  unix_micros: number;
+}
+
+export interface ToggleParams {
+  reset: "Trigger";
+  set: "Trigger";
+  readonly state: { Bool: boolean };
+  toggle: "Trigger";
+}
+
+export interface TriggerDelayParams {
+  cancel: "Trigger";
+  /** In s. */
+  delay: { Float: number };
+  input: "Trigger";
+  readonly output: "Trigger";
+}
+
+export type UpdatePolicy = "Immediate" | "EndOfTick" | "NextTick";
+
+export type Value =
+  | "Trigger"
+  | { Bool: boolean }
+  | { Int: number }
+  | { Float: number }
+  | { String: string }
+  | { Vec2: Vec2 }
+  | { Vec3: Vec3 }
+  | { Vec4: Vec4 }
+  | { Quat: Quat }
+  | { ColorRgba: ColorRgba }
+  | { FloatRange: FloatRange }
+  | { IntRange: IntRange }
+  | { Bytes: number[] }
+  | { Timestamp: Timestamp }
+  | { Duration: Duration }
+  | { Curve: Curve }
+  | { Gradient: Gradient }
+  | { Enum: { enum_id: EnumId; variant: EnumVariantId } }
+  | { Reference: ReferenceValue }
+  | { List: Value[] }
+  | { FloatArray: number[] }
+  | { IntArray: number[] }
+  | { Map: Record<string, Value> };
+
+export type ValueConstraints =
+  | "None"
+  | {
+    Int: {
+      clamp: boolean;
+      max?: number | null;
+      min?: number | null;
+      step?: number | null;
+    };
+  }
+  | {
+    Float: {
+      clamp: boolean;
+      max?: number | null;
+      min?: number | null;
+      step?: number | null;
+    };
+  }
+  | { String: { max_len?: number | null; pattern?: string | null } }
+  | { Enum: { allowed: EnumVariantId[]; enum_id: EnumId } }
+  | { Reference: { target?: string | null } }
+  | {
+    Duration: { clamp: boolean; max?: Duration | null; min?: Duration | null };
+  }
+  | {
+    Curve: {
+      clamp: boolean;
+      domain?: FloatRange | null;
+      range?: FloatRange | null;
+    };
+  }
+  | { Gradient: { clamp: boolean; domain?: FloatRange | null } }
+  | {
+    Collection: {
+      element: ValueConstraints;
+      max_len?: number | null;
+      min_len?: number | null;
+    };
+  };
+
+/**
+ * A change to one element of a collection value, so large arrays do not
+ * travel whole for a single edit.
+ */
+export type ValuePatch =
+  | { SetIndex: { index: number; value: Value } }
+  | { Insert: { index: number; value: Value } }
+  | { Remove: { index: number } }
+  | { SetKey: { key: string; value: Value } }
+  | { RemoveKey: { key: string } };
+
+export interface ValueRecorderParams {
+  clear: "Trigger";
+  input: { Float: number };
+  /** In s. */
+  readonly length: { Float: number };
+  looping: { Bool: boolean };
+  readonly output: { Float: number };
+  play: { Bool: boolean };
+  record: { Bool: boolean };
+}
+
+export interface Vec2 {
+  x: number;
+  y: number;
+}
+
+export interface Vec3 {
+  x: number;
+  y: number;
+  z: number;
+}
+
+export interface Vec4 {
+  w: number;
+  x: number;
+  y: number;
+  z: number;
+}
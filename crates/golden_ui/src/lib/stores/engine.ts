@@ -1,4 +1,5 @@
 import { derived, writable, type Readable, type Writable } from "svelte/store";
+import { applyValuePatch, type ValueInput, type ValueObject } from "../utils/values";
 
 type NodeId = number | string;
 
@@ -126,19 +127,21 @@ function normalizeNodeId(value: unknown): NodeId | null {
 }
 
 function applyParamChangedEvents(batchEvents: Event[]) {
-  const changed = new Map<NodeId, unknown>();
+  // Per parameter, the batch's changes in order: whole values or patches.
+  const changed = new Map<NodeId, Array<(value: unknown) => unknown>>();
 
   for (const event of batchEvents) {
     const kind = event.kind;
     if (!kind || typeof kind !== "object") {
       continue;
     }
-    if (!("ParamChanged" in kind)) {
+    if (!("ParamChanged" in kind) && !("ParamPatched" in kind)) {
       continue;
     }
 
-    const payload = (kind as Record<string, unknown>).ParamChanged as
-      | { param?: unknown; value?: unknown }
+    const payload = ((kind as Record<string, unknown>).ParamChanged ??
+      (kind as Record<string, unknown>).ParamPatched) as
+      | { param?: unknown; value?: unknown; patch?: unknown }
       | undefined;
     if (!payload) {
       continue;
@@ -149,7 +152,13 @@ function applyParamChangedEvents(batchEvents: Event[]) {
       continue;
     }
 
-    changed.set(nodeId, payload.value);
+    const steps = changed.get(nodeId) ?? [];
+    if ("ParamChanged" in kind) {
+      steps.push(() => payload.value);
+    } else {
+      steps.push((value) => applyValuePatch(value as ValueInput, payload.patch as ValueObject));
+    }
+    changed.set(nodeId, steps);
   }
 
   if (changed.size === 0) {
@@ -158,12 +167,13 @@ function applyParamChangedEvents(batchEvents: Event[]) {
 
   params.update((current) =>
     current.map((param) => {
-      if (!changed.has(param.param_node_id)) {
+      const steps = changed.get(param.param_node_id);
+      if (!steps) {
         return param;
       }
       return {
         ...param,
-        value: changed.get(param.param_node_id)
+        value: steps.reduce((value, step) => step(value), param.value as unknown)
       };
     })
   );
@@ -229,7 +239,13 @@ function setSelection(nodeId: NodeId) {
   selection.set({ nodeId });
 }
 
-function setParam(paramNodeId: NodeId, value: unknown, propagation = "Immediate") {
+// `unit`, when given, is the unit `value` is written in; the server converts it.
+function setParam(
+  paramNodeId: NodeId,
+  value: unknown,
+  propagation = "Immediate",
+  unit?: string
+) {
   send({
     msg: "SetParam",
     req_id: null,
@@ -237,6 +253,7 @@ function setParam(paramNodeId: NodeId, value: unknown, propagation = "Immediate"
       edit_session_id: null,
       param_node_id: paramNodeId,
       value,
+      ...(unit ? { unit } : {}),
       propagation
     }
   });
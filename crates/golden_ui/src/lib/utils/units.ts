@@ -0,0 +1,74 @@
+// Mirrors the registry in golden_schema::units; keep the two tables in sync.
+
+export type Dimension = "Time" | "Frequency" | "Angle" | "Ratio";
+
+type Unit = {
+  symbol: string;
+  aliases: string[];
+  dimension: Dimension;
+  // Base units per one of this unit; null for decibels.
+  factor: number | null;
+};
+
+const UNITS: Unit[] = [
+  { symbol: "s", aliases: ["sec", "seconds"], dimension: "Time", factor: 1 },
+  { symbol: "ms", aliases: ["milliseconds"], dimension: "Time", factor: 1e-3 },
+  { symbol: "us", aliases: ["µs", "microseconds"], dimension: "Time", factor: 1e-6 },
+  { symbol: "min", aliases: ["minutes"], dimension: "Time", factor: 60 },
+  { symbol: "Hz", aliases: ["hz"], dimension: "Frequency", factor: 1 },
+  { symbol: "kHz", aliases: ["khz"], dimension: "Frequency", factor: 1e3 },
+  { symbol: "BPM", aliases: ["bpm"], dimension: "Frequency", factor: 1 / 60 },
+  { symbol: "rad", aliases: ["radians"], dimension: "Angle", factor: 1 },
+  { symbol: "deg", aliases: ["°", "degrees"], dimension: "Angle", factor: Math.PI / 180 },
+  { symbol: "turn", aliases: ["turns"], dimension: "Angle", factor: 2 * Math.PI },
+  { symbol: "x", aliases: ["ratio"], dimension: "Ratio", factor: 1 },
+  { symbol: "%", aliases: ["percent"], dimension: "Ratio", factor: 0.01 },
+  { symbol: "dB", aliases: ["db"], dimension: "Ratio", factor: null }
+];
+
+export function findUnit(symbol: string | null | undefined) {
+  if (!symbol) {
+    return undefined;
+  }
+  return UNITS.find((unit) => unit.symbol === symbol || unit.aliases.includes(symbol));
+}
+
+export function unitsOf(dimension: Dimension) {
+  return UNITS.filter((unit) => unit.dimension === dimension).map((unit) => unit.symbol);
+}
+
+function toBase(unit: Unit, value: number) {
+  return unit.factor === null ? Math.pow(10, value / 20) : value * unit.factor;
+}
+
+function fromBase(unit: Unit, value: number) {
+  // Ratios of zero or below are -Infinity dB, as in the engine.
+  return unit.factor === null ? 20 * Math.log10(Math.max(value, 0)) : value / unit.factor;
+}
+
+// `value` converted between two units of one dimension, or null if it cannot be.
+export function convertUnit(value: number, from: string, to: string) {
+  const source = findUnit(from);
+  const target = findUnit(to);
+  if (!source || !target || source.dimension !== target.dimension) {
+    return null;
+  }
+  return fromBase(target, toBase(source, value));
+}
+
+// Picks the first preferred unit the parameter's unit converts to, falling
+// back to the parameter's own unit.
+export function displayUnit(unit: string | null | undefined, preferred: string[]) {
+  const dimension = findUnit(unit)?.dimension;
+  const match = preferred.find((symbol) => dimension && findUnit(symbol)?.dimension === dimension);
+  return match ?? unit ?? null;
+}
+
+export function formatInUnit(value: number, unit: string | null | undefined, preferred: string[] = []) {
+  const shown = displayUnit(unit, preferred);
+  if (!unit || !shown) {
+    return String(value);
+  }
+  const converted = convertUnit(value, unit, shown) ?? value;
+  return `${Number(converted.toFixed(4))} ${shown}`;
+}
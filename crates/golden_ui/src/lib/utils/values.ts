@@ -1,9 +1,16 @@
-type Vec2 = { x: number; y: number };
-type Vec3 = { x: number; y: number; z: number };
-type ColorRgba = { r: number; g: number; b: number; a: number };
+import type {
+  ColorRgba,
+  CurveKey,
+  FloatRange as Range,
+  GradientStop,
+  Value,
+  Vec2,
+  Vec3,
+  Vec4
+} from "../generated/golden";
 
-type ValueObject = Record<string, any>;
-export type ValueInput = ValueObject | string | null | undefined;
+export type ValueObject = Record<string, any>;
+export type ValueInput = Value | ValueObject | string | null | undefined;
 
 export function unwrapValue(value: ValueInput) {
   if (value && typeof value === "object") {
@@ -26,16 +33,62 @@ export function formatValue(value: ValueInput) {
     const vec = inner as Vec3;
     return `x:${vec.x} y:${vec.y} z:${vec.z}`;
   }
+  if (kind === "Vec4" || kind === "Quat") {
+    const vec = inner as Vec4;
+    return `x:${vec.x} y:${vec.y} z:${vec.z} w:${vec.w}`;
+  }
+  if (kind === "FloatRange" || kind === "IntRange") {
+    const range = inner as Range;
+    return `${range.min}..${range.max}`;
+  }
+  if (kind === "Bytes") {
+    return (inner as number[]).map((byte) => byte.toString(16).padStart(2, "0")).join(" ");
+  }
+  if (kind === "Timestamp") return new Date(inner.unix_micros / 1000).toISOString();
+  if (kind === "Duration") return `${inner.secs + inner.nanos / 1e9}s`;
   if (kind === "ColorRgba") {
     const color = inner as ColorRgba;
     return `rgba(${color.r}, ${color.g}, ${color.b}, ${color.a})`;
   }
+  if (kind === "Curve") {
+    const keys = inner.keys as CurveKey[];
+    return `curve(${keys.map((key) => `${key.x}:${key.y}`).join(", ")})`;
+  }
+  if (kind === "Gradient") {
+    const stops = inner.stops as GradientStop[];
+    return `gradient(${stops.map((stop) => `${stop.t}:${formatValue({ ColorRgba: stop.color })}`).join(", ")})`;
+  }
   if (kind === "Enum") return `${inner.enum_id}::${inner.variant}`;
   if (kind === "Reference") return inner.uuid;
   if (kind === "Trigger") return "Trigger";
+  if (kind === "FloatArray" || kind === "IntArray") return `[${(inner as number[]).join(", ")}]`;
+  if (kind === "List") return `[${(inner as ValueInput[]).map(formatValue).join(", ")}]`;
+  if (kind === "Map") {
+    const entries = Object.entries(inner as Record<string, ValueInput>);
+    return `{${entries.map(([key, item]) => `${key}: ${formatValue(item)}`).join(", ")}}`;
+  }
   return String(inner ?? "");
 }
 
+// Applies a ValuePatch to a List, FloatArray, IntArray or Map value.
+export function applyValuePatch(value: ValueInput, patch: ValueObject): ValueInput {
+  const { kind, value: inner } = unwrapValue(value);
+  const op = Object.keys(patch)[0];
+  const args = patch[op];
+  if (kind === "Map") {
+    const entries = { ...inner };
+    if (op === "SetKey") entries[args.key] = args.value;
+    if (op === "RemoveKey") delete entries[args.key];
+    return { Map: entries };
+  }
+  const element = kind === "List" ? args.value : unwrapValue(args.value).value;
+  const items = [...inner];
+  if (op === "SetIndex") items[args.index] = element;
+  if (op === "Insert") items.splice(args.index, 0, element);
+  if (op === "Remove") items.splice(args.index, 1);
+  return { [kind]: items };
+}
+
 export function buildValue(kind: string, value: unknown) {
   if (kind === "Trigger") {
     return "Trigger";
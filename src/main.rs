@@ -3,7 +3,9 @@ use golden_prelude::params;
 use golden_prelude::*;
 
 #[derive(GoldenNode)]
+#[behaviour(continuous)]
 pub struct OscOutput {
+    #[node_id]
     pub id: schema::NodeId,
     pub connection: FolderHandle,
     pub intensity: ParameterHandle<f64>,
@@ -13,7 +15,12 @@ pub struct OscOutput {
     pub drive: ParameterHandle<f64>,
     pub value: ParameterHandle<f64>,
     pub panic: ParameterHandle<Trigger>,
+    #[state]
     prog: f64,
+    sender: Option<net::osc::OscSender>,
+    /// Set when connecting failed, so it is not retried every tick until the
+    /// destination changes.
+    unreachable: bool,
 }
 
 impl OscOutput {
@@ -26,7 +33,7 @@ impl OscOutput {
             port: i64 = 9000 (min=1, max=65535);
         }
 
-        drive: f64 = 0.0;
+        drive: f64 = 0.0 (save = "None");
         value: f64 = 0.5;
         panic: Trigger (behavior="Append");
     }
@@ -39,6 +46,11 @@ impl NodeReactive for OscOutput {
             self.drive.set_immediate(ctx, 0.0);
             println!("Panic triggered! Drive reset to 0.0");
         }
+        // Reconnect on the next update with the new destination.
+        if node_id == self.host.node_id || node_id == self.port.node_id {
+            self.sender = None;
+            self.unreachable = false;
+        }
     }
 }
 
@@ -53,21 +65,39 @@ impl NodeContinuous for OscOutput {
             // Simple processing logic: output is intensity multiplied by drive and value
             let output = intensity * anim_cos * value;
             // println!("OscOutput processing: intensity={intensity}, drive={anim_cos}, value={value}, output={output}");
+            self.send_output(ctx, output);
         }
     }
 }
 
-struct OscOutputBehaviour {
-    inner: OscOutput,
-}
-
-impl NodeBehaviour for OscOutputBehaviour {
-    fn process(&mut self, ctx: &mut ProcessCtx) {
-        NodeReactive::process(&mut self.inner, ctx);
-    }
+impl OscOutput {
+    fn send_output(&mut self, ctx: &ProcessCtx, output: f64) {
+        if self.sender.is_none() {
+            if self.unreachable {
+                return;
+            }
+            let host = self.host.get(ctx).unwrap_or_default();
+            let port = self.port.get(ctx).unwrap_or(9000);
+            let Ok(port) = u16::try_from(port) else {
+                return;
+            };
+            match net::osc::OscSender::connect(&host, port) {
+                Ok(sender) => self.sender = Some(sender),
+                Err(err) => {
+                    eprintln!("OscOutput cannot reach {host}:{port}: {err}");
+                    self.unreachable = true;
+                    return;
+                }
+            }
+        }
 
-    fn update(&mut self, ctx: &mut ProcessCtx) {
-        NodeContinuous::update(&mut self.inner, ctx);
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let Some(name) = ctx.read_meta(self.id).map(|meta| meta.short_name.0.clone()) else {
+            return;
+        };
+        let _ = sender.send_value(&format!("/{name}/output"), &Value::Float(output));
     }
 }
 
@@ -111,31 +141,10 @@ fn build_demo_engine() -> Engine {
     let root = engine.root_id();
     let outputs = engine.create_child_container(root, "Outputs", "outputs");
     let _devices = engine.create_child_container(root, "Devices", "devices");
-    let _mappings = engine.create_child_container(root, "Mappings", "mappings");
+    let mappings = engine.create_child_container(root, "Mappings", "mappings");
 
     let mut manager_data = ManagerData::new();
-    manager_data.register_node_type(
-        schema::NodeTypeId("OscOutput".to_string()),
-        OscOutput::schema(),
-        |binding| {
-            let osc = OscOutput {
-                id: binding.node_id,
-                connection: binding.folder("connection").expect("missing folder 'connection'"),
-                intensity: binding.param("intensity").expect("missing param 'intensity'"),
-                enabled: binding.param("enabled").expect("missing param 'enabled'"),
-                host: binding.param("host").expect("missing param 'host'"),
-                port: binding.param("port").expect("missing param 'port'"),
-                drive: binding.param("drive").expect("missing param 'drive'"),
-                value: binding.param("value").expect("missing param 'value'"),
-                panic: binding.param("panic").expect("missing param 'panic'"),
-                prog: 0.0,
-            };
-
-            Box::new(OscOutputBehaviour {
-                inner: osc,
-            })
-        },
-    );
+    manager_data.register::<OscOutput>();
 
     let manager = engine.create_node(
         schema::NodeTypeId("OutputManager".to_string()),
@@ -146,6 +155,17 @@ fn build_demo_engine() -> Engine {
     );
     engine.add_child(outputs, manager);
 
+    let mut mapping_types = ManagerData::new();
+    nodes::Mapping::register(&mut mapping_types);
+    let mapping_manager = engine.create_node(
+        schema::NodeTypeId("MappingManager".to_string()),
+        NodeExecution::Passive,
+        NodeData::Manager(mapping_types),
+        engine.create_meta("mapping_manager"),
+        None,
+    );
+    engine.add_child(mappings, mapping_manager);
+
     engine
 }
 